@@ -0,0 +1,186 @@
+use serde::Serialize;
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+use std::path::Path;
+
+/// GPU acceleration the current binary was built with, what the machine
+/// offers, and which one Whisper will actually use.
+#[derive(Clone, Debug, Serialize)]
+pub struct AccelerationInfo {
+    /// Backends compiled into this binary.
+    pub compiled: Vec<String>,
+    /// Backends whose drivers or runtimes were found on this machine.
+    pub detected: Vec<String>,
+    /// The backend Whisper runs on, `cpu` when none applies.
+    pub active: String,
+    /// Explains a mismatch, e.g. Vulkan drivers present but not compiled in.
+    pub note: Option<String>,
+}
+
+/// What the machine offers for picking a model that runs well on it.
+#[derive(Clone, Debug, Serialize)]
+pub struct HardwareProfile {
+    pub memory_mb: u64,
+    pub cpu_cores: usize,
+    /// GPU backend Whisper would use, `None` on the CPU.
+    pub gpu_backend: Option<String>,
+    /// Language of the operating system, e.g. `de`.
+    pub language: Option<String>,
+}
+
+pub fn detect_hardware(gpu_enabled: bool) -> HardwareProfile {
+    let mut system = sysinfo::System::new();
+    system.refresh_memory();
+    let active = whisper_backend(gpu_enabled);
+    HardwareProfile {
+        memory_mb: system.total_memory() / (1024 * 1024),
+        cpu_cores: std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1),
+        gpu_backend: (active != "cpu").then_some(active),
+        language: sys_locale::get_locale().and_then(|locale| {
+            locale
+                .split(['-', '_'])
+                .next()
+                .map(|language| language.to_lowercase())
+        }),
+    }
+}
+
+/// The backend the next Whisper model load should try, `cpu` when none
+/// applies.
+pub fn whisper_backend(gpu_enabled: bool) -> String {
+    get_acceleration_info(gpu_enabled).active
+}
+
+/// `gpu_enabled` is the user's GPU acceleration setting, with it off Whisper
+/// always runs on the CPU.
+pub fn get_acceleration_info(gpu_enabled: bool) -> AccelerationInfo {
+    let compiled = compiled_backends();
+    let detected = detected_backends();
+
+    let active = compiled
+        .iter()
+        .find(|backend| gpu_enabled && detected.contains(backend))
+        .cloned()
+        .unwrap_or_else(|| "cpu".to_string());
+
+    let unused: Vec<&String> = detected.iter().filter(|b| !compiled.contains(b)).collect();
+    let note = if !gpu_enabled && !compiled.is_empty() {
+        Some("GPU acceleration is turned off in settings.".to_string())
+    } else if active == "cpu" && !unused.is_empty() {
+        Some(format!(
+            "This build runs Whisper on the CPU. A build with {} support would use the GPU found on this machine.",
+            unused
+                .iter()
+                .map(|b| b.as_str())
+                .collect::<Vec<_>>()
+                .join(" or ")
+        ))
+    } else if active == "cpu" && !compiled.is_empty() {
+        Some(format!(
+            "This build supports {}, but no matching driver or runtime was found.",
+            compiled.join(", ")
+        ))
+    } else {
+        None
+    };
+
+    AccelerationInfo {
+        compiled,
+        detected,
+        active,
+        note,
+    }
+}
+
+fn compiled_backends() -> Vec<String> {
+    let mut backends = Vec::new();
+    if cfg!(feature = "coreml") {
+        backends.push("coreml".to_string());
+    }
+    if cfg!(feature = "cuda") {
+        backends.push("cuda".to_string());
+    }
+    if cfg!(feature = "vulkan") {
+        backends.push("vulkan".to_string());
+    }
+    if cfg!(feature = "hipblas") {
+        backends.push("rocm".to_string());
+    }
+    backends
+}
+
+#[cfg(target_os = "linux")]
+fn detected_backends() -> Vec<String> {
+    let mut backends = Vec::new();
+
+    // The NVIDIA kernel driver ships libcuda, the toolkit isn't needed
+    let cuda_driver = [
+        "/usr/lib/x86_64-linux-gnu/libcuda.so.1",
+        "/usr/lib/aarch64-linux-gnu/libcuda.so.1",
+        "/usr/lib64/libcuda.so.1",
+        "/usr/lib/libcuda.so.1",
+    ]
+    .iter()
+    .any(|path| Path::new(path).exists());
+    if cuda_driver && Path::new("/dev/nvidiactl").exists() {
+        backends.push("cuda".to_string());
+    }
+
+    // The loader alone isn't enough, a driver (ICD) has to be installed as well
+    let vulkan_loader = [
+        "/usr/lib/x86_64-linux-gnu/libvulkan.so.1",
+        "/usr/lib/aarch64-linux-gnu/libvulkan.so.1",
+        "/usr/lib64/libvulkan.so.1",
+        "/usr/lib/libvulkan.so.1",
+    ]
+    .iter()
+    .any(|path| Path::new(path).exists());
+    let vulkan_driver = ["/usr/share/vulkan/icd.d", "/etc/vulkan/icd.d"]
+        .iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .any(|entry| entry.path().extension().and_then(|e| e.to_str()) == Some("json"));
+    if vulkan_loader && vulkan_driver {
+        backends.push("vulkan".to_string());
+    }
+
+    // ROCm needs both the kernel driver and the HIP runtime
+    if Path::new("/dev/kfd").exists() && Path::new("/opt/rocm").exists() {
+        backends.push("rocm".to_string());
+    }
+
+    backends
+}
+
+#[cfg(target_os = "macos")]
+fn detected_backends() -> Vec<String> {
+    if cfg!(target_arch = "aarch64") {
+        vec!["coreml".to_string()]
+    } else {
+        Vec::new()
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn detected_backends() -> Vec<String> {
+    // GPU drivers install their runtimes into System32
+    let system32 =
+        Path::new(&std::env::var("SystemRoot").unwrap_or_else(|_| "C:\\Windows".to_string()))
+            .join("System32");
+
+    let mut backends = Vec::new();
+    if system32.join("nvcuda.dll").exists() {
+        backends.push("cuda".to_string());
+    }
+    if system32.join("vulkan-1.dll").exists() {
+        backends.push("vulkan".to_string());
+    }
+    backends
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn detected_backends() -> Vec<String> {
+    Vec::new()
+}
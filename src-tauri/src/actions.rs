@@ -1,23 +1,73 @@
 use crate::audio_feedback::{SoundType, play_feedback_sound};
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use crate::audio_toolkit::{apply_replacement_rules, parse_correction, Recording};
+use crate::events::{self, AppEvent};
+use crate::focus::{self, FocusedWindow};
 use crate::managers::audio::AudioRecordingManager;
 use crate::managers::history::HistoryManager;
+use crate::managers::offline_queue::{is_offline_error, OfflineQueueManager};
 use crate::managers::transcription::TranscriptionManager;
+use crate::managers::transcription_queue::TranscriptionQueue;
+use crate::ocr;
 use crate::overlay::{show_recording_overlay, show_transcribing_overlay};
-use crate::settings::get_settings;
+use crate::settings::{get_settings, write_settings};
 use crate::tray::{change_tray_icon, TrayIconState};
+use crate::tts;
 use crate::utils;
-use log::{debug, error};
+use crate::ManagedToggleState;
+use log::{debug, error, warn};
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
-use std::sync::Arc;
-use std::time::Instant;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::async_runtime::JoinHandle;
 use tauri::AppHandle;
 use tauri::Manager;
 
+const AUTO_SUBMIT_POLL: Duration = Duration::from_millis(250);
+const MAX_DURATION_POLL: Duration = Duration::from_secs(1);
+const PARTIAL_INTERVAL: Duration = Duration::from_millis(1500);
+/// New audio needed before the partial transcript is refreshed.
+const PARTIAL_MIN_NEW_SAMPLES: usize = WHISPER_SAMPLE_RATE as usize;
+/// Only the tail of long recordings is transcribed live, so each pass stays
+/// within one Whisper window.
+const PARTIAL_WINDOW_SAMPLES: usize = 30 * WHISPER_SAMPLE_RATE as usize;
+/// Time a refocused window gets to come to the front.
+const REFOCUS_SETTLE: Duration = Duration::from_millis(200);
+const REFOCUS_POLL: Duration = Duration::from_secs(1);
+/// How long a transcript locked to an app waits for it to get the focus back.
+const REFOCUS_WAIT: Duration = Duration::from_secs(10 * 60);
+
+// Transcription held back by deferred paste until the paste here shortcut
+static PENDING_PASTE: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+// Lookup of the window that had the focus when each binding's recording
+// started, handed to that recording's transcription when it stops
+static RECORDING_TARGETS: Lazy<Mutex<HashMap<String, JoinHandle<Option<FocusedWindow>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct TranscriptionCompleted {
+    pub binding_id: String,
+    pub text: String,
+    /// Window focused when the transcript was ready, where it's pasted.
+    pub target: Option<FocusedWindow>,
+    /// Another app got the focus while recording.
+    pub target_changed: bool,
+    /// Waiting for the paste here shortcut instead of being pasted.
+    pub held_back: bool,
+}
+
 // Shortcut Action Trait
 pub trait ShortcutAction: Send + Sync {
     fn start(&self, app: &AppHandle, binding_id: &str, shortcut_str: &str);
     fn stop(&self, app: &AppHandle, binding_id: &str, shortcut_str: &str);
+
+    /// Momentary actions only run `start` on press, whether or not push to
+    /// talk is on.
+    fn is_momentary(&self) -> bool {
+        false
+    }
 }
 
 // Transcribe Action
@@ -36,11 +86,17 @@ impl ShortcutAction for TranscribeAction {
         change_tray_icon(app, TrayIconState::Recording);
         show_recording_overlay(app);
 
+        // Looking up the window can take a moment, don't hold up recording
+        RECORDING_TARGETS.lock().unwrap().insert(
+            binding_id.clone(),
+            tauri::async_runtime::spawn_blocking(focus::focused_window),
+        );
+
         let rm = app.state::<Arc<AudioRecordingManager>>();
 
-        // Get the microphone mode to determine audio feedback timing
-        let settings = get_settings(app);
-        let is_always_on = settings.always_on_microphone;
+        // Get the microphone mode to determine audio feedback timing. The
+        // battery saver can override the setting, so ask the recorder.
+        let is_always_on = rm.is_always_on();
         debug!("Microphone mode - always_on: {}", is_always_on);
 
         if is_always_on {
@@ -68,6 +124,17 @@ impl ShortcutAction for TranscribeAction {
             }
         }
 
+        let settings = get_settings(app);
+        if !settings.push_to_talk && settings.auto_submit_seconds > 0 {
+            spawn_auto_submit(app, &binding_id, settings.auto_submit_seconds);
+        }
+        if settings.live_transcription {
+            spawn_partial_transcription(app, &binding_id);
+        }
+        if settings.max_recording_seconds > 0 {
+            spawn_max_duration_stop(app, &binding_id, settings.max_recording_seconds);
+        }
+
         debug!(
             "TranscribeAction::start completed in {:?}",
             start_time.elapsed()
@@ -82,6 +149,7 @@ impl ShortcutAction for TranscribeAction {
         let rm = Arc::clone(&app.state::<Arc<AudioRecordingManager>>());
         let tm = Arc::clone(&app.state::<Arc<TranscriptionManager>>());
         let hm = Arc::clone(&app.state::<Arc<HistoryManager>>());
+        let queue = Arc::clone(&app.state::<Arc<TranscriptionQueue>>());
 
         change_tray_icon(app, TrayIconState::Transcribing);
         show_transcribing_overlay(app);
@@ -91,6 +159,9 @@ impl ShortcutAction for TranscribeAction {
 
         let binding_id = binding_id.to_string(); // Clone binding_id for the async task
 
+        // Taken now, a new recording on this binding replaces it
+        let recording_target = RECORDING_TARGETS.lock().unwrap().remove(&binding_id);
+
         tauri::async_runtime::spawn(async move {
             let binding_id = binding_id.clone(); // Clone for the inner async task
             debug!(
@@ -99,54 +170,128 @@ impl ShortcutAction for TranscribeAction {
             );
 
             let stop_recording_time = Instant::now();
-            if let Some(samples) = rm.stop_recording(&binding_id) {
+            // Queued as the rest of the recording is taken, after any chunk
+            // auto-submitted before it
+            if let Some((ticket, recording)) =
+                queue.reserve(&binding_id, || rm.stop_recording(&binding_id))
+            {
                 debug!(
                     "Recording stopped and samples retrieved in {:?}, sample count: {}",
                     stop_recording_time.elapsed(),
-                    samples.len()
+                    recording.audio.len()
                 );
 
+                // Wait for earlier recordings and auto-submitted chunks
+                let job = ticket.wait().await;
+
+                // In debug mode, compare two models on the same audio
+                // once the regular transcription is done
+                let settings = get_settings(&ah);
+                let comparison = settings
+                    .comparison_models
+                    .filter(|_| settings.debug_mode)
+                    .and_then(|models| Some((models, recording.audio.samples().ok()?)));
+
                 let transcription_time = Instant::now();
-                let samples_clone = samples.clone(); // Clone for history saving
-                match tm.transcribe(samples).await {
-                    Ok(transcription) => {
+                match tm
+                    .transcribe_for_binding(&recording.audio, &binding_id, &job.cancel)
+                    .await
+                {
+                    Ok(transcript) => {
+                        let transcription = transcript.text.clone();
                         debug!(
                             "Transcription completed in {:?}: '{}'",
                             transcription_time.elapsed(),
                             transcription
                         );
                         if !transcription.is_empty() {
+                            let before = match recording_target {
+                                Some(lookup) => lookup.await.ok().flatten(),
+                                None => None,
+                            };
+                            let mut target = focus::focused_window();
+                            let moved = |target: &Option<FocusedWindow>| {
+                                before
+                                    .as_ref()
+                                    .zip(target.as_ref())
+                                    .is_some_and(|(before, now)| before.is_other_app(now))
+                            };
+                            let target_changed = moved(&target);
+                            if target_changed {
+                                debug!("Focus moved to another app while recording");
+                            }
+
+                            // Output locked to the recording's app goes back to it
+                            if target_changed && settings.lock_to_recording_app {
+                                if let Some(before) = &before {
+                                    match focus::activate(before) {
+                                        Ok(()) => {
+                                            tokio::time::sleep(REFOCUS_SETTLE).await;
+                                            target = focus::focused_window();
+                                        }
+                                        Err(e) => {
+                                            debug!("Couldn't refocus {}: {}", before.app_name, e)
+                                        }
+                                    }
+                                }
+                            }
+                            let still_moved = moved(&target);
+                            let wait_for_app = still_moved && settings.lock_to_recording_app;
+                            let held_back = settings.deferred_paste
+                                || wait_for_app
+                                || (still_moved && settings.confirm_paste_on_focus_change);
+                            events::emit(
+                                &ah,
+                                AppEvent::TranscriptionCompleted(TranscriptionCompleted {
+                                    binding_id: binding_id.clone(),
+                                    text: transcription.clone(),
+                                    target: target.clone(),
+                                    target_changed,
+                                    held_back,
+                                }),
+                            );
+
                             // Save to history
                             let hm_clone = Arc::clone(&hm);
-                            let transcription_for_history = transcription.clone();
                             tauri::async_runtime::spawn(async move {
                                 if let Err(e) = hm_clone
-                                    .save_transcription(samples_clone, transcription_for_history)
+                                    .save_transcription_for_target(recording, transcript, target)
                                     .await
                                 {
                                     error!("Failed to save transcription to history: {}", e);
                                 }
                             });
-                            let transcription_clone = transcription.clone();
-                            let ah_clone = ah.clone();
-                            let paste_time = Instant::now();
-                            ah.run_on_main_thread(move || {
-                                match utils::paste(transcription_clone, ah_clone.clone()) {
-                                    Ok(()) => debug!(
-                                        "Text pasted successfully in {:?}",
-                                        paste_time.elapsed()
-                                    ),
-                                    Err(e) => eprintln!("Failed to paste transcription: {}", e),
+                            if held_back {
+                                // Wait for the user to pick the target
+                                *PENDING_PASTE.lock().unwrap() = Some(transcription.clone());
+                                events::emit(&ah, AppEvent::PastePending(transcription.clone()));
+                                if let Some(window) = before.filter(|_| wait_for_app) {
+                                    spawn_paste_on_refocus(&ah, window, transcription.clone());
                                 }
-                                // Hide the overlay after transcription is complete
-                                utils::hide_recording_overlay(&ah_clone);
-                                change_tray_icon(&ah_clone, TrayIconState::Idle);
-                            })
-                            .unwrap_or_else(|e| {
-                                eprintln!("Failed to run paste on main thread: {:?}", e);
                                 utils::hide_recording_overlay(&ah);
                                 change_tray_icon(&ah, TrayIconState::Idle);
-                            });
+                            } else {
+                                let transcription_clone = transcription.clone();
+                                let ah_clone = ah.clone();
+                                let paste_time = Instant::now();
+                                ah.run_on_main_thread(move || {
+                                    match utils::paste(transcription_clone, ah_clone.clone()) {
+                                        Ok(()) => debug!(
+                                            "Text pasted successfully in {:?}",
+                                            paste_time.elapsed()
+                                        ),
+                                        Err(e) => eprintln!("Failed to paste transcription: {}", e),
+                                    }
+                                    // Hide the overlay after transcription is complete
+                                    utils::hide_recording_overlay(&ah_clone);
+                                    change_tray_icon(&ah_clone, TrayIconState::Idle);
+                                })
+                                .unwrap_or_else(|e| {
+                                    eprintln!("Failed to run paste on main thread: {:?}", e);
+                                    utils::hide_recording_overlay(&ah);
+                                    change_tray_icon(&ah, TrayIconState::Idle);
+                                });
+                            }
                         } else {
                             utils::hide_recording_overlay(&ah);
                             change_tray_icon(&ah, TrayIconState::Idle);
@@ -154,10 +299,28 @@ impl ShortcutAction for TranscribeAction {
                     }
                     Err(err) => {
                         debug!("Global Shortcut Transcription error: {}", err);
+                        // Keep the recording for later when the API provider
+                        // can't be reached
+                        if settings.queue_when_offline && is_offline_error(&err) {
+                            if let Some(queue) = ah.try_state::<Arc<OfflineQueueManager>>() {
+                                if let Err(e) = queue.enqueue(&recording.audio).await {
+                                    error!("Failed to queue recording: {}", e);
+                                }
+                            }
+                        }
                         utils::hide_recording_overlay(&ah);
                         change_tray_icon(&ah, TrayIconState::Idle);
                     }
                 }
+
+                if let Some((models, audio)) = comparison {
+                    let tm = Arc::clone(&tm);
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = tm.compare(audio, &models.model_a, &models.model_b).await {
+                            error!("Transcription comparison failed: {}", e);
+                        }
+                    });
+                }
             } else {
                 debug!("No samples retrieved from recording stop");
                 utils::hide_recording_overlay(&ah);
@@ -172,6 +335,467 @@ impl ShortcutAction for TranscribeAction {
     }
 }
 
+/// Pastes a transcript held back for the app it was recorded in once that
+/// app has the focus again, unless it was pasted or discarded meanwhile.
+fn spawn_paste_on_refocus(app: &AppHandle, window: FocusedWindow, text: String) {
+    let ah = app.clone();
+    std::thread::spawn(move || {
+        let deadline = Instant::now() + REFOCUS_WAIT;
+        while Instant::now() < deadline {
+            std::thread::sleep(REFOCUS_POLL);
+            if PENDING_PASTE.lock().unwrap().as_deref() != Some(text.as_str()) {
+                return;
+            }
+            if !focus::focused_window().is_some_and(|now| !window.is_other_app(&now)) {
+                continue;
+            }
+            if PENDING_PASTE.lock().unwrap().take().is_none() {
+                return;
+            }
+
+            debug!("{} has the focus again, pasting", window.app_name);
+            let ah_clone = ah.clone();
+            if let Err(e) = ah.run_on_main_thread(move || {
+                if let Err(e) = utils::paste(text, ah_clone.clone()) {
+                    eprintln!("Failed to paste held back transcription: {}", e);
+                }
+                events::emit(&ah_clone, AppEvent::PastePendingCleared(()));
+            }) {
+                eprintln!("Failed to run paste on main thread: {:?}", e);
+            }
+            return;
+        }
+        debug!(
+            "{} didn't get the focus back, the transcript stays pending",
+            window.app_name
+        );
+    });
+}
+
+/// Toggle mode only: while the binding keeps recording, finalizes the speech
+/// captured so far at the first pause after `seconds` of it and pastes it, so
+/// long dictations come out in pieces instead of one large buffer.
+fn spawn_auto_submit(app: &AppHandle, binding_id: &str, seconds: u32) {
+    let ah = app.clone();
+    let binding_id = binding_id.to_string();
+    let min_samples = seconds as usize * WHISPER_SAMPLE_RATE as usize;
+
+    std::thread::spawn(move || {
+        let rm = Arc::clone(&ah.state::<Arc<AudioRecordingManager>>());
+        let tm = Arc::clone(&ah.state::<Arc<TranscriptionManager>>());
+        let hm = Arc::clone(&ah.state::<Arc<HistoryManager>>());
+        let queue = Arc::clone(&ah.state::<Arc<TranscriptionQueue>>());
+
+        while rm.is_recording_binding(&binding_id) {
+            std::thread::sleep(AUTO_SUBMIT_POLL);
+
+            tauri::async_runtime::block_on(async {
+                // Queued as the chunk is taken, so the final stop can't get
+                // the rest of the recording in ahead of it
+                let Some((ticket, samples)) = queue.reserve(&binding_id, || {
+                    rm.take_chunk(&binding_id, min_samples).map(Recording::from)
+                }) else {
+                    return;
+                };
+                let job = ticket.wait().await;
+                debug!("Auto-submitting {} samples", samples.len());
+
+                // The model may have been unloaded since recording started
+                tm.initiate_model_load();
+                match tm
+                    .transcribe_for_binding(&samples, &binding_id, &job.cancel)
+                    .await
+                {
+                    Ok(transcript) if !transcript.text.is_empty() => {
+                        let hm_clone = Arc::clone(&hm);
+                        let transcription = transcript.text.clone();
+                        tauri::async_runtime::spawn(async move {
+                            if let Err(e) = hm_clone
+                                .save_transcription(samples.into(), transcript)
+                                .await
+                            {
+                                error!("Failed to save transcription to history: {}", e);
+                            }
+                        });
+
+                        // Trailing space so the next chunk doesn't run into this one
+                        let text = format!("{} ", transcription);
+                        let ah_clone = ah.clone();
+                        if let Err(e) = ah.run_on_main_thread(move || {
+                            if let Err(e) = utils::paste(text, ah_clone) {
+                                eprintln!("Failed to paste transcription chunk: {}", e);
+                            }
+                        }) {
+                            eprintln!("Failed to run paste on main thread: {:?}", e);
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => error!("Auto-submit transcription failed: {}", e),
+                }
+            });
+        }
+        debug!("Auto-submit loop finished for binding {}", binding_id);
+    });
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct RecordingLimitReached {
+    pub binding_id: String,
+    pub max_seconds: u32,
+}
+
+/// Stops the binding's recording once it has run for `max_seconds` and
+/// transcribes it as if the shortcut had stopped it, so a stuck key or a
+/// forgotten toggle doesn't record for hours.
+fn spawn_max_duration_stop(app: &AppHandle, binding_id: &str, max_seconds: u32) {
+    let ah = app.clone();
+    let binding_id = binding_id.to_string();
+    let max_duration = Duration::from_secs(max_seconds.into());
+
+    std::thread::spawn(move || {
+        let rm = Arc::clone(&ah.state::<Arc<AudioRecordingManager>>());
+
+        while rm.is_recording_binding(&binding_id) {
+            std::thread::sleep(MAX_DURATION_POLL);
+
+            // A new recording may have started meanwhile, go by its own start
+            let Some(elapsed) = rm.recording_elapsed() else {
+                continue;
+            };
+            if elapsed < max_duration || !rm.is_recording_binding(&binding_id) {
+                continue;
+            }
+            warn!(
+                "Recording for binding {} reached the {}s limit, stopping it",
+                binding_id, max_seconds
+            );
+
+            // The shortcut is no longer "on" for toggle mode
+            if let Ok(mut states) = ah.state::<ManagedToggleState>().lock() {
+                states.active_toggles.insert(binding_id.clone(), false);
+            }
+            if let Some(action) = ACTION_MAP.get(&binding_id) {
+                action.stop(&ah, &binding_id, "max-duration");
+            }
+            events::emit(
+                &ah,
+                AppEvent::RecordingLimitReached(RecordingLimitReached {
+                    binding_id: binding_id.clone(),
+                    max_seconds,
+                }),
+            );
+            break;
+        }
+        debug!("Max duration watch finished for binding {}", binding_id);
+    });
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct PartialTranscript {
+    pub binding_id: String,
+    pub text: String,
+}
+
+/// While the binding records, transcribes the audio so far every
+/// `PARTIAL_INTERVAL` and emits it as `transcription-partial` for the
+/// overlay. The final transcription still runs on the whole recording.
+fn spawn_partial_transcription(app: &AppHandle, binding_id: &str) {
+    let ah = app.clone();
+    let binding_id = binding_id.to_string();
+
+    std::thread::spawn(move || {
+        let rm = Arc::clone(&ah.state::<Arc<AudioRecordingManager>>());
+        let tm = Arc::clone(&ah.state::<Arc<TranscriptionManager>>());
+        let mut transcribed_len = 0;
+
+        while rm.is_recording_binding(&binding_id) {
+            std::thread::sleep(PARTIAL_INTERVAL);
+
+            let Some((len, tail)) = rm.tail(&binding_id, PARTIAL_WINDOW_SAMPLES) else {
+                continue;
+            };
+            // Auto-submit takes the buffer, start over when it shrinks
+            if len < transcribed_len {
+                transcribed_len = 0;
+            }
+            if len < transcribed_len + PARTIAL_MIN_NEW_SAMPLES {
+                continue;
+            }
+            transcribed_len = len;
+
+            let Some(text) = tm.transcribe_partial(tail) else {
+                continue;
+            };
+            // The recording may have stopped while transcribing
+            if rm.is_recording_binding(&binding_id) {
+                events::emit(
+                    &ah,
+                    AppEvent::TranscriptionPartial(PartialTranscript {
+                        binding_id: binding_id.clone(),
+                        text,
+                    }),
+                );
+            }
+        }
+        debug!(
+            "Partial transcription loop finished for binding {}",
+            binding_id
+        );
+    });
+}
+
+// Paste Here Action
+struct PasteHereAction;
+
+impl ShortcutAction for PasteHereAction {
+    fn start(&self, app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        let Some(text) = PENDING_PASTE.lock().unwrap().take() else {
+            debug!("Paste here pressed without a pending transcription");
+            return;
+        };
+
+        let ah = app.clone();
+        if let Err(e) = app.run_on_main_thread(move || {
+            if let Err(e) = utils::paste(text, ah.clone()) {
+                eprintln!("Failed to paste pending transcription: {}", e);
+            }
+            events::emit(&ah, AppEvent::PastePendingCleared(()));
+        }) {
+            eprintln!("Failed to run paste on main thread: {:?}", e);
+        }
+    }
+
+    fn stop(&self, _app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {}
+
+    fn is_momentary(&self) -> bool {
+        true
+    }
+}
+
+/// Drops a transcription held back by deferred paste, e.g. when the user
+/// decides not to paste it anywhere.
+pub fn discard_pending_paste(app: &AppHandle) {
+    if PENDING_PASTE.lock().unwrap().take().is_some() {
+        events::emit(app, AppEvent::PastePendingCleared(()));
+    }
+}
+
+// OCR Clipboard Action
+struct OcrClipboardAction;
+
+impl ShortcutAction for OcrClipboardAction {
+    fn start(&self, app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        let ah = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let ocr_app = ah.clone();
+            let text = match tauri::async_runtime::spawn_blocking(move || {
+                ocr::recognize_clipboard_image(&ocr_app)
+            })
+            .await
+            {
+                Ok(Ok(text)) => text,
+                Ok(Err(e)) => {
+                    error!("Clipboard OCR failed: {}", e);
+                    events::emit(&ah, AppEvent::OcrFailed(e.to_string()));
+                    return;
+                }
+                Err(e) => {
+                    error!("Clipboard OCR task failed: {}", e);
+                    return;
+                }
+            };
+
+            let tm = Arc::clone(&ah.state::<Arc<TranscriptionManager>>());
+            let text = tm.post_process_text(text);
+            if text.is_empty() {
+                debug!("No text found in clipboard image");
+                return;
+            }
+
+            let ah_clone = ah.clone();
+            if let Err(e) = ah.run_on_main_thread(move || {
+                if let Err(e) = utils::paste(text, ah_clone) {
+                    eprintln!("Failed to paste OCR text: {}", e);
+                }
+            }) {
+                eprintln!("Failed to run paste on main thread: {:?}", e);
+            }
+        });
+    }
+
+    fn stop(&self, _app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {}
+
+    fn is_momentary(&self) -> bool {
+        true
+    }
+}
+
+// Read Back Action
+struct ReadBackAction;
+
+impl ShortcutAction for ReadBackAction {
+    fn start(&self, app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        // Pressing again while reading stops it
+        if tts::is_speaking() {
+            tts::stop();
+            return;
+        }
+
+        let tm = app.state::<Arc<TranscriptionManager>>();
+        let Some(text) = tm.last_transcript() else {
+            debug!("Nothing to read back yet");
+            return;
+        };
+        let voice = get_settings(app).readback_voice;
+        if let Err(e) = tts::speak(&text, voice.as_deref()) {
+            error!("Failed to read back transcript: {}", e);
+        }
+    }
+
+    fn stop(&self, _app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {}
+
+    fn is_momentary(&self) -> bool {
+        true
+    }
+}
+
+// Marker Action
+struct MarkerAction;
+
+impl ShortcutAction for MarkerAction {
+    fn start(&self, app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        let rm = app.state::<Arc<AudioRecordingManager>>();
+        match rm.add_marker() {
+            Some(offset_ms) => {
+                debug!("Marker set at {}ms", offset_ms);
+                events::emit(app, AppEvent::RecordingMarkerAdded(offset_ms));
+            }
+            None => debug!("Marker pressed while not recording"),
+        }
+    }
+
+    fn stop(&self, _app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {}
+
+    fn is_momentary(&self) -> bool {
+        true
+    }
+}
+
+// Cycle Model Action
+struct CycleModelAction;
+
+impl ShortcutAction for CycleModelAction {
+    fn start(&self, app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        let tm = Arc::clone(&app.state::<Arc<TranscriptionManager>>());
+        // Loading a model can take a while, keep it off the shortcut thread
+        std::thread::spawn(move || {
+            if let Err(e) = tm.cycle_favorite_model() {
+                debug!("Model not switched: {}", e);
+            }
+        });
+    }
+
+    fn stop(&self, _app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {}
+
+    fn is_momentary(&self) -> bool {
+        true
+    }
+}
+
+// Cancel Transcription Action
+struct CancelTranscriptionAction;
+
+impl ShortcutAction for CancelTranscriptionAction {
+    fn start(&self, app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        let queue = app.state::<Arc<TranscriptionQueue>>();
+        if !queue.cancel_running() {
+            debug!("Cancel pressed while not transcribing");
+        }
+    }
+
+    fn stop(&self, _app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {}
+
+    fn is_momentary(&self) -> bool {
+        true
+    }
+}
+
+// Correct Action
+struct CorrectAction;
+
+impl ShortcutAction for CorrectAction {
+    fn start(&self, app: &AppHandle, binding_id: &str, _shortcut_str: &str) {
+        let tm = app.state::<Arc<TranscriptionManager>>();
+        tm.initiate_model_load();
+
+        let rm = app.state::<Arc<AudioRecordingManager>>();
+        if rm.try_start_recording(binding_id) {
+            change_tray_icon(app, TrayIconState::Recording);
+            show_recording_overlay(app);
+            play_feedback_sound(app, SoundType::Start);
+        } else {
+            debug!("Failed to start recording a correction");
+        }
+    }
+
+    fn stop(&self, app: &AppHandle, binding_id: &str, _shortcut_str: &str) {
+        let rm = Arc::clone(&app.state::<Arc<AudioRecordingManager>>());
+        let Some(recording) = rm.stop_recording(binding_id) else {
+            return;
+        };
+        play_feedback_sound(app, SoundType::Stop);
+        change_tray_icon(app, TrayIconState::Transcribing);
+        show_transcribing_overlay(app);
+
+        let ah = app.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = apply_spoken_correction(&ah, recording.audio.into_samples()).await {
+                debug!("Correction not applied: {}", e);
+                events::emit(&ah, AppEvent::CorrectionFailed(e));
+            }
+            utils::hide_recording_overlay(&ah);
+            change_tray_icon(&ah, TrayIconState::Idle);
+        });
+    }
+}
+
+/// Transcribes a spoken correction and retypes the last paste with it.
+async fn apply_spoken_correction(app: &AppHandle, samples: Vec<f32>) -> Result<(), String> {
+    let tm = Arc::clone(&app.state::<Arc<TranscriptionManager>>());
+    // The command isn't dictation, keep it out of the context
+    let command = tm
+        .transcribe_background(samples)
+        .await
+        .map_err(|e| e.to_string())?
+        .text;
+
+    let rule =
+        parse_correction(&command).ok_or_else(|| format!("\"{}\" is not a correction", command))?;
+    let pasted = utils::last_paste().ok_or_else(|| "Nothing was pasted yet".to_string())?;
+    let corrected = apply_replacement_rules(&pasted, std::slice::from_ref(&rule));
+    if corrected == pasted {
+        return Err(format!("\"{}\" is not in the last paste", rule.from));
+    }
+
+    let ah = app.clone();
+    app.run_on_main_thread(move || {
+        if let Err(e) = utils::retype_last_paste(corrected, ah) {
+            eprintln!("Failed to retype corrected text: {}", e);
+        }
+    })
+    .map_err(|e| format!("Failed to run retype on main thread: {:?}", e))?;
+
+    let mut settings = get_settings(app);
+    if settings.correction_adds_rule {
+        settings
+            .replacement_rules
+            .retain(|r| !r.from.eq_ignore_ascii_case(&rule.from));
+        settings.replacement_rules.push(rule);
+        write_settings(app, settings);
+    }
+    Ok(())
+}
+
 // Test Action
 struct TestAction;
 
@@ -202,6 +826,34 @@ pub static ACTION_MAP: Lazy<HashMap<String, Arc<dyn ShortcutAction>>> = Lazy::ne
         "transcribe".to_string(),
         Arc::new(TranscribeAction) as Arc<dyn ShortcutAction>,
     );
+    map.insert(
+        "paste_here".to_string(),
+        Arc::new(PasteHereAction) as Arc<dyn ShortcutAction>,
+    );
+    map.insert(
+        "ocr_clipboard".to_string(),
+        Arc::new(OcrClipboardAction) as Arc<dyn ShortcutAction>,
+    );
+    map.insert(
+        "read_back".to_string(),
+        Arc::new(ReadBackAction) as Arc<dyn ShortcutAction>,
+    );
+    map.insert(
+        "marker".to_string(),
+        Arc::new(MarkerAction) as Arc<dyn ShortcutAction>,
+    );
+    map.insert(
+        "cycle_model".to_string(),
+        Arc::new(CycleModelAction) as Arc<dyn ShortcutAction>,
+    );
+    map.insert(
+        "correct".to_string(),
+        Arc::new(CorrectAction) as Arc<dyn ShortcutAction>,
+    );
+    map.insert(
+        "cancel_transcription".to_string(),
+        Arc::new(CancelTranscriptionAction) as Arc<dyn ShortcutAction>,
+    );
     map.insert(
         "test".to_string(),
         Arc::new(TestAction) as Arc<dyn ShortcutAction>,
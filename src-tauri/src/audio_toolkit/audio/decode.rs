@@ -0,0 +1,99 @@
+use super::FrameResampler;
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use anyhow::Result;
+use std::fs::File;
+use std::io::Cursor;
+use std::path::Path;
+use std::time::Duration;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::DecoderOptions;
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// Extensions [`decode_audio_file`] can read.
+pub const DECODABLE_EXTENSIONS: &[&str] = &["wav", "mp3", "m4a", "mp4", "aac", "flac", "ogg"];
+
+/// Decode any supported audio file into 16 kHz mono samples, e.g. voice
+/// memos recorded on a phone
+pub fn decode_audio_file<P: AsRef<Path>>(file_path: P) -> Result<Vec<f32>> {
+    let path = file_path.as_ref();
+    let source = MediaSourceStream::new(Box::new(File::open(path)?), Default::default());
+    let extension = path.extension().and_then(|e| e.to_str());
+    decode(source, extension, &path.display().to_string())
+}
+
+/// Like [`decode_audio_file`], for audio that arrived in memory, e.g. as an
+/// upload or attachment. The extension helps detecting the format.
+pub fn decode_audio_bytes(bytes: Vec<u8>, extension: Option<&str>) -> Result<Vec<f32>> {
+    let source = MediaSourceStream::new(Box::new(Cursor::new(bytes)), Default::default());
+    decode(source, extension, "audio data")
+}
+
+fn decode(source: MediaSourceStream, extension: Option<&str>, name: &str) -> Result<Vec<f32>> {
+    let mut hint = Hint::new();
+    if let Some(extension) = extension {
+        hint.with_extension(extension);
+    }
+
+    let probed = symphonia::default::get_probe().format(
+        &hint,
+        source,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    )?;
+    let mut format = probed.format;
+    let track = format
+        .default_track()
+        .ok_or_else(|| anyhow::anyhow!("No audio track in {}", name))?;
+    let track_id = track.id;
+    let sample_rate = track
+        .codec_params
+        .sample_rate
+        .ok_or_else(|| anyhow::anyhow!("Unknown sample rate in {}", name))?;
+    let mut decoder =
+        symphonia::default::get_codecs().make(&track.codec_params, &DecoderOptions::default())?;
+
+    let mut mono = Vec::new();
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A corrupt packet only loses a few milliseconds
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(e) => return Err(e.into()),
+        };
+
+        let spec = *decoded.spec();
+        let channels = spec.channels.count().max(1);
+        let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buffer.copy_interleaved_ref(decoded);
+        mono.extend(
+            buffer
+                .samples()
+                .chunks(channels)
+                .map(|frame| frame.iter().sum::<f32>() / channels as f32),
+        );
+    }
+
+    let mut samples = Vec::with_capacity(mono.len());
+    let mut resampler = FrameResampler::new(
+        sample_rate as usize,
+        WHISPER_SAMPLE_RATE as usize,
+        Duration::from_millis(30),
+    );
+    resampler.push(&mono, |frame| samples.extend_from_slice(frame));
+    resampler.finish(|frame| samples.extend_from_slice(frame));
+    Ok(samples)
+}
@@ -1,12 +1,18 @@
 // Re-export all audio components
+mod decode;
 mod device;
 mod recorder;
 mod resampler;
+mod spill;
 mod utils;
 mod visualizer;
 
+pub use decode::{decode_audio_bytes, decode_audio_file, DECODABLE_EXTENSIONS};
 pub use device::{list_input_devices, list_output_devices, CpalDeviceInfo};
 pub use recorder::AudioRecorder;
 pub use resampler::FrameResampler;
-pub use utils::save_wav_file;
+pub use spill::Recording;
+pub use utils::{
+    read_wav_file, save_wav_file, save_wav_file_at_rate, save_wav_recording, split_at_pauses,
+};
 pub use visualizer::AudioVisualiser;
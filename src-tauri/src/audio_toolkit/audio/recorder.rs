@@ -10,7 +10,10 @@ use cpal::{
 };
 
 use crate::audio_toolkit::{
-    audio::{AudioVisualiser, FrameResampler},
+    audio::{
+        spill::{Recording, SpillBuffer},
+        AudioVisualiser, FrameResampler,
+    },
     constants,
     vad::{self, VadFrame},
     VoiceActivityDetector,
@@ -18,7 +21,10 @@ use crate::audio_toolkit::{
 
 enum Cmd {
     Start,
-    Stop(mpsc::Sender<Vec<f32>>),
+    Stop(mpsc::Sender<Recording>),
+    TakeAtPause(usize, mpsc::Sender<Option<Vec<f32>>>),
+    Tail(usize, mpsc::Sender<Option<(usize, Vec<f32>)>>),
+    TakeOriginal(mpsc::Sender<Option<(u32, Vec<f32>)>>),
     Shutdown,
 }
 
@@ -28,6 +34,11 @@ pub struct AudioRecorder {
     worker_handle: Option<std::thread::JoinHandle<()>>,
     vad: Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
     level_cb: Option<Arc<dyn Fn(Vec<f32>) + Send + Sync + 'static>>,
+    gain: f32,
+    buffer_size: Option<u32>,
+    memory_limit: Option<usize>,
+    sample_rate: Option<u32>,
+    keep_original: bool,
 }
 
 impl AudioRecorder {
@@ -38,6 +49,11 @@ impl AudioRecorder {
             worker_handle: None,
             vad: None,
             level_cb: None,
+            gain: 1.0,
+            buffer_size: None,
+            memory_limit: None,
+            sample_rate: Some(constants::WHISPER_SAMPLE_RATE),
+            keep_original: false,
         })
     }
 
@@ -46,6 +62,40 @@ impl AudioRecorder {
         self
     }
 
+    /// Amplifies the input before voice detection, for quiet microphones.
+    pub fn with_gain(mut self, gain: f32) -> Self {
+        self.gain = gain;
+        self
+    }
+
+    /// Requests a fixed capture buffer of this many frames instead of the
+    /// host default. Larger buffers trade latency for fewer dropouts.
+    pub fn with_buffer_size(mut self, frames: Option<u32>) -> Self {
+        self.buffer_size = frames;
+        self
+    }
+
+    /// Keeps at most this many recorded samples in memory and moves older
+    /// ones to a temporary file, for recordings that run for hours.
+    pub fn with_memory_limit(mut self, samples: Option<usize>) -> Self {
+        self.memory_limit = samples;
+        self
+    }
+
+    /// Captures at this rate when the device supports it, or at the
+    /// device's default rate with `None`. Models still get 16 kHz audio.
+    pub fn with_sample_rate(mut self, sample_rate: Option<u32>) -> Self {
+        self.sample_rate = sample_rate;
+        self
+    }
+
+    /// Also keeps the recording at the capture rate and without voice
+    /// detection, for [`AudioRecorder::take_original`].
+    pub fn with_original_audio(mut self, keep: bool) -> Self {
+        self.keep_original = keep;
+        self
+    }
+
     pub fn with_level_callback<F>(mut self, cb: F) -> Self
     where
         F: Fn(Vec<f32>) + Send + Sync + 'static,
@@ -74,9 +124,14 @@ impl AudioRecorder {
         let vad = self.vad.clone();
         // Move the optional level callback into the worker thread
         let level_cb = self.level_cb.clone();
+        let gain = self.gain;
+        let buffer_size = self.buffer_size;
+        let memory_limit = self.memory_limit;
+        let preferred_rate = self.sample_rate;
+        let keep_original = self.keep_original;
 
         let worker = std::thread::spawn(move || {
-            let config = AudioRecorder::get_preferred_config(&thread_device)
+            let config = AudioRecorder::get_preferred_config(&thread_device, preferred_rate)
                 .expect("failed to fetch preferred config");
 
             let sample_rate = config.sample_rate().0;
@@ -91,33 +146,62 @@ impl AudioRecorder {
             );
 
             let stream = match config.sample_format() {
-                cpal::SampleFormat::U8 => {
-                    AudioRecorder::build_stream::<u8>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
-                }
-                cpal::SampleFormat::I8 => {
-                    AudioRecorder::build_stream::<i8>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
-                }
-                cpal::SampleFormat::I16 => {
-                    AudioRecorder::build_stream::<i16>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
-                }
-                cpal::SampleFormat::I32 => {
-                    AudioRecorder::build_stream::<i32>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
-                }
-                cpal::SampleFormat::F32 => {
-                    AudioRecorder::build_stream::<f32>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
-                }
+                cpal::SampleFormat::U8 => AudioRecorder::build_stream::<u8>(
+                    &thread_device,
+                    &config,
+                    buffer_size,
+                    sample_tx,
+                    channels,
+                )
+                .unwrap(),
+                cpal::SampleFormat::I8 => AudioRecorder::build_stream::<i8>(
+                    &thread_device,
+                    &config,
+                    buffer_size,
+                    sample_tx,
+                    channels,
+                )
+                .unwrap(),
+                cpal::SampleFormat::I16 => AudioRecorder::build_stream::<i16>(
+                    &thread_device,
+                    &config,
+                    buffer_size,
+                    sample_tx,
+                    channels,
+                )
+                .unwrap(),
+                cpal::SampleFormat::I32 => AudioRecorder::build_stream::<i32>(
+                    &thread_device,
+                    &config,
+                    buffer_size,
+                    sample_tx,
+                    channels,
+                )
+                .unwrap(),
+                cpal::SampleFormat::F32 => AudioRecorder::build_stream::<f32>(
+                    &thread_device,
+                    &config,
+                    buffer_size,
+                    sample_tx,
+                    channels,
+                )
+                .unwrap(),
                 _ => panic!("unsupported sample format"),
             };
 
             stream.play().expect("failed to start stream");
 
             // keep the stream alive while we process samples
-            run_consumer(sample_rate, vad, sample_rx, cmd_rx, level_cb);
+            run_consumer(
+                sample_rate,
+                vad,
+                sample_rx,
+                cmd_rx,
+                level_cb,
+                gain,
+                memory_limit,
+                keep_original,
+            );
             // stream is dropped here, after run_consumer returns
         });
 
@@ -135,7 +219,7 @@ impl AudioRecorder {
         Ok(())
     }
 
-    pub fn stop(&self) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+    pub fn stop(&self) -> Result<Recording, Box<dyn std::error::Error>> {
         let (resp_tx, resp_rx) = mpsc::channel();
         if let Some(tx) = &self.cmd_tx {
             tx.send(Cmd::Stop(resp_tx))?;
@@ -143,6 +227,42 @@ impl AudioRecorder {
         Ok(resp_rx.recv()?) // wait for the samples
     }
 
+    /// Takes the samples recorded so far without stopping, but only once at
+    /// least `min_samples` are buffered and the speaker is pausing.
+    pub fn take_at_pause(
+        &self,
+        min_samples: usize,
+    ) -> Result<Option<Vec<f32>>, Box<dyn std::error::Error>> {
+        let (resp_tx, resp_rx) = mpsc::channel();
+        if let Some(tx) = &self.cmd_tx {
+            tx.send(Cmd::TakeAtPause(min_samples, resp_tx))?;
+        }
+        Ok(resp_rx.recv()?)
+    }
+
+    /// The number of samples recorded so far and a copy of the last `max`
+    /// of them, `None` when not recording.
+    pub fn tail(
+        &self,
+        max: usize,
+    ) -> Result<Option<(usize, Vec<f32>)>, Box<dyn std::error::Error>> {
+        let (resp_tx, resp_rx) = mpsc::channel();
+        if let Some(tx) = &self.cmd_tx {
+            tx.send(Cmd::Tail(max, resp_tx))?;
+        }
+        Ok(resp_rx.recv()?)
+    }
+
+    /// The last stopped recording at the capture rate, with that rate. Only
+    /// kept with [`AudioRecorder::with_original_audio`].
+    pub fn take_original(&self) -> Result<Option<(u32, Vec<f32>)>, Box<dyn std::error::Error>> {
+        let (resp_tx, resp_rx) = mpsc::channel();
+        if let Some(tx) = &self.cmd_tx {
+            tx.send(Cmd::TakeOriginal(resp_tx))?;
+        }
+        Ok(resp_rx.recv()?)
+    }
+
     pub fn close(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         if let Some(tx) = self.cmd_tx.take() {
             let _ = tx.send(Cmd::Shutdown);
@@ -157,6 +277,7 @@ impl AudioRecorder {
     fn build_stream<T>(
         device: &cpal::Device,
         config: &cpal::SupportedStreamConfig,
+        buffer_size: Option<u32>,
         sample_tx: mpsc::Sender<Vec<f32>>,
         channels: usize,
     ) -> Result<cpal::Stream, cpal::BuildStreamError>
@@ -192,8 +313,19 @@ impl AudioRecorder {
             }
         };
 
+        let mut stream_config: cpal::StreamConfig = config.clone().into();
+        if let Some(frames) = buffer_size {
+            // Stay within what the device supports, hosts reject anything else
+            let frames = match config.buffer_size() {
+                cpal::SupportedBufferSize::Range { min, max } => frames.clamp(*min, *max),
+                cpal::SupportedBufferSize::Unknown => frames,
+            };
+            log::debug!("Using fixed capture buffer of {} frames", frames);
+            stream_config.buffer_size = cpal::BufferSize::Fixed(frames);
+        }
+
         device.build_input_stream(
-            &config.clone().into(),
+            &stream_config,
             stream_cb,
             |err| eprintln!("Stream error: {}", err),
             None,
@@ -202,22 +334,24 @@ impl AudioRecorder {
 
     fn get_preferred_config(
         device: &cpal::Device,
+        sample_rate: Option<u32>,
     ) -> Result<cpal::SupportedStreamConfig, Box<dyn std::error::Error>> {
+        let Some(sample_rate) = sample_rate else {
+            return Ok(device.default_input_config()?);
+        };
         let supported_configs = device.supported_input_configs()?;
 
-        // Try to find a config that supports 16kHz
+        // Try to find a config that supports the requested rate
         for config_range in supported_configs {
-            if config_range.min_sample_rate().0 <= constants::WHISPER_SAMPLE_RATE
-                && config_range.max_sample_rate().0 >= constants::WHISPER_SAMPLE_RATE
+            if config_range.min_sample_rate().0 <= sample_rate
+                && config_range.max_sample_rate().0 >= sample_rate
             {
-                // Found a config that supports 16kHz, use it
-                return Ok(
-                    config_range.with_sample_rate(cpal::SampleRate(constants::WHISPER_SAMPLE_RATE))
-                );
+                // Found a config that supports it, use it
+                return Ok(config_range.with_sample_rate(cpal::SampleRate(sample_rate)));
             }
         }
 
-        // If no config supports 16kHz, fall back to default
+        // If no config supports it, fall back to default
         Ok(device.default_input_config()?)
     }
 }
@@ -228,6 +362,9 @@ fn run_consumer(
     sample_rx: mpsc::Receiver<Vec<f32>>,
     cmd_rx: mpsc::Receiver<Cmd>,
     level_cb: Option<Arc<dyn Fn(Vec<f32>) + Send + Sync + 'static>>,
+    gain: f32,
+    memory_limit: Option<usize>,
+    keep_original: bool,
 ) {
     let mut frame_resampler = FrameResampler::new(
         in_sample_rate as usize,
@@ -235,8 +372,12 @@ fn run_consumer(
         Duration::from_millis(30),
     );
 
-    let mut processed_samples = Vec::<f32>::new();
+    let mut processed_samples = SpillBuffer::new(memory_limit);
+    // The input before resampling and voice detection, when it's kept
+    let mut original_samples = SpillBuffer::new(memory_limit);
+    let mut finished_original: Option<Recording> = None;
     let mut recording = false;
+    let mut in_pause = false;
 
     // ---------- spectrum visualisation setup ---------------------------- //
     const BUCKETS: usize = 16;
@@ -249,32 +390,42 @@ fn run_consumer(
         4000.0, // vocal_max_hz
     );
 
+    /// Returns whether the frame was kept as speech.
     fn handle_frame(
         samples: &[f32],
         recording: bool,
         vad: &Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
-        out_buf: &mut Vec<f32>,
-    ) {
+        out_buf: &mut SpillBuffer,
+    ) -> bool {
         if !recording {
-            return;
+            return false;
         }
 
         if let Some(vad_arc) = vad {
             let mut det = vad_arc.lock().unwrap();
             match det.push_frame(samples).unwrap_or(VadFrame::Speech(samples)) {
-                VadFrame::Speech(buf) => out_buf.extend_from_slice(buf),
-                VadFrame::Noise => {}
+                VadFrame::Speech(buf) => {
+                    out_buf.extend_from_slice(buf);
+                    true
+                }
+                VadFrame::Noise => false,
             }
         } else {
             out_buf.extend_from_slice(samples);
+            true
         }
     }
 
     loop {
-        let raw = match sample_rx.recv() {
+        let mut raw = match sample_rx.recv() {
             Ok(s) => s,
             Err(_) => break, // stream closed
         };
+        if gain != 1.0 {
+            for sample in raw.iter_mut() {
+                *sample = (*sample * gain).clamp(-1.0, 1.0);
+            }
+        }
 
         // ---------- spectrum processing ---------------------------------- //
         if let Some(buckets) = visualizer.feed(&raw) {
@@ -283,9 +434,13 @@ fn run_consumer(
             }
         }
 
+        if recording && keep_original {
+            original_samples.extend_from_slice(&raw);
+        }
+
         // ---------- existing pipeline ------------------------------------ //
         frame_resampler.push(&raw, &mut |frame: &[f32]| {
-            handle_frame(frame, recording, &vad, &mut processed_samples)
+            in_pause = !handle_frame(frame, recording, &vad, &mut processed_samples);
         });
 
         // non-blocking check for a command
@@ -293,7 +448,10 @@ fn run_consumer(
             match cmd {
                 Cmd::Start => {
                     processed_samples.clear();
+                    original_samples.clear();
+                    finished_original = None;
                     recording = true;
+                    in_pause = false;
                     visualizer.reset(); // Reset visualization buffer
                     if let Some(v) = &vad {
                         v.lock().unwrap().reset();
@@ -304,10 +462,31 @@ fn run_consumer(
 
                     frame_resampler.finish(&mut |frame: &[f32]| {
                         // we still want to process the last few frames
-                        handle_frame(frame, true, &vad, &mut processed_samples)
+                        handle_frame(frame, true, &vad, &mut processed_samples);
                     });
 
-                    let _ = reply_tx.send(std::mem::take(&mut processed_samples));
+                    finished_original = keep_original.then(|| original_samples.take());
+                    let _ = reply_tx.send(processed_samples.take());
+                }
+                Cmd::TakeAtPause(min_samples, reply_tx) => {
+                    let chunk = (recording && in_pause && processed_samples.len() >= min_samples)
+                        .then(|| processed_samples.take().into_samples());
+                    if chunk.is_some() {
+                        // The rest of the recording no longer starts where this does
+                        original_samples.clear();
+                    }
+                    let _ = reply_tx.send(chunk);
+                }
+                Cmd::Tail(max, reply_tx) => {
+                    let tail =
+                        recording.then(|| (processed_samples.len(), processed_samples.tail(max)));
+                    let _ = reply_tx.send(tail);
+                }
+                Cmd::TakeOriginal(reply_tx) => {
+                    let original = finished_original
+                        .take()
+                        .map(|s| (in_sample_rate, s.into_samples()));
+                    let _ = reply_tx.send(original);
                 }
                 Cmd::Shutdown => return,
             }
@@ -0,0 +1,317 @@
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const SAMPLE_BYTES: usize = std::mem::size_of::<f32>();
+
+/// Numbers spill files so recorders in one process don't share one.
+static SPILL_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Recorded samples, kept in memory up to a limit. Beyond it, the samples
+/// in memory are moved to a temporary file, so a recording of hours holds
+/// only the most recent minutes in RAM. Reading the samples back is
+/// transparent.
+pub struct SpillBuffer {
+    memory: Vec<f32>,
+    /// Samples kept in memory before spilling, `None` for no limit.
+    limit: Option<usize>,
+    file: Option<(File, PathBuf)>,
+    spilled: usize,
+}
+
+impl SpillBuffer {
+    pub fn new(limit: Option<usize>) -> Self {
+        Self {
+            memory: Vec::new(),
+            limit,
+            file: None,
+            spilled: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.spilled + self.memory.len()
+    }
+
+    pub fn extend_from_slice(&mut self, samples: &[f32]) {
+        self.memory.extend_from_slice(samples);
+        if self.limit.is_some_and(|limit| self.memory.len() > limit) {
+            if let Err(e) = self.spill() {
+                // Better to use the memory than to lose the recording
+                log::error!(
+                    "Failed to spill recording to disk, keeping it in memory: {}",
+                    e
+                );
+                self.limit = None;
+            }
+        }
+    }
+
+    /// Every sample, emptying the buffer. Spilled samples stay on disk,
+    /// owned by the returned recording.
+    pub fn take(&mut self) -> Recording {
+        Recording {
+            spill: self.file.take().map(|(_, path)| path),
+            spilled: std::mem::take(&mut self.spilled),
+            memory: std::mem::take(&mut self.memory),
+        }
+    }
+
+    /// The last `max` samples, leaving the buffer as it is.
+    pub fn tail(&mut self, max: usize) -> Vec<f32> {
+        let from_memory = max.min(self.memory.len());
+        let from_file = (max - from_memory).min(self.spilled);
+        let mut samples = if from_file > 0 {
+            self.read_spilled(self.spilled - from_file)
+                .unwrap_or_else(|e| {
+                    log::error!("Failed to read spilled recording: {}", e);
+                    Vec::new()
+                })
+        } else {
+            Vec::new()
+        };
+        samples.extend_from_slice(&self.memory[self.memory.len() - from_memory..]);
+        samples
+    }
+
+    pub fn clear(&mut self) {
+        self.memory.clear();
+        self.spilled = 0;
+        if let Some((file, path)) = self.file.take() {
+            drop(file);
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    fn spill(&mut self) -> std::io::Result<()> {
+        if self.file.is_none() {
+            let path = std::env::temp_dir().join(format!(
+                "handy-recording-{}-{}.f32",
+                std::process::id(),
+                SPILL_COUNTER.fetch_add(1, Ordering::Relaxed)
+            ));
+            let file = File::options()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(&path)?;
+            self.file = Some((file, path));
+        }
+        let (file, _) = self.file.as_mut().unwrap();
+
+        file.seek(SeekFrom::End(0))?;
+        let mut writer = BufWriter::new(&*file);
+        for sample in &self.memory {
+            writer.write_all(&sample.to_le_bytes())?;
+        }
+        writer.flush()?;
+
+        self.spilled += self.memory.len();
+        self.memory.clear();
+        Ok(())
+    }
+
+    /// Spilled samples from `start` on.
+    fn read_spilled(&mut self, start: usize) -> std::io::Result<Vec<f32>> {
+        let Some((file, _)) = self.file.as_mut() else {
+            return Ok(Vec::new());
+        };
+        file.seek(SeekFrom::Start((start * SAMPLE_BYTES) as u64))?;
+
+        let mut samples = Vec::with_capacity(self.spilled - start + self.memory.len());
+        read_samples(
+            &mut BufReader::new(&*file),
+            self.spilled - start,
+            &mut samples,
+        )?;
+        Ok(samples)
+    }
+}
+
+impl Drop for SpillBuffer {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// A finished recording taken from a [`SpillBuffer`]. What was spilled
+/// stays in the file and is read a range at a time, so a long recording
+/// never has to fit in memory at once. The file goes with the recording.
+pub struct Recording {
+    spill: Option<PathBuf>,
+    spilled: usize,
+    memory: Vec<f32>,
+}
+
+impl Recording {
+    pub fn len(&self) -> usize {
+        self.spilled + self.memory.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Up to `len` samples from `start` on.
+    pub fn read(&self, start: usize, len: usize) -> std::io::Result<Vec<f32>> {
+        let end = start.saturating_add(len).min(self.len());
+        let start = start.min(end);
+        let mut samples = Vec::with_capacity(end - start);
+        if let Some(path) = self.spill.as_ref().filter(|_| start < self.spilled) {
+            let mut file = File::open(path)?;
+            file.seek(SeekFrom::Start((start * SAMPLE_BYTES) as u64))?;
+            let count = end.min(self.spilled) - start;
+            read_samples(&mut BufReader::new(file), count, &mut samples)?;
+        }
+        let from = start.max(self.spilled) - self.spilled;
+        let to = end.max(self.spilled) - self.spilled;
+        samples.extend_from_slice(&self.memory[from..to]);
+        Ok(samples)
+    }
+
+    /// Every sample, read into memory.
+    pub fn samples(&self) -> std::io::Result<Vec<f32>> {
+        self.read(0, self.len())
+    }
+
+    /// Every sample, read into memory. Only the samples that were never
+    /// spilled are left if the file can't be read.
+    pub fn into_samples(mut self) -> Vec<f32> {
+        if self.spilled == 0 {
+            return std::mem::take(&mut self.memory);
+        }
+        self.samples().unwrap_or_else(|e| {
+            log::error!("Failed to read spilled recording: {}", e);
+            std::mem::take(&mut self.memory)
+        })
+    }
+
+    /// Appends silence up to `len` samples.
+    pub fn pad_to(&mut self, len: usize) {
+        if len > self.len() {
+            self.memory.resize(len - self.spilled, 0.0);
+        }
+    }
+}
+
+impl From<Vec<f32>> for Recording {
+    fn from(samples: Vec<f32>) -> Self {
+        Self {
+            spill: None,
+            spilled: 0,
+            memory: samples,
+        }
+    }
+}
+
+impl Drop for Recording {
+    fn drop(&mut self) {
+        if let Some(path) = self.spill.take() {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+fn read_samples(
+    reader: &mut impl Read,
+    count: usize,
+    samples: &mut Vec<f32>,
+) -> std::io::Result<()> {
+    let mut bytes = [0u8; SAMPLE_BYTES];
+    for _ in 0..count {
+        reader.read_exact(&mut bytes)?;
+        samples.push(f32::from_le_bytes(bytes));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ramp(range: std::ops::Range<usize>) -> Vec<f32> {
+        range.map(|i| i as f32).collect()
+    }
+
+    fn spill_path(buffer: &SpillBuffer) -> PathBuf {
+        buffer.file.as_ref().expect("buffer has spilled").1.clone()
+    }
+
+    #[test]
+    fn spills_only_past_the_limit() {
+        let mut buffer = SpillBuffer::new(Some(4));
+        buffer.extend_from_slice(&ramp(0..4));
+        assert!(buffer.file.is_none());
+        assert_eq!(buffer.memory.len(), 4);
+
+        buffer.extend_from_slice(&ramp(4..6));
+        assert!(spill_path(&buffer).exists());
+        assert_eq!(buffer.spilled, 6);
+        assert!(buffer.memory.is_empty());
+        assert_eq!(buffer.len(), 6);
+    }
+
+    #[test]
+    fn tail_reads_across_the_file_and_memory() {
+        let mut buffer = SpillBuffer::new(Some(4));
+        buffer.extend_from_slice(&ramp(0..6));
+        buffer.extend_from_slice(&ramp(6..8));
+
+        assert_eq!(buffer.tail(2), ramp(6..8));
+        assert_eq!(buffer.tail(4), ramp(4..8));
+        assert_eq!(buffer.tail(100), ramp(0..8));
+        // Reading leaves the buffer as it was
+        assert_eq!(buffer.len(), 8);
+    }
+
+    #[test]
+    fn take_hands_the_spill_file_to_the_recording() {
+        let mut buffer = SpillBuffer::new(Some(4));
+        buffer.extend_from_slice(&ramp(0..6));
+        buffer.extend_from_slice(&ramp(6..8));
+        let path = spill_path(&buffer);
+
+        let recording = buffer.take();
+        assert_eq!(buffer.len(), 0);
+        assert!(buffer.file.is_none());
+        assert_eq!(recording.len(), 8);
+        assert_eq!(recording.read(4, 3).unwrap(), ramp(4..7));
+        assert_eq!(recording.read(6, 100).unwrap(), ramp(6..8));
+        assert_eq!(recording.read(20, 5).unwrap(), Vec::<f32>::new());
+        assert_eq!(recording.samples().unwrap(), ramp(0..8));
+
+        // The buffer is ready for the next recording
+        buffer.extend_from_slice(&ramp(0..2));
+        assert_eq!(buffer.take().into_samples(), ramp(0..2));
+
+        assert!(path.exists());
+        drop(recording);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn clear_removes_the_spill_file() {
+        let mut buffer = SpillBuffer::new(Some(4));
+        buffer.extend_from_slice(&ramp(0..6));
+        let path = spill_path(&buffer);
+
+        buffer.clear();
+        assert!(!path.exists());
+        assert_eq!(buffer.len(), 0);
+        assert!(buffer.tail(4).is_empty());
+    }
+
+    #[test]
+    fn padding_a_spilled_recording_appends_silence() {
+        let mut buffer = SpillBuffer::new(Some(4));
+        buffer.extend_from_slice(&ramp(0..6));
+        let mut recording = buffer.take();
+
+        recording.pad_to(8);
+        let mut expected = ramp(0..6);
+        expected.extend([0.0, 0.0]);
+        assert_eq!(recording.into_samples(), expected);
+    }
+}
@@ -1,13 +1,25 @@
 use anyhow::Result;
-use hound::{WavSpec, WavWriter};
+use hound::{WavReader, WavSpec, WavWriter};
 use log::debug;
 use std::path::Path;
+use std::time::Duration;
 
-/// Save audio samples as a WAV file
+use crate::audio_toolkit::audio::{FrameResampler, Recording};
+
+/// Save 16 kHz audio samples as a WAV file
 pub async fn save_wav_file<P: AsRef<Path>>(file_path: P, samples: &[f32]) -> Result<()> {
+    save_wav_file_at_rate(file_path, samples, 16000).await
+}
+
+/// Save audio samples recorded at `sample_rate` as a WAV file
+pub async fn save_wav_file_at_rate<P: AsRef<Path>>(
+    file_path: P,
+    samples: &[f32],
+    sample_rate: u32,
+) -> Result<()> {
     let spec = WavSpec {
         channels: 1,
-        sample_rate: 16000,
+        sample_rate,
         bits_per_sample: 16,
         sample_format: hound::SampleFormat::Int,
     };
@@ -24,3 +36,83 @@ pub async fn save_wav_file<P: AsRef<Path>>(file_path: P, samples: &[f32]) -> Res
     debug!("Saved WAV file: {:?}", file_path.as_ref());
     Ok(())
 }
+
+/// Save a 16 kHz recording as a WAV file, reading spilled samples back a
+/// block at a time
+pub async fn save_wav_recording<P: AsRef<Path>>(file_path: P, recording: &Recording) -> Result<()> {
+    const BLOCK_SAMPLES: usize = 16000 * 60;
+
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate: 16000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    let mut writer = WavWriter::create(file_path.as_ref(), spec)?;
+    for start in (0..recording.len()).step_by(BLOCK_SAMPLES) {
+        for sample in recording.read(start, BLOCK_SAMPLES)? {
+            writer.write_sample((sample * i16::MAX as f32) as i16)?;
+        }
+    }
+
+    writer.finalize()?;
+    debug!("Saved WAV file: {:?}", file_path.as_ref());
+    Ok(())
+}
+
+/// Read a WAV file written by [`save_wav_file`] or [`save_wav_file_at_rate`]
+/// back into samples, resampled to 16 kHz
+pub fn read_wav_file<P: AsRef<Path>>(file_path: P) -> Result<Vec<f32>> {
+    let mut reader = WavReader::open(file_path.as_ref())?;
+    let spec = reader.spec();
+    if spec.channels != 1 || spec.bits_per_sample != 16 {
+        return Err(anyhow::anyhow!(
+            "Unsupported WAV format: {} channels, {} Hz, {} bits",
+            spec.channels,
+            spec.sample_rate,
+            spec.bits_per_sample
+        ));
+    }
+
+    let samples = reader
+        .samples::<i16>()
+        .map(|s| Ok(s? as f32 / i16::MAX as f32))
+        .collect::<Result<Vec<f32>>>()?;
+    if spec.sample_rate == 16000 {
+        return Ok(samples);
+    }
+
+    let mut resampler =
+        FrameResampler::new(spec.sample_rate as usize, 16000, Duration::from_millis(30));
+    let mut resampled = Vec::with_capacity(samples.len() * 16000 / spec.sample_rate as usize);
+    resampler.push(&samples, |frame| resampled.extend_from_slice(frame));
+    resampler.finish(|frame| resampled.extend_from_slice(frame));
+    Ok(resampled)
+}
+
+/// Splits `samples` into chunks of at most `max_len` samples. Each chunk ends
+/// at the quietest 30 ms in its last `search_len` samples, which is usually a
+/// pause, so words aren't cut in half.
+pub fn split_at_pauses(samples: &[f32], max_len: usize, search_len: usize) -> Vec<&[f32]> {
+    const FRAME: usize = 480;
+    let energy = |frame: &[f32]| frame.iter().map(|s| s * s).sum::<f32>();
+
+    let mut chunks = Vec::new();
+    let mut rest = samples;
+    while rest.len() > max_len {
+        let search_start = max_len.saturating_sub(search_len);
+        let cut = (search_start..=max_len.saturating_sub(FRAME))
+            .step_by(FRAME)
+            .min_by(|&a, &b| energy(&rest[a..a + FRAME]).total_cmp(&energy(&rest[b..b + FRAME])))
+            .map_or(max_len, |start| start + FRAME / 2)
+            .max(1);
+        let (chunk, tail) = rest.split_at(cut);
+        chunks.push(chunk);
+        rest = tail;
+    }
+    if !rest.is_empty() {
+        chunks.push(rest);
+    }
+    chunks
+}
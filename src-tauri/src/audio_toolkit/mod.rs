@@ -5,8 +5,15 @@ pub mod utils;
 pub mod vad;
 
 pub use audio::{
-    list_input_devices, list_output_devices, save_wav_file, AudioRecorder, CpalDeviceInfo,
+    decode_audio_bytes, decode_audio_file, list_input_devices, list_output_devices, read_wav_file,
+    save_wav_file, save_wav_file_at_rate, save_wav_recording, split_at_pauses, AudioRecorder,
+    CpalDeviceInfo, Recording, DECODABLE_EXTENSIONS,
+};
+pub use text::{
+    apply_custom_words, apply_custom_words_with_corrections, apply_replacement_rules,
+    capitalize_sentences, is_hallucination, parse_correction, remove_filler_words,
+    segment_paragraphs, word_diff, word_error_rate, DiffSegment, ReplacementRule, TimedSegment,
+    WordCorrection,
 };
-pub use text::apply_custom_words;
 pub use utils::get_cpal_host;
-pub use vad::{SileroVad, VoiceActivityDetector};
+pub use vad::{trim_silence, SileroVad, VoiceActivityDetector};
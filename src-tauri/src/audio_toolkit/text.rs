@@ -1,6 +1,23 @@
 use natural::phonetics::soundex;
+use serde::{Deserialize, Serialize};
 use strsim::levenshtein;
 
+/// A literal phrase replacement applied to transcripts, e.g. "acme" -> "ACME Corp".
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReplacementRule {
+    pub from: String,
+    pub to: String,
+}
+
+/// A word that custom word correction replaced with a different word.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WordCorrection {
+    pub original: String,
+    pub replacement: String,
+    /// The combined match score, compared against the correction threshold.
+    pub score: f64,
+}
+
 /// Applies custom word corrections to transcribed text using fuzzy matching
 ///
 /// This function corrects words in the input text by finding the best matches
@@ -16,8 +33,19 @@ use strsim::levenshtein;
 /// # Returns
 /// The corrected text with custom words applied
 pub fn apply_custom_words(text: &str, custom_words: &[String], threshold: f64) -> String {
+    apply_custom_words_with_corrections(text, custom_words, threshold).0
+}
+
+/// Like [`apply_custom_words`], but also returns the corrections that changed
+/// a word. Matches that only differ in case are not reported.
+pub fn apply_custom_words_with_corrections(
+    text: &str,
+    custom_words: &[String],
+    threshold: f64,
+) -> (String, Vec<WordCorrection>) {
+    let mut corrections = Vec::new();
     if custom_words.is_empty() {
-        return text.to_string();
+        return (text.to_string(), corrections);
     }
 
     // Pre-compute lowercase versions to avoid repeated allocations
@@ -81,6 +109,13 @@ pub fn apply_custom_words(text: &str, custom_words: &[String], threshold: f64) -
         if let Some(replacement) = best_match {
             // Preserve the original case pattern as much as possible
             let corrected = preserve_case_pattern(word, replacement);
+            if cleaned_word != replacement.to_lowercase() {
+                corrections.push(WordCorrection {
+                    original: word.trim_matches(|c: char| !c.is_alphabetic()).to_string(),
+                    replacement: corrected.clone(),
+                    score: best_score,
+                });
+            }
 
             // Preserve punctuation from original word
             let (prefix, suffix) = extract_punctuation(word);
@@ -90,7 +125,418 @@ pub fn apply_custom_words(text: &str, custom_words: &[String], threshold: f64) -
         }
     }
 
-    corrected_words.join(" ")
+    (corrected_words.join(" "), corrections)
+}
+
+/// A run of words in a [`word_diff`].
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", content = "text", rename_all = "snake_case")]
+pub enum DiffSegment {
+    /// In both texts.
+    Same(String),
+    /// Only in the first text.
+    Removed(String),
+    /// Only in the second text.
+    Added(String),
+}
+
+/// Word-level diff of two transcripts, based on their longest common
+/// subsequence of words. Words are compared ignoring case.
+pub fn word_diff(a: &str, b: &str) -> Vec<DiffSegment> {
+    let a: Vec<&str> = a.split_whitespace().collect();
+    let b: Vec<&str> = b.split_whitespace().collect();
+    let same = |x: &str, y: &str| x.to_lowercase() == y.to_lowercase();
+
+    // lcs[i][j] is the LCS length of a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if same(a[i], b[j]) {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut segments: Vec<DiffSegment> = Vec::new();
+    let mut push = |segment: DiffSegment| {
+        let merged = match (segments.last_mut(), &segment) {
+            (Some(DiffSegment::Same(text)), DiffSegment::Same(word))
+            | (Some(DiffSegment::Removed(text)), DiffSegment::Removed(word))
+            | (Some(DiffSegment::Added(text)), DiffSegment::Added(word)) => {
+                text.push(' ');
+                text.push_str(word);
+                true
+            }
+            _ => false,
+        };
+        if !merged {
+            segments.push(segment);
+        }
+    };
+
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && same(a[i], b[j]) {
+            push(DiffSegment::Same(b[j].to_string()));
+            i += 1;
+            j += 1;
+        } else if j == b.len() || (i < a.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            push(DiffSegment::Removed(a[i].to_string()));
+            i += 1;
+        } else {
+            push(DiffSegment::Added(b[j].to_string()));
+            j += 1;
+        }
+    }
+    segments
+}
+
+/// Word error rate of `hypothesis` against `reference`: substituted, deleted
+/// and inserted words over the number of reference words. Case and
+/// punctuation are ignored.
+pub fn word_error_rate(reference: &str, hypothesis: &str) -> f32 {
+    let normalize = |text: &str| -> Vec<String> {
+        text.split_whitespace()
+            .map(|w| {
+                w.trim_matches(|c: char| !c.is_alphanumeric())
+                    .to_lowercase()
+            })
+            .filter(|w| !w.is_empty())
+            .collect()
+    };
+    let reference = normalize(reference);
+    let hypothesis = normalize(hypothesis);
+    if reference.is_empty() {
+        return if hypothesis.is_empty() { 0.0 } else { 1.0 };
+    }
+
+    // Word-level Levenshtein distance, one row at a time
+    let mut previous: Vec<usize> = (0..=hypothesis.len()).collect();
+    for (i, r) in reference.iter().enumerate() {
+        let mut current = vec![i + 1; hypothesis.len() + 1];
+        for (j, h) in hypothesis.iter().enumerate() {
+            let substitution = previous[j] + usize::from(r != h);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[hypothesis.len()] as f32 / reference.len() as f32
+}
+
+/// A stretch of transcript and where it is in the audio, in seconds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimedSegment {
+    pub start: f32,
+    pub end: f32,
+    pub text: String,
+}
+
+/// Phrases that tend to open a new topic when a segment starts with them.
+const PARAGRAPH_CUES: &[&str] = &[
+    "moving on",
+    "next",
+    "another thing",
+    "on another note",
+    "secondly",
+    "thirdly",
+    "finally",
+    "in conclusion",
+    "to summarize",
+    "anyway",
+    "that said",
+];
+/// Paragraphs shorter than this are never broken, so a pause to think
+/// doesn't leave a dangling half sentence.
+const MIN_PARAGRAPH_WORDS: usize = 20;
+
+/// Groups transcript segments into paragraphs. A new paragraph starts after
+/// a pause of at least `pause_secs`, or half of that when the next segment
+/// opens with a discourse cue such as "moving on".
+pub fn segment_paragraphs(segments: &[TimedSegment], pause_secs: f32) -> Vec<String> {
+    let mut paragraphs: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut previous_end: Option<f32> = None;
+
+    for segment in segments {
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        if let Some(end) = previous_end {
+            let pause = segment.start - end;
+            let lower = text.to_lowercase();
+            let has_cue = PARAGRAPH_CUES.iter().any(|cue| {
+                lower.starts_with(cue)
+                    && !lower[cue.len()..].starts_with(|c: char| c.is_alphanumeric())
+            });
+            let needed = if has_cue {
+                pause_secs / 2.0
+            } else {
+                pause_secs
+            };
+            if pause >= needed && current.split_whitespace().count() >= MIN_PARAGRAPH_WORDS {
+                paragraphs.push(std::mem::take(&mut current));
+            }
+        }
+
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(text);
+        previous_end = Some(segment.end);
+    }
+
+    if !current.is_empty() {
+        paragraphs.push(current);
+    }
+    paragraphs
+}
+
+/// Removes filler words and phrases such as "um" or "you know", matched
+/// case-insensitively on whole words. A comma after a filler goes with it,
+/// other punctuation is kept, and a sentence that started with a filler is
+/// capitalized again. Line breaks are preserved.
+pub fn remove_filler_words(text: &str, fillers: &[String]) -> String {
+    let fillers: Vec<Vec<String>> = fillers
+        .iter()
+        .map(|f| f.split_whitespace().map(|w| w.to_lowercase()).collect())
+        .filter(|f: &Vec<String>| !f.is_empty())
+        .collect();
+    if fillers.is_empty() {
+        return text.to_string();
+    }
+
+    text.split('\n')
+        .map(|line| remove_fillers_from_line(line, &fillers))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn remove_fillers_from_line(line: &str, fillers: &[Vec<String>]) -> String {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let bare: Vec<String> = words
+        .iter()
+        .map(|w| {
+            w.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .collect();
+
+    let mut kept: Vec<String> = Vec::new();
+    let mut capitalize_next = false;
+    let mut i = 0;
+    while i < words.len() {
+        let matched = fillers
+            .iter()
+            .filter(|f| bare[i..].starts_with(f))
+            .map(|f| f.len())
+            .max();
+        let Some(len) = matched else {
+            let mut word = words[i].to_string();
+            if std::mem::take(&mut capitalize_next) {
+                let mut chars = word.chars();
+                if let Some(first) = chars.next() {
+                    word = first.to_uppercase().chain(chars).collect();
+                }
+            }
+            kept.push(word);
+            i += 1;
+            continue;
+        };
+
+        let at_sentence_start = kept.last().map_or(true, |w| w.ends_with(['.', '!', '?']));
+        if at_sentence_start && words[i].starts_with(char::is_uppercase) {
+            capitalize_next = true;
+        }
+        // Sentence punctuation after the filler stays with the previous word
+        let (_, trailing) = extract_punctuation(words[i + len - 1]);
+        let trailing = trailing.trim_start_matches(',');
+        if !trailing.is_empty() {
+            match kept.last_mut() {
+                Some(previous) => {
+                    let base = previous.trim_end_matches(',').len();
+                    previous.truncate(base);
+                    previous.push_str(trailing);
+                }
+                None => capitalize_next = true,
+            }
+        }
+        i += len;
+    }
+
+    let leading_space = &line[..line.len() - line.trim_start().len()];
+    format!("{}{}", leading_space, kept.join(" "))
+}
+
+/// Capitalizes the first letter of the text and of every sentence after
+/// `.`, `!` or `?`.
+pub fn capitalize_sentences(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut at_sentence_start = true;
+    for c in text.chars() {
+        if at_sentence_start && c.is_alphabetic() {
+            result.extend(c.to_uppercase());
+            at_sentence_start = false;
+            continue;
+        }
+        if matches!(c, '.' | '!' | '?') {
+            at_sentence_start = true;
+        } else if !c.is_whitespace() && !matches!(c, '"' | '\'' | '(') {
+            at_sentence_start = false;
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// Applies phrase replacement rules to transcribed text
+///
+/// Matching is case-insensitive and only happens on word boundaries, so a rule
+/// for "acme" does not touch "acmes". Rules are applied in order.
+pub fn apply_replacement_rules(text: &str, rules: &[ReplacementRule]) -> String {
+    let mut result = text.to_string();
+
+    for rule in rules {
+        let from: Vec<char> = rule.from.trim().chars().collect();
+        if from.is_empty() {
+            continue;
+        }
+
+        let mut output = String::with_capacity(result.len());
+        let mut prev: Option<char> = None;
+        let mut i = 0;
+
+        while i < result.len() {
+            let rest = &result[i..];
+
+            if prev.map_or(true, |c| !c.is_alphanumeric()) {
+                if let Some(len) = match_ignore_case(rest, &from) {
+                    let next = rest[len..].chars().next();
+                    if next.map_or(true, |c| !c.is_alphanumeric()) {
+                        output.push_str(&rule.to);
+                        prev = rest[..len].chars().next_back();
+                        i += len;
+                        continue;
+                    }
+                }
+            }
+
+            let c = rest.chars().next().unwrap();
+            output.push(c);
+            prev = Some(c);
+            i += c.len_utf8();
+        }
+
+        result = output;
+    }
+
+    result
+}
+
+/// Phrases Whisper is known to make up from silence or noise, mostly from
+/// the video subtitles it was trained on. Compared after [`hallucination_words`].
+const HALLUCINATIONS: &[&str] = &[
+    "thanks for watching",
+    "thank you for watching",
+    "thank you so much for watching",
+    "thanks for watching and see you next time",
+    "please subscribe",
+    "like and subscribe",
+    "subscribe to my channel",
+    "don't forget to like and subscribe",
+    "subtitles by the amara org community",
+    "transcription by castingwords",
+    "thank you",
+    "thank you very much",
+    "you",
+    "bye",
+    "music",
+    "applause",
+    "silence",
+    "blank audio",
+];
+/// A short phrase said this many times in a row in one segment is a loop.
+const HALLUCINATION_REPEATS: usize = 3;
+
+fn hallucination_words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// Whether a segment is boilerplate Whisper tends to make up when there is
+/// no speech, such as "Thanks for watching!", or one phrase repeated over
+/// and over. Only meaningful for segments that are likely silence, real
+/// speech can say the same.
+pub fn is_hallucination(text: &str) -> bool {
+    let words = hallucination_words(text);
+    if words.is_empty() {
+        return false;
+    }
+    let joined = words.join(" ");
+    if HALLUCINATIONS.contains(&joined.as_str()) {
+        return true;
+    }
+
+    (1..=words.len() / HALLUCINATION_REPEATS)
+        .any(|len| words.len() % len == 0 && words.chunks(len).all(|chunk| chunk == &words[..len]))
+}
+
+/// Reads a spoken correction such as "change acme to ACME Corp", "correct
+/// acme to ACME Corp" or "replace acme with ACME Corp" into the phrase to
+/// replace and its replacement.
+pub fn parse_correction(command: &str) -> Option<ReplacementRule> {
+    let command = command
+        .trim()
+        .trim_end_matches(|c: char| c.is_ascii_punctuation());
+    let lower = command.to_lowercase();
+    // Lowercasing may change byte offsets outside ASCII
+    if lower.len() != command.len() {
+        return None;
+    }
+
+    for (verb, separator) in [
+        ("change ", " to "),
+        ("correct ", " to "),
+        ("replace ", " with "),
+    ] {
+        if !lower.starts_with(verb) {
+            continue;
+        }
+        let Some(split) = lower[verb.len()..].find(separator) else {
+            continue;
+        };
+        let split = verb.len() + split;
+        let from = command[verb.len()..split].trim();
+        let to = command[split + separator.len()..].trim();
+        if !from.is_empty() && !to.is_empty() {
+            return Some(ReplacementRule {
+                from: from.to_string(),
+                to: to.to_string(),
+            });
+        }
+    }
+    None
+}
+
+/// Returns the byte length of the prefix of `text` matching `pattern` case-insensitively
+fn match_ignore_case(text: &str, pattern: &[char]) -> Option<usize> {
+    let mut chars = text.chars();
+    let mut len = 0;
+
+    for p in pattern {
+        let c = chars.next()?;
+        if !c.to_lowercase().eq(p.to_lowercase()) {
+            return None;
+        }
+        len += c.len_utf8();
+    }
+
+    Some(len)
 }
 
 /// Preserves the case pattern of the original word when applying a replacement
@@ -136,6 +582,32 @@ fn extract_punctuation(word: &str) -> (&str, &str) {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_hallucination() {
+        assert!(is_hallucination(" Thanks for watching!"));
+        assert!(is_hallucination("[BLANK_AUDIO]"));
+        assert!(is_hallucination("Bye. Bye. Bye. Bye."));
+        assert!(!is_hallucination("Thanks for watching the kids today."));
+        assert!(!is_hallucination("Send the report by Friday."));
+    }
+
+    #[test]
+    fn test_parse_correction() {
+        let rule = parse_correction("Change acme to ACME Corp.").unwrap();
+        assert_eq!(rule.from, "acme");
+        assert_eq!(rule.to, "ACME Corp");
+
+        let rule = parse_correction("replace colour with color").unwrap();
+        assert_eq!(rule.from, "colour");
+        assert_eq!(rule.to, "color");
+    }
+
+    #[test]
+    fn test_parse_correction_rejects_other_text() {
+        assert!(parse_correction("let's go to the store").is_none());
+        assert!(parse_correction("change to something").is_none());
+    }
+
     #[test]
     fn test_apply_custom_words_exact_match() {
         let text = "hello world";
@@ -152,6 +624,93 @@ mod tests {
         assert_eq!(result, "hello world");
     }
 
+    #[test]
+    fn test_apply_custom_words_reports_corrections() {
+        let text = "Helo world";
+        let custom_words = vec!["hello".to_string(), "world".to_string()];
+        let (result, corrections) = apply_custom_words_with_corrections(text, &custom_words, 0.5);
+        assert_eq!(result, "Hello world");
+        assert_eq!(corrections.len(), 1);
+        assert_eq!(corrections[0].original, "Helo");
+        assert_eq!(corrections[0].replacement, "Hello");
+    }
+
+    #[test]
+    fn test_word_diff() {
+        let diff = word_diff("send it to the team", "Send it to a team today");
+        assert_eq!(
+            diff,
+            vec![
+                DiffSegment::Same("Send it to".to_string()),
+                DiffSegment::Removed("the".to_string()),
+                DiffSegment::Added("a".to_string()),
+                DiffSegment::Same("team".to_string()),
+                DiffSegment::Added("today".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_word_error_rate() {
+        assert_eq!(
+            word_error_rate("The quick brown fox.", "the quick brown fox"),
+            0.0
+        );
+        // One substitution and one deletion over four words
+        assert_eq!(word_error_rate("the quick brown fox", "the quack fox"), 0.5);
+        assert_eq!(word_error_rate("", ""), 0.0);
+    }
+
+    #[test]
+    fn test_segment_paragraphs() {
+        let sentence = "one two three four five six seven eight nine ten";
+        let segment = |start: f32, text: &str| TimedSegment {
+            start,
+            end: start + 4.0,
+            text: format!(" {}", text),
+        };
+        let segments = vec![
+            segment(0.0, sentence),
+            segment(4.5, sentence),
+            // Long pause
+            segment(11.0, sentence),
+            segment(15.5, sentence),
+            // Short pause, but a cue
+            segment(21.0, &format!("Moving on, {}", sentence)),
+            // Same pause without a cue
+            segment(26.5, sentence),
+        ];
+
+        let paragraphs = segment_paragraphs(&segments, 2.5);
+        assert_eq!(paragraphs.len(), 3);
+        assert_eq!(paragraphs[0], format!("{} {}", sentence, sentence));
+        assert!(paragraphs[2].starts_with("Moving on,"));
+        assert_eq!(paragraphs[2].split_whitespace().count(), 22);
+    }
+
+    #[test]
+    fn test_remove_filler_words() {
+        let fillers = vec!["um".to_string(), "uh".to_string(), "you know".to_string()];
+        assert_eq!(
+            remove_filler_words("Um, so we, uh, ship it, you know.", &fillers),
+            "So we, ship it."
+        );
+        assert_eq!(
+            remove_filler_words("It works.\n\nUh I think so", &fillers),
+            "It works.\n\nI think so"
+        );
+        // Only whole words
+        assert_eq!(remove_filler_words("umbrella", &fillers), "umbrella");
+    }
+
+    #[test]
+    fn test_capitalize_sentences() {
+        assert_eq!(
+            capitalize_sentences("sure. see you at 3.30? \"great\" thanks"),
+            "Sure. See you at 3.30? \"Great\" thanks"
+        );
+    }
+
     #[test]
     fn test_preserve_case_pattern() {
         assert_eq!(preserve_case_pattern("HELLO", "world"), "WORLD");
@@ -166,6 +725,18 @@ mod tests {
         assert_eq!(extract_punctuation("...hello..."), ("...", "..."));
     }
 
+    #[test]
+    fn test_apply_replacement_rules() {
+        let rules = vec![ReplacementRule {
+            from: "acme".to_string(),
+            to: "ACME Corp".to_string(),
+        }];
+        assert_eq!(
+            apply_replacement_rules("I work at Acme, not acmes.", &rules),
+            "I work at ACME Corp, not acmes."
+        );
+    }
+
     #[test]
     fn test_empty_custom_words() {
         let text = "hello world";
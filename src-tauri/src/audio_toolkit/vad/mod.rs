@@ -25,6 +25,19 @@ pub trait VoiceActivityDetector: Send + Sync {
     fn reset(&mut self) {}
 }
 
+/// Keeps only what `vad` passes as speech, dropping silence at the start,
+/// at the end and between phrases. A trailing partial frame is dropped.
+pub fn trim_silence(vad: &mut dyn VoiceActivityDetector, samples: &[f32]) -> Result<Vec<f32>> {
+    vad.reset();
+    let mut speech = Vec::with_capacity(samples.len());
+    for frame in samples.chunks_exact(silero::SILERO_FRAME_SAMPLES) {
+        if let VadFrame::Speech(buf) = vad.push_frame(frame)? {
+            speech.extend_from_slice(buf);
+        }
+    }
+    Ok(speech)
+}
+
 mod silero;
 mod smoothed;
 
@@ -7,7 +7,7 @@ use super::{VadFrame, VoiceActivityDetector};
 use crate::audio_toolkit::constants;
 
 const SILERO_FRAME_MS: u32 = 30;
-const SILERO_FRAME_SAMPLES: usize =
+pub(super) const SILERO_FRAME_SAMPLES: usize =
     (constants::WHISPER_SAMPLE_RATE * SILERO_FRAME_MS / 1000) as usize;
 
 pub struct SileroVad {
@@ -29,8 +29,9 @@ impl SileroVad {
     }
 }
 
-impl VoiceActivityDetector for SileroVad {
-    fn push_frame<'a>(&'a mut self, frame: &'a [f32]) -> Result<VadFrame<'a>> {
+impl SileroVad {
+    /// Speech probability of a single 30 ms frame, without thresholding.
+    pub fn speech_probability(&mut self, frame: &[f32]) -> Result<f32> {
         if frame.len() != SILERO_FRAME_SAMPLES {
             anyhow::bail!(
                 "expected {SILERO_FRAME_SAMPLES} samples, got {}",
@@ -45,7 +46,13 @@ impl VoiceActivityDetector for SileroVad {
 
         // println!("Silero VAD result: prob = {}", result.prob);
 
-        if result.prob > self.threshold {
+        Ok(result.prob)
+    }
+}
+
+impl VoiceActivityDetector for SileroVad {
+    fn push_frame<'a>(&'a mut self, frame: &'a [f32]) -> Result<VadFrame<'a>> {
+        if self.speech_probability(frame)? > self.threshold {
             Ok(VadFrame::Speech(frame))
         } else {
             Ok(VadFrame::Noise)
@@ -3,9 +3,14 @@ use enigo::Enigo;
 use enigo::Key;
 use enigo::Keyboard;
 use enigo::Settings;
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
 use tauri::AppHandle;
 use tauri_plugin_clipboard_manager::ClipboardExt;
 
+// Text of the last paste, so a spoken correction can retype it
+static LAST_PASTE: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
 /// Sends a paste command (Cmd+V or Ctrl+V) using platform-specific virtual key codes.
 /// This ensures the paste works regardless of keyboard layout (e.g., Russian, AZERTY, DVORAK).
 fn send_paste() -> Result<(), String> {
@@ -97,5 +102,27 @@ pub fn paste(text: String, app_handle: AppHandle) -> Result<(), String> {
             .map_err(|e| format!("Failed to copy to clipboard: {}", e))?;
     }
 
+    *LAST_PASTE.lock().unwrap() = Some(text);
     Ok(())
 }
+
+pub fn last_paste() -> Option<String> {
+    LAST_PASTE.lock().unwrap().clone()
+}
+
+/// Replaces the last pasted text with `text`: erases it with backspaces,
+/// which only works while the cursor is still right after it, then pastes
+/// `text` in its place.
+pub fn retype_last_paste(text: String, app_handle: AppHandle) -> Result<(), String> {
+    let previous = last_paste().ok_or_else(|| "Nothing was pasted yet".to_string())?;
+
+    let mut enigo = Enigo::new(&Settings::default())
+        .map_err(|e| format!("Failed to initialize Enigo: {}", e))?;
+    for _ in 0..previous.chars().count() {
+        enigo
+            .key(Key::Backspace, enigo::Direction::Click)
+            .map_err(|e| format!("Failed to erase pasted text: {}", e))?;
+    }
+
+    paste(text, app_handle)
+}
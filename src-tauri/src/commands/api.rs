@@ -1,5 +1,7 @@
+use crate::managers::provider_health::{ProviderHealth, ProviderHealthManager};
 use crate::settings::{get_settings, write_settings};
-use tauri::AppHandle;
+use std::sync::Arc;
+use tauri::{AppHandle, State};
 
 #[tauri::command]
 pub fn set_mistral_api_key(app: AppHandle, api_key: String) -> Result<(), String> {
@@ -96,3 +98,25 @@ pub fn has_gladia_api_key(app: AppHandle) -> Result<bool, String> {
     let settings = get_settings(&app);
     Ok(settings.gladia_api_key.is_some())
 }
+
+#[tauri::command]
+pub fn set_huggingface_token(app: AppHandle, token: String) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.huggingface_token = if token.is_empty() { None } else { Some(token) };
+    write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn has_huggingface_token(app: AppHandle) -> Result<bool, String> {
+    let settings = get_settings(&app);
+    Ok(settings.huggingface_token.is_some())
+}
+
+/// Whether each API provider with a key is reachable, for the model picker.
+#[tauri::command]
+pub fn get_provider_health(
+    provider_health_manager: State<'_, Arc<ProviderHealthManager>>,
+) -> Result<Vec<ProviderHealth>, String> {
+    Ok(provider_health_manager.get())
+}
@@ -1,11 +1,18 @@
 use crate::audio_feedback;
 use crate::audio_toolkit::audio::{list_input_devices, list_output_devices};
-use crate::managers::audio::{AudioRecordingManager, MicrophoneMode};
+use crate::managers::audio::{AudioRecordingManager, MicrophoneMode, NoiseCalibration};
+use crate::managers::power::PowerManager;
 use crate::settings::{get_settings, write_settings};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use std::time::Duration;
 use tauri::{AppHandle, Manager};
 
+const MIN_BUFFER_FRAMES: u32 = 64;
+const MAX_BUFFER_FRAMES: u32 = 8192;
+const MIN_CAPTURE_RATE: u32 = 8000;
+const MAX_CAPTURE_RATE: u32 = 192_000;
+
 #[derive(Serialize)]
 pub struct CustomSounds {
     start: bool,
@@ -43,9 +50,10 @@ pub fn update_microphone_mode(app: AppHandle, always_on: bool) -> Result<(), Str
     settings.always_on_microphone = always_on;
     write_settings(&app, settings);
 
-    // Update the audio manager mode
+    // Update the audio manager mode, unless the battery saver holds it off
     let rm = app.state::<Arc<AudioRecordingManager>>();
-    let new_mode = if always_on {
+    let power_manager = app.state::<Arc<PowerManager>>();
+    let new_mode = if power_manager.allows_always_on_microphone(&get_settings(&app)) {
         MicrophoneMode::AlwaysOn
     } else {
         MicrophoneMode::OnDemand
@@ -159,3 +167,91 @@ pub fn play_test_sound(app: AppHandle, sound_type: String) {
     };
     audio_feedback::play_test_sound(&app, sound);
 }
+
+/// Measures ambient noise for a few seconds and stores the recommended VAD
+/// threshold and input gain.
+#[tauri::command]
+pub async fn calibrate_noise_floor(
+    app: AppHandle,
+    seconds: Option<u64>,
+) -> Result<NoiseCalibration, String> {
+    let duration = Duration::from_secs(seconds.unwrap_or(3).clamp(1, 10));
+    let rm = app.state::<Arc<AudioRecordingManager>>().inner().clone();
+    tauri::async_runtime::spawn_blocking(move || rm.calibrate_noise_floor(duration))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("Noise calibration failed: {}", e))
+}
+
+/// Sets the capture buffer size in frames, or back to the host default with
+/// `None`, and reopens the microphone with it.
+#[tauri::command]
+pub fn change_audio_buffer_size_setting(app: AppHandle, frames: Option<u32>) -> Result<(), String> {
+    if let Some(frames) = frames {
+        if !(MIN_BUFFER_FRAMES..=MAX_BUFFER_FRAMES).contains(&frames) {
+            return Err(format!(
+                "Buffer size must be between {} and {} frames",
+                MIN_BUFFER_FRAMES, MAX_BUFFER_FRAMES
+            ));
+        }
+    }
+
+    let mut settings = get_settings(&app);
+    settings.audio_buffer_size = frames;
+    write_settings(&app, settings);
+
+    app.state::<Arc<AudioRecordingManager>>()
+        .reload_recorder()
+        .map_err(|e| format!("Failed to apply buffer size: {}", e))
+}
+
+/// Sets the sample rate to capture at, or back to the default with `None`,
+/// and reopens the microphone with it.
+#[tauri::command]
+pub fn change_capture_sample_rate_setting(
+    app: AppHandle,
+    sample_rate: Option<u32>,
+) -> Result<(), String> {
+    if let Some(sample_rate) = sample_rate {
+        if !(MIN_CAPTURE_RATE..=MAX_CAPTURE_RATE).contains(&sample_rate) {
+            return Err(format!(
+                "Sample rate must be between {} and {} Hz",
+                MIN_CAPTURE_RATE, MAX_CAPTURE_RATE
+            ));
+        }
+    }
+
+    let mut settings = get_settings(&app);
+    settings.capture_sample_rate = sample_rate;
+    write_settings(&app, settings);
+
+    app.state::<Arc<AudioRecordingManager>>()
+        .reload_recorder()
+        .map_err(|e| format!("Failed to apply sample rate: {}", e))
+}
+
+/// Sets whether history keeps recordings as captured, and reopens the
+/// microphone so the capture rate follows.
+#[tauri::command]
+pub fn change_keep_original_audio_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.keep_original_audio = enabled;
+    write_settings(&app, settings);
+
+    app.state::<Arc<AudioRecordingManager>>()
+        .reload_recorder()
+        .map_err(|e| format!("Failed to apply audio quality: {}", e))
+}
+
+/// Sets how many MB of a recording stay in memory before older audio is
+/// moved to a temporary file, 0 for no limit, and reopens the microphone.
+#[tauri::command]
+pub fn change_recording_memory_limit_setting(app: AppHandle, megabytes: u32) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.recording_memory_limit_mb = megabytes;
+    write_settings(&app, settings);
+
+    app.state::<Arc<AudioRecordingManager>>()
+        .reload_recorder()
+        .map_err(|e| format!("Failed to apply memory limit: {}", e))
+}
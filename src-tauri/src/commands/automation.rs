@@ -0,0 +1,43 @@
+use crate::managers::automation::validate_rule;
+use crate::settings::{get_settings, write_settings, AutomationRule};
+use tauri::AppHandle;
+
+#[tauri::command]
+pub fn get_automation_rules(app: AppHandle) -> Result<Vec<AutomationRule>, String> {
+    Ok(get_settings(&app).automation_rules)
+}
+
+/// Adds a rule, or replaces the rule with the same id. An empty id creates a
+/// new rule.
+#[tauri::command]
+pub fn save_automation_rule(
+    app: AppHandle,
+    mut rule: AutomationRule,
+) -> Result<AutomationRule, String> {
+    validate_rule(&rule)?;
+    rule.name = rule.name.trim().to_string();
+    if rule.id.is_empty() {
+        rule.id = format!("rule-{}", chrono::Utc::now().timestamp_millis());
+    }
+
+    let mut settings = get_settings(&app);
+    match settings
+        .automation_rules
+        .iter_mut()
+        .find(|r| r.id == rule.id)
+    {
+        Some(existing) => *existing = rule.clone(),
+        None => settings.automation_rules.push(rule.clone()),
+    }
+    write_settings(&app, settings);
+
+    Ok(rule)
+}
+
+#[tauri::command]
+pub fn delete_automation_rule(app: AppHandle, id: String) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.automation_rules.retain(|r| r.id != id);
+    write_settings(&app, settings);
+    Ok(())
+}
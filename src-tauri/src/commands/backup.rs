@@ -0,0 +1,121 @@
+use crate::managers::backup::{BackupManager, CloudBackupInfo};
+use crate::managers::download_queue::DownloadQueueManager;
+use crate::managers::model::{is_api_model, ModelManager};
+use crate::settings::{get_settings, write_settings, CloudBackupConfig};
+use log::error;
+use std::path::Path;
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub fn set_cloud_backup_config(
+    app: AppHandle,
+    config: Option<CloudBackupConfig>,
+) -> Result<(), String> {
+    if let Some(config) = &config {
+        if !config.endpoint.starts_with("https://") {
+            return Err("Backup endpoint must use HTTPS".to_string());
+        }
+        if config.bucket.is_empty() || config.access_key_id.is_empty() {
+            return Err("Bucket and access key are required".to_string());
+        }
+    }
+
+    let mut settings = get_settings(&app);
+    settings.cloud_backup = config;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_cloud_backup_config(app: AppHandle) -> Result<Option<CloudBackupConfig>, String> {
+    Ok(get_settings(&app).cloud_backup)
+}
+
+#[tauri::command]
+pub async fn create_cloud_backup(
+    backup_manager: State<'_, Arc<BackupManager>>,
+    passphrase: String,
+) -> Result<String, String> {
+    backup_manager
+        .create_backup(&passphrase)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_cloud_backups(
+    backup_manager: State<'_, Arc<BackupManager>>,
+) -> Result<Vec<CloudBackupInfo>, String> {
+    backup_manager
+        .list_backups()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn restore_cloud_backup(
+    backup_manager: State<'_, Arc<BackupManager>>,
+    key: String,
+    passphrase: String,
+) -> Result<(), String> {
+    backup_manager
+        .restore_backup(&key, &passphrase)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn export_state_bundle(
+    backup_manager: State<'_, Arc<BackupManager>>,
+    model_manager: State<'_, Arc<ModelManager>>,
+    path: String,
+    include_models: bool,
+) -> Result<(), String> {
+    let models = if include_models {
+        model_manager
+            .get_available_models()
+            .into_iter()
+            .filter(|m| m.is_downloaded && !is_api_model(&m.id))
+            .map(|m| m.id)
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    backup_manager
+        .export_bundle(Path::new(&path), models)
+        .map_err(|e| e.to_string())
+}
+
+/// Restores a state bundle and starts downloading any listed models that are
+/// missing here. Returns the ids of the models queued for download.
+#[tauri::command]
+pub fn import_state_bundle(
+    backup_manager: State<'_, Arc<BackupManager>>,
+    model_manager: State<'_, Arc<ModelManager>>,
+    download_queue: State<'_, Arc<DownloadQueueManager>>,
+    path: String,
+) -> Result<Vec<String>, String> {
+    let manifest = backup_manager
+        .import_bundle(Path::new(&path))
+        .map_err(|e| e.to_string())?;
+
+    let missing: Vec<String> = manifest
+        .models
+        .into_iter()
+        .filter(|id| {
+            model_manager
+                .get_model_info(id)
+                .is_some_and(|info| !info.is_downloaded)
+        })
+        .collect();
+
+    for model_id in &missing {
+        if let Err(e) = download_queue.enqueue(model_id) {
+            error!("Failed to queue model {} from bundle: {}", model_id, e);
+        }
+    }
+
+    Ok(missing)
+}
@@ -0,0 +1,42 @@
+use crate::crash_report::{self, CrashReport};
+use crate::settings::{get_settings, write_settings};
+use tauri::AppHandle;
+
+#[tauri::command]
+pub fn get_crash_reports(app: AppHandle) -> Result<Vec<CrashReport>, String> {
+    crash_report::list_reports(&app).map_err(|e| e.to_string())
+}
+
+/// What submitting a report would send, for the user to review first.
+#[tauri::command]
+pub fn get_crash_report_payload(app: AppHandle, id: String) -> Result<String, String> {
+    crash_report::report_payload(&app, &id).map_err(|e| e.to_string())
+}
+
+/// Sends a stored report. The UI only calls this after the user agreed to
+/// send that report.
+#[tauri::command]
+pub async fn submit_crash_report(app: AppHandle, id: String) -> Result<(), String> {
+    crash_report::submit_report(&app, &id)
+        .await
+        .map_err(|e| format!("Failed to submit crash report: {}", e))
+}
+
+#[tauri::command]
+pub fn delete_crash_report(app: AppHandle, id: String) -> Result<(), String> {
+    crash_report::delete_report(&app, &id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn change_crash_report_url_setting(app: AppHandle, url: Option<String>) -> Result<(), String> {
+    let url = url.map(|u| u.trim().to_string()).filter(|u| !u.is_empty());
+    if let Some(url) = &url {
+        if !(url.starts_with("https://") || url.starts_with("http://")) {
+            return Err(format!("Invalid crash report URL: {}", url));
+        }
+    }
+    let mut settings = get_settings(&app);
+    settings.crash_report_url = url;
+    write_settings(&app, settings);
+    Ok(())
+}
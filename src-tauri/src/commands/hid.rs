@@ -0,0 +1,70 @@
+use crate::managers::hid::{HidCapture, HidDeviceSummary, HidManager};
+use crate::settings::{get_settings, write_settings, HidBinding};
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub fn list_hid_devices(
+    hid_manager: State<'_, Arc<HidManager>>,
+) -> Result<Vec<HidDeviceSummary>, String> {
+    hid_manager.list_devices()
+}
+
+#[tauri::command]
+pub fn start_hid_capture(hid_manager: State<'_, Arc<HidManager>>) -> Result<(), String> {
+    hid_manager.start_capture();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn cancel_hid_capture(hid_manager: State<'_, Arc<HidManager>>) -> Result<(), String> {
+    hid_manager.cancel_capture();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_hid_bindings(app: AppHandle) -> Result<Vec<HidBinding>, String> {
+    Ok(get_settings(&app).hid_bindings)
+}
+
+/// Binds a captured control to an action, replacing any existing binding for
+/// the same control.
+#[tauri::command]
+pub fn add_hid_binding(
+    app: AppHandle,
+    binding_id: String,
+    capture: HidCapture,
+) -> Result<HidBinding, String> {
+    if !get_settings(&app).bindings.contains_key(&binding_id) {
+        return Err(format!("Binding with id '{}' not found", binding_id));
+    }
+
+    let binding = HidBinding {
+        binding_id,
+        vendor_id: capture.vendor_id,
+        product_id: capture.product_id,
+        device_name: capture.device_name,
+        report_index: capture.report_index,
+        mask: capture.mask,
+    };
+
+    let mut settings = get_settings(&app);
+    settings.hid_bindings.retain(|b| {
+        !(b.vendor_id == binding.vendor_id
+            && b.product_id == binding.product_id
+            && b.report_index == binding.report_index
+            && b.mask == binding.mask)
+    });
+    settings.hid_bindings.push(binding.clone());
+    write_settings(&app, settings);
+
+    Ok(binding)
+}
+
+#[tauri::command]
+pub fn remove_hid_binding(app: AppHandle, binding: HidBinding) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.hid_bindings.retain(|b| b != &binding);
+    write_settings(&app, settings);
+    Ok(())
+}
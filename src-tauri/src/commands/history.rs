@@ -1,4 +1,9 @@
-use crate::managers::history::{HistoryEntry, HistoryManager};
+use crate::engine::SegmentConfidence;
+use crate::import::{self, ImportKind, ImportReport};
+use crate::managers::history::{CorrectionQualityReport, HistoryEntry, HistoryManager};
+use crate::managers::history_sync::{HistorySyncManager, SyncReport};
+use crate::settings::{get_settings, write_settings, HistoryFilter, SavedSearch};
+use std::path::Path;
 use std::sync::Arc;
 use tauri::{AppHandle, State};
 
@@ -13,6 +18,115 @@ pub async fn get_history_entries(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn update_history_entry_text(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+    text: String,
+) -> Result<(), String> {
+    history_manager
+        .update_transcription_text(id, text)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_entry_alternatives(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+) -> Result<Vec<String>, String> {
+    history_manager
+        .get_alternatives(id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Confidence of the entry's segments and words, to highlight what likely
+/// needs correcting.
+#[tauri::command]
+pub async fn get_entry_confidence(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+) -> Result<Vec<SegmentConfidence>, String> {
+    history_manager
+        .get_confidence(id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_entry_raw_text(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+) -> Result<Option<String>, String> {
+    history_manager
+        .get_raw_text(id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_entry_markers(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+) -> Result<Vec<u64>, String> {
+    history_manager
+        .get_markers(id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Writes an entry's markers to `path` as SRT chapters.
+#[tauri::command]
+pub async fn export_entry_markers(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+    path: String,
+) -> Result<(), String> {
+    let srt = history_manager
+        .export_markers_srt(id)
+        .await
+        .map_err(|e| e.to_string())?;
+    std::fs::write(&path, srt).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+#[tauri::command]
+pub async fn swap_to_alternative(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+    index: usize,
+) -> Result<HistoryEntry, String> {
+    history_manager
+        .swap_to_alternative(id, index)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_alternatives_count(app: AppHandle, count: u32) -> Result<(), String> {
+    if count > 5 {
+        return Err("At most 5 alternatives can be kept".to_string());
+    }
+    let mut settings = get_settings(&app);
+    settings.alternatives_count = count;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_correction_quality_report(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+) -> Result<CorrectionQualityReport, String> {
+    let threshold = get_settings(&app).word_correction_threshold;
+    history_manager
+        .correction_quality_report(threshold)
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn toggle_history_entry_saved(
     _app: AppHandle,
@@ -65,3 +179,136 @@ pub async fn update_history_limit(
 
     Ok(())
 }
+
+#[tauri::command]
+pub async fn search_history(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    filter: HistoryFilter,
+) -> Result<Vec<HistoryEntry>, String> {
+    history_manager
+        .search_entries(&filter)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_history_entry_tags(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+    tags: Vec<String>,
+) -> Result<(), String> {
+    history_manager
+        .set_tags(id, tags)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_saved_searches(app: AppHandle) -> Result<Vec<SavedSearch>, String> {
+    Ok(get_settings(&app).saved_searches)
+}
+
+#[tauri::command]
+pub fn save_search(
+    app: AppHandle,
+    id: Option<String>,
+    name: String,
+    filter: HistoryFilter,
+) -> Result<SavedSearch, String> {
+    if name.trim().is_empty() {
+        return Err("Saved search name cannot be empty".to_string());
+    }
+
+    let mut settings = get_settings(&app);
+    let search = SavedSearch {
+        id: id.unwrap_or_else(|| format!("search-{}", chrono::Utc::now().timestamp_millis())),
+        name: name.trim().to_string(),
+        filter,
+    };
+
+    // Saving with an existing id updates that search in place
+    match settings
+        .saved_searches
+        .iter_mut()
+        .find(|s| s.id == search.id)
+    {
+        Some(existing) => *existing = search.clone(),
+        None => settings.saved_searches.push(search.clone()),
+    }
+    write_settings(&app, settings);
+
+    Ok(search)
+}
+
+#[tauri::command]
+pub fn delete_saved_search(app: AppHandle, id: String) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.saved_searches.retain(|s| s.id != id);
+    write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn run_saved_search(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: String,
+) -> Result<Vec<HistoryEntry>, String> {
+    let search = get_settings(&app)
+        .saved_searches
+        .into_iter()
+        .find(|s| s.id == id)
+        .ok_or_else(|| format!("Saved search not found: {}", id))?;
+
+    history_manager
+        .search_entries(&search.filter)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_history_sync_folder(app: AppHandle, folder: Option<String>) -> Result<(), String> {
+    let folder = folder.filter(|f| !f.trim().is_empty());
+    if let Some(folder) = &folder {
+        if !std::path::Path::new(folder).is_dir() {
+            return Err(format!("Sync folder does not exist: {}", folder));
+        }
+    }
+
+    let mut settings = get_settings(&app);
+    settings.history_sync_folder = folder;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_history_sync_include_audio(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.history_sync_include_audio = enabled;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn sync_history_now(
+    sync_manager: State<'_, Arc<HistorySyncManager>>,
+) -> Result<SyncReport, String> {
+    let sync_manager = sync_manager.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || sync_manager.sync_now())
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn import_external_data(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    kind: ImportKind,
+    path: String,
+) -> Result<ImportReport, String> {
+    import::import_external_data(&app, &history_manager, kind, Path::new(&path))
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,31 @@
+use crate::managers::metrics::{MetricsManager, MetricsSnapshot};
+use crate::settings::{get_settings, write_settings};
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub fn set_metrics_endpoint(
+    app: AppHandle,
+    metrics_manager: State<'_, Arc<MetricsManager>>,
+    enabled: bool,
+    port: Option<u16>,
+) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.metrics_enabled = enabled;
+    if let Some(port) = port {
+        if port == 0 {
+            return Err("Metrics port must be between 1 and 65535".to_string());
+        }
+        settings.metrics_port = port;
+    }
+    write_settings(&app, settings);
+
+    metrics_manager.apply_settings()
+}
+
+#[tauri::command]
+pub fn get_metrics_snapshot(
+    metrics_manager: State<'_, Arc<MetricsManager>>,
+) -> Result<MetricsSnapshot, String> {
+    Ok(metrics_manager.snapshot())
+}
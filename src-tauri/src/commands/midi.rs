@@ -0,0 +1,73 @@
+use crate::managers::midi::{MidiCapture, MidiManager};
+use crate::settings::{get_settings, write_settings, MidiAction, MidiBinding};
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub fn list_midi_ports(midi_manager: State<'_, Arc<MidiManager>>) -> Result<Vec<String>, String> {
+    midi_manager.list_ports()
+}
+
+#[tauri::command]
+pub fn start_midi_capture(midi_manager: State<'_, Arc<MidiManager>>) -> Result<(), String> {
+    midi_manager.start_capture();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn cancel_midi_capture(midi_manager: State<'_, Arc<MidiManager>>) -> Result<(), String> {
+    midi_manager.cancel_capture();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_midi_bindings(app: AppHandle) -> Result<Vec<MidiBinding>, String> {
+    Ok(get_settings(&app).midi_bindings)
+}
+
+/// Binds a captured control to an action, replacing whatever the same control
+/// on the same port was bound to.
+#[tauri::command]
+pub fn add_midi_binding(
+    app: AppHandle,
+    midi_manager: State<'_, Arc<MidiManager>>,
+    capture: MidiCapture,
+    action: MidiAction,
+    any_port: bool,
+) -> Result<MidiBinding, String> {
+    let mut settings = get_settings(&app);
+
+    if let MidiAction::Binding { binding_id } = &action {
+        if !settings.bindings.contains_key(binding_id) {
+            return Err(format!("Binding with id '{}' not found", binding_id));
+        }
+    }
+
+    let binding = MidiBinding {
+        port_name: (!any_port).then_some(capture.port_name),
+        trigger: capture.trigger,
+        action,
+    };
+
+    settings
+        .midi_bindings
+        .retain(|b| !(b.trigger == binding.trigger && b.port_name == binding.port_name));
+    settings.midi_bindings.push(binding.clone());
+    write_settings(&app, settings);
+    midi_manager.reload_bindings();
+
+    Ok(binding)
+}
+
+#[tauri::command]
+pub fn remove_midi_binding(
+    app: AppHandle,
+    midi_manager: State<'_, Arc<MidiManager>>,
+    binding: MidiBinding,
+) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.midi_bindings.retain(|b| b != &binding);
+    write_settings(&app, settings);
+    midi_manager.reload_bindings();
+    Ok(())
+}
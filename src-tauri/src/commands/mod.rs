@@ -1,11 +1,30 @@
 pub mod api;
 pub mod audio;
+pub mod automation;
+pub mod backup;
+pub mod crash_reports;
+pub mod hid;
 pub mod history;
+pub mod metrics;
+pub mod midi;
 pub mod models;
+pub mod phone_import;
+pub mod power;
+pub mod practice;
+pub mod profiles;
+pub mod stream_deck;
 pub mod transcription;
+pub mod vocabulary;
+pub mod voice_profile;
 
+use crate::events::{self, EventSchema};
+use crate::health::{self, HealthReport};
+use crate::managers::model::ModelManager;
+use crate::settings::{get_settings, write_settings, ReleaseChannel};
+use crate::update_check::{check_for_update, AppUpdate};
 use crate::utils::cancel_current_operation;
-use tauri::{AppHandle, Manager};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
 
 #[tauri::command]
 pub fn cancel_operation(app: AppHandle) {
@@ -21,3 +40,38 @@ pub fn get_app_dir_path(app: AppHandle) -> Result<String, String> {
 
     Ok(app_data_dir.to_string_lossy().to_string())
 }
+
+#[tauri::command]
+pub async fn check_for_app_update(app: AppHandle) -> Result<Option<AppUpdate>, String> {
+    let channel = get_settings(&app).release_channel;
+    check_for_update(channel).await.map_err(|e| e.to_string())
+}
+
+/// Reruns the startup health check, e.g. after the user fixed a problem.
+#[tauri::command]
+pub fn run_health_check(app: AppHandle) -> HealthReport {
+    health::check(&app)
+}
+
+/// Every event the backend emits, with its payload type.
+#[tauri::command]
+pub fn get_event_schema() -> EventSchema {
+    events::schema()
+}
+
+/// Switches the release channel and refetches the model catalog for it.
+#[tauri::command]
+pub async fn set_release_channel(
+    app: AppHandle,
+    model_manager: State<'_, Arc<ModelManager>>,
+    channel: ReleaseChannel,
+) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.release_channel = channel;
+    write_settings(&app, settings);
+
+    model_manager
+        .refresh_catalog()
+        .await
+        .map_err(|e| format!("Failed to fetch the {} catalog: {}", channel.as_str(), e))
+}
@@ -1,8 +1,22 @@
-use crate::managers::model::{is_api_model, ModelInfo, ModelManager};
+use crate::audio_toolkit::read_wav_file;
+use crate::managers::download_queue::{DownloadQueueItem, DownloadQueueManager};
+use crate::managers::history::HistoryManager;
+use crate::managers::model::{
+    is_api_model, IntegrityStatus, ModelDetails, ModelInfo, ModelManager, ModelRecommendation,
+    ModelUpdate, ModelsDirStatus, StaleModelFile, UnusedModel,
+};
 use crate::managers::transcription::TranscriptionManager;
-use crate::settings::{get_settings, write_settings};
+use crate::settings::{
+    get_settings, write_settings, ModelBenchmark, ModelOverrides, UnusedModelPolicy,
+};
+use std::path::Path;
 use std::sync::Arc;
-use tauri::{AppHandle, State};
+use tauri::path::BaseDirectory;
+use tauri::{AppHandle, Manager, State};
+
+/// Reference clip for `benchmark_model` and its transcript.
+const BENCHMARK_AUDIO: &str = "resources/benchmark/reference.wav";
+const BENCHMARK_TRANSCRIPT: &str = "resources/benchmark/reference.txt";
 
 #[tauri::command]
 pub async fn get_available_models(
@@ -19,6 +33,18 @@ pub async fn get_model_info(
     Ok(model_manager.get_model_info(&model_id))
 }
 
+/// License, provenance and local state of a model, available before it is
+/// downloaded.
+#[tauri::command]
+pub async fn get_model_details(
+    model_manager: State<'_, Arc<ModelManager>>,
+    model_id: String,
+) -> Result<ModelDetails, String> {
+    model_manager
+        .get_model_details(&model_id)
+        .ok_or_else(|| format!("Model not found: {}", model_id))
+}
+
 #[tauri::command]
 pub async fn download_model(
     model_manager: State<'_, Arc<ModelManager>>,
@@ -40,9 +66,141 @@ pub async fn delete_model(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn check_model_integrity(
+    model_manager: State<'_, Arc<ModelManager>>,
+    model_id: String,
+) -> Result<IntegrityStatus, String> {
+    model_manager
+        .verify_model(&model_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn repair_model(
+    model_manager: State<'_, Arc<ModelManager>>,
+    model_id: String,
+) -> Result<(), String> {
+    model_manager
+        .repair_model(&model_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Measures latency and real time factor of a downloaded model on this
+/// machine with the bundled reference clip. Without the clip the latest
+/// history recording is used, which has no transcript to measure accuracy.
+#[tauri::command]
+pub async fn benchmark_model(
+    app: AppHandle,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    model_id: String,
+) -> Result<ModelBenchmark, String> {
+    let bundled = app
+        .path()
+        .resolve(BENCHMARK_AUDIO, BaseDirectory::Resource)
+        .ok()
+        .filter(|path| path.exists());
+
+    let (audio, reference) = match bundled {
+        Some(path) => {
+            let reference = app
+                .path()
+                .resolve(BENCHMARK_TRANSCRIPT, BaseDirectory::Resource)
+                .ok()
+                .and_then(|path| std::fs::read_to_string(path).ok());
+            (read_wav_file(path).map_err(|e| e.to_string())?, reference)
+        }
+        None => {
+            let entry = history_manager
+                .get_history_entries()
+                .await
+                .map_err(|e| e.to_string())?
+                .into_iter()
+                .next()
+                .ok_or_else(|| "No reference clip or recording to benchmark with".to_string())?;
+            let path = history_manager.get_audio_file_path(&entry.file_name);
+            (read_wav_file(path).map_err(|e| e.to_string())?, None)
+        }
+    };
+
+    transcription_manager
+        .benchmark(&model_id, audio, reference.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn check_model_updates(
+    model_manager: State<'_, Arc<ModelManager>>,
+) -> Result<Vec<ModelUpdate>, String> {
+    Ok(model_manager.check_for_updates())
+}
+
+/// Switches a model to another quantization, reloading it when it is the
+/// one in use and the variant is already downloaded.
+#[tauri::command]
+pub async fn select_model_variant(
+    model_manager: State<'_, Arc<ModelManager>>,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+    model_id: String,
+    variant_id: String,
+) -> Result<ModelInfo, String> {
+    let model = model_manager
+        .select_variant(&model_id, &variant_id)
+        .map_err(|e| e.to_string())?;
+
+    if model.is_downloaded
+        && transcription_manager.get_current_model().as_deref() == Some(model_id.as_str())
+    {
+        transcription_manager
+            .load_model(&model_id)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(model)
+}
+
+/// Upgrades a model in place, reloading it when it is the one in use.
+#[tauri::command]
+pub async fn upgrade_model(
+    model_manager: State<'_, Arc<ModelManager>>,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+    model_id: String,
+) -> Result<(), String> {
+    model_manager
+        .upgrade_model(&model_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if transcription_manager.get_current_model().as_deref() == Some(model_id.as_str()) {
+        transcription_manager
+            .load_model(&model_id)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn download_coreml_encoder(
+    model_manager: State<'_, Arc<ModelManager>>,
+    model_id: String,
+) -> Result<(), String> {
+    model_manager
+        .download_coreml_encoder(&model_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_models_dir_status(
+    model_manager: State<'_, Arc<ModelManager>>,
+) -> Result<ModelsDirStatus, String> {
+    Ok(model_manager.get_models_dir_status())
+}
+
 #[tauri::command]
 pub async fn set_active_model(
-    app_handle: AppHandle,
     model_manager: State<'_, Arc<ModelManager>>,
     transcription_manager: State<'_, Arc<TranscriptionManager>>,
     model_id: String,
@@ -57,16 +215,111 @@ pub async fn set_active_model(
         return Err(format!("Model not downloaded: {}", model_id));
     }
 
-    // Load the model in the transcription manager
+    // Load the model and switch to the settings saved for it
+    transcription_manager
+        .select_model(&model_id)
+        .map_err(|e| e.to_string())
+}
+
+/// The model to offer on first launch, `None` once one is set up.
+#[tauri::command]
+pub async fn get_model_recommendation(
+    model_manager: State<'_, Arc<ModelManager>>,
+) -> Result<Option<ModelRecommendation>, String> {
+    Ok(model_manager.recommend_model())
+}
+
+/// Downloads the recommended model and selects it once it's ready.
+#[tauri::command]
+pub async fn download_recommended_model(
+    model_manager: State<'_, Arc<ModelManager>>,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+) -> Result<String, String> {
+    let recommendation = model_manager
+        .recommend_model()
+        .ok_or_else(|| "A model is already set up".to_string())?;
+    model_manager
+        .download_model(&recommendation.model_id)
+        .await
+        .map_err(|e| e.to_string())?;
     transcription_manager
-        .load_model(&model_id)
+        .select_model(&recommendation.model_id)
         .map_err(|e| e.to_string())?;
+    Ok(recommendation.model_id)
+}
+
+#[tauri::command]
+pub fn get_favorite_models(app_handle: AppHandle) -> Vec<String> {
+    get_settings(&app_handle).favorite_models
+}
+
+#[tauri::command]
+pub fn set_model_favorite(
+    model_manager: State<'_, Arc<ModelManager>>,
+    model_id: String,
+    favorite: bool,
+) -> Result<(), String> {
+    model_manager
+        .set_favorite(&model_id, favorite)
+        .map_err(|e| e.to_string())
+}
+
+/// Rates a model from 1 to 5 stars, or clears the rating with `None`. The
+/// rating is folded into the model's accuracy score.
+#[tauri::command]
+pub fn set_model_rating(
+    model_manager: State<'_, Arc<ModelManager>>,
+    model_id: String,
+    rating: Option<u8>,
+) -> Result<(), String> {
+    model_manager
+        .set_rating(&model_id, rating)
+        .map_err(|e| e.to_string())
+}
+
+/// Switches to the next favorite model and returns its id.
+#[tauri::command]
+pub async fn cycle_favorite_model(
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+) -> Result<String, String> {
+    transcription_manager
+        .cycle_favorite_model()
+        .map_err(|e| e.to_string())
+}
+
+/// Settings saved for a model, empty when it has none.
+#[tauri::command]
+pub fn get_model_overrides(app_handle: AppHandle, model_id: String) -> ModelOverrides {
+    get_settings(&app_handle)
+        .model_overrides
+        .get(&model_id)
+        .cloned()
+        .unwrap_or_default()
+}
 
-    // Update settings
+/// Saves settings for a model. They take effect the next time it is
+/// selected, or right away for the selected model.
+#[tauri::command]
+pub fn set_model_overrides(
+    app_handle: AppHandle,
+    model_id: String,
+    overrides: ModelOverrides,
+) -> Result<(), String> {
     let mut settings = get_settings(&app_handle);
-    settings.selected_model = model_id.clone();
+    if let Some(preset) = &overrides.preset {
+        if !settings.presets().iter().any(|p| &p.id == preset) {
+            return Err(format!("Unknown preset '{}'", preset));
+        }
+    }
+    if overrides.is_empty() {
+        settings.model_overrides.remove(&model_id);
+    } else {
+        settings.model_overrides.insert(model_id.clone(), overrides);
+    }
+    if settings.selected_model == model_id {
+        settings.apply_model_overrides(&model_id);
+    }
     write_settings(&app_handle, settings);
-
     Ok(())
 }
 
@@ -90,10 +343,19 @@ pub async fn is_model_loading(
     Ok(!transcription_manager.is_model_loaded())
 }
 
+/// Whether the startup scan has finished, for a UI that missed
+/// `models-ready`.
+#[tauri::command]
+pub fn are_models_ready(model_manager: State<'_, Arc<ModelManager>>) -> bool {
+    model_manager.is_ready()
+}
+
 #[tauri::command]
 pub async fn has_any_models_available(
     model_manager: State<'_, Arc<ModelManager>>,
 ) -> Result<bool, String> {
+    // Right after launch nothing counts as downloaded yet
+    model_manager.wait_until_ready();
     let models = model_manager.get_available_models();
     Ok(models.iter().any(|m| m.is_downloaded))
 }
@@ -102,6 +364,7 @@ pub async fn has_any_models_available(
 pub async fn has_any_models_or_downloads(
     model_manager: State<'_, Arc<ModelManager>>,
 ) -> Result<bool, String> {
+    model_manager.wait_until_ready();
     let models = model_manager.get_available_models();
     // Return true if any models are downloaded OR if any downloads are in progress
     Ok(models.iter().any(|m| m.is_downloaded))
@@ -109,12 +372,42 @@ pub async fn has_any_models_or_downloads(
 
 #[tauri::command]
 pub async fn cancel_download(
-    model_manager: State<'_, Arc<ModelManager>>,
+    download_queue: State<'_, Arc<DownloadQueueManager>>,
     model_id: String,
 ) -> Result<(), String> {
-    model_manager
-        .cancel_download(&model_id)
-        .map_err(|e| e.to_string())
+    download_queue.cancel(&model_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn queue_model_download(
+    download_queue: State<'_, Arc<DownloadQueueManager>>,
+    model_id: String,
+) -> Result<(), String> {
+    download_queue.enqueue(&model_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_download_queue(
+    download_queue: State<'_, Arc<DownloadQueueManager>>,
+) -> Result<Vec<DownloadQueueItem>, String> {
+    Ok(download_queue.items())
+}
+
+#[tauri::command]
+pub async fn set_max_concurrent_downloads(
+    app: AppHandle,
+    download_queue: State<'_, Arc<DownloadQueueManager>>,
+    limit: usize,
+) -> Result<(), String> {
+    if !(1..=4).contains(&limit) {
+        return Err("Concurrent downloads must be between 1 and 4".to_string());
+    }
+    let mut settings = get_settings(&app);
+    settings.max_concurrent_downloads = limit;
+    write_settings(&app, settings);
+    // Start waiting downloads right away when the limit went up
+    download_queue.pump();
+    Ok(())
 }
 
 #[tauri::command]
@@ -122,3 +415,139 @@ pub async fn get_recommended_first_model() -> Result<String, String> {
     // Recommend Parakeet V3 model for first-time users - fastest and most accurate
     Ok("parakeet-tdt-0.6b-v3".to_string())
 }
+
+#[tauri::command]
+pub async fn refresh_model_catalog(
+    model_manager: State<'_, Arc<ModelManager>>,
+) -> Result<Vec<ModelInfo>, String> {
+    model_manager
+        .refresh_catalog()
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(model_manager.get_available_models())
+}
+
+#[tauri::command]
+pub async fn register_custom_model(
+    model_manager: State<'_, Arc<ModelManager>>,
+    path: String,
+    name: Option<String>,
+) -> Result<ModelInfo, String> {
+    model_manager
+        .register_custom_model(Path::new(&path), name)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn remove_custom_model(
+    model_manager: State<'_, Arc<ModelManager>>,
+    model_id: String,
+) -> Result<(), String> {
+    model_manager
+        .remove_custom_model(&model_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_unused_models(
+    app: AppHandle,
+    model_manager: State<'_, Arc<ModelManager>>,
+) -> Result<Vec<UnusedModel>, String> {
+    let days = get_settings(&app).unused_model_policy.days;
+    Ok(model_manager.unused_models(days))
+}
+
+/// Deletes models the user confirmed from the unused models list. Returns
+/// the ids that were actually deleted.
+#[tauri::command]
+pub async fn delete_unused_models(
+    model_manager: State<'_, Arc<ModelManager>>,
+    model_ids: Vec<String>,
+) -> Result<Vec<String>, String> {
+    model_manager
+        .delete_unused_models(&model_ids)
+        .map_err(|e| e.to_string())
+}
+
+/// Copies a downloaded model to `path`, as a single archive when `archive`
+/// is set.
+#[tauri::command]
+pub async fn export_model(
+    model_manager: State<'_, Arc<ModelManager>>,
+    model_id: String,
+    path: String,
+    archive: bool,
+) -> Result<(), String> {
+    let model_manager = model_manager.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        model_manager.export_model(&model_id, Path::new(&path), archive)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// Installs a model from an exported archive or a plain copy of its files,
+/// the latter needs `model_id`.
+#[tauri::command]
+pub async fn import_model(
+    model_manager: State<'_, Arc<ModelManager>>,
+    path: String,
+    model_id: Option<String>,
+) -> Result<String, String> {
+    model_manager
+        .import_model(Path::new(&path), model_id.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Removes leftovers of interrupted downloads and installs, or with
+/// `dry_run` only lists them.
+#[tauri::command]
+pub async fn clean_up_model_files(
+    model_manager: State<'_, Arc<ModelManager>>,
+    dry_run: bool,
+) -> Result<Vec<StaleModelFile>, String> {
+    model_manager
+        .clean_up_stale_files(dry_run)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_unused_model_policy(app: AppHandle, policy: UnusedModelPolicy) -> Result<(), String> {
+    if policy.days == 0 {
+        return Err("Days must be at least 1".to_string());
+    }
+    let mut settings = get_settings(&app);
+    settings.unused_model_policy = policy;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+/// Sets the user's own mirrors for a model, replacing any set before.
+#[tauri::command]
+pub fn set_model_mirrors(
+    app: AppHandle,
+    model_id: String,
+    urls: Vec<String>,
+) -> Result<(), String> {
+    let urls: Vec<String> = urls
+        .into_iter()
+        .map(|u| u.trim().to_string())
+        .filter(|u| !u.is_empty())
+        .collect();
+    if let Some(url) = urls.iter().find(|u| {
+        !(u.starts_with("https://") || u.starts_with("http://") || u.starts_with("hf://"))
+    }) {
+        return Err(format!("Invalid mirror URL: {}", url));
+    }
+
+    let mut settings = get_settings(&app);
+    if urls.is_empty() {
+        settings.model_mirrors.remove(&model_id);
+    } else {
+        settings.model_mirrors.insert(model_id, urls);
+    }
+    write_settings(&app, settings);
+    Ok(())
+}
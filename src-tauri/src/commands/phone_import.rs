@@ -0,0 +1,39 @@
+use crate::managers::phone_import::{PhoneImportConfig, PhoneImportManager};
+use crate::settings::{get_settings, write_settings};
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub fn get_phone_import_config(
+    phone_import_manager: State<'_, Arc<PhoneImportManager>>,
+) -> Result<PhoneImportConfig, String> {
+    Ok(phone_import_manager.get_config())
+}
+
+#[tauri::command]
+pub fn set_phone_import_endpoint(
+    app: AppHandle,
+    phone_import_manager: State<'_, Arc<PhoneImportManager>>,
+    enabled: bool,
+    port: Option<u16>,
+) -> Result<PhoneImportConfig, String> {
+    let mut settings = get_settings(&app);
+    settings.phone_import_enabled = enabled;
+    if let Some(port) = port {
+        if port == 0 {
+            return Err("Phone import port must be between 1 and 65535".to_string());
+        }
+        settings.phone_import_port = port;
+    }
+    write_settings(&app, settings);
+
+    phone_import_manager.apply_settings()?;
+    Ok(phone_import_manager.get_config())
+}
+
+#[tauri::command]
+pub fn regenerate_phone_import_token(
+    phone_import_manager: State<'_, Arc<PhoneImportManager>>,
+) -> Result<PhoneImportConfig, String> {
+    phone_import_manager.regenerate_token()
+}
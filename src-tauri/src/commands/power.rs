@@ -0,0 +1,25 @@
+use crate::managers::power::{PowerManager, PowerStatus};
+use crate::settings::{get_settings, write_settings, BatterySaverPolicy};
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub fn get_power_status(
+    power_manager: State<'_, Arc<PowerManager>>,
+) -> Result<PowerStatus, String> {
+    Ok(power_manager.status())
+}
+
+#[tauri::command]
+pub fn set_battery_saver_policy(
+    app: AppHandle,
+    power_manager: State<'_, Arc<PowerManager>>,
+    policy: BatterySaverPolicy,
+) -> Result<PowerStatus, String> {
+    let mut settings = get_settings(&app);
+    settings.battery_saver = policy;
+    write_settings(&app, settings);
+
+    power_manager.apply_policy();
+    Ok(power_manager.status())
+}
@@ -0,0 +1,54 @@
+use crate::managers::practice::{PracticeManager, PracticeSentence, PracticeSummary};
+use crate::settings::PracticeScore;
+use std::sync::Arc;
+use tauri::State;
+
+/// A sentence to read for the next practice attempt.
+#[tauri::command]
+pub fn get_practice_sentence(
+    practice_manager: State<'_, Arc<PracticeManager>>,
+) -> Result<PracticeSentence, String> {
+    Ok(practice_manager.next_sentence())
+}
+
+/// Starts recording a practice sentence.
+#[tauri::command]
+pub fn start_practice_attempt(
+    practice_manager: State<'_, Arc<PracticeManager>>,
+) -> Result<(), String> {
+    practice_manager.start_attempt().map_err(|e| e.to_string())
+}
+
+/// Stops recording and scores the reading of the sentence at `index`.
+#[tauri::command]
+pub async fn finish_practice_attempt(
+    practice_manager: State<'_, Arc<PracticeManager>>,
+    index: usize,
+) -> Result<PracticeScore, String> {
+    practice_manager
+        .finish_attempt(index)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_practice_scores(
+    practice_manager: State<'_, Arc<PracticeManager>>,
+) -> Result<Vec<PracticeScore>, String> {
+    Ok(practice_manager.scores())
+}
+
+#[tauri::command]
+pub fn get_practice_summary(
+    practice_manager: State<'_, Arc<PracticeManager>>,
+) -> Result<PracticeSummary, String> {
+    Ok(practice_manager.summary())
+}
+
+#[tauri::command]
+pub fn clear_practice_scores(
+    practice_manager: State<'_, Arc<PracticeManager>>,
+) -> Result<(), String> {
+    practice_manager.clear_scores();
+    Ok(())
+}
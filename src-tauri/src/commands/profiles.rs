@@ -0,0 +1,31 @@
+use crate::profiles::{self, ProfileInfo};
+use tauri::AppHandle;
+
+#[tauri::command]
+pub fn list_profiles(app: AppHandle) -> Vec<ProfileInfo> {
+    profiles::list_profiles(&app)
+}
+
+#[tauri::command]
+pub fn create_profile(
+    app: AppHandle,
+    name: String,
+    pin: Option<String>,
+) -> Result<ProfileInfo, String> {
+    profiles::create_profile(&app, &name, pin.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_profile(app: AppHandle, id: String, pin: Option<String>) -> Result<(), String> {
+    profiles::delete_profile(&app, &id, pin.as_deref()).map_err(|e| e.to_string())
+}
+
+/// Restarts the app into another profile, `None` for the default one.
+#[tauri::command]
+pub fn switch_profile(
+    app: AppHandle,
+    id: Option<String>,
+    pin: Option<String>,
+) -> Result<(), String> {
+    profiles::switch_profile(&app, id.as_deref(), pin.as_deref()).map_err(|e| e.to_string())
+}
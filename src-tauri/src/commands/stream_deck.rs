@@ -0,0 +1,39 @@
+use crate::managers::stream_deck::{StreamDeckConfig, StreamDeckManager};
+use crate::settings::{get_settings, write_settings};
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub fn get_stream_deck_config(
+    stream_deck_manager: State<'_, Arc<StreamDeckManager>>,
+) -> Result<StreamDeckConfig, String> {
+    Ok(stream_deck_manager.get_config())
+}
+
+#[tauri::command]
+pub fn set_stream_deck_endpoint(
+    app: AppHandle,
+    stream_deck_manager: State<'_, Arc<StreamDeckManager>>,
+    enabled: bool,
+    port: Option<u16>,
+) -> Result<StreamDeckConfig, String> {
+    let mut settings = get_settings(&app);
+    settings.stream_deck_enabled = enabled;
+    if let Some(port) = port {
+        if port == 0 {
+            return Err("Stream Deck port must be between 1 and 65535".to_string());
+        }
+        settings.stream_deck_port = port;
+    }
+    write_settings(&app, settings);
+
+    stream_deck_manager.apply_settings()?;
+    Ok(stream_deck_manager.get_config())
+}
+
+#[tauri::command]
+pub fn regenerate_stream_deck_token(
+    stream_deck_manager: State<'_, Arc<StreamDeckManager>>,
+) -> Result<StreamDeckConfig, String> {
+    stream_deck_manager.regenerate_token()
+}
@@ -1,5 +1,17 @@
-use crate::managers::transcription::TranscriptionManager;
-use crate::settings::{get_settings, write_settings, ModelUnloadTimeout};
+use crate::acceleration::AccelerationInfo;
+use crate::audio_toolkit::read_wav_file;
+use crate::managers::email_ingest::EmailIngestManager;
+use crate::managers::history::HistoryManager;
+use crate::managers::offline_queue::OfflineQueueManager;
+use crate::managers::transcription::{TranscriptionComparison, TranscriptionManager};
+use crate::managers::transcription_queue::{TranscriptionJob, TranscriptionQueue};
+use crate::managers::watch_folder::WatchFolderManager;
+use crate::settings::{
+    get_settings, write_settings, AppSettings, ComparisonModels, ContextContinuation, CpuUsage,
+    EmailIngestConfig, ModelUnloadTimeout, ParagraphBreaks, PostProcessPreset, WatchFolderConfig,
+    WhisperFallback, WhisperSampling, MAX_WHISPER_CANDIDATES,
+};
+use std::sync::Arc;
 use tauri::{AppHandle, State};
 
 #[tauri::command]
@@ -30,3 +42,530 @@ pub fn unload_model_manually(
         .unload_model()
         .map_err(|e| format!("Failed to unload model: {}", e))
 }
+
+#[tauri::command]
+pub fn get_acceleration_info(app: AppHandle) -> Result<AccelerationInfo, String> {
+    let gpu_enabled = get_settings(&app).gpu_acceleration;
+    Ok(crate::acceleration::get_acceleration_info(gpu_enabled))
+}
+
+/// Turns GPU acceleration on or off, reloading the current local model so
+/// the change applies right away.
+#[tauri::command]
+pub fn set_gpu_acceleration(
+    app: AppHandle,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.gpu_acceleration = enabled;
+    if enabled {
+        // Give models that fell back to the CPU another chance
+        settings.model_backends.clear();
+    }
+    write_settings(&app, settings);
+
+    if transcription_manager.engine_backend().is_some() {
+        if let Some(model_id) = transcription_manager.get_current_model() {
+            transcription_manager
+                .load_model(&model_id)
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_last_transcription_backend(
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+) -> Option<String> {
+    transcription_manager.last_backend()
+}
+
+#[tauri::command]
+pub fn set_context_continuation(
+    app: AppHandle,
+    continuation: ContextContinuation,
+) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.context_continuation = continuation;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_paragraph_breaks(app: AppHandle, breaks: ParagraphBreaks) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.paragraph_breaks = breaks;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_cpu_usage(app: AppHandle, usage: CpuUsage) -> Result<(), String> {
+    if usage.threads == Some(0) {
+        return Err("Thread count must be at least 1".to_string());
+    }
+    let mut settings = get_settings(&app);
+    settings.cpu_usage = usage;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_whisper_sampling(app: AppHandle, sampling: WhisperSampling) -> Result<(), String> {
+    let candidates = match sampling {
+        WhisperSampling::Greedy { best_of } => best_of,
+        WhisperSampling::BeamSearch { beam_size } => beam_size,
+    };
+    if !(1..=MAX_WHISPER_CANDIDATES).contains(&candidates) {
+        return Err(format!(
+            "Must be between 1 and {} candidates",
+            MAX_WHISPER_CANDIDATES
+        ));
+    }
+    let mut settings = get_settings(&app);
+    settings.whisper_sampling = sampling;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+/// Sets Whisper's temperature fallback, `None` restores the defaults.
+#[tauri::command]
+pub fn set_whisper_fallback(
+    app: AppHandle,
+    fallback: Option<WhisperFallback>,
+) -> Result<(), String> {
+    let fallback = fallback.unwrap_or_default();
+    if !(0.0..=1.0).contains(&fallback.temperature) {
+        return Err("Temperature must be between 0 and 1".to_string());
+    }
+    if !(0.0..=1.0).contains(&fallback.temperature_inc) {
+        return Err("Temperature increment must be between 0 and 1".to_string());
+    }
+    if !fallback.entropy_threshold.is_finite() || fallback.entropy_threshold <= 0.0 {
+        return Err("Entropy threshold must be above 0".to_string());
+    }
+    if !fallback.logprob_threshold.is_finite() || fallback.logprob_threshold > 0.0 {
+        return Err("Log probability threshold must be 0 or below".to_string());
+    }
+    let mut settings = get_settings(&app);
+    settings.whisper_fallback = fallback;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_trim_silence(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.trim_silence = enabled;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_filter_hallucinations(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.filter_hallucinations = enabled;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_confirm_paste_on_focus_change(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.confirm_paste_on_focus_change = enabled;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+/// Sets the global no-speech threshold, `None` for Whisper's default.
+#[tauri::command]
+pub fn set_no_speech_threshold(app: AppHandle, threshold: Option<f32>) -> Result<(), String> {
+    if threshold.is_some_and(|t| !(0.0..=1.0).contains(&t)) {
+        return Err("No-speech threshold must be between 0 and 1".to_string());
+    }
+    let mut settings = get_settings(&app);
+    settings.no_speech_threshold = threshold;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_suppress_non_speech_tokens(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.suppress_non_speech_tokens = enabled;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_chunk_transcription_seconds(app: AppHandle, seconds: u32) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.chunk_transcription_seconds = seconds;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_lock_to_recording_app(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.lock_to_recording_app = enabled;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn reset_dictation_context(
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+) -> Result<(), String> {
+    transcription_manager.reset_context();
+    Ok(())
+}
+
+/// Models to compare every recording with while debug mode is on, `None`
+/// to stop comparing.
+#[tauri::command]
+pub fn set_comparison_models(
+    app: AppHandle,
+    models: Option<ComparisonModels>,
+) -> Result<(), String> {
+    if models.as_ref().is_some_and(|m| m.model_a == m.model_b) {
+        return Err("Pick two different models to compare".to_string());
+    }
+    let mut settings = get_settings(&app);
+    settings.comparison_models = models;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+/// Compares two models on the recording of a history entry.
+#[tauri::command]
+pub async fn compare_history_entry(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+    id: i64,
+    model_a: String,
+    model_b: String,
+) -> Result<TranscriptionComparison, String> {
+    let entry = history_manager
+        .get_entry_by_id(id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("History entry {} not found", id))?;
+    let audio = read_wav_file(history_manager.get_audio_file_path(&entry.file_name))
+        .map_err(|e| e.to_string())?;
+
+    transcription_manager
+        .compare(audio, &model_a, &model_b)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Transcribes a history entry's recording again with speaker labels and
+/// stores the labeled text in the entry.
+#[tauri::command]
+pub async fn label_history_speakers(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+    id: i64,
+) -> Result<String, String> {
+    let entry = history_manager
+        .get_entry_by_id(id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("History entry {} not found", id))?;
+    let audio = read_wav_file(history_manager.get_audio_file_path(&entry.file_name))
+        .map_err(|e| e.to_string())?;
+
+    let text = transcription_manager
+        .transcribe_with_speakers(audio)
+        .await
+        .map_err(|e| e.to_string())?;
+    if text.is_empty() {
+        return Err("No speech found in the recording".to_string());
+    }
+    history_manager
+        .update_transcription_text(id, text.clone())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(text)
+}
+
+/// Formats a history entry's raw transcript again with the preset `preset`,
+/// or the global settings when `None`, and stores the result in the entry.
+/// Nothing is transcribed, so it's a cheap way to try out presets.
+#[tauri::command]
+pub async fn reprocess_entry(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+    id: i64,
+    preset: Option<String>,
+) -> Result<String, String> {
+    let entry = history_manager
+        .get_entry_by_id(id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("History entry {} not found", id))?;
+    let raw = history_manager
+        .get_raw_text(id)
+        .await
+        .map_err(|e| e.to_string())?
+        .unwrap_or(entry.transcription_text);
+
+    let text = transcription_manager
+        .reprocess(raw.clone(), preset.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
+    history_manager
+        .update_reprocessed_text(id, &raw, &text)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(text)
+}
+
+/// Recordings waiting to be transcribed or being transcribed, in order.
+#[tauri::command]
+pub fn get_transcription_queue(
+    transcription_queue: State<'_, Arc<TranscriptionQueue>>,
+) -> Result<Vec<TranscriptionJob>, String> {
+    Ok(transcription_queue.jobs())
+}
+
+/// Stops the running transcription, returning whether one was running.
+#[tauri::command]
+pub fn cancel_transcription(
+    transcription_queue: State<'_, Arc<TranscriptionQueue>>,
+) -> Result<bool, String> {
+    Ok(transcription_queue.cancel_running())
+}
+
+#[tauri::command]
+pub fn set_queue_when_offline(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.queue_when_offline = enabled;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_offline_queue_length(
+    offline_queue: State<'_, Arc<OfflineQueueManager>>,
+) -> Result<usize, String> {
+    Ok(offline_queue.queued_count())
+}
+
+/// Tries to deliver queued recordings now instead of waiting for the next
+/// retry.
+#[tauri::command]
+pub async fn retry_offline_queue(
+    offline_queue: State<'_, Arc<OfflineQueueManager>>,
+) -> Result<usize, String> {
+    offline_queue.drain().await;
+    Ok(offline_queue.queued_count())
+}
+
+/// Reads the text in the clipboard image and post-processes it like a
+/// transcript, without pasting it.
+#[tauri::command]
+pub async fn ocr_clipboard_image(
+    app: AppHandle,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+) -> Result<String, String> {
+    let ocr_app = app.clone();
+    let text = tauri::async_runtime::spawn_blocking(move || {
+        crate::ocr::recognize_clipboard_image(&ocr_app)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())?;
+    Ok(transcription_manager.post_process_text(text))
+}
+
+#[tauri::command]
+pub fn set_ocr_language(app: AppHandle, language: String) -> Result<(), String> {
+    let language = language.trim();
+    if language.is_empty() {
+        return Err("OCR language can't be empty".to_string());
+    }
+    let mut settings = get_settings(&app);
+    settings.ocr_language = language.to_string();
+    write_settings(&app, settings);
+    Ok(())
+}
+
+/// Reads the last transcript aloud.
+#[tauri::command]
+pub fn read_back_last_transcript(
+    app: AppHandle,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+) -> Result<(), String> {
+    let text = transcription_manager
+        .last_transcript()
+        .ok_or_else(|| "Nothing has been transcribed yet".to_string())?;
+    let voice = get_settings(&app).readback_voice;
+    crate::tts::speak(&text, voice.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn stop_read_back() {
+    crate::tts::stop();
+}
+
+#[tauri::command]
+pub fn set_readback_voice(app: AppHandle, voice: Option<String>) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.readback_voice = voice.filter(|v| !v.trim().is_empty());
+    write_settings(&app, settings);
+    Ok(())
+}
+
+/// Built-in presets merged with the user's own.
+#[tauri::command]
+pub fn get_post_process_presets(app: AppHandle) -> Vec<PostProcessPreset> {
+    get_settings(&app).presets()
+}
+
+/// Adds a preset, or replaces the one with the same id. Saving a built-in
+/// preset's id customizes it.
+#[tauri::command]
+pub fn save_post_process_preset(app: AppHandle, preset: PostProcessPreset) -> Result<(), String> {
+    if preset.id.trim().is_empty() || preset.name.trim().is_empty() {
+        return Err("Preset needs an id and a name".to_string());
+    }
+    let mut settings = get_settings(&app);
+    match settings
+        .post_process_presets
+        .iter_mut()
+        .find(|p| p.id == preset.id)
+    {
+        Some(existing) => *existing = preset,
+        None => settings.post_process_presets.push(preset),
+    }
+    write_settings(&app, settings);
+    Ok(())
+}
+
+/// Deletes a user preset, or resets a customized built-in one. Bindings
+/// left without a preset fall back to the active one.
+#[tauri::command]
+pub fn delete_post_process_preset(app: AppHandle, id: String) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    let before = settings.post_process_presets.len();
+    settings.post_process_presets.retain(|p| p.id != id);
+    if settings.post_process_presets.len() == before {
+        return Err(format!("No custom preset '{}'", id));
+    }
+    if !settings.presets().iter().any(|p| p.id == id) {
+        settings.binding_presets.retain(|_, preset| preset != &id);
+        if settings.active_preset.as_deref() == Some(id.as_str()) {
+            settings.active_preset = None;
+        }
+    }
+    write_settings(&app, settings);
+    Ok(())
+}
+
+fn check_preset_exists(settings: &AppSettings, id: &str) -> Result<(), String> {
+    if settings.presets().iter().any(|p| p.id == id) {
+        Ok(())
+    } else {
+        Err(format!("Unknown preset '{}'", id))
+    }
+}
+
+#[tauri::command]
+pub fn set_active_preset(app: AppHandle, id: Option<String>) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    if let Some(id) = &id {
+        check_preset_exists(&settings, id)?;
+    }
+    settings.active_preset = id;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+/// Assigns a preset to a binding, `None` to use the active preset.
+#[tauri::command]
+pub fn set_binding_preset(
+    app: AppHandle,
+    binding_id: String,
+    id: Option<String>,
+) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    match id {
+        Some(id) => {
+            check_preset_exists(&settings, &id)?;
+            settings.binding_presets.insert(binding_id, id);
+        }
+        None => {
+            settings.binding_presets.remove(&binding_id);
+        }
+    }
+    write_settings(&app, settings);
+    Ok(())
+}
+
+/// Watches `path` for audio files, `None` stops watching.
+#[tauri::command]
+pub fn set_watch_folder(
+    app: AppHandle,
+    path: Option<String>,
+    model: Option<String>,
+    speaker_labels: Option<bool>,
+) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.watch_folder = match path {
+        Some(path) => {
+            if !std::path::Path::new(&path).is_dir() {
+                return Err(format!("Not a folder: {}", path));
+            }
+            Some(WatchFolderConfig {
+                path,
+                model,
+                speaker_labels: speaker_labels.unwrap_or(false),
+            })
+        }
+        None => None,
+    };
+    write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn retry_watch_folder_failures(watch_folder: State<'_, Arc<WatchFolderManager>>) {
+    watch_folder.retry_failed();
+}
+
+/// Sets up or, with `None`, turns off transcribing emailed memos. The
+/// password goes to the system keychain; without one the stored password is
+/// kept.
+#[tauri::command]
+pub fn set_email_ingest(
+    app: AppHandle,
+    email_ingest: State<'_, Arc<EmailIngestManager>>,
+    config: Option<EmailIngestConfig>,
+    password: Option<String>,
+) -> Result<(), String> {
+    if let (Some(config), Some(password)) = (&config, &password) {
+        EmailIngestManager::set_password(config, password)
+            .map_err(|e| format!("Failed to store the password: {}", e))?;
+    }
+    let mut settings = get_settings(&app);
+    settings.email_ingest = config;
+    write_settings(&app, settings);
+    email_ingest.reset();
+    Ok(())
+}
+
+/// Checks the memo mailbox now, returning how many memos were transcribed.
+#[tauri::command]
+pub async fn check_email_ingest(
+    email_ingest: State<'_, Arc<EmailIngestManager>>,
+) -> Result<usize, String> {
+    let email_ingest = email_ingest.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || email_ingest.poll())
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
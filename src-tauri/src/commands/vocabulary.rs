@@ -0,0 +1,69 @@
+use crate::managers::vocabulary::{SharedVocabulary, VocabularyManager};
+use crate::settings::{get_settings, write_settings};
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn set_shared_vocabulary_url(
+    app: AppHandle,
+    vocabulary_manager: State<'_, Arc<VocabularyManager>>,
+    url: Option<String>,
+) -> Result<SharedVocabulary, String> {
+    let url = url.filter(|u| !u.trim().is_empty());
+    if let Some(url) = &url {
+        if !url.starts_with("https://") && !url.starts_with("http://") {
+            return Err(format!("Invalid shared vocabulary URL: {}", url));
+        }
+    }
+
+    let mut settings = get_settings(&app);
+    settings.shared_vocabulary_url = url;
+    write_settings(&app, settings);
+
+    vocabulary_manager
+        .refresh()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_shared_vocabulary(
+    vocabulary_manager: State<'_, Arc<VocabularyManager>>,
+) -> Result<SharedVocabulary, String> {
+    Ok(vocabulary_manager.get_shared())
+}
+
+#[tauri::command]
+pub async fn refresh_shared_vocabulary(
+    vocabulary_manager: State<'_, Arc<VocabularyManager>>,
+) -> Result<SharedVocabulary, String> {
+    vocabulary_manager
+        .refresh()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_session_words(
+    vocabulary_manager: State<'_, Arc<VocabularyManager>>,
+) -> Result<Vec<String>, String> {
+    Ok(vocabulary_manager.session_words())
+}
+
+/// Sets custom words for this session only, on top of the saved ones. An
+/// empty list resets them.
+#[tauri::command]
+pub fn set_session_words(
+    vocabulary_manager: State<'_, Arc<VocabularyManager>>,
+    words: Vec<String>,
+) -> Result<Vec<String>, String> {
+    Ok(vocabulary_manager.set_session_words(words))
+}
+
+#[tauri::command]
+pub fn clear_session_words(
+    vocabulary_manager: State<'_, Arc<VocabularyManager>>,
+) -> Result<(), String> {
+    vocabulary_manager.set_session_words(Vec::new());
+    Ok(())
+}
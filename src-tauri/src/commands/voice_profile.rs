@@ -0,0 +1,60 @@
+use crate::managers::voice_profile::{CalibrationSample, VoiceProfileManager};
+use crate::settings::{get_settings, write_settings, VoiceProfile};
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+
+/// Starts recording a calibration sentence.
+#[tauri::command]
+pub fn start_calibration_sample(
+    voice_profile_manager: State<'_, Arc<VoiceProfileManager>>,
+) -> Result<(), String> {
+    voice_profile_manager
+        .start_sample()
+        .map_err(|e| e.to_string())
+}
+
+/// Stops recording and compares the result with the sentence that was read.
+#[tauri::command]
+pub async fn finish_calibration_sample(
+    voice_profile_manager: State<'_, Arc<VoiceProfileManager>>,
+    expected: String,
+) -> Result<CalibrationSample, String> {
+    voice_profile_manager
+        .finish_sample(&expected)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_calibration_samples(
+    voice_profile_manager: State<'_, Arc<VoiceProfileManager>>,
+) -> Result<Vec<CalibrationSample>, String> {
+    Ok(voice_profile_manager.samples())
+}
+
+#[tauri::command]
+pub fn clear_calibration_samples(
+    voice_profile_manager: State<'_, Arc<VoiceProfileManager>>,
+) -> Result<(), String> {
+    voice_profile_manager.clear_samples();
+    Ok(())
+}
+
+/// Learns keywords from the recorded samples.
+#[tauri::command]
+pub fn build_voice_profile(
+    voice_profile_manager: State<'_, Arc<VoiceProfileManager>>,
+) -> Result<VoiceProfile, String> {
+    voice_profile_manager
+        .build_profile()
+        .map_err(|e| e.to_string())
+}
+
+/// Saves a profile edited by the user, e.g. with keywords removed.
+#[tauri::command]
+pub fn set_voice_profile(app: AppHandle, profile: VoiceProfile) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.voice_profile = profile;
+    write_settings(&app, settings);
+    Ok(())
+}
@@ -0,0 +1,452 @@
+//! Crash reports kept on this machine. Panics are written by a panic hook;
+//! native crashes, e.g. inside whisper.cpp, are captured as minidumps by a
+//! small monitor process, since a crashing process can't be trusted to write
+//! them itself. Nothing is sent anywhere unless the user submits a report to
+//! a server they configured.
+
+use crate::settings::get_settings;
+use anyhow::Result;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+const CRASH_DIR: &str = "crashes";
+/// Log lines kept for a report.
+const LOG_TAIL_LINES: usize = 200;
+/// Starts the process as the minidump monitor instead of the app.
+const MONITOR_ARG: &str = "--crash-monitor";
+const MONITOR_CONNECT_ATTEMPTS: u32 = 50;
+
+/// Message kinds sent to the monitor.
+const MSG_METADATA: u32 = 1;
+const MSG_LOG_LINE: u32 = 2;
+
+static LOG_TAIL: Lazy<Mutex<VecDeque<String>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+static METADATA: Lazy<Mutex<CrashMetadata>> = Lazy::new(|| Mutex::new(CrashMetadata::current()));
+static MONITOR: OnceCell<minidumper::Client> = OnceCell::new();
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CrashKind {
+    Panic,
+    Native,
+}
+
+/// What was running when the crash happened.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct CrashMetadata {
+    app_version: String,
+    os: String,
+    arch: String,
+    model: Option<String>,
+}
+
+impl CrashMetadata {
+    fn current() -> Self {
+        Self {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            model: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub id: String,
+    pub timestamp: i64,
+    pub kind: CrashKind,
+    pub message: Option<String>,
+    pub location: Option<String>,
+    pub backtrace: Option<String>,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub model: Option<String>,
+    pub log_tail: Vec<String>,
+    /// File name of the minidump next to the report, native crashes only.
+    pub minidump: Option<String>,
+    #[serde(default)]
+    pub submitted: bool,
+}
+
+/// Time of the crash plus process id, unique enough across app runs.
+fn new_report_id() -> String {
+    format!(
+        "{}-{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S"),
+        std::process::id()
+    )
+}
+
+impl CrashReport {
+    fn new(kind: CrashKind, metadata: CrashMetadata, log_tail: Vec<String>) -> Self {
+        Self {
+            id: new_report_id(),
+            timestamp: chrono::Local::now().timestamp(),
+            kind,
+            message: None,
+            location: None,
+            backtrace: None,
+            app_version: metadata.app_version,
+            os: metadata.os,
+            arch: metadata.arch,
+            model: metadata.model,
+            log_tail,
+            minidump: None,
+            submitted: false,
+        }
+    }
+}
+
+/// Forwards to env_logger as before and keeps the recent info-level and
+/// above lines for crash reports, whatever `RUST_LOG` says. Transcripts must
+/// only ever be logged below info, or they'd end up in reports.
+struct TailLogger {
+    inner: env_logger::Logger,
+}
+
+impl Log for TailLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Info || self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.inner.matches(record) {
+            self.inner.log(record);
+        }
+        if record.level() > Level::Info {
+            return;
+        }
+        let line = format!(
+            "{} {} {}: {}",
+            chrono::Local::now().format("%H:%M:%S%.3f"),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        if let Some(client) = MONITOR.get() {
+            let _ = client.send_message(MSG_LOG_LINE, line.as_bytes());
+        }
+        if let Ok(mut tail) = LOG_TAIL.lock() {
+            push_line(&mut tail, line);
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+fn push_line(tail: &mut VecDeque<String>, line: String) {
+    if tail.len() == LOG_TAIL_LINES {
+        tail.pop_front();
+    }
+    tail.push_back(line);
+}
+
+/// Replaces `env_logger::init`, so crash reports can include recent logs.
+pub fn init_logging() {
+    let inner = env_logger::Builder::from_default_env().build();
+    let max_level = inner.filter().max(LevelFilter::Info);
+    if log::set_boxed_logger(Box::new(TailLogger { inner })).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+pub fn crash_dir(app: &AppHandle) -> Result<PathBuf> {
+    Ok(app.path().app_data_dir()?.join(CRASH_DIR))
+}
+
+/// Records the loaded model for later reports.
+pub fn set_model(model: Option<&str>) {
+    let metadata = {
+        let Ok(mut metadata) = METADATA.lock() else {
+            return;
+        };
+        metadata.model = model.map(str::to_string);
+        metadata.clone()
+    };
+    send_metadata(&metadata);
+}
+
+fn send_metadata(metadata: &CrashMetadata) {
+    if let (Some(client), Ok(json)) = (MONITOR.get(), serde_json::to_vec(metadata)) {
+        let _ = client.send_message(MSG_METADATA, json);
+    }
+}
+
+/// Installs the panic hook and starts the minidump monitor. Failing to start
+/// the monitor only loses native crash capture.
+pub fn install(app: &AppHandle) {
+    let dir = match crash_dir(app) {
+        Ok(dir) => dir,
+        Err(e) => {
+            log::warn!("Crash reports disabled: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = fs::create_dir_all(&dir) {
+        log::warn!(
+            "Crash reports disabled, can't create {}: {}",
+            dir.display(),
+            e
+        );
+        return;
+    }
+
+    install_panic_hook(dir.clone());
+    if let Err(e) = start_monitor(&dir) {
+        log::warn!("Native crashes won't be captured: {}", e);
+    }
+}
+
+fn install_panic_hook(dir: PathBuf) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        // The panicking thread may hold these locks, so never wait on them
+        let metadata = METADATA
+            .try_lock()
+            .map(|m| m.clone())
+            .unwrap_or_else(|_| CrashMetadata::current());
+        let log_tail = LOG_TAIL
+            .try_lock()
+            .map(|tail| tail.iter().cloned().collect())
+            .unwrap_or_default();
+
+        let mut report = CrashReport::new(CrashKind::Panic, metadata, log_tail);
+        report.message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned());
+        report.location = info.location().map(|l| l.to_string());
+        report.backtrace = Some(std::backtrace::Backtrace::force_capture().to_string());
+        if let Err(e) = write_report(&dir, &report) {
+            eprintln!("Failed to write crash report: {}", e);
+        }
+
+        previous(info);
+    }));
+}
+
+/// Launches this executable as the monitor and hooks native crash signals
+/// up to it.
+fn start_monitor(dir: &Path) -> Result<()> {
+    let socket_name = format!("handy-crash-{}", std::process::id());
+    Command::new(std::env::current_exe()?)
+        .arg(MONITOR_ARG)
+        .arg(&socket_name)
+        .arg(dir)
+        .spawn()?;
+
+    let mut attempts = 0;
+    let client = loop {
+        match minidumper::Client::with_name(socket_name.as_str()) {
+            Ok(client) => break client,
+            Err(e) if attempts >= MONITOR_CONNECT_ATTEMPTS => {
+                return Err(anyhow::anyhow!("Crash monitor didn't start: {}", e))
+            }
+            Err(_) => {
+                attempts += 1;
+                thread::sleep(Duration::from_millis(100));
+            }
+        }
+    };
+    let _ = MONITOR.set(client);
+    if let Ok(metadata) = METADATA.lock() {
+        send_metadata(&metadata);
+    }
+
+    let handler = crash_handler::CrashHandler::attach(unsafe {
+        crash_handler::make_crash_event(move |context: &crash_handler::CrashContext| {
+            let dumped = MONITOR
+                .get()
+                .map(|client| client.request_dump(context).is_ok())
+                .unwrap_or(false);
+            crash_handler::CrashEventResult::Handled(dumped)
+        })
+    })?;
+    // Stays attached until the process exits
+    std::mem::forget(handler);
+    Ok(())
+}
+
+/// Runs the minidump monitor when the process was started as one and
+/// returns whether it did, in which case the app must not start.
+pub fn run_monitor_if_requested() -> bool {
+    let args: Vec<String> = std::env::args().collect();
+    let [_, flag, socket_name, dir] = args.as_slice() else {
+        return false;
+    };
+    if flag != MONITOR_ARG {
+        return false;
+    }
+
+    let handler = MonitorHandler {
+        dir: PathBuf::from(dir),
+        metadata: Mutex::new(CrashMetadata::current()),
+        log_tail: Mutex::new(VecDeque::new()),
+        minidump_id: Mutex::new(None),
+    };
+    let shutdown = std::sync::atomic::AtomicBool::new(false);
+    match minidumper::Server::with_name(socket_name.as_str()) {
+        Ok(mut server) => {
+            if let Err(e) = server.run(Box::new(handler), &shutdown, None) {
+                eprintln!("Crash monitor stopped: {}", e);
+            }
+        }
+        Err(e) => eprintln!("Failed to start crash monitor: {}", e),
+    }
+    true
+}
+
+/// The monitor side: collects metadata and log lines from the app and turns
+/// a dump request into a minidump plus report.
+struct MonitorHandler {
+    dir: PathBuf,
+    metadata: Mutex<CrashMetadata>,
+    log_tail: Mutex<VecDeque<String>>,
+    minidump_id: Mutex<Option<String>>,
+}
+
+impl minidumper::ServerHandler for MonitorHandler {
+    fn create_minidump_file(&self) -> Result<(fs::File, PathBuf), std::io::Error> {
+        let id = new_report_id();
+        let path = self.dir.join(format!("{}.dmp", id));
+        let file = fs::File::create(&path)?;
+        *self.minidump_id.lock().unwrap() = Some(id);
+        Ok((file, path))
+    }
+
+    fn on_minidump_created(
+        &self,
+        result: Result<minidumper::MinidumpBinary, minidumper::Error>,
+    ) -> minidumper::LoopAction {
+        match result {
+            Ok(binary) => {
+                let metadata = self.metadata.lock().unwrap().clone();
+                let log_tail = self.log_tail.lock().unwrap().iter().cloned().collect();
+                let mut report = CrashReport::new(CrashKind::Native, metadata, log_tail);
+                if let Some(id) = self.minidump_id.lock().unwrap().take() {
+                    report.id = id;
+                }
+                report.minidump = binary
+                    .path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string());
+                if let Err(e) = write_report(&self.dir, &report) {
+                    eprintln!("Failed to write crash report: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Failed to write minidump: {}", e),
+        }
+        // The app is gone after a native crash
+        minidumper::LoopAction::Exit
+    }
+
+    fn on_message(&self, kind: u32, buffer: Vec<u8>) {
+        match kind {
+            MSG_METADATA => {
+                if let Ok(metadata) = serde_json::from_slice(&buffer) {
+                    *self.metadata.lock().unwrap() = metadata;
+                }
+            }
+            MSG_LOG_LINE => {
+                let line = String::from_utf8_lossy(&buffer).to_string();
+                push_line(&mut self.log_tail.lock().unwrap(), line);
+            }
+            _ => {}
+        }
+    }
+
+    fn on_client_disconnected(&self, _num_clients: usize) -> minidumper::LoopAction {
+        minidumper::LoopAction::Exit
+    }
+}
+
+fn write_report(dir: &Path, report: &CrashReport) -> Result<()> {
+    let json = serde_json::to_string_pretty(report)?;
+    fs::write(dir.join(format!("{}.json", report.id)), json)?;
+    Ok(())
+}
+
+/// Stored reports, newest first.
+pub fn list_reports(app: &AppHandle) -> Result<Vec<CrashReport>> {
+    let dir = crash_dir(app)?;
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Ok(Vec::new());
+    };
+    let mut reports: Vec<CrashReport> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|e| serde_json::from_slice(&fs::read(e.path()).ok()?).ok())
+        .collect();
+    reports.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    Ok(reports)
+}
+
+fn load_report(dir: &Path, id: &str) -> Result<CrashReport> {
+    if id.contains(['/', '\\']) {
+        return Err(anyhow::anyhow!("Invalid crash report id: {}", id));
+    }
+    let data = fs::read(dir.join(format!("{}.json", id)))
+        .map_err(|_| anyhow::anyhow!("Crash report not found: {}", id))?;
+    Ok(serde_json::from_slice(&data)?)
+}
+
+pub fn delete_report(app: &AppHandle, id: &str) -> Result<()> {
+    let dir = crash_dir(app)?;
+    let report = load_report(&dir, id)?;
+    if let Some(minidump) = &report.minidump {
+        let _ = fs::remove_file(dir.join(minidump));
+    }
+    fs::remove_file(dir.join(format!("{}.json", id)))?;
+    Ok(())
+}
+
+/// The report exactly as [`submit_report`] uploads it, next to the minidump
+/// if there is one, so the user can see what they're sending.
+pub fn report_payload(app: &AppHandle, id: &str) -> Result<String> {
+    let report = load_report(&crash_dir(app)?, id)?;
+    Ok(serde_json::to_string_pretty(&report)?)
+}
+
+/// Uploads a report and its minidump to the configured crash report server.
+/// Only called on an explicit request from the user.
+pub async fn submit_report(app: &AppHandle, id: &str) -> Result<()> {
+    let url = get_settings(app)
+        .crash_report_url
+        .ok_or_else(|| anyhow::anyhow!("No crash report server is configured"))?;
+    let dir = crash_dir(app)?;
+    let mut report = load_report(&dir, id)?;
+
+    let mut form = reqwest::multipart::Form::new().text("report", report_payload(app, id)?);
+    if let Some(minidump) = &report.minidump {
+        let data = fs::read(dir.join(minidump))?;
+        form = form.part(
+            "minidump",
+            reqwest::multipart::Part::bytes(data).file_name(minidump.clone()),
+        );
+    }
+    reqwest::Client::new()
+        .post(&url)
+        .multipart(form)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    report.submitted = true;
+    write_report(&dir, &report)?;
+    log::info!("Submitted crash report {}", id);
+    Ok(())
+}
@@ -0,0 +1,229 @@
+//! Tells the speakers of a transcribed recording apart, so multi-speaker
+//! files come out as "Speaker 1: / Speaker 2:" turns.
+//!
+//! Every transcript segment gets a voice print, the shape of its average
+//! log-mel spectrum and how much each band varies, and segments with close
+//! prints are grouped into speakers. That separates clearly different
+//! voices without another model to download, but similar voices may end up
+//! as one speaker.
+
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use crate::audio_toolkit::TimedSegment;
+use rustfft::{num_complex::Complex32, FftPlanner};
+
+/// 32 ms analysis window with a 10 ms hop.
+const FFT_SIZE: usize = 512;
+const HOP_SIZE: usize = 160;
+const MEL_BANDS: usize = 40;
+const MIN_HZ: f32 = 60.0;
+const MAX_HZ: f32 = 7600.0;
+/// Frames quieter than this are pauses and say nothing about the voice.
+const SILENCE_POWER: f32 = 1e-6;
+/// Shorter segments hold too little voice for a print and are given to the
+/// speaker before them.
+const MIN_SEGMENT_SECS: f32 = 0.8;
+const MIN_VOICED_FRAMES: usize = 20;
+/// Groups whose prints are closer than this, as the RMS difference in
+/// nats, are the same speaker.
+const SAME_SPEAKER_DISTANCE: f32 = 0.9;
+/// Groups are merged further even when distinct once there are more.
+const MAX_SPEAKERS: usize = 6;
+
+/// Consecutive segments of one speaker. Speakers are numbered from 1 in the
+/// order they first speak.
+#[derive(Clone, Debug)]
+pub struct SpeakerTurn {
+    pub speaker: usize,
+    pub text: String,
+}
+
+/// Labels every segment of `audio` with its speaker and merges consecutive
+/// segments of the same speaker into turns. Segment times are in seconds.
+pub fn speaker_turns(audio: &[f32], segments: &[TimedSegment]) -> Vec<SpeakerTurn> {
+    let mel_filters = mel_filters();
+    let prints: Vec<Option<Vec<f32>>> = segments
+        .iter()
+        .map(|segment| {
+            if segment.end - segment.start < MIN_SEGMENT_SECS {
+                return None;
+            }
+            let start = (segment.start * WHISPER_SAMPLE_RATE as f32) as usize;
+            let end = ((segment.end * WHISPER_SAMPLE_RATE as f32) as usize).min(audio.len());
+            voice_print(audio.get(start..end)?, &mel_filters)
+        })
+        .collect();
+
+    let groups = cluster(&prints);
+    let mut previous = groups.iter().flatten().next().copied().unwrap_or(0);
+    let mut order: Vec<usize> = Vec::new();
+    let mut turns: Vec<SpeakerTurn> = Vec::new();
+    for (segment, group) in segments.iter().zip(groups) {
+        let group = group.unwrap_or(previous);
+        previous = group;
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        let speaker = match order.iter().position(|&g| g == group) {
+            Some(index) => index + 1,
+            None => {
+                order.push(group);
+                order.len()
+            }
+        };
+        match turns.last_mut() {
+            Some(turn) if turn.speaker == speaker => {
+                turn.text.push(' ');
+                turn.text.push_str(text);
+            }
+            _ => turns.push(SpeakerTurn {
+                speaker,
+                text: text.to_string(),
+            }),
+        }
+    }
+    turns
+}
+
+/// One "Speaker N: ..." paragraph per turn.
+pub fn format_turns(turns: &[SpeakerTurn]) -> String {
+    turns
+        .iter()
+        .map(|turn| format!("Speaker {}: {}", turn.speaker, turn.text))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Mean and standard deviation of each log-mel band over the voiced frames.
+/// Every frame is shifted to a zero mean first, so loudness doesn't count.
+fn voice_print(samples: &[f32], mel_filters: &[Vec<(usize, f32)>]) -> Option<Vec<f32>> {
+    if samples.len() < FFT_SIZE {
+        return None;
+    }
+    let fft = FftPlanner::<f32>::new().plan_fft_forward(FFT_SIZE);
+    let window: Vec<f32> = (0..FFT_SIZE)
+        .map(|i| 0.5 * (1.0 - (2.0 * std::f32::consts::PI * i as f32 / FFT_SIZE as f32).cos()))
+        .collect();
+
+    let mut buffer = vec![Complex32::new(0.0, 0.0); FFT_SIZE];
+    let mut frames: Vec<Vec<f32>> = Vec::new();
+    for frame in samples.windows(FFT_SIZE).step_by(HOP_SIZE) {
+        let power = frame.iter().map(|s| s * s).sum::<f32>() / FFT_SIZE as f32;
+        if power < SILENCE_POWER {
+            continue;
+        }
+        for ((b, s), w) in buffer.iter_mut().zip(frame).zip(&window) {
+            *b = Complex32::new(s * w, 0.0);
+        }
+        fft.process(&mut buffer);
+
+        let mut bands: Vec<f32> = mel_filters
+            .iter()
+            .map(|filter| {
+                filter
+                    .iter()
+                    .map(|&(bin, weight)| buffer[bin].norm_sqr() * weight)
+                    .sum::<f32>()
+                    .max(1e-10)
+                    .ln()
+            })
+            .collect();
+        let mean = bands.iter().sum::<f32>() / MEL_BANDS as f32;
+        bands.iter_mut().for_each(|b| *b -= mean);
+        frames.push(bands);
+    }
+    if frames.len() < MIN_VOICED_FRAMES {
+        return None;
+    }
+
+    let count = frames.len() as f32;
+    let mut print = vec![0.0; MEL_BANDS * 2];
+    for band in 0..MEL_BANDS {
+        let mean = frames.iter().map(|f| f[band]).sum::<f32>() / count;
+        let variance = frames.iter().map(|f| (f[band] - mean).powi(2)).sum::<f32>() / count;
+        print[band] = mean;
+        print[MEL_BANDS + band] = variance.sqrt();
+    }
+    Some(print)
+}
+
+/// Triangular mel filters as (FFT bin, weight) pairs.
+fn mel_filters() -> Vec<Vec<(usize, f32)>> {
+    let to_mel = |hz: f32| 2595.0 * (1.0 + hz / 700.0).log10();
+    let to_hz = |mel: f32| 700.0 * (10f32.powf(mel / 2595.0) - 1.0);
+    let (min_mel, max_mel) = (to_mel(MIN_HZ), to_mel(MAX_HZ));
+    let bin_hz = WHISPER_SAMPLE_RATE as f32 / FFT_SIZE as f32;
+    let edges: Vec<f32> = (0..MEL_BANDS + 2)
+        .map(|i| to_hz(min_mel + (max_mel - min_mel) * i as f32 / (MEL_BANDS + 1) as f32) / bin_hz)
+        .collect();
+
+    (0..MEL_BANDS)
+        .map(|band| {
+            let (low, center, high) = (edges[band], edges[band + 1], edges[band + 2]);
+            (low.ceil() as usize..=high.floor() as usize)
+                .filter_map(|bin| {
+                    let position = bin as f32;
+                    let weight = if position <= center {
+                        (position - low) / (center - low).max(f32::EPSILON)
+                    } else {
+                        (high - position) / (high - center).max(f32::EPSILON)
+                    };
+                    (weight > 0.0 && bin <= FFT_SIZE / 2).then_some((bin, weight))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Average-linkage clustering of the prints. Returns a group per print,
+/// `None` where there was no print.
+fn cluster(prints: &[Option<Vec<f32>>]) -> Vec<Option<usize>> {
+    let present: Vec<usize> = (0..prints.len()).filter(|&i| prints[i].is_some()).collect();
+    let n = present.len();
+    let mut distances = vec![vec![0.0f32; n]; n];
+    for a in 0..n {
+        for b in a + 1..n {
+            let (pa, pb) = (
+                prints[present[a]].as_ref().unwrap(),
+                prints[present[b]].as_ref().unwrap(),
+            );
+            let sum = pa.iter().zip(pb).map(|(x, y)| (x - y).powi(2)).sum::<f32>();
+            let distance = (sum / pa.len() as f32).sqrt();
+            distances[a][b] = distance;
+            distances[b][a] = distance;
+        }
+    }
+
+    let mut groups: Vec<Vec<usize>> = (0..n).map(|i| vec![i]).collect();
+    while groups.len() > 1 {
+        let mut closest = (0, 1, f32::MAX);
+        for a in 0..groups.len() {
+            for b in a + 1..groups.len() {
+                let total: f32 = groups[a]
+                    .iter()
+                    .flat_map(|&x| groups[b].iter().map(move |&y| (x, y)))
+                    .map(|(x, y)| distances[x][y])
+                    .sum();
+                let average = total / (groups[a].len() * groups[b].len()) as f32;
+                if average < closest.2 {
+                    closest = (a, b, average);
+                }
+            }
+        }
+        let (a, b, distance) = closest;
+        if distance > SAME_SPEAKER_DISTANCE && groups.len() <= MAX_SPEAKERS {
+            break;
+        }
+        let merged = groups.remove(b);
+        groups[a].extend(merged);
+    }
+
+    let mut result = vec![None; prints.len()];
+    for (group, members) in groups.iter().enumerate() {
+        for &member in members {
+            result[present[member]] = Some(group);
+        }
+    }
+    result
+}
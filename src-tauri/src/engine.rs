@@ -0,0 +1,526 @@
+//! Local speech recognition engines behind one interface, so the
+//! transcription manager doesn't need to know which library runs a model.
+//! Adding an engine means implementing [`SpeechEngine`] and a [`load`] arm.
+
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use crate::audio_toolkit::is_hallucination;
+use crate::managers::model::EngineType;
+use crate::settings::{WhisperFallback, WhisperSampling};
+use crate::vosk::VoskEngine;
+use anyhow::Result;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use transcribe_rs::{
+    engines::{
+        moonshine::{ModelVariant, MoonshineEngine, MoonshineModelParams},
+        parakeet::{
+            ParakeetEngine, ParakeetInferenceParams, ParakeetModelParams, TimestampGranularity,
+        },
+    },
+    TranscriptionEngine, TranscriptionResult, TranscriptionSegment,
+};
+use whisper_rs::{
+    FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperSegment,
+    WhisperState,
+};
+
+/// Whisper's no-speech probability above which a segment that reads like a
+/// known hallucination is dropped.
+const HALLUCINATION_NO_SPEECH: f32 = 0.5;
+/// No-speech threshold when none is set. transcribe-rs decoded Whisper with
+/// 0.2 rather than whisper.cpp's 0.6, keep transcripts as they were.
+const DEFAULT_NO_SPEECH_THRESHOLD: f32 = 0.2;
+
+/// How to decode one transcription. Engines ignore what they don't support.
+#[derive(Clone, Debug, Default)]
+pub struct DecodeOptions {
+    /// `None` detects the language.
+    pub language: Option<String>,
+    pub translate: bool,
+    pub initial_prompt: Option<String>,
+    pub no_speech_threshold: Option<f32>,
+    pub suppress_blank: Option<bool>,
+    /// Keep Whisper from writing out non-speech like "[Music]" or "(sighs)".
+    /// `None` suppresses it, as transcribe-rs did.
+    pub suppress_non_speech: Option<bool>,
+    /// Threads to decode with, `None` for the engine's default.
+    pub threads: Option<u32>,
+    /// Decode below normal thread priority.
+    pub low_priority: bool,
+    pub sampling: WhisperSampling,
+    pub fallback: WhisperFallback,
+    /// Drop segments that are likely silence and read like text Whisper
+    /// makes up from it.
+    pub filter_hallucinations: bool,
+    /// Stops decoding early once set, for engines that can.
+    pub abort: Option<Arc<AtomicBool>>,
+}
+
+/// The transcription was cancelled before it finished.
+#[derive(Debug)]
+pub struct TranscriptionCancelled;
+
+impl std::fmt::Display for TranscriptionCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Transcription cancelled")
+    }
+}
+
+impl std::error::Error for TranscriptionCancelled {}
+
+/// A word with how sure the engine is that it heard it right, from 0 to 1.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScoredWord {
+    pub text: String,
+    pub confidence: f32,
+}
+
+/// How sure the engine is of a stretch of the transcript. Times are in
+/// seconds. Scores are for the engine's own text, before corrections and
+/// post-processing.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SegmentConfidence {
+    pub start: f32,
+    pub end: f32,
+    pub text: String,
+    pub confidence: f32,
+    pub words: Vec<ScoredWord>,
+}
+
+pub trait SpeechEngine: Send {
+    /// Backend the model runs on, e.g. `cuda` or `cpu`.
+    fn backend(&self) -> &str;
+
+    /// The only language the model transcribes, `None` for models that
+    /// follow the language setting.
+    fn fixed_language(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Transcribes 16 kHz mono samples.
+    fn transcribe(
+        &mut self,
+        audio: Vec<f32>,
+        options: &DecodeOptions,
+    ) -> Result<TranscriptionResult>;
+
+    /// Like [`SpeechEngine::transcribe`], with the confidence of every
+    /// segment for engines that report it.
+    fn transcribe_scored(
+        &mut self,
+        audio: Vec<f32>,
+        options: &DecodeOptions,
+    ) -> Result<(TranscriptionResult, Vec<SegmentConfidence>)> {
+        Ok((self.transcribe(audio, options)?, Vec::new()))
+    }
+
+    /// Frees the model ahead of dropping the engine.
+    fn unload(&mut self) {}
+}
+
+/// Loads a model with the engine it was made for. Whisper tries `backend`
+/// first and falls back to the CPU when the GPU can't take the model, e.g.
+/// because it ran out of memory; the other engines always run on the CPU.
+/// Returns why the GPU failed after a fallback.
+pub fn load(
+    engine_type: &EngineType,
+    model_path: &Path,
+    backend: &str,
+    english_only: bool,
+) -> Result<(Box<dyn SpeechEngine>, Option<String>)> {
+    match engine_type {
+        EngineType::Whisper => {
+            let (engine, gpu_error) = Whisper::load(model_path, backend, english_only)?;
+            Ok((Box::new(engine), gpu_error))
+        }
+        EngineType::Parakeet => {
+            let mut engine = ParakeetEngine::new();
+            engine
+                .load_model_with_params(model_path, ParakeetModelParams::int8())
+                .map_err(|e| anyhow::anyhow!("Failed to load parakeet model: {}", e))?;
+            Ok((Box::new(Parakeet(engine)), None))
+        }
+        EngineType::Moonshine => {
+            let mut engine = MoonshineEngine::new();
+            engine
+                .load_model_with_params(
+                    model_path,
+                    MoonshineModelParams::variant(ModelVariant::Base),
+                )
+                .map_err(|e| anyhow::anyhow!("Failed to load moonshine model: {}", e))?;
+            Ok((Box::new(Moonshine(engine)), None))
+        }
+        EngineType::Vosk => {
+            let engine = VoskEngine::load(model_path)
+                .map_err(|e| anyhow::anyhow!("Failed to load vosk model: {}", e))?;
+            Ok((Box::new(engine), None))
+        }
+    }
+}
+
+/// Runs on whisper.cpp directly rather than through transcribe-rs, which
+/// doesn't expose the token probabilities.
+struct Whisper {
+    context: WhisperContext,
+    state: WhisperState,
+    backend: String,
+    /// Distil-Whisper produces garbage for anything but English.
+    english_only: bool,
+}
+
+impl Whisper {
+    fn load(
+        model_path: &Path,
+        backend: &str,
+        english_only: bool,
+    ) -> Result<(Self, Option<String>)> {
+        let mut gpu_error = None;
+        if backend != "cpu" {
+            match Self::load_context(model_path, true) {
+                Ok((context, state)) => {
+                    let whisper = Self {
+                        context,
+                        state,
+                        backend: backend.to_string(),
+                        english_only,
+                    };
+                    return Ok((whisper, None));
+                }
+                Err(e) => {
+                    warn!(
+                        "Loading on {} failed, falling back to the CPU: {}",
+                        backend, e
+                    );
+                    gpu_error = Some(e.to_string());
+                }
+            }
+        }
+
+        let (context, state) = Self::load_context(model_path, false)?;
+        let whisper = Self {
+            context,
+            state,
+            backend: "cpu".to_string(),
+            english_only,
+        };
+        Ok((whisper, gpu_error))
+    }
+
+    fn load_context(model_path: &Path, use_gpu: bool) -> Result<(WhisperContext, WhisperState)> {
+        let path = model_path
+            .to_str()
+            .ok_or_else(|| anyhow::anyhow!("Invalid model path: {:?}", model_path))?;
+        let mut params = WhisperContextParameters::default();
+        params.use_gpu(use_gpu);
+        let context = WhisperContext::new_with_params(path, params)
+            .map_err(|e| anyhow::anyhow!("Failed to load whisper model: {}", e))?;
+        let state = context
+            .create_state()
+            .map_err(|e| anyhow::anyhow!("Failed to create whisper state: {}", e))?;
+        Ok((context, state))
+    }
+
+    /// Scores the tokens of `segment`. Tokens starting with a space start a
+    /// new word, and a word is only as sure as its least sure token.
+    fn segment_confidence(
+        &self,
+        segment: &WhisperSegment,
+        start: f32,
+        end: f32,
+        text: &str,
+    ) -> Result<SegmentConfidence> {
+        let eot = self.context.token_eot();
+        let mut words: Vec<(Vec<u8>, f32)> = Vec::new();
+        for token in (0..segment.n_tokens()).filter_map(|i| segment.get_token(i)) {
+            // Timestamps and other special tokens aren't text
+            if token.token_id() >= eot {
+                continue;
+            }
+            // Raw bytes, as a character can be split across tokens
+            let bytes = token.to_bytes()?;
+            let probability = token.token_probability();
+            match words.last_mut() {
+                Some((word, confidence)) if !bytes.starts_with(b" ") => {
+                    word.extend_from_slice(bytes);
+                    *confidence = confidence.min(probability);
+                }
+                _ => words.push((bytes.to_vec(), probability)),
+            }
+        }
+
+        let words: Vec<ScoredWord> = words
+            .into_iter()
+            .map(|(bytes, confidence)| ScoredWord {
+                text: String::from_utf8_lossy(&bytes).trim().to_string(),
+                confidence,
+            })
+            .filter(|word| !word.text.is_empty())
+            .collect();
+        let confidence = if words.is_empty() {
+            0.0
+        } else {
+            words.iter().map(|w| w.confidence).sum::<f32>() / words.len() as f32
+        };
+        Ok(SegmentConfidence {
+            start,
+            end,
+            text: text.trim().to_string(),
+            confidence,
+            words,
+        })
+    }
+}
+
+impl SpeechEngine for Whisper {
+    fn backend(&self) -> &str {
+        &self.backend
+    }
+
+    fn fixed_language(&self) -> Option<&'static str> {
+        self.english_only.then_some("en")
+    }
+
+    fn transcribe(
+        &mut self,
+        audio: Vec<f32>,
+        options: &DecodeOptions,
+    ) -> Result<TranscriptionResult> {
+        self.transcribe_scored(audio, options)
+            .map(|(result, _)| result)
+    }
+
+    fn transcribe_scored(
+        &mut self,
+        audio: Vec<f32>,
+        options: &DecodeOptions,
+    ) -> Result<(TranscriptionResult, Vec<SegmentConfidence>)> {
+        let (language, translate) = if self.english_only {
+            (Some("en"), false)
+        } else {
+            (options.language.as_deref(), options.translate)
+        };
+        // The params hold raw pointers, so they're built on the thread that
+        // decodes
+        let decode = |state: &mut WhisperState| -> Result<()> {
+            let strategy = match options.sampling {
+                WhisperSampling::Greedy { best_of } => SamplingStrategy::Greedy {
+                    best_of: best_of.max(1) as i32,
+                },
+                WhisperSampling::BeamSearch { beam_size } => SamplingStrategy::BeamSearch {
+                    beam_size: beam_size.max(1) as i32,
+                    patience: -1.0,
+                },
+            };
+            let mut params = FullParams::new(strategy);
+            params.set_language(language);
+            params.set_translate(translate);
+            if let Some(prompt) = &options.initial_prompt {
+                params.set_initial_prompt(prompt);
+            }
+            params.set_no_speech_thold(
+                options
+                    .no_speech_threshold
+                    .unwrap_or(DEFAULT_NO_SPEECH_THRESHOLD),
+            );
+            if let Some(suppress) = options.suppress_blank {
+                params.set_suppress_blank(suppress);
+            }
+            params.set_suppress_nst(options.suppress_non_speech.unwrap_or(true));
+            params.set_temperature(options.fallback.temperature);
+            params.set_temperature_inc(options.fallback.temperature_inc);
+            params.set_entropy_thold(options.fallback.entropy_threshold);
+            params.set_logprob_thold(options.fallback.logprob_threshold);
+            if let Some(threads) = options.threads {
+                params.set_n_threads(threads.max(1) as i32);
+            }
+            if let Some(abort) = options.abort.clone() {
+                params.set_abort_callback_safe(move || abort.load(Ordering::Relaxed));
+            }
+            params.set_print_special(false);
+            params.set_print_progress(false);
+            params.set_print_realtime(false);
+            params.set_print_timestamps(false);
+
+            if let Err(e) = state.full(params, &audio) {
+                if options
+                    .abort
+                    .as_ref()
+                    .is_some_and(|abort| abort.load(Ordering::Relaxed))
+                {
+                    return Err(TranscriptionCancelled.into());
+                }
+                return Err(anyhow::anyhow!("Whisper transcription failed: {}", e));
+            }
+            Ok(())
+        };
+
+        if options.low_priority {
+            // A thread of its own, as the lowered priority can't always be
+            // raised back and whisper.cpp's workers inherit it
+            let state = &mut self.state;
+            std::thread::scope(|scope| {
+                scope
+                    .spawn(|| {
+                        lower_thread_priority();
+                        decode(state)
+                    })
+                    .join()
+            })
+            .map_err(|_| anyhow::anyhow!("Whisper transcription panicked"))??;
+        } else {
+            decode(&mut self.state)?;
+        }
+
+        let mut text = String::new();
+        let mut segments = Vec::new();
+        let mut confidence = Vec::new();
+        for segment in self.state.as_iter() {
+            let segment_text = segment.to_str_lossy()?.into_owned();
+            // Timestamps are in centiseconds
+            let start = segment.start_timestamp() as f32 / 100.0;
+            let end = segment.end_timestamp() as f32 / 100.0;
+            if options.filter_hallucinations
+                && segment.no_speech_probability() > HALLUCINATION_NO_SPEECH
+                && is_hallucination(&segment_text)
+            {
+                debug!("Dropped likely hallucination: {}", segment_text.trim());
+                continue;
+            }
+            confidence.push(self.segment_confidence(&segment, start, end, &segment_text)?);
+            text.push_str(&segment_text);
+            segments.push(TranscriptionSegment {
+                start,
+                end,
+                text: segment_text,
+            });
+        }
+
+        let result = TranscriptionResult {
+            text: text.trim().to_string(),
+            segments: Some(segments),
+        };
+        Ok((result, confidence))
+    }
+}
+
+/// Drops the calling thread below normal priority, so transcription yields
+/// the CPU to the apps in use.
+fn lower_thread_priority() {
+    #[cfg(target_os = "linux")]
+    {
+        // The nice value is per thread on Linux
+        if unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, 10) } != 0 {
+            warn!(
+                "Failed to lower transcription priority: {}",
+                std::io::Error::last_os_error()
+            );
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let result =
+            unsafe { libc::pthread_set_qos_class_self_np(libc::qos_class_t::QOS_CLASS_UTILITY, 0) };
+        if result != 0 {
+            warn!(
+                "Failed to lower transcription priority: {}",
+                std::io::Error::from_raw_os_error(result)
+            );
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows_sys::Win32::System::Threading::{
+            GetCurrentThread, SetThreadPriority, THREAD_PRIORITY_BELOW_NORMAL,
+        };
+        if unsafe { SetThreadPriority(GetCurrentThread(), THREAD_PRIORITY_BELOW_NORMAL) } == 0 {
+            warn!(
+                "Failed to lower transcription priority: {}",
+                std::io::Error::last_os_error()
+            );
+        }
+    }
+}
+
+/// Runs through ONNX Runtime on the CPU.
+struct Parakeet(ParakeetEngine);
+
+impl SpeechEngine for Parakeet {
+    fn backend(&self) -> &str {
+        "cpu"
+    }
+
+    fn fixed_language(&self) -> Option<&'static str> {
+        Some("en")
+    }
+
+    fn transcribe(
+        &mut self,
+        audio: Vec<f32>,
+        _options: &DecodeOptions,
+    ) -> Result<TranscriptionResult> {
+        let params = ParakeetInferenceParams {
+            timestamp_granularity: TimestampGranularity::Segment,
+            ..Default::default()
+        };
+        self.0
+            .transcribe_samples(audio, Some(params))
+            .map_err(|e| anyhow::anyhow!("Parakeet transcription failed: {}", e))
+    }
+
+    fn unload(&mut self) {
+        self.0.unload_model();
+    }
+}
+
+/// Runs through ONNX Runtime on the CPU like Parakeet.
+struct Moonshine(MoonshineEngine);
+
+impl SpeechEngine for Moonshine {
+    fn backend(&self) -> &str {
+        "cpu"
+    }
+
+    fn fixed_language(&self) -> Option<&'static str> {
+        Some("en")
+    }
+
+    fn transcribe(
+        &mut self,
+        audio: Vec<f32>,
+        _options: &DecodeOptions,
+    ) -> Result<TranscriptionResult> {
+        self.0
+            .transcribe_samples(audio, None)
+            .map_err(|e| anyhow::anyhow!("Moonshine transcription failed: {}", e))
+    }
+
+    fn unload(&mut self) {
+        self.0.unload_model();
+    }
+}
+
+/// Vosk models are single-language and have no segments. Dropping the
+/// engine frees the model.
+impl SpeechEngine for VoskEngine {
+    fn backend(&self) -> &str {
+        "cpu"
+    }
+
+    fn transcribe(
+        &mut self,
+        audio: Vec<f32>,
+        _options: &DecodeOptions,
+    ) -> Result<TranscriptionResult> {
+        let text = VoskEngine::transcribe(self, &audio, WHISPER_SAMPLE_RATE)
+            .map_err(|e| anyhow::anyhow!("Vosk transcription failed: {}", e))?;
+        Ok(TranscriptionResult {
+            text,
+            segments: None,
+        })
+    }
+}
@@ -0,0 +1,288 @@
+//! Every event the backend sends, in one versioned schema.
+//!
+//! Events go out through [`emit`], which sends each one twice: under its own
+//! name with the bare payload, as the settings UI listens for them, and as a
+//! `{version, type, payload}` envelope on [`EVENT_CHANNEL`] for integrations
+//! that want everything in one place. The envelope also goes to Stream Deck
+//! plugins on `GET /events`. `get_event_schema` lists the events.
+//!
+//! The overlay window's own `show-overlay`, `hide-overlay` and `mic-level`
+//! updates are not part of the schema, they only drive its animation.
+//!
+//! Bump [`EVENT_SCHEMA_VERSION`] when a payload changes incompatibly; adding
+//! an event or an optional field doesn't need a bump.
+
+use crate::actions::{PartialTranscript, RecordingLimitReached, TranscriptionCompleted};
+use crate::health::HealthReport;
+use crate::managers::download_queue::{DownloadFinished, DownloadQueueItem};
+use crate::managers::email_ingest::EmailMemoEvent;
+use crate::managers::hid::HidCapture;
+use crate::managers::history_sync::SyncReport;
+use crate::managers::midi::MidiCapture;
+use crate::managers::model::{DownloadProgress, ModelUpdate, ModelsDirStatus, UnusedModel};
+use crate::managers::offline_queue::OfflineDelivery;
+use crate::managers::power::PowerStatus;
+use crate::managers::provider_health::ProviderHealth;
+use crate::managers::session_lock::LockedRecordingEvent;
+use crate::managers::stream_deck::StreamDeckManager;
+use crate::managers::transcription::{
+    GpuFallbackEvent, ModelLoadProgress, ModelStateEvent, TranscriptionComparison,
+    TranscriptionProgress,
+};
+use crate::managers::transcription_queue::TranscriptionJob;
+use crate::managers::vocabulary::SharedVocabulary;
+use crate::managers::watch_folder::WatchedFileEvent;
+use crate::settings::ModelBenchmark;
+use crate::update_check::AppUpdate;
+use log::warn;
+use serde::Serialize;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+/// Event carrying the envelope of every other event.
+pub const EVENT_CHANNEL: &str = "app-event";
+
+/// A model operation that failed.
+#[derive(Clone, Debug, Serialize)]
+pub struct ModelError {
+    pub model_id: String,
+    pub error: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ModelDownloadSource {
+    pub model_id: String,
+    pub url: String,
+    pub is_mirror: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ModelCorrupted {
+    pub model_id: String,
+    pub reason: String,
+}
+
+/// A setting changed from outside the settings UI, e.g. by a shortcut.
+#[derive(Clone, Debug, Serialize)]
+pub struct SettingChanged {
+    pub setting: String,
+    pub value: serde_json::Value,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct EventDescription {
+    pub name: &'static str,
+    pub payload: &'static str,
+    pub description: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct EventSchema {
+    pub version: u32,
+    pub channel: &'static str,
+    pub events: Vec<EventDescription>,
+}
+
+#[derive(Serialize)]
+struct Envelope<'a> {
+    version: u32,
+    #[serde(rename = "type")]
+    event_type: &'a str,
+    payload: &'a serde_json::Value,
+}
+
+/// Defines [`AppEvent`] and its schema from one list, so the two can't
+/// drift apart.
+macro_rules! app_events {
+    ($($(#[doc = $doc:literal])+ $variant:ident($payload:ty) = $name:literal,)+) => {
+        #[derive(Serialize)]
+        #[serde(tag = "type", content = "payload")]
+        pub enum AppEvent {
+            $(
+                $(#[doc = $doc])+
+                #[serde(rename = $name)]
+                $variant($payload),
+            )+
+        }
+
+        impl AppEvent {
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$variant(_) => $name,)+
+                }
+            }
+        }
+
+        /// The events with their payload types, for `get_event_schema`.
+        pub fn schema() -> EventSchema {
+            EventSchema {
+                version: EVENT_SCHEMA_VERSION,
+                channel: EVENT_CHANNEL,
+                events: vec![$(EventDescription {
+                    name: $name,
+                    payload: stringify!($payload),
+                    description: [$($doc.trim()),+].join(" "),
+                },)+],
+            }
+        }
+    };
+}
+
+app_events! {
+    /// The history list changed.
+    HistoryUpdated(()) = "history-updated",
+    /// Files were synced with the history sync folder.
+    HistorySyncCompleted(SyncReport) = "history-sync-completed",
+    /// A transcript is waiting for the paste here shortcut.
+    PastePending(String) = "paste-pending",
+    /// The pending transcript was pasted or dropped.
+    PastePendingCleared(()) = "paste-pending-cleared",
+    /// Text transcribed so far while recording.
+    TranscriptionPartial(PartialTranscript) = "transcription-partial",
+    /// A dictation was transcribed, with the window it goes to.
+    TranscriptionCompleted(TranscriptionCompleted) = "transcription-complete",
+    /// Two models transcribed the same audio.
+    TranscriptionComparison(TranscriptionComparison) = "transcription-comparison",
+    /// A recording waits to be transcribed.
+    TranscriptionQueued(TranscriptionJob) = "transcription-queued",
+    /// A queued recording is being transcribed.
+    TranscriptionStarted(TranscriptionJob) = "transcription-started",
+    /// A queued recording was transcribed, or failed to be.
+    TranscriptionFinished(TranscriptionJob) = "transcription-finished",
+    /// Chunks of a long recording transcribed so far.
+    TranscriptionProgress(TranscriptionProgress) = "transcription-progress",
+    /// The running transcription was cancelled.
+    TranscriptionCancelled(()) = "transcription-cancelled",
+    /// A recording was queued until the connection is back; the number queued.
+    TranscriptionQueuedOffline(usize) = "transcription-queued-offline",
+    /// A queued recording was transcribed.
+    OfflineTranscriptionDelivered(OfflineDelivery) = "offline-transcription-delivered",
+    /// Offset in ms of a marker set in the running recording.
+    RecordingMarkerAdded(u64) = "recording-marker-added",
+    /// A recording ran into the length limit and was stopped.
+    RecordingLimitReached(RecordingLimitReached) = "recording-limit-reached",
+    /// The session locked while recording.
+    RecordingStoppedOnLock(LockedRecordingEvent) = "recording-stopped-on-lock",
+    /// Reading the clipboard image failed.
+    OcrFailed(String) = "ocr-failed",
+    /// Why a spoken correction couldn't be applied.
+    CorrectionFailed(String) = "correction-failed",
+    /// A setting changed outside the settings UI.
+    SettingsChanged(SettingChanged) = "settings-changed",
+    /// The active shortcut layer, `null` for the base layer.
+    ShortcutLayerChanged(Option<String>) = "shortcut-layer-changed",
+    /// A MIDI control was captured for a binding.
+    MidiMessageCaptured(MidiCapture) = "midi-message-captured",
+    /// A HID control was captured for a binding.
+    HidControlCaptured(HidCapture) = "hid-control-captured",
+    /// Battery or battery saver status changed.
+    PowerStatusChanged(PowerStatus) = "power-status-changed",
+    /// An API provider became reachable, slow or unreachable.
+    ProviderHealthChanged(ProviderHealth) = "provider-health-changed",
+    /// Result of the startup health check.
+    HealthReport(HealthReport) = "health-report",
+    /// A new release is available.
+    UpdateAvailable(AppUpdate) = "update-available",
+    /// The tray asked the UI to check for updates.
+    CheckForUpdates(()) = "check-for-updates",
+    /// The team vocabulary was refreshed.
+    SharedVocabularyUpdated(SharedVocabulary) = "shared-vocabulary-updated",
+    /// A file in the watch folder was transcribed.
+    WatchFolderTranscribed(WatchedFileEvent) = "watch-folder-transcribed",
+    /// A file in the watch folder couldn't be transcribed.
+    WatchFolderFailed(WatchedFileEvent) = "watch-folder-failed",
+    /// An emailed memo was transcribed.
+    EmailIngestTranscribed(EmailMemoEvent) = "email-ingest-transcribed",
+    /// An emailed memo couldn't be transcribed.
+    EmailIngestFailed(EmailMemoEvent) = "email-ingest-failed",
+    /// Name of the uploaded backup.
+    CloudBackupCompleted(String) = "cloud-backup-completed",
+    /// Name of the restored backup.
+    CloudBackupRestored(String) = "cloud-backup-restored",
+    /// A model started or finished loading, failed, was unloaded or switched.
+    ModelStateChanged(ModelStateEvent) = "model-state-changed",
+    /// How far loading a model got.
+    ModelLoadProgress(ModelLoadProgress) = "model-load-progress",
+    /// Id of a loaded model that finished its warm-up run.
+    ModelWarmed(String) = "model-warmed",
+    /// A model failed on the GPU and now runs on the CPU.
+    GpuFallback(GpuFallbackEvent) = "gpu-fallback",
+    /// A model was benchmarked.
+    ModelBenchmarkCompleted(ModelBenchmark) = "model-benchmark-completed",
+    /// The startup scan of the models directory finished.
+    ModelsReady(()) = "models-ready",
+    /// Downloads are off because the models directory isn't writable.
+    ModelsDirReadOnly(ModelsDirStatus) = "models-dir-read-only",
+    /// The list of models changed.
+    ModelCatalogUpdated(()) = "model-catalog-updated",
+    /// Downloaded models with a newer version.
+    ModelUpdateAvailable(Vec<ModelUpdate>) = "model-update-available",
+    /// Id of a model upgraded to its newer version.
+    ModelUpgraded(String) = "model-upgraded",
+    /// Models unused for a while, offered for deletion.
+    UnusedModelsFound(Vec<UnusedModel>) = "unused-models-found",
+    /// Models unused for a while, to be deleted by the UI.
+    UnusedModelsCleanupRequested(Vec<UnusedModel>) = "unused-models-cleanup-requested",
+    /// The mirror a model is downloaded from.
+    ModelDownloadSource(ModelDownloadSource) = "model-download-source",
+    /// Bytes downloaded of a model.
+    ModelDownloadProgress(DownloadProgress) = "model-download-progress",
+    /// Id of a model whose download was cancelled.
+    ModelDownloadCancelled(String) = "model-download-cancelled",
+    /// A model download failed.
+    ModelDownloadFailed(ModelError) = "model-download-failed",
+    /// Id of a model that finished downloading.
+    ModelDownloadComplete(String) = "model-download-complete",
+    /// Id of a model whose download is being checked.
+    ModelVerificationStarted(String) = "model-verification-started",
+    /// A downloaded model didn't match its checksum.
+    ModelVerificationFailed(ModelError) = "model-verification-failed",
+    /// Id of a model whose archive is being unpacked.
+    ModelExtractionStarted(String) = "model-extraction-started",
+    /// Unpacking a model archive failed.
+    ModelExtractionFailed(ModelError) = "model-extraction-failed",
+    /// Id of a model whose archive was unpacked.
+    ModelExtractionCompleted(String) = "model-extraction-completed",
+    /// Id of a model whose Core ML encoder was downloaded.
+    CoremlEncoderReady(String) = "coreml-encoder-ready",
+    /// A downloaded model's files are damaged.
+    ModelCorrupted(ModelCorrupted) = "model-corrupted",
+    /// Id of a damaged model that was downloaded again.
+    ModelRepaired(String) = "model-repaired",
+    /// The download queue changed.
+    DownloadQueueChanged(Vec<DownloadQueueItem>) = "download-queue-changed",
+    /// A queued download finished or failed.
+    DownloadQueueItemFinished(DownloadFinished) = "download-queue-item-finished",
+}
+
+/// Sends `event` to the UI under its own name and as an envelope on
+/// [`EVENT_CHANNEL`] and to Stream Deck plugins.
+pub fn emit(app: &AppHandle, event: AppEvent) {
+    let name = event.name();
+    let payload = match serde_json::to_value(&event) {
+        Ok(serde_json::Value::Object(mut fields)) => {
+            fields.remove("payload").unwrap_or(serde_json::Value::Null)
+        }
+        Ok(_) => serde_json::Value::Null,
+        Err(e) => {
+            warn!("Failed to serialize {} event: {}", name, e);
+            return;
+        }
+    };
+
+    if let Err(e) = app.emit(name, &payload) {
+        warn!("Failed to emit {} event: {}", name, e);
+    }
+    let envelope = Envelope {
+        version: EVENT_SCHEMA_VERSION,
+        event_type: name,
+        payload: &payload,
+    };
+    let _ = app.emit(EVENT_CHANNEL, &envelope);
+    if let Some(stream_deck) = app.try_state::<Arc<StreamDeckManager>>() {
+        if let Ok(json) = serde_json::to_string(&envelope) {
+            stream_deck.publish_event(&json);
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! Finds the application and window that has the keyboard focus, which is
+//! where a transcript gets pasted.
+//!
+//! macOS asks System Events through `osascript`, which needs the automation
+//! permission for the window title. Linux uses `xdotool` and only works on
+//! X11. Windows asks the window manager directly.
+
+use anyhow::{anyhow, Result};
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FocusedWindow {
+    pub app_name: String,
+    /// `None` when the window has no title or it can't be read.
+    pub title: Option<String>,
+    /// The X11 window id or Windows window handle, to bring the window
+    /// back to the front.
+    #[serde(skip)]
+    pub handle: Option<u64>,
+}
+
+impl FocusedWindow {
+    /// Whether `other` belongs to a different application. Titles aren't
+    /// compared, they change as the user works within an app.
+    pub fn is_other_app(&self, other: &FocusedWindow) -> bool {
+        self.app_name != other.app_name
+    }
+}
+
+/// The focused window, `None` when it can't be found out.
+pub fn focused_window() -> Option<FocusedWindow> {
+    let window = platform_focused_window();
+    if window.is_none() {
+        debug!("Couldn't find the focused window");
+    }
+    window
+}
+
+/// Brings `window` back to the front, or on macOS its application.
+pub fn activate(window: &FocusedWindow) -> Result<()> {
+    debug!("Bringing {} back to the front", window.app_name);
+    platform_activate(window)
+}
+
+fn non_empty(text: &str) -> Option<String> {
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn platform_focused_window() -> Option<FocusedWindow> {
+    const SCRIPT: &str = r#"
+tell application "System Events"
+    set frontApp to first application process whose frontmost is true
+    set appName to name of frontApp
+    set windowTitle to ""
+    try
+        set windowTitle to name of front window of frontApp
+    end try
+end tell
+return appName & linefeed & windowTitle"#;
+
+    let output = std::process::Command::new("osascript")
+        .args(["-e", SCRIPT])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    let output = String::from_utf8_lossy(&output.stdout);
+    let mut lines = output.lines();
+    Some(FocusedWindow {
+        app_name: non_empty(lines.next()?)?,
+        title: lines.next().and_then(non_empty),
+        handle: None,
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn platform_activate(window: &FocusedWindow) -> Result<()> {
+    let script = format!(
+        "tell application \"{}\" to activate",
+        window.app_name.replace('\\', "\\\\").replace('"', "\\\"")
+    );
+    let status = std::process::Command::new("osascript")
+        .args(["-e", &script])
+        .status()?;
+    if !status.success() {
+        return Err(anyhow!("osascript exited with {}", status));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn platform_focused_window() -> Option<FocusedWindow> {
+    let xdotool = |args: &[&str]| -> Option<String> {
+        let output = std::process::Command::new("xdotool")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())?;
+        non_empty(&String::from_utf8_lossy(&output.stdout))
+    };
+
+    let id = xdotool(&["getactivewindow"])?;
+    let pid = xdotool(&["getwindowpid", &id])?;
+    let app_name = std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok()?;
+    Some(FocusedWindow {
+        app_name: non_empty(&app_name)?,
+        title: xdotool(&["getwindowname", &id]),
+        handle: id.parse().ok(),
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn platform_activate(window: &FocusedWindow) -> Result<()> {
+    let id = window
+        .handle
+        .ok_or_else(|| anyhow!("No window id for {}", window.app_name))?;
+    let status = std::process::Command::new("xdotool")
+        .args(["windowactivate", "--sync", &id.to_string()])
+        .status()?;
+    if !status.success() {
+        return Err(anyhow!("xdotool exited with {}", status));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn platform_focused_window() -> Option<FocusedWindow> {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        GetForegroundWindow, GetWindowTextW, GetWindowThreadProcessId,
+    };
+
+    unsafe {
+        let window = GetForegroundWindow();
+        if window.is_null() {
+            return None;
+        }
+
+        let mut title = [0u16; 512];
+        let length = GetWindowTextW(window, title.as_mut_ptr(), title.len() as i32);
+        let title = String::from_utf16_lossy(&title[..length.max(0) as usize]);
+
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(window, &mut pid);
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if process.is_null() {
+            return None;
+        }
+        let mut path = [0u16; 1024];
+        let mut size = path.len() as u32;
+        let found =
+            QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, path.as_mut_ptr(), &mut size);
+        CloseHandle(process);
+        if found == 0 {
+            return None;
+        }
+
+        let path = String::from_utf16_lossy(&path[..size as usize]);
+        let app_name = std::path::Path::new(&path).file_stem()?.to_string_lossy();
+        Some(FocusedWindow {
+            app_name: non_empty(&app_name)?,
+            title: non_empty(&title),
+            handle: Some(window as u64),
+        })
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn platform_activate(window: &FocusedWindow) -> Result<()> {
+    use windows_sys::Win32::UI::WindowsAndMessaging::SetForegroundWindow;
+
+    let handle = window
+        .handle
+        .ok_or_else(|| anyhow!("No window handle for {}", window.app_name))?;
+    // Windows may refuse to hand over the focus, e.g. while the user types
+    // in another window
+    if unsafe { SetForegroundWindow(handle as _) } == 0 {
+        return Err(anyhow!("Windows refused to focus {}", window.app_name));
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn platform_focused_window() -> Option<FocusedWindow> {
+    None
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn platform_activate(_window: &FocusedWindow) -> Result<()> {
+    Err(anyhow!("Focusing windows isn't supported on this platform"))
+}
@@ -0,0 +1,259 @@
+//! Startup self-check, so the UI can explain why dictation won't work before
+//! the first attempt fails.
+
+use crate::audio_toolkit::list_input_devices;
+use crate::events::{self, AppEvent};
+use crate::managers::model::{is_api_model, IntegrityStatus, ModelManager};
+use crate::managers::provider_health::api_provider;
+use crate::settings::{get_settings, AppSettings};
+use crate::shortcut::active_bindings;
+use log::{info, warn};
+use serde::Serialize;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+
+/// Let shortcut registration and the first model scan finish first.
+const STARTUP_DELAY: Duration = Duration::from_secs(3);
+/// Below this much free space recordings and history writes may fail.
+const MIN_FREE_SPACE_MB: u64 = 500;
+
+#[derive(Clone, Copy, Debug, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HealthCheck {
+    pub id: String,
+    pub status: HealthStatus,
+    pub message: String,
+    /// Settings page that fixes the problem, for the UI to link to.
+    pub fix: Option<String>,
+}
+
+impl HealthCheck {
+    fn ok(id: &str, message: impl Into<String>) -> Self {
+        Self {
+            id: id.to_string(),
+            status: HealthStatus::Ok,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    fn problem(id: &str, status: HealthStatus, message: impl Into<String>, fix: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            status,
+            message: message.into(),
+            fix: Some(fix.to_string()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HealthReport {
+    /// No check failed; warnings don't stop dictation.
+    pub healthy: bool,
+    pub checks: Vec<HealthCheck>,
+    pub checked_at: i64,
+}
+
+/// Runs all checks.
+pub fn check(app: &AppHandle) -> HealthReport {
+    let settings = get_settings(app);
+    let checks = vec![
+        check_microphone(&settings),
+        check_shortcuts(app, &settings),
+        check_model(app, &settings),
+        check_disk_space(app),
+    ];
+    HealthReport {
+        healthy: checks.iter().all(|c| c.status != HealthStatus::Error),
+        checks,
+        checked_at: chrono::Utc::now().timestamp(),
+    }
+}
+
+/// Checks once shortly after launch and emits the result as
+/// `health-report`.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    thread::spawn(move || {
+        thread::sleep(STARTUP_DELAY);
+        if let Some(model_manager) = app.try_state::<Arc<ModelManager>>() {
+            model_manager.wait_until_ready();
+        }
+        let report = check(&app);
+        for problem in report
+            .checks
+            .iter()
+            .filter(|c| c.status != HealthStatus::Ok)
+        {
+            warn!("Health check {}: {}", problem.id, problem.message);
+        }
+        if report.healthy {
+            info!("Startup health check passed");
+        }
+        events::emit(&app, AppEvent::HealthReport(report));
+    });
+}
+
+fn check_microphone(settings: &AppSettings) -> HealthCheck {
+    const ID: &str = "microphone";
+    let devices = match list_input_devices() {
+        Ok(devices) => devices,
+        Err(e) => {
+            return HealthCheck::problem(
+                ID,
+                HealthStatus::Error,
+                format!("Microphones can't be accessed: {}", e),
+                "microphone",
+            )
+        }
+    };
+    if devices.is_empty() {
+        return HealthCheck::problem(ID, HealthStatus::Error, "No microphone found", "microphone");
+    }
+    match &settings.selected_microphone {
+        Some(name) if !devices.iter().any(|d| &d.name == name) => HealthCheck::problem(
+            ID,
+            HealthStatus::Warning,
+            format!("{} isn't connected, the default microphone is used", name),
+            "microphone",
+        ),
+        _ => HealthCheck::ok(ID, format!("{} microphone(s) available", devices.len())),
+    }
+}
+
+fn check_shortcuts(app: &AppHandle, settings: &AppSettings) -> HealthCheck {
+    const ID: &str = "shortcuts";
+    let mut unregistered: Vec<String> = active_bindings(settings)
+        .into_values()
+        .filter(|b| !b.current_binding.is_empty())
+        .filter(|b| {
+            b.current_binding
+                .parse::<Shortcut>()
+                .map(|s| !app.global_shortcut().is_registered(s))
+                .unwrap_or(true)
+        })
+        .map(|b| format!("{} ({})", b.name, b.current_binding))
+        .collect();
+    if unregistered.is_empty() {
+        return HealthCheck::ok(ID, "All shortcuts are registered");
+    }
+    unregistered.sort();
+    HealthCheck::problem(
+        ID,
+        HealthStatus::Error,
+        format!(
+            "Shortcuts not registered, another app may be using them: {}",
+            unregistered.join(", ")
+        ),
+        "shortcuts",
+    )
+}
+
+fn check_model(app: &AppHandle, settings: &AppSettings) -> HealthCheck {
+    const ID: &str = "model";
+    let model_id = settings.selected_model.as_str();
+    if model_id.is_empty() {
+        return HealthCheck::problem(ID, HealthStatus::Error, "No model selected", "models");
+    }
+    if is_api_model(model_id) {
+        return check_api_key(settings, model_id);
+    }
+
+    let Some(model_manager) = app
+        .try_state::<Arc<ModelManager>>()
+        .filter(|manager| manager.is_ready())
+    else {
+        return HealthCheck::ok(ID, "Model check skipped during startup");
+    };
+    let Some(model) = model_manager.get_model_info(model_id) else {
+        return HealthCheck::problem(
+            ID,
+            HealthStatus::Error,
+            format!("Selected model {} is no longer available", model_id),
+            "models",
+        );
+    };
+    if !model.is_downloaded {
+        return HealthCheck::problem(
+            ID,
+            HealthStatus::Error,
+            format!("{} isn't downloaded", model.name),
+            "models",
+        );
+    }
+    if !model.engine_type.is_supported() {
+        return HealthCheck::problem(
+            ID,
+            HealthStatus::Error,
+            format!("{} needs an engine this build doesn't include", model.name),
+            "models",
+        );
+    }
+    match model_manager.check_model_integrity(model_id) {
+        Ok(IntegrityStatus::Ok) => HealthCheck::ok(ID, format!("{} is ready", model.name)),
+        Ok(IntegrityStatus::Corrupt(reason)) => HealthCheck::problem(
+            ID,
+            HealthStatus::Error,
+            format!("{} is damaged: {}", model.name, reason),
+            "models",
+        ),
+        Err(e) => HealthCheck::problem(
+            ID,
+            HealthStatus::Warning,
+            format!("{} couldn't be checked: {}", model.name, e),
+            "models",
+        ),
+    }
+}
+
+fn check_api_key(settings: &AppSettings, model_id: &str) -> HealthCheck {
+    const ID: &str = "model";
+    let Some((provider, key)) = api_provider(settings, model_id) else {
+        return HealthCheck::ok(ID, "API model selected");
+    };
+    if key.is_some() {
+        HealthCheck::ok(ID, format!("{} API key is set", provider))
+    } else {
+        HealthCheck::problem(
+            ID,
+            HealthStatus::Error,
+            format!("The selected model needs a {} API key", provider),
+            "api_keys",
+        )
+    }
+}
+
+fn check_disk_space(app: &AppHandle) -> HealthCheck {
+    const ID: &str = "disk_space";
+    let available = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())
+        .and_then(|dir| fs2::available_space(&dir).map_err(|e| e.to_string()));
+    match available {
+        Ok(bytes) if bytes / (1024 * 1024) < MIN_FREE_SPACE_MB => HealthCheck::problem(
+            ID,
+            HealthStatus::Warning,
+            format!("Only {} MB of disk space left", bytes / (1024 * 1024)),
+            "storage",
+        ),
+        Ok(bytes) => HealthCheck::ok(ID, format!("{} MB free", bytes / (1024 * 1024))),
+        Err(e) => HealthCheck::problem(
+            ID,
+            HealthStatus::Warning,
+            format!("Free disk space couldn't be determined: {}", e),
+            "storage",
+        ),
+    }
+}
@@ -0,0 +1,319 @@
+//! Importers for data exported by other dictation apps, so switching to Handy
+//! doesn't mean losing past transcriptions or a carefully built vocabulary.
+
+use crate::managers::history::{HistoryManager, ImportedEntry};
+use crate::settings::{get_settings, write_settings};
+use anyhow::{anyhow, Result};
+use chrono::DateTime;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tauri::AppHandle;
+
+/// Plain text files with these names are read as word lists, one per line.
+const WORD_LIST_FILES: &[&str] = &["vocabulary.txt", "custom_words.txt", "words.txt"];
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportKind {
+    /// A SuperWhisper recordings folder (one `meta.json` per recording) or a
+    /// single exported JSON file.
+    SuperWhisper,
+    /// MacWhisper JSON transcript exports, a single file or a folder of them.
+    MacWhisper,
+    /// A folder of `.txt` files, one transcription per file.
+    PlainText,
+}
+
+impl ImportKind {
+    fn app_name(self) -> &'static str {
+        match self {
+            ImportKind::SuperWhisper => "SuperWhisper",
+            ImportKind::MacWhisper => "MacWhisper",
+            ImportKind::PlainText => "Text import",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ImportReport {
+    pub history_imported: usize,
+    /// Entries that were already imported by an earlier run.
+    pub history_skipped: usize,
+    pub custom_words_added: usize,
+    pub errors: Vec<String>,
+}
+
+struct Transcript {
+    text: String,
+    timestamp: Option<i64>,
+    title: Option<String>,
+}
+
+#[derive(Default)]
+struct Collected {
+    transcripts: Vec<Transcript>,
+    words: Vec<String>,
+}
+
+pub fn import_external_data(
+    app: &AppHandle,
+    history_manager: &HistoryManager,
+    kind: ImportKind,
+    path: &Path,
+) -> Result<ImportReport> {
+    if !path.exists() {
+        return Err(anyhow!("Path does not exist: {}", path.display()));
+    }
+
+    let mut report = ImportReport::default();
+    let mut collected = Collected::default();
+
+    for file in files_to_read(kind, path)? {
+        let result = match kind {
+            ImportKind::PlainText => read_text_file(&file, &mut collected),
+            ImportKind::SuperWhisper | ImportKind::MacWhisper => {
+                read_json_file(&file, &mut collected)
+            }
+        };
+        if let Err(e) = result {
+            report
+                .errors
+                .push(format!("Failed to read {}: {}", file.display(), e));
+        }
+    }
+
+    let known = history_manager.get_imported_sync_ids()?;
+    for transcript in collected.transcripts {
+        let sync_id = import_id(kind, &transcript);
+        if known.contains(&sync_id) {
+            report.history_skipped += 1;
+            continue;
+        }
+
+        let entry = ImportedEntry {
+            file_name: String::new(),
+            timestamp: transcript
+                .timestamp
+                .unwrap_or_else(|| chrono::Utc::now().timestamp()),
+            saved: false,
+            title: transcript.title,
+            transcription_text: transcript.text,
+            tags: Vec::new(),
+            app_name: Some(kind.app_name().to_string()),
+            sync_id: Some(sync_id),
+        };
+        match history_manager.insert_imported_entry(entry) {
+            Ok(_) => report.history_imported += 1,
+            Err(e) => report.errors.push(format!("Failed to import entry: {}", e)),
+        }
+    }
+
+    if !collected.words.is_empty() {
+        let mut settings = get_settings(app);
+        let mut existing: HashSet<String> = settings
+            .custom_words
+            .iter()
+            .map(|w| w.to_lowercase())
+            .collect();
+        for word in collected.words {
+            if existing.insert(word.to_lowercase()) {
+                settings.custom_words.push(word);
+                report.custom_words_added += 1;
+            }
+        }
+        write_settings(app, settings);
+    }
+
+    info!(
+        "Imported {} history entries and {} custom words from {}",
+        report.history_imported,
+        report.custom_words_added,
+        kind.app_name()
+    );
+    Ok(report)
+}
+
+/// Lists the files to read for an import, descending one level into folders
+/// so SuperWhisper's per-recording folders are picked up.
+fn files_to_read(kind: ImportKind, path: &Path) -> Result<Vec<PathBuf>> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let wanted = match kind {
+        ImportKind::PlainText => "txt",
+        ImportKind::SuperWhisper | ImportKind::MacWhisper => "json",
+    };
+    let has_extension = |p: &Path| {
+        p.extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case(wanted))
+    };
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(path)?.filter_map(|e| e.ok()) {
+        let entry_path = entry.path();
+        if entry_path.is_dir() {
+            for nested in fs::read_dir(&entry_path)?.filter_map(|e| e.ok()) {
+                if has_extension(&nested.path()) {
+                    files.push(nested.path());
+                }
+            }
+        } else if has_extension(&entry_path) {
+            files.push(entry_path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn read_text_file(path: &Path, collected: &mut Collected) -> Result<()> {
+    let contents = fs::read_to_string(path)?;
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    if WORD_LIST_FILES.contains(&file_name.as_str()) {
+        collected.words.extend(
+            contents
+                .lines()
+                .map(str::trim)
+                .filter(|w| !w.is_empty())
+                .map(String::from),
+        );
+        return Ok(());
+    }
+
+    let text = contents.trim();
+    if text.is_empty() {
+        return Ok(());
+    }
+    let timestamp = fs::metadata(path)?
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64);
+
+    collected.transcripts.push(Transcript {
+        text: text.to_string(),
+        timestamp,
+        title: path.file_stem().map(|s| s.to_string_lossy().to_string()),
+    });
+    Ok(())
+}
+
+/// Both apps have changed their export layout over time, so the JSON is read
+/// loosely: an array of recordings, an object holding one, or an object with
+/// a list of recordings under a known key.
+fn read_json_file(path: &Path, collected: &mut Collected) -> Result<()> {
+    let value: Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+
+    let records: Vec<&Value> = match &value {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(map) => {
+            collected.words.extend(word_list(&value));
+            match ["recordings", "transcripts", "items", "history"]
+                .iter()
+                .find_map(|key| map.get(*key).and_then(Value::as_array))
+            {
+                Some(items) => items.iter().collect(),
+                None => vec![&value],
+            }
+        }
+        _ => return Err(anyhow!("Unexpected JSON document")),
+    };
+
+    for record in records {
+        if let Some(transcript) = parse_record(record) {
+            collected.transcripts.push(transcript);
+        }
+    }
+    Ok(())
+}
+
+fn parse_record(record: &Value) -> Option<Transcript> {
+    let text = ["result", "llmResult", "rawResult", "text", "transcript"]
+        .iter()
+        .find_map(|key| record.get(*key).and_then(Value::as_str))
+        .map(str::to_string)
+        .or_else(|| {
+            // MacWhisper exports carry the transcript as timed segments
+            let segments = record.get("segments")?.as_array()?;
+            let joined = segments
+                .iter()
+                .filter_map(|s| s.get("text").and_then(Value::as_str))
+                .map(str::trim)
+                .collect::<Vec<_>>()
+                .join(" ");
+            Some(joined)
+        })?;
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+
+    let timestamp = ["datetime", "date", "createdAt", "dateCreated", "timestamp"]
+        .iter()
+        .find_map(|key| record.get(*key))
+        .and_then(parse_timestamp);
+    let title = ["title", "name", "fileName"]
+        .iter()
+        .find_map(|key| record.get(*key).and_then(Value::as_str))
+        .map(str::to_string);
+
+    Some(Transcript {
+        text: text.to_string(),
+        timestamp,
+        title,
+    })
+}
+
+fn parse_timestamp(value: &Value) -> Option<i64> {
+    match value {
+        // Millisecond timestamps are far beyond any plausible second count
+        Value::Number(n) => n
+            .as_i64()
+            .map(|t| if t > 100_000_000_000 { t / 1000 } else { t }),
+        Value::String(s) => DateTime::parse_from_rfc3339(s)
+            .map(|dt| dt.timestamp())
+            .ok()
+            .or_else(|| {
+                warn!("Unrecognized timestamp in import: {}", s);
+                None
+            }),
+        _ => None,
+    }
+}
+
+fn word_list(value: &Value) -> Vec<String> {
+    ["vocabulary", "customWords", "custom_words", "words"]
+        .iter()
+        .find_map(|key| value.get(*key).and_then(Value::as_array))
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::trim)
+                .filter(|w| !w.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// A stable id so importing the same export twice doesn't duplicate history.
+fn import_id(kind: ImportKind, transcript: &Transcript) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(transcript.text.as_bytes());
+    hasher.update(transcript.timestamp.unwrap_or_default().to_le_bytes());
+    let digest = hasher.finalize();
+    let hash: String = digest[..12].iter().map(|b| format!("{:02x}", b)).collect();
+    format!("import:{}:{}", kind.app_name(), hash)
+}
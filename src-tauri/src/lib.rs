@@ -1,25 +1,58 @@
+mod acceleration;
 mod actions;
 mod audio_feedback;
 pub mod audio_toolkit;
 mod clipboard;
 mod commands;
+mod crash_report;
+mod diarization;
+mod engine;
+mod events;
+mod focus;
+mod health;
+mod import;
+mod local_http;
 mod managers;
+mod ocr;
 mod overlay;
+mod profiles;
 mod settings;
 mod shortcut;
 mod tray;
+mod tts;
+mod update_check;
 mod utils;
+mod vosk;
 
+use events::AppEvent;
 use managers::audio::AudioRecordingManager;
+use managers::automation::AutomationManager;
+use managers::backup::BackupManager;
+use managers::download_queue::DownloadQueueManager;
+use managers::email_ingest::EmailIngestManager;
+use managers::hid::HidManager;
 use managers::history::HistoryManager;
+use managers::history_sync::HistorySyncManager;
+use managers::metrics::MetricsManager;
+use managers::midi::MidiManager;
 use managers::model::ModelManager;
+use managers::offline_queue::OfflineQueueManager;
+use managers::phone_import::PhoneImportManager;
+use managers::power::PowerManager;
+use managers::practice::PracticeManager;
+use managers::provider_health::ProviderHealthManager;
+use managers::session_lock::SessionLockManager;
+use managers::stream_deck::StreamDeckManager;
 use managers::transcription::TranscriptionManager;
+use managers::transcription_queue::TranscriptionQueue;
+use managers::vocabulary::VocabularyManager;
+use managers::voice_profile::VoiceProfileManager;
+use managers::watch_folder::WatchFolderManager;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use tauri::image::Image;
 
 use tauri::tray::TrayIconBuilder;
-use tauri::Emitter;
 use tauri::{AppHandle, Manager};
 use tauri_plugin_autostart::{MacosLauncher, ManagerExt};
 
@@ -54,28 +87,127 @@ fn show_main_window(app: &AppHandle) {
 }
 
 fn initialize_core_logic(app_handle: &AppHandle) {
+    crash_report::install(app_handle);
+
     // First, initialize the managers
     let recording_manager = Arc::new(
         AudioRecordingManager::new(app_handle).expect("Failed to initialize recording manager"),
     );
     let model_manager =
         Arc::new(ModelManager::new(app_handle).expect("Failed to initialize model manager"));
+    let vocabulary_manager =
+        VocabularyManager::new(app_handle).expect("Failed to initialize vocabulary manager");
+    let metrics_manager = Arc::new(MetricsManager::new(app_handle));
+    let power_manager = PowerManager::new(app_handle);
     let transcription_manager = Arc::new(
-        TranscriptionManager::new(app_handle, model_manager.clone())
-            .expect("Failed to initialize transcription manager"),
+        TranscriptionManager::new(
+            app_handle,
+            model_manager.clone(),
+            vocabulary_manager.clone(),
+            metrics_manager.clone(),
+            power_manager.clone(),
+        )
+        .expect("Failed to initialize transcription manager"),
     );
+    let transcription_queue = TranscriptionQueue::new(app_handle);
+    let provider_health_manager =
+        ProviderHealthManager::new(app_handle, transcription_manager.clone());
     let history_manager =
         Arc::new(HistoryManager::new(app_handle).expect("Failed to initialize history manager"));
+    let history_sync_manager = HistorySyncManager::new(app_handle, history_manager.clone());
+    let backup_manager = Arc::new(BackupManager::new(app_handle, history_manager.clone()));
+    let automation_manager = Arc::new(AutomationManager::new(
+        app_handle,
+        history_manager.clone(),
+    ));
+    let hid_manager = HidManager::new(app_handle);
+    let stream_deck_manager = Arc::new(StreamDeckManager::new(app_handle));
+    let midi_manager = MidiManager::new(app_handle);
+    let session_lock_manager = SessionLockManager::new(app_handle);
+    let download_queue_manager = DownloadQueueManager::new(app_handle, model_manager.clone());
+    let offline_queue_manager = OfflineQueueManager::new(
+        app_handle,
+        transcription_manager.clone(),
+        history_manager.clone(),
+    )
+    .expect("Failed to initialize offline queue manager");
+    let voice_profile_manager = VoiceProfileManager::new(
+        app_handle,
+        recording_manager.clone(),
+        transcription_manager.clone(),
+    );
+    let practice_manager = PracticeManager::new(
+        app_handle,
+        recording_manager.clone(),
+        transcription_manager.clone(),
+    );
+    let watch_folder_manager = WatchFolderManager::new(
+        app_handle,
+        transcription_manager.clone(),
+        history_manager.clone(),
+    );
+    let email_ingest_manager = EmailIngestManager::new(
+        app_handle,
+        transcription_manager.clone(),
+        history_manager.clone(),
+    );
+    let phone_import_manager = Arc::new(PhoneImportManager::new(
+        app_handle,
+        transcription_manager.clone(),
+        history_manager.clone(),
+    ));
 
     // Add managers to Tauri's managed state
     app_handle.manage(recording_manager.clone());
     app_handle.manage(model_manager.clone());
     app_handle.manage(transcription_manager.clone());
+    app_handle.manage(transcription_queue.clone());
+    app_handle.manage(vocabulary_manager.clone());
+    app_handle.manage(metrics_manager.clone());
     app_handle.manage(history_manager.clone());
+    app_handle.manage(history_sync_manager.clone());
+    app_handle.manage(backup_manager.clone());
+    app_handle.manage(automation_manager.clone());
+    app_handle.manage(hid_manager.clone());
+    app_handle.manage(stream_deck_manager.clone());
+    app_handle.manage(midi_manager.clone());
+    app_handle.manage(power_manager.clone());
+    app_handle.manage(provider_health_manager.clone());
+    app_handle.manage(session_lock_manager.clone());
+    app_handle.manage(download_queue_manager.clone());
+    app_handle.manage(offline_queue_manager.clone());
+    app_handle.manage(voice_profile_manager.clone());
+    app_handle.manage(practice_manager.clone());
+    app_handle.manage(watch_folder_manager.clone());
+    app_handle.manage(email_ingest_manager.clone());
+    app_handle.manage(phone_import_manager.clone());
+
+    // Start out with the battery saver applied if we're on battery
+    power_manager.apply_policy();
+
+    // Find out which models are downloaded without holding up the window
+    model_manager.start_initialization();
+
+    // Look for models that haven't been used in a while
+    model_manager.start_housekeeping();
+
+    // Pick up models published since this build, falling back silently
+    let catalog_manager = model_manager.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = catalog_manager.refresh_catalog().await {
+            log::warn!("Failed to refresh model catalog: {}", e);
+        }
+    });
+
+    // Let people without the auto-updater know about new releases
+    update_check::start(app_handle);
 
     // Initialize the shortcuts
     shortcut::init_shortcuts(app_handle);
 
+    // Tell the UI early about anything that would stop dictation
+    health::start(app_handle);
+
     // Apply macOS Accessory policy if starting hidden
     #[cfg(target_os = "macos")]
     {
@@ -108,7 +240,7 @@ fn initialize_core_logic(app_handle: &AppHandle) {
             }
             "check_updates" => {
                 show_main_window(app);
-                let _ = app.emit("check-for-updates", ());
+                events::emit(app, AppEvent::CheckForUpdates(()));
             }
             "cancel" => {
                 use crate::utils::cancel_current_operation;
@@ -119,6 +251,16 @@ fn initialize_core_logic(app_handle: &AppHandle) {
             "quit" => {
                 app.exit(0);
             }
+            id if id.starts_with("layer:") => {
+                let layer = Some(&id["layer:".len()..])
+                    .filter(|l| !l.is_empty())
+                    .map(str::to_string);
+                if let Err(e) = shortcut::switch_shortcut_layer(app.clone(), layer) {
+                    eprintln!("Failed to switch shortcut layer: {}", e);
+                    // Undo the check mark the click toggled
+                    shortcut::refresh_layer_menu(app);
+                }
+            }
             _ => {}
         })
         .build(app_handle)
@@ -146,14 +288,17 @@ fn initialize_core_logic(app_handle: &AppHandle) {
 
 #[tauri::command]
 fn trigger_update_check(app: AppHandle) -> Result<(), String> {
-    app.emit("check-for-updates", ())
-        .map_err(|e| e.to_string())?;
+    events::emit(&app, AppEvent::CheckForUpdates(()));
     Ok(())
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    env_logger::init();
+    // The crash monitor is this executable started with its own arguments
+    if crash_report::run_monitor_if_requested() {
+        return;
+    }
+    crash_report::init_logging();
 
     tauri::Builder::default()
         .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
@@ -221,6 +366,10 @@ pub fn run() {
             shortcut::change_binding,
             shortcut::reset_binding,
             shortcut::change_ptt_setting,
+            shortcut::change_auto_submit_setting,
+            shortcut::change_max_recording_setting,
+            shortcut::change_live_transcription_setting,
+            shortcut::change_lock_behavior_setting,
             shortcut::change_audio_feedback_setting,
             shortcut::change_audio_feedback_volume_setting,
             shortcut::change_sound_theme_setting,
@@ -234,25 +383,119 @@ pub fn run() {
             shortcut::change_paste_method_setting,
             shortcut::change_clipboard_handling_setting,
             shortcut::update_custom_words,
+            shortcut::update_replacement_rules,
+            shortcut::change_remove_filler_words_setting,
+            shortcut::update_filler_words,
+            shortcut::update_language_vocabulary,
             shortcut::suspend_binding,
             shortcut::resume_binding,
+            shortcut::switch_shortcut_layer,
+            shortcut::save_shortcut_layer,
+            shortcut::delete_shortcut_layer,
             shortcut::change_mute_while_recording_setting,
+            shortcut::change_deferred_paste_setting,
+            shortcut::discard_pending_paste,
+            shortcut::change_update_checks_setting,
             trigger_update_check,
             commands::cancel_operation,
             commands::get_app_dir_path,
+            commands::check_for_app_update,
+            commands::set_release_channel,
+            commands::run_health_check,
+            commands::get_event_schema,
+            commands::crash_reports::get_crash_reports,
+            commands::crash_reports::submit_crash_report,
+            commands::crash_reports::delete_crash_report,
+            commands::crash_reports::get_crash_report_payload,
+            commands::crash_reports::change_crash_report_url_setting,
             commands::models::get_available_models,
             commands::models::get_model_info,
+            commands::models::get_model_details,
             commands::models::download_model,
             commands::models::delete_model,
+            commands::models::check_model_integrity,
+            commands::models::repair_model,
+            commands::models::check_model_updates,
+            commands::models::upgrade_model,
+            commands::models::select_model_variant,
+            commands::models::benchmark_model,
+            commands::models::get_models_dir_status,
+            commands::models::download_coreml_encoder,
+            commands::models::refresh_model_catalog,
+            commands::models::register_custom_model,
+            commands::models::remove_custom_model,
+            commands::transcription::get_acceleration_info,
+            commands::transcription::set_gpu_acceleration,
+            commands::transcription::get_last_transcription_backend,
+            commands::transcription::set_context_continuation,
+            commands::transcription::set_paragraph_breaks,
+            commands::transcription::set_trim_silence,
+            commands::transcription::set_cpu_usage,
+            commands::transcription::set_whisper_sampling,
+            commands::transcription::set_whisper_fallback,
+            commands::transcription::set_filter_hallucinations,
+            commands::transcription::set_confirm_paste_on_focus_change,
+            commands::transcription::set_no_speech_threshold,
+            commands::transcription::set_suppress_non_speech_tokens,
+            commands::transcription::set_lock_to_recording_app,
+            commands::transcription::set_chunk_transcription_seconds,
+            commands::transcription::reprocess_entry,
+            commands::transcription::get_transcription_queue,
+            commands::transcription::cancel_transcription,
+            commands::transcription::get_post_process_presets,
+            commands::transcription::save_post_process_preset,
+            commands::transcription::delete_post_process_preset,
+            commands::transcription::set_active_preset,
+            commands::transcription::set_binding_preset,
+            commands::transcription::set_watch_folder,
+            commands::transcription::retry_watch_folder_failures,
+            commands::transcription::set_email_ingest,
+            commands::transcription::check_email_ingest,
+            commands::transcription::reset_dictation_context,
+            commands::transcription::set_comparison_models,
+            commands::transcription::compare_history_entry,
+            commands::transcription::label_history_speakers,
+            commands::transcription::set_queue_when_offline,
+            commands::transcription::get_offline_queue_length,
+            commands::transcription::retry_offline_queue,
+            commands::transcription::ocr_clipboard_image,
+            commands::transcription::set_ocr_language,
+            commands::transcription::read_back_last_transcript,
+            commands::transcription::stop_read_back,
+            commands::transcription::set_readback_voice,
             commands::models::cancel_download,
+            commands::models::queue_model_download,
+            commands::models::get_download_queue,
+            commands::models::set_max_concurrent_downloads,
+            commands::models::get_unused_models,
+            commands::models::delete_unused_models,
+            commands::models::clean_up_model_files,
+            commands::models::export_model,
+            commands::models::import_model,
+            commands::models::set_unused_model_policy,
+            commands::models::set_model_mirrors,
             commands::models::set_active_model,
+            commands::models::get_model_recommendation,
+            commands::models::download_recommended_model,
+            commands::models::get_model_overrides,
+            commands::models::set_model_overrides,
+            commands::models::get_favorite_models,
+            commands::models::set_model_favorite,
+            commands::models::set_model_rating,
+            commands::models::cycle_favorite_model,
             commands::models::get_current_model,
             commands::models::get_transcription_model_status,
             commands::models::is_model_loading,
+            commands::models::are_models_ready,
             commands::models::has_any_models_available,
             commands::models::has_any_models_or_downloads,
             commands::models::get_recommended_first_model,
             commands::audio::update_microphone_mode,
+            commands::audio::calibrate_noise_floor,
+            commands::audio::change_audio_buffer_size_setting,
+            commands::audio::change_recording_memory_limit_setting,
+            commands::audio::change_capture_sample_rate_setting,
+            commands::audio::change_keep_original_audio_setting,
             commands::audio::get_microphone_mode,
             commands::audio::get_available_microphones,
             commands::audio::set_selected_microphone,
@@ -272,6 +515,9 @@ pub fn run() {
             commands::api::set_gladia_api_key,
             commands::api::get_gladia_api_key,
             commands::api::has_gladia_api_key,
+            commands::api::set_huggingface_token,
+            commands::api::has_huggingface_token,
+            commands::api::get_provider_health,
             commands::audio::play_test_sound,
             commands::audio::check_custom_sounds,
             commands::transcription::set_model_unload_timeout,
@@ -279,9 +525,82 @@ pub fn run() {
             commands::transcription::unload_model_manually,
             commands::history::get_history_entries,
             commands::history::toggle_history_entry_saved,
+            commands::history::update_history_entry_text,
+            commands::history::get_correction_quality_report,
+            commands::history::get_entry_alternatives,
+            commands::history::get_entry_confidence,
+            commands::history::get_entry_raw_text,
+            commands::history::get_entry_markers,
+            commands::history::export_entry_markers,
+            commands::history::swap_to_alternative,
+            commands::history::set_alternatives_count,
             commands::history::get_audio_file_path,
             commands::history::delete_history_entry,
-            commands::history::update_history_limit
+            commands::history::update_history_limit,
+            commands::history::search_history,
+            commands::history::set_history_entry_tags,
+            commands::history::get_saved_searches,
+            commands::history::save_search,
+            commands::history::delete_saved_search,
+            commands::history::run_saved_search,
+            commands::history::set_history_sync_folder,
+            commands::history::set_history_sync_include_audio,
+            commands::history::sync_history_now,
+            commands::history::import_external_data,
+            commands::automation::get_automation_rules,
+            commands::automation::save_automation_rule,
+            commands::automation::delete_automation_rule,
+            commands::backup::set_cloud_backup_config,
+            commands::backup::get_cloud_backup_config,
+            commands::backup::create_cloud_backup,
+            commands::backup::list_cloud_backups,
+            commands::backup::restore_cloud_backup,
+            commands::backup::export_state_bundle,
+            commands::backup::import_state_bundle,
+            commands::vocabulary::set_shared_vocabulary_url,
+            commands::vocabulary::get_shared_vocabulary,
+            commands::vocabulary::refresh_shared_vocabulary,
+            commands::vocabulary::get_session_words,
+            commands::vocabulary::set_session_words,
+            commands::vocabulary::clear_session_words,
+            commands::voice_profile::start_calibration_sample,
+            commands::voice_profile::finish_calibration_sample,
+            commands::voice_profile::get_calibration_samples,
+            commands::voice_profile::clear_calibration_samples,
+            commands::voice_profile::build_voice_profile,
+            commands::voice_profile::set_voice_profile,
+            commands::practice::get_practice_sentence,
+            commands::practice::start_practice_attempt,
+            commands::practice::finish_practice_attempt,
+            commands::practice::get_practice_scores,
+            commands::practice::get_practice_summary,
+            commands::practice::clear_practice_scores,
+            commands::profiles::list_profiles,
+            commands::profiles::create_profile,
+            commands::profiles::delete_profile,
+            commands::profiles::switch_profile,
+            commands::metrics::set_metrics_endpoint,
+            commands::metrics::get_metrics_snapshot,
+            commands::hid::list_hid_devices,
+            commands::hid::start_hid_capture,
+            commands::hid::cancel_hid_capture,
+            commands::hid::get_hid_bindings,
+            commands::hid::add_hid_binding,
+            commands::hid::remove_hid_binding,
+            commands::stream_deck::get_stream_deck_config,
+            commands::stream_deck::set_stream_deck_endpoint,
+            commands::stream_deck::regenerate_stream_deck_token,
+            commands::phone_import::get_phone_import_config,
+            commands::phone_import::set_phone_import_endpoint,
+            commands::phone_import::regenerate_phone_import_token,
+            commands::midi::list_midi_ports,
+            commands::midi::start_midi_capture,
+            commands::midi::cancel_midi_capture,
+            commands::midi::get_midi_bindings,
+            commands::midi::add_midi_binding,
+            commands::midi::remove_midi_binding,
+            commands::power::get_power_status,
+            commands::power::set_battery_saver_policy
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,353 @@
+//! The small HTTP/1.1 server behind the metrics, Stream Deck and phone import
+//! endpoints: a listener thread that can be stopped, request line and header
+//! parsing, token checks and replies. Every connection is one request.
+
+use crate::settings::{get_settings, write_settings, AppSettings};
+use log::{debug, error};
+use rand::RngCore;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// Most bytes the request line and headers together may take.
+const MAX_HEAD_BYTES: u64 = 8 * 1024;
+/// Most connections handled at once per endpoint. Further ones are closed
+/// right away, so a flood can't start a thread per connection.
+const MAX_CONNECTIONS: usize = 16;
+
+/// A running endpoint. Dropping it leaves the thread running, call
+/// [`LocalServer::stop`].
+pub struct LocalServer {
+    pub port: u16,
+    shutdown: Arc<AtomicBool>,
+    handle: thread::JoinHandle<()>,
+}
+
+impl LocalServer {
+    /// Listens on `host` and `port` and hands every connection to `handler`
+    /// on a thread of its own. `name` labels the endpoint in errors and logs.
+    pub fn start<F>(name: &'static str, host: &str, port: u16, handler: F) -> Result<Self, String>
+    where
+        F: Fn(TcpStream) -> std::io::Result<()> + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind((host, port))
+            .map_err(|e| format!("Failed to bind {} port {}: {}", name, port, e))?;
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| format!("Failed to configure {} listener: {}", name, e))?;
+
+        let handler = Arc::new(handler);
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let shutdown_flag = shutdown.clone();
+        let active = Arc::new(AtomicUsize::new(0));
+        let handle = thread::spawn(move || {
+            while !shutdown_flag.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        if active.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                            active.fetch_sub(1, Ordering::SeqCst);
+                            debug!("{} is busy, dropping a connection", name);
+                            continue;
+                        }
+                        let handler = handler.clone();
+                        let slot = ConnectionSlot(active.clone());
+                        thread::spawn(move || {
+                            let _slot = slot;
+                            let result =
+                                stream.set_nonblocking(false).and_then(|()| handler(stream));
+                            if let Err(e) = result {
+                                debug!("{} request failed: {}", name, e);
+                            }
+                        });
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                        thread::sleep(Duration::from_millis(100));
+                    }
+                    Err(e) => {
+                        error!("{} listener error: {}", name, e);
+                        thread::sleep(Duration::from_secs(1));
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            port,
+            shutdown,
+            handle,
+        })
+    }
+
+    /// Stops accepting connections and waits for the listener thread.
+    /// Requests already being handled finish on their own.
+    pub fn stop(self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+        let _ = self.handle.join();
+    }
+}
+
+/// One of the [`MAX_CONNECTIONS`] taken by a connection, given back when
+/// its thread ends, even by a panic.
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// The request line and headers of a request. The body, if any, is left in
+/// `reader`.
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    query: String,
+    headers: Vec<(String, String)>,
+    pub reader: BufReader<TcpStream>,
+}
+
+impl Request {
+    /// Reads the request line and headers, giving up on a client that sends
+    /// nothing for `timeout` or more than [`MAX_HEAD_BYTES`] of them.
+    pub fn read(stream: &TcpStream, timeout: Duration) -> std::io::Result<Self> {
+        stream.set_read_timeout(Some(timeout))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let (request_line, headers) = read_head(&mut reader)?;
+
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or("").to_string();
+        let target = parts.next().unwrap_or("");
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        Ok(Self {
+            method,
+            path: path.to_string(),
+            query: query.to_string(),
+            headers,
+            reader,
+        })
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The decoded value of query parameter `name`.
+    pub fn query_param(&self, name: &str) -> Option<String> {
+        query_param(&self.query, name)
+    }
+
+    pub fn content_length(&self) -> Option<usize> {
+        self.header("content-length")?.parse().ok()
+    }
+
+    /// The path split into its segments, without empty ones at either end.
+    pub fn segments(&self) -> Vec<&str> {
+        self.path.trim_matches('/').split('/').collect()
+    }
+
+    /// Whether the request carries `token`, either as a bearer token or as
+    /// `?token=`. Compared in constant time, so the time a wrong guess takes
+    /// doesn't tell how much of it was right.
+    pub fn has_token(&self, token: &str) -> bool {
+        let bearer = self
+            .header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "));
+        bearer.is_some_and(|bearer| constant_time_eq(bearer.as_bytes(), token.as_bytes()))
+            || self
+                .query_param("token")
+                .is_some_and(|query| constant_time_eq(query.as_bytes(), token.as_bytes()))
+    }
+}
+
+/// Reads the request line and the headers up to the blank line after them,
+/// failing once they take more than [`MAX_HEAD_BYTES`].
+fn read_head(reader: &mut impl BufRead) -> std::io::Result<(String, Vec<(String, String)>)> {
+    let mut head = reader.take(MAX_HEAD_BYTES);
+    let mut read_line = |line: &mut String| -> std::io::Result<usize> {
+        let read = head.read_line(line)?;
+        // Out of budget before the line, or the headers, ended
+        if head.limit() == 0 && !line.ends_with('\n') {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "request headers too large",
+            ));
+        }
+        Ok(read)
+    };
+
+    let mut request_line = String::new();
+    read_line(&mut request_line)?;
+
+    let mut headers = Vec::new();
+    loop {
+        let mut header = String::new();
+        if read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+        }
+    }
+    Ok((request_line, headers))
+}
+
+/// Compares without stopping at the first difference. Only the length
+/// shows through the timing.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn query_param(query: &str, name: &str) -> Option<String> {
+    query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix(name)?.strip_prefix('='))
+        .map(percent_decode)
+}
+
+/// Decodes `%XX` escapes and `+` in a query value.
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| bytes.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (escaped, bytes[i]) {
+            (Some(byte), _) => {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+            (None, b'+') => decoded.push(b' '),
+            (None, byte) => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// Writes a complete response and closes the exchange.
+pub fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+pub fn respond_json(stream: &mut TcpStream, status: &str, body: &str) -> std::io::Result<()> {
+    respond(stream, status, "application/json", body)
+}
+
+/// Returns the access token stored in the settings field `slot`, generating
+/// and persisting one on first use.
+pub fn token(app: &AppHandle, slot: fn(&mut AppSettings) -> &mut Option<String>) -> String {
+    let mut settings = get_settings(app);
+    if let Some(token) = slot(&mut settings).clone() {
+        return token;
+    }
+
+    let mut bytes = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let token: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    *slot(&mut settings) = Some(token.clone());
+    write_settings(app, settings);
+    token
+}
+
+/// Forgets the token in `slot`, so the next [`token`] call makes a new one.
+pub fn clear_token(app: &AppHandle, slot: fn(&mut AppSettings) -> &mut Option<String>) {
+    let mut settings = get_settings(app);
+    *slot(&mut settings) = None;
+    write_settings(app, settings);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_decode_handles_escapes_and_plus() {
+        assert_eq!(percent_decode("Voice+memo%2001.m4a"), "Voice memo 01.m4a");
+        assert_eq!(percent_decode("caf%C3%A9"), "café");
+        assert_eq!(percent_decode("a%2Bb"), "a+b");
+    }
+
+    #[test]
+    fn percent_decode_keeps_broken_escapes() {
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz%4"), "%zz%4");
+    }
+
+    #[test]
+    fn read_head_parses_the_request_line_and_headers() {
+        let mut input: &[u8] =
+            b"GET /import?name=a HTTP/1.1\r\nHost: phone\r\nContent-Length: 4\r\n\r\nbody";
+        let (request_line, headers) = read_head(&mut input).unwrap();
+        assert_eq!(request_line, "GET /import?name=a HTTP/1.1\r\n");
+        assert_eq!(
+            headers,
+            vec![
+                ("host".to_string(), "phone".to_string()),
+                ("content-length".to_string(), "4".to_string()),
+            ]
+        );
+        assert_eq!(input, b"body");
+    }
+
+    #[test]
+    fn read_head_rejects_oversized_headers() {
+        let long_line = format!(
+            "GET /{} HTTP/1.1\r\n\r\n",
+            "a".repeat(MAX_HEAD_BYTES as usize)
+        );
+        assert!(read_head(&mut long_line.as_bytes()).is_err());
+
+        let many_headers = format!(
+            "GET / HTTP/1.1\r\n{}\r\n",
+            "X-Pad: 0123456789\r\n".repeat(1000)
+        );
+        assert!(read_head(&mut many_headers.as_bytes()).is_err());
+
+        // The budget running out right at the end of a header line
+        let request_line = "GET / HTTP/1.1\r\n";
+        let pad = "a".repeat(MAX_HEAD_BYTES as usize - request_line.len() - "X-Pad: \r\n".len());
+        let at_limit = format!("{}X-Pad: {}\r\nX-More: 1\r\n\r\n", request_line, pad);
+        assert!(read_head(&mut at_limit.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn constant_time_eq_compares_whole_tokens() {
+        assert!(constant_time_eq(b"abc123", b"abc123"));
+        assert!(!constant_time_eq(b"abc123", b"abc124"));
+        assert!(!constant_time_eq(b"abc", b"abc123"));
+        assert!(constant_time_eq(b"", b""));
+    }
+
+    #[test]
+    fn query_param_matches_whole_names() {
+        let query = "nametag=x&name=memo%201.m4a&token=abc";
+        assert_eq!(query_param(query, "name").as_deref(), Some("memo 1.m4a"));
+        assert_eq!(query_param(query, "token").as_deref(), Some("abc"));
+        assert_eq!(query_param(query, "tok"), None);
+    }
+}
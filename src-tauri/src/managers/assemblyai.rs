@@ -1,3 +1,4 @@
+use crate::engine::{ScoredWord, SegmentConfidence};
 use crate::settings::get_settings;
 use anyhow::Result;
 use serde::Deserialize;
@@ -20,6 +21,18 @@ struct AssemblyAITranscriptStatus {
     status: String,
     text: Option<String>,
     error: Option<String>,
+    confidence: Option<f32>,
+    #[serde(default)]
+    words: Vec<AssemblyAIWord>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AssemblyAIWord {
+    text: String,
+    /// Milliseconds.
+    start: u64,
+    end: u64,
+    confidence: f32,
 }
 
 #[derive(Clone)]
@@ -36,7 +49,32 @@ impl AssemblyAIApiManager {
         }
     }
 
-    pub async fn transcribe(&self, audio_data: Vec<f32>) -> Result<String> {
+    /// Makes a cheap authenticated request, to check the API is up and keep
+    /// the connection warm.
+    pub async fn ping(&self) -> Result<()> {
+        let api_key = get_settings(&self.app_handle)
+            .assemblyai_api_key
+            .ok_or_else(|| anyhow::anyhow!("AssemblyAI API key not set"))?;
+        let response = self
+            .client
+            .get("https://api.assemblyai.com/v2/transcript")
+            .query(&[("limit", "1")])
+            .header("authorization", &api_key)
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            debug!("[AssemblyAI] Ping failed with status {}", status);
+            return Err(anyhow::anyhow!("AssemblyAI API returned status {}", status));
+        }
+        Ok(())
+    }
+
+    /// Returns the transcript and the confidence of its words.
+    pub async fn transcribe(
+        &self,
+        audio_data: Vec<f32>,
+    ) -> Result<(String, Vec<SegmentConfidence>)> {
         info!("[AssemblyAI] Starting transcription with {} audio samples", audio_data.len());
         
         let settings = get_settings(&self.app_handle);
@@ -159,9 +197,10 @@ impl AssemblyAIApiManager {
             
             match status_result.status.as_str() {
                 "completed" => {
+                    let confidence = word_confidence(&status_result);
                     let transcript = status_result.text.unwrap_or_default();
-                    info!("[AssemblyAI] Transcription successful: {}", transcript);
-                    return Ok(transcript);
+                    debug!("[AssemblyAI] Transcription successful: {}", transcript);
+                    return Ok((transcript, confidence));
                 },
                 "error" => {
                     let error_msg = status_result.error.unwrap_or("Unknown error".to_string());
@@ -177,6 +216,31 @@ impl AssemblyAIApiManager {
     }
 }
 
+/// The whole transcript as one segment, AssemblyAI scores every word.
+fn word_confidence(status: &AssemblyAITranscriptStatus) -> Vec<SegmentConfidence> {
+    let (Some(first), Some(last)) = (status.words.first(), status.words.last()) else {
+        return Vec::new();
+    };
+    let words: Vec<ScoredWord> = status
+        .words
+        .iter()
+        .map(|word| ScoredWord {
+            text: word.text.clone(),
+            confidence: word.confidence,
+        })
+        .collect();
+    let confidence = status
+        .confidence
+        .unwrap_or_else(|| words.iter().map(|w| w.confidence).sum::<f32>() / words.len() as f32);
+    vec![SegmentConfidence {
+        start: first.start as f32 / 1000.0,
+        end: last.end as f32 / 1000.0,
+        text: status.text.clone().unwrap_or_default(),
+        confidence,
+        words,
+    }]
+}
+
 fn float_to_wav(audio_data: &[f32]) -> Result<Vec<u8>> {
     let spec = hound::WavSpec {
         channels: 1,
@@ -1,12 +1,21 @@
-use crate::audio_toolkit::{list_input_devices, vad::SmoothedVad, AudioRecorder, SileroVad};
-use crate::settings::get_settings;
+use crate::audio_toolkit::{
+    list_input_devices, vad::SmoothedVad, AudioRecorder, Recording, SileroVad,
+};
+use crate::settings::{get_settings, write_settings};
 use crate::utils;
+use cpal::Device;
 use log::{debug, info};
+use serde::Serialize;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tauri::Manager;
 
 const WHISPER_SAMPLE_RATE: usize = 16000;
+/// Silero works on 30 ms frames.
+const VAD_FRAME_SAMPLES: usize = WHISPER_SAMPLE_RATE * 30 / 1000;
+const QUIET_NOISE_FLOOR_DB: f32 = -70.0;
+const MAX_INPUT_GAIN: f32 = 4.0;
 
 /* ──────────────────────────────────────────────────────────────── */
 
@@ -22,13 +31,36 @@ pub enum MicrophoneMode {
     OnDemand,
 }
 
+/// A stopped recording with what was captured along with it, kept together
+/// so a recording waiting in the transcription queue doesn't pick up the
+/// next one's.
+pub struct FinishedRecording {
+    pub audio: Recording,
+    /// Markers set while recording, in ms from its start.
+    pub markers: Vec<u64>,
+    /// The recording at the capture rate, with that rate. Only kept when
+    /// the setting is on.
+    pub original: Option<(u32, Vec<f32>)>,
+}
+
+impl From<Recording> for FinishedRecording {
+    fn from(audio: Recording) -> Self {
+        Self {
+            audio,
+            markers: Vec::new(),
+            original: None,
+        }
+    }
+}
+
 /* ──────────────────────────────────────────────────────────────── */
 
 fn create_audio_recorder(
     vad_path: &str,
     app_handle: &tauri::AppHandle,
 ) -> Result<AudioRecorder, anyhow::Error> {
-    let silero = SileroVad::new(vad_path, 0.3)
+    let settings = get_settings(app_handle);
+    let silero = SileroVad::new(vad_path, settings.vad_threshold)
         .map_err(|e| anyhow::anyhow!("Failed to create SileroVad: {}", e))?;
     let smoothed_vad = SmoothedVad::new(Box::new(silero), 15, 15, 2);
 
@@ -37,6 +69,18 @@ fn create_audio_recorder(
     let recorder = AudioRecorder::new()
         .map_err(|e| anyhow::anyhow!("Failed to create AudioRecorder: {}", e))?
         .with_vad(Box::new(smoothed_vad))
+        .with_gain(settings.input_gain)
+        .with_buffer_size(settings.audio_buffer_size)
+        // The device's own rate when the original is kept and none is set
+        .with_sample_rate(
+            settings
+                .capture_sample_rate
+                .or((!settings.keep_original_audio).then_some(WHISPER_SAMPLE_RATE as u32)),
+        )
+        .with_original_audio(settings.keep_original_audio)
+        .with_memory_limit((settings.recording_memory_limit_mb > 0).then(|| {
+            settings.recording_memory_limit_mb as usize * 1024 * 1024 / std::mem::size_of::<f32>()
+        }))
         .with_level_callback({
             let app_handle = app_handle.clone();
             move |levels| {
@@ -47,6 +91,14 @@ fn create_audio_recorder(
     Ok(recorder)
 }
 
+/// Result of measuring the room with `calibrate_noise_floor`.
+#[derive(Clone, Debug, Serialize)]
+pub struct NoiseCalibration {
+    pub noise_floor_db: f32,
+    pub vad_threshold: f32,
+    pub input_gain: f32,
+}
+
 /* ──────────────────────────────────────────────────────────────── */
 
 #[derive(Clone)]
@@ -59,6 +111,11 @@ pub struct AudioRecordingManager {
     is_open: Arc<Mutex<bool>>,
     is_recording: Arc<Mutex<bool>>,
     initial_volume: Arc<Mutex<Option<u8>>>,
+
+    /// When the current recording started, for marker offsets.
+    recording_started: Arc<Mutex<Option<Instant>>>,
+    /// Markers of the current recording, in ms from its start.
+    markers: Arc<Mutex<Vec<u64>>>,
 }
 
 impl AudioRecordingManager {
@@ -81,6 +138,9 @@ impl AudioRecordingManager {
             is_open: Arc::new(Mutex::new(false)),
             is_recording: Arc::new(Mutex::new(false)),
             initial_volume: Arc::new(Mutex::new(None)),
+
+            recording_started: Arc::new(Mutex::new(None)),
+            markers: Arc::new(Mutex::new(Vec::new())),
         };
 
         // Always-on?  Open immediately.
@@ -112,14 +172,7 @@ impl AudioRecordingManager {
             *initial_volume_guard = None;
         }
 
-        let vad_path = self
-            .app_handle
-            .path()
-            .resolve(
-                "resources/models/silero_vad_v4.onnx",
-                tauri::path::BaseDirectory::Resource,
-            )
-            .map_err(|e| anyhow::anyhow!("Failed to resolve VAD path: {}", e))?;
+        let vad_path = self.vad_path()?;
         let mut recorder_opt = self.recorder.lock().unwrap();
 
         if recorder_opt.is_none() {
@@ -129,23 +182,7 @@ impl AudioRecordingManager {
             )?);
         }
 
-        // Get the selected device from settings
-        let settings = get_settings(&self.app_handle);
-        let selected_device = if let Some(device_name) = settings.selected_microphone {
-            // Find the device by name
-            match list_input_devices() {
-                Ok(devices) => devices
-                    .into_iter()
-                    .find(|d| d.name == device_name)
-                    .map(|d| d.device),
-                Err(e) => {
-                    debug!("Failed to list devices, using default: {}", e);
-                    None
-                }
-            }
-        } else {
-            None
-        };
+        let selected_device = self.selected_device();
 
         if let Some(rec) = recorder_opt.as_mut() {
             rec.open(selected_device)
@@ -160,6 +197,31 @@ impl AudioRecordingManager {
         Ok(())
     }
 
+    fn vad_path(&self) -> Result<PathBuf, anyhow::Error> {
+        self.app_handle
+            .path()
+            .resolve(
+                "resources/models/silero_vad_v4.onnx",
+                tauri::path::BaseDirectory::Resource,
+            )
+            .map_err(|e| anyhow::anyhow!("Failed to resolve VAD path: {}", e))
+    }
+
+    /// The microphone chosen in settings, or `None` for the default device.
+    fn selected_device(&self) -> Option<Device> {
+        let device_name = get_settings(&self.app_handle).selected_microphone?;
+        match list_input_devices() {
+            Ok(devices) => devices
+                .into_iter()
+                .find(|d| d.name == device_name)
+                .map(|d| d.device),
+            Err(e) => {
+                debug!("Failed to list devices, using default: {}", e);
+                None
+            }
+        }
+    }
+
     pub fn stop_microphone_stream(&self) {
         let mut open_flag = self.is_open.lock().unwrap();
         if !*open_flag {
@@ -187,6 +249,10 @@ impl AudioRecordingManager {
 
     /* ---------- mode switching --------------------------------------------- */
 
+    pub fn is_always_on(&self) -> bool {
+        matches!(*self.mode.lock().unwrap(), MicrophoneMode::AlwaysOn)
+    }
+
     pub fn update_mode(&self, new_mode: MicrophoneMode) -> Result<(), anyhow::Error> {
         let mode_guard = self.mode.lock().unwrap();
         let cur_mode = mode_guard.clone();
@@ -226,6 +292,8 @@ impl AudioRecordingManager {
             if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
                 if rec.start().is_ok() {
                     *self.is_recording.lock().unwrap() = true;
+                    *self.recording_started.lock().unwrap() = Some(Instant::now());
+                    self.markers.lock().unwrap().clear();
                     *state = RecordingState::Recording {
                         binding_id: binding_id.to_string(),
                     };
@@ -240,6 +308,95 @@ impl AudioRecordingManager {
         }
     }
 
+    /// Rebuilds the recorder so VAD and gain settings take effect. Does
+    /// nothing while recording; the new settings apply from the next stream.
+    pub fn reload_recorder(&self) -> Result<(), anyhow::Error> {
+        if !matches!(*self.state.lock().unwrap(), RecordingState::Idle) {
+            return Ok(());
+        }
+
+        let was_open = *self.is_open.lock().unwrap();
+        self.stop_microphone_stream();
+        *self.recorder.lock().unwrap() = None;
+        if was_open {
+            self.start_microphone_stream()?;
+        }
+        Ok(())
+    }
+
+    /// Records the room for `duration` without any voice detection and
+    /// derives a VAD threshold and input gain from it. The user should stay
+    /// quiet while this runs. The results are saved to settings.
+    pub fn calibrate_noise_floor(
+        &self,
+        duration: Duration,
+    ) -> Result<NoiseCalibration, anyhow::Error> {
+        if !matches!(*self.state.lock().unwrap(), RecordingState::Idle) {
+            return Err(anyhow::anyhow!("Cannot calibrate while recording"));
+        }
+
+        let mut recorder = AudioRecorder::new()
+            .map_err(|e| anyhow::anyhow!("Failed to create AudioRecorder: {}", e))?;
+        recorder
+            .open(self.selected_device())
+            .map_err(|e| anyhow::anyhow!("Failed to open recorder: {}", e))?;
+        recorder
+            .start()
+            .map_err(|e| anyhow::anyhow!("Failed to start recording: {}", e))?;
+        std::thread::sleep(duration);
+        let samples = recorder
+            .stop()
+            .map_err(|e| anyhow::anyhow!("Failed to stop recording: {}", e))?;
+        let _ = recorder.close();
+
+        if samples.len() < WHISPER_SAMPLE_RATE / 2 {
+            return Err(anyhow::anyhow!("Not enough audio was captured"));
+        }
+
+        let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+        let noise_floor_db = 20.0 * rms.max(1e-9).log10();
+
+        // How speech-like the room sounds to Silero decides the threshold
+        let vad_path = self.vad_path()?;
+        let mut silero = SileroVad::new(vad_path, 0.5)
+            .map_err(|e| anyhow::anyhow!("Failed to create SileroVad: {}", e))?;
+        let mut probabilities: Vec<f32> = samples
+            .chunks_exact(VAD_FRAME_SAMPLES)
+            .filter_map(|frame| silero.speech_probability(frame).ok())
+            .collect();
+        probabilities.sort_by(|a, b| a.total_cmp(b));
+        let p95 = probabilities
+            .get(probabilities.len() * 95 / 100)
+            .copied()
+            .unwrap_or(0.0);
+        let vad_threshold = ((p95 + 0.15).clamp(0.3, 0.8) * 100.0).round() / 100.0;
+
+        // A very low floor usually means a quiet microphone rather than a
+        // silent room, so boost it towards a typical level
+        let input_gain = if noise_floor_db < QUIET_NOISE_FLOOR_DB {
+            let boost = 10f32.powf((QUIET_NOISE_FLOOR_DB - noise_floor_db) / 20.0);
+            (boost.min(MAX_INPUT_GAIN) * 10.0).round() / 10.0
+        } else {
+            1.0
+        };
+
+        let calibration = NoiseCalibration {
+            noise_floor_db,
+            vad_threshold,
+            input_gain,
+        };
+        info!("Noise calibration: {:?}", calibration);
+
+        let mut settings = get_settings(&self.app_handle);
+        settings.noise_floor_db = Some(noise_floor_db);
+        settings.vad_threshold = vad_threshold;
+        settings.input_gain = input_gain;
+        write_settings(&self.app_handle, settings);
+        self.reload_recorder()?;
+
+        Ok(calibration)
+    }
+
     pub fn update_selected_device(&self) -> Result<(), anyhow::Error> {
         // If currently open, restart the microphone stream to use the new device
         if *self.is_open.lock().unwrap() {
@@ -249,7 +406,7 @@ impl AudioRecordingManager {
         Ok(())
     }
 
-    pub fn stop_recording(&self, binding_id: &str) -> Option<Vec<f32>> {
+    pub fn stop_recording(&self, binding_id: &str) -> Option<FinishedRecording> {
         let mut state = self.state.lock().unwrap();
 
         match *state {
@@ -259,20 +416,29 @@ impl AudioRecordingManager {
                 *state = RecordingState::Idle;
                 drop(state);
 
-                let samples = if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
-                    match rec.stop() {
-                        Ok(buf) => buf,
-                        Err(e) => {
-                            eprintln!("stop() failed: {e}");
-                            Vec::new()
-                        }
-                    }
-                } else {
-                    eprintln!("Recorder not available");
-                    Vec::new()
-                };
+                let (mut samples, original) =
+                    if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
+                        let samples = match rec.stop() {
+                            Ok(buf) => buf,
+                            Err(e) => {
+                                eprintln!("stop() failed: {e}");
+                                Recording::from(Vec::new())
+                            }
+                        };
+                        // Taken now, the recorder closes below in on-demand mode
+                        let original = rec.take_original().unwrap_or_else(|e| {
+                            eprintln!("take_original() failed: {e}");
+                            None
+                        });
+                        (samples, original)
+                    } else {
+                        eprintln!("Recorder not available");
+                        (Recording::from(Vec::new()), None)
+                    };
 
                 *self.is_recording.lock().unwrap() = false;
+                *self.recording_started.lock().unwrap() = None;
+                let markers = std::mem::take(&mut *self.markers.lock().unwrap());
 
                 // In on-demand mode turn the mic off again
                 if matches!(*self.mode.lock().unwrap(), MicrophoneMode::OnDemand) {
@@ -283,17 +449,90 @@ impl AudioRecordingManager {
                 let s_len = samples.len();
                 // println!("Got {} samples", { s_len });
                 if s_len < WHISPER_SAMPLE_RATE && s_len > 0 {
-                    let mut padded = samples;
-                    padded.resize(WHISPER_SAMPLE_RATE * 5 / 4, 0.0);
-                    Some(padded)
-                } else {
-                    Some(samples)
+                    samples.pad_to(WHISPER_SAMPLE_RATE * 5 / 4);
                 }
+                Some(FinishedRecording {
+                    audio: samples,
+                    markers,
+                    original,
+                })
             }
             _ => None,
         }
     }
 
+    /// Takes the speech recorded so far for `binding_id` while it keeps
+    /// recording, once there is at least `min_samples` of it and the speaker
+    /// pauses.
+    pub fn take_chunk(&self, binding_id: &str, min_samples: usize) -> Option<Vec<f32>> {
+        let state = self.state.lock().unwrap();
+        match *state {
+            RecordingState::Recording {
+                binding_id: ref active,
+            } if active == binding_id => {
+                let recorder = self.recorder.lock().unwrap();
+                match recorder.as_ref()?.take_at_pause(min_samples) {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        eprintln!("take_at_pause() failed: {e}");
+                        None
+                    }
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// The length of the speech recorded so far for `binding_id` and a copy
+    /// of its last `max` samples, leaving the recording untouched.
+    pub fn tail(&self, binding_id: &str, max: usize) -> Option<(usize, Vec<f32>)> {
+        let state = self.state.lock().unwrap();
+        match *state {
+            RecordingState::Recording {
+                binding_id: ref active,
+            } if active == binding_id => {
+                let recorder = self.recorder.lock().unwrap();
+                match recorder.as_ref()?.tail(max) {
+                    Ok(tail) => tail,
+                    Err(e) => {
+                        eprintln!("tail() failed: {e}");
+                        None
+                    }
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Marks the current moment of the recording, returning its offset in
+    /// ms. The offset is wall-clock time since the recording started.
+    pub fn add_marker(&self) -> Option<u64> {
+        let started = (*self.recording_started.lock().unwrap())?;
+        let offset_ms = started.elapsed().as_millis() as u64;
+        self.markers.lock().unwrap().push(offset_ms);
+        Some(offset_ms)
+    }
+
+    /// Time since the current recording started, `None` when not recording.
+    pub fn recording_elapsed(&self) -> Option<Duration> {
+        Some(self.recording_started.lock().unwrap()?.elapsed())
+    }
+
+    /// The binding that started the current recording, if any.
+    pub fn active_binding(&self) -> Option<String> {
+        match &*self.state.lock().unwrap() {
+            RecordingState::Recording { binding_id } => Some(binding_id.clone()),
+            RecordingState::Idle => None,
+        }
+    }
+
+    pub fn is_recording_binding(&self, binding_id: &str) -> bool {
+        matches!(
+            &*self.state.lock().unwrap(),
+            RecordingState::Recording { binding_id: active } if active == binding_id
+        )
+    }
+
     /// Cancel any ongoing recording without returning audio samples
     pub fn cancel_recording(&self) {
         let mut state = self.state.lock().unwrap();
@@ -307,6 +546,8 @@ impl AudioRecordingManager {
             }
 
             *self.is_recording.lock().unwrap() = false;
+            *self.recording_started.lock().unwrap() = None;
+            self.markers.lock().unwrap().clear();
 
             // In on-demand mode turn the mic off again
             if matches!(*self.mode.lock().unwrap(), MicrophoneMode::OnDemand) {
@@ -0,0 +1,276 @@
+use crate::managers::history::{HistoryEntry, HistoryManager};
+use crate::settings::{get_settings, AutomationAction, AutomationConditions, AutomationRule};
+use anyhow::{anyhow, Result};
+use log::{debug, error, info};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::AppHandle;
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Evaluates the user's automation rules against every new recording and runs
+/// the actions of the rules that match.
+pub struct AutomationManager {
+    app_handle: AppHandle,
+    history_manager: Arc<HistoryManager>,
+    client: reqwest::Client,
+}
+
+impl AutomationManager {
+    pub fn new(app_handle: &AppHandle, history_manager: Arc<HistoryManager>) -> Self {
+        Self {
+            app_handle: app_handle.clone(),
+            history_manager,
+            client: reqwest::Client::builder()
+                .timeout(WEBHOOK_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Runs the matching rules for a newly saved entry. A failing action is
+    /// logged and doesn't stop the remaining ones.
+    pub async fn process_entry(&self, entry: HistoryEntry) {
+        let rules = get_settings(&self.app_handle).automation_rules;
+        for rule in rules
+            .iter()
+            .filter(|r| r.enabled && matches(&r.conditions, &entry))
+        {
+            debug!("Automation rule '{}' matched entry {}", rule.name, entry.id);
+            for action in &rule.actions {
+                if let Err(e) = self.run_action(action, &entry).await {
+                    error!("Automation rule '{}' action failed: {}", rule.name, e);
+                }
+            }
+        }
+    }
+
+    async fn run_action(&self, action: &AutomationAction, entry: &HistoryEntry) -> Result<()> {
+        match action {
+            AutomationAction::Tag { tag } => {
+                // Re-read so tags added by an earlier action aren't lost
+                let current = self
+                    .history_manager
+                    .get_entry_by_id(entry.id)
+                    .await?
+                    .ok_or_else(|| anyhow!("History entry {} no longer exists", entry.id))?;
+                let mut tags = current.tags;
+                tags.push(tag.clone());
+                self.history_manager.set_tags(entry.id, tags).await
+            }
+            AutomationAction::Webhook { url } => {
+                let response = self.client.post(url).json(entry).send().await?;
+                if !response.status().is_success() {
+                    return Err(anyhow!("Webhook returned {}", response.status()));
+                }
+                Ok(())
+            }
+            AutomationAction::AppendToFile { path } => {
+                let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+                writeln!(file, "{}\t{}", entry.title, entry.transcription_text)?;
+                Ok(())
+            }
+            AutomationAction::RunHook { command, args } => {
+                let mut child = Command::new(command)
+                    .args(args)
+                    .env("HANDY_ENTRY_ID", entry.id.to_string())
+                    .env("HANDY_TIMESTAMP", entry.timestamp.to_string())
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::null())
+                    .spawn()?;
+                if let Some(mut stdin) = child.stdin.take() {
+                    stdin.write_all(entry.transcription_text.as_bytes())?;
+                }
+                // Hooks run detached, reap them off the async runtime
+                std::thread::spawn(move || {
+                    let _ = child.wait();
+                });
+                info!("Started automation hook {}", command);
+                Ok(())
+            }
+        }
+    }
+}
+
+fn matches(conditions: &AutomationConditions, entry: &HistoryEntry) -> bool {
+    let text = &entry.transcription_text;
+    let length = text.chars().count();
+
+    if let Some(app_name) = conditions.app_name.as_ref().filter(|a| !a.is_empty()) {
+        if entry.app_name.as_deref() != Some(app_name.as_str()) {
+            return false;
+        }
+    }
+    if conditions.min_length.is_some_and(|min| length < min)
+        || conditions.max_length.is_some_and(|max| length > max)
+    {
+        return false;
+    }
+
+    let keywords: Vec<String> = conditions
+        .keywords
+        .iter()
+        .map(|k| k.trim().to_lowercase())
+        .filter(|k| !k.is_empty())
+        .collect();
+    if !keywords.is_empty() {
+        let lower = text.to_lowercase();
+        return keywords.iter().any(|k| lower.contains(k.as_str()));
+    }
+
+    true
+}
+
+/// Disables restored rules that would run a program, write a file or send
+/// transcripts away, unless this machine already has the same rule enabled.
+/// A backup or bundle may come from someone else, and its rules would run on
+/// the next dictation. Returns the names of the rules disabled.
+pub fn disable_restored_rules(
+    restored: &mut [AutomationRule],
+    current: &[AutomationRule],
+) -> Vec<String> {
+    let mut disabled = Vec::new();
+    for rule in restored.iter_mut().filter(|r| r.enabled) {
+        let reaches_outside = rule
+            .actions
+            .iter()
+            .any(|a| !matches!(a, AutomationAction::Tag { .. }));
+        if reaches_outside && !current.contains(rule) {
+            rule.enabled = false;
+            disabled.push(rule.name.clone());
+        }
+    }
+    disabled
+}
+
+/// Checks a rule before it is stored.
+pub fn validate_rule(rule: &AutomationRule) -> Result<(), String> {
+    if rule.name.trim().is_empty() {
+        return Err("Rule name cannot be empty".to_string());
+    }
+    if rule.actions.is_empty() {
+        return Err("A rule needs at least one action".to_string());
+    }
+    for action in &rule.actions {
+        match action {
+            AutomationAction::Webhook { url }
+                if !(url.starts_with("https://") || url.starts_with("http://")) =>
+            {
+                return Err(format!("Invalid webhook URL: {}", url));
+            }
+            AutomationAction::Tag { tag } if tag.trim().is_empty() => {
+                return Err("Tag cannot be empty".to_string());
+            }
+            AutomationAction::AppendToFile { path } if path.trim().is_empty() => {
+                return Err("File path cannot be empty".to_string());
+            }
+            AutomationAction::RunHook { command, .. } if command.trim().is_empty() => {
+                return Err("Hook command cannot be empty".to_string());
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(text: &str, app_name: Option<&str>) -> HistoryEntry {
+        HistoryEntry {
+            id: 1,
+            file_name: String::new(),
+            timestamp: 0,
+            saved: false,
+            title: String::new(),
+            transcription_text: text.to_string(),
+            tags: Vec::new(),
+            app_name: app_name.map(str::to_string),
+            window_title: None,
+        }
+    }
+
+    fn rule(name: &str, actions: Vec<AutomationAction>) -> AutomationRule {
+        AutomationRule {
+            id: name.to_string(),
+            name: name.to_string(),
+            enabled: true,
+            conditions: AutomationConditions::default(),
+            actions,
+        }
+    }
+
+    #[test]
+    fn no_conditions_match_everything() {
+        assert!(matches(&AutomationConditions::default(), &entry("", None)));
+    }
+
+    #[test]
+    fn keywords_match_any_ignoring_case() {
+        let conditions = AutomationConditions {
+            keywords: vec!["Invoice".to_string(), " ".to_string(), "todo".to_string()],
+            ..Default::default()
+        };
+        assert!(matches(&conditions, &entry("send the INVOICE today", None)));
+        assert!(matches(&conditions, &entry("add a TODO", None)));
+        assert!(!matches(&conditions, &entry("nothing to see", None)));
+    }
+
+    #[test]
+    fn length_bounds_count_characters() {
+        let conditions = AutomationConditions {
+            min_length: Some(3),
+            max_length: Some(4),
+            ..Default::default()
+        };
+        assert!(!matches(&conditions, &entry("ab", None)));
+        assert!(matches(&conditions, &entry("äöü", None)));
+        assert!(!matches(&conditions, &entry("abcde", None)));
+    }
+
+    #[test]
+    fn app_name_must_be_equal() {
+        let conditions = AutomationConditions {
+            app_name: Some("Slack".to_string()),
+            ..Default::default()
+        };
+        assert!(matches(&conditions, &entry("hi", Some("Slack"))));
+        assert!(!matches(&conditions, &entry("hi", Some("Mail"))));
+        assert!(!matches(&conditions, &entry("hi", None)));
+    }
+
+    #[test]
+    fn restored_rules_reaching_outside_are_disabled() {
+        let tag = rule(
+            "tag",
+            vec![AutomationAction::Tag {
+                tag: "work".to_string(),
+            }],
+        );
+        let hook = rule(
+            "hook",
+            vec![AutomationAction::RunHook {
+                command: "sh".to_string(),
+                args: Vec::new(),
+            }],
+        );
+        let own_webhook = rule(
+            "webhook",
+            vec![AutomationAction::Webhook {
+                url: "https://example.com".to_string(),
+            }],
+        );
+
+        let mut restored = vec![tag.clone(), hook.clone(), own_webhook.clone()];
+        let disabled = disable_restored_rules(&mut restored, &[own_webhook]);
+
+        assert_eq!(disabled, vec!["hook".to_string()]);
+        assert!(restored[0].enabled);
+        assert!(!restored[1].enabled);
+        assert!(restored[2].enabled);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::engine::{ScoredWord, SegmentConfidence};
 use crate::settings::get_settings;
 use anyhow::Result;
 use serde::Deserialize;
@@ -22,6 +23,20 @@ struct DeepgramChannel {
 #[derive(Debug, Deserialize)]
 struct DeepgramAlternative {
     transcript: String,
+    #[serde(default)]
+    confidence: f32,
+    #[serde(default)]
+    words: Vec<DeepgramWord>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepgramWord {
+    word: String,
+    /// Only sent with smart formatting.
+    punctuated_word: Option<String>,
+    start: f32,
+    end: f32,
+    confidence: f32,
 }
 
 #[derive(Clone)]
@@ -38,7 +53,33 @@ impl DeepgramApiManager {
         }
     }
 
-    pub async fn transcribe(&self, audio_data: Vec<f32>) -> Result<String> {
+    /// Makes a cheap authenticated request, to check the API is up and keep
+    /// the connection warm.
+    pub async fn ping(&self) -> Result<()> {
+        let api_key = get_settings(&self.app_handle)
+            .deepgram_api_key
+            .ok_or_else(|| anyhow::anyhow!("Deepgram API key not set"))?;
+        let response = self
+            .client
+            .get("https://api.deepgram.com/v1/projects")
+            .header("Authorization", format!("Token {}", api_key))
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            debug!("[Deepgram] Ping failed with status {}", status);
+            return Err(anyhow::anyhow!("Deepgram API returned status {}", status));
+        }
+        Ok(())
+    }
+
+    /// Returns the transcript, up to `alternatives_count` alternative
+    /// transcripts as ranked by Deepgram, and the confidence of the
+    /// transcript's words.
+    pub async fn transcribe(
+        &self,
+        audio_data: Vec<f32>,
+    ) -> Result<(String, Vec<String>, Vec<SegmentConfidence>)> {
         info!("[Deepgram] Starting transcription with {} audio samples", audio_data.len());
         
         let settings = get_settings(&self.app_handle);
@@ -58,14 +99,27 @@ impl DeepgramApiManager {
         debug!("[Deepgram] URL: https://api.deepgram.com/v1/listen");
         debug!("[Deepgram] Model: nova-3");
         
+        // One more than requested, the first is the transcript itself
+        let alternatives_count = settings.alternatives_count;
+        let alternatives_param = (alternatives_count + 1).to_string();
         let response = self
             .client
             .post("https://api.deepgram.com/v1/listen")
             .query(&[
                 ("model", "nova-3"),
                 ("smart_format", "true"),
-                ("language", "multi")
+                ("language", "multi"),
+                ("alternatives", alternatives_param.as_str())
             ])
+            // Boost the words of the user's voice profile
+            .query(
+                &settings
+                    .voice_profile
+                    .provider_keywords()
+                    .iter()
+                    .map(|keyword| ("keyterm", keyword.as_str()))
+                    .collect::<Vec<_>>(),
+            )
             .header("Authorization", format!("Token {}", api_key))
             .header("Content-Type", "audio/wav")
             .body(wav_data)
@@ -101,19 +155,56 @@ impl DeepgramApiManager {
             })?;
         
         // Extract transcript from Deepgram response structure
-        let transcript = transcription
+        let mut alternatives = transcription
             .results
             .channels
-            .first()
-            .and_then(|channel| channel.alternatives.first())
-            .map(|alternative| alternative.transcript.clone())
-            .unwrap_or_default();
+            .into_iter()
+            .next()
+            .map(|channel| channel.alternatives)
+            .unwrap_or_default()
+            .into_iter();
+        let (transcript, confidence) = match alternatives.next() {
+            Some(best) => {
+                let confidence = word_confidence(&best);
+                (best.transcript, confidence)
+            }
+            None => (String::new(), Vec::new()),
+        };
+        let alternatives: Vec<String> = alternatives
+            .map(|alternative| alternative.transcript)
+            .filter(|t| !t.is_empty() && *t != transcript)
+            .take(alternatives_count as usize)
+            .collect();
         
-        info!("[Deepgram] Transcription successful: {}", transcript);
-        Ok(transcript)
+        debug!("[Deepgram] Transcription successful: {}", transcript);
+        Ok((transcript, alternatives, confidence))
     }
 }
 
+/// The whole transcript as one segment, Deepgram scores every word.
+fn word_confidence(alternative: &DeepgramAlternative) -> Vec<SegmentConfidence> {
+    let (Some(first), Some(last)) = (alternative.words.first(), alternative.words.last()) else {
+        return Vec::new();
+    };
+    vec![SegmentConfidence {
+        start: first.start,
+        end: last.end,
+        text: alternative.transcript.clone(),
+        confidence: alternative.confidence,
+        words: alternative
+            .words
+            .iter()
+            .map(|word| ScoredWord {
+                text: word
+                    .punctuated_word
+                    .clone()
+                    .unwrap_or_else(|| word.word.clone()),
+                confidence: word.confidence,
+            })
+            .collect(),
+    }]
+}
+
 fn float_to_wav(audio_data: &[f32]) -> Result<Vec<u8>> {
     let spec = hound::WavSpec {
         channels: 1,
@@ -0,0 +1,163 @@
+use crate::events::{self, AppEvent};
+use crate::managers::model::ModelManager;
+use crate::settings::get_settings;
+use anyhow::Result;
+use log::{error, info};
+use serde::Serialize;
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use tauri::AppHandle;
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum QueueItemStatus {
+    Queued { position: usize },
+    Downloading,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct DownloadQueueItem {
+    pub model_id: String,
+    #[serde(flatten)]
+    pub status: QueueItemStatus,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct DownloadFinished {
+    pub model_id: String,
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+struct QueueState {
+    queued: VecDeque<String>,
+    active: HashSet<String>,
+}
+
+/// Runs model downloads in the background, at most `max_concurrent_downloads`
+/// at a time, and keeps the rest waiting in order.
+///
+/// Every change to the queue is published as `download-queue-changed`, a
+/// finished item as `download-queue-item-finished`. Progress of a running
+/// download comes from the usual `model-download-progress` events.
+pub struct DownloadQueueManager {
+    app_handle: AppHandle,
+    model_manager: Arc<ModelManager>,
+    state: Mutex<QueueState>,
+}
+
+impl DownloadQueueManager {
+    pub fn new(app_handle: &AppHandle, model_manager: Arc<ModelManager>) -> Arc<Self> {
+        Arc::new(Self {
+            app_handle: app_handle.clone(),
+            model_manager,
+            state: Mutex::new(QueueState::default()),
+        })
+    }
+
+    /// Adds a model to the end of the queue. Models that are already queued
+    /// or downloading are left where they are.
+    pub fn enqueue(self: &Arc<Self>, model_id: &str) -> Result<()> {
+        if self.model_manager.get_model_info(model_id).is_none() {
+            return Err(anyhow::anyhow!("Model not found: {}", model_id));
+        }
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.active.contains(model_id) || state.queued.iter().any(|id| id == model_id) {
+                return Ok(());
+            }
+            state.queued.push_back(model_id.to_string());
+        }
+        self.pump();
+        Ok(())
+    }
+
+    /// Removes a queued model, or stops its download. A stopped download
+    /// keeps its partial file so it can be resumed, its task then finishes
+    /// like a failed download.
+    pub fn cancel(&self, model_id: &str) -> Result<()> {
+        self.state
+            .lock()
+            .unwrap()
+            .queued
+            .retain(|id| id != model_id);
+        self.publish();
+        self.model_manager.cancel_download(model_id)
+    }
+
+    pub fn items(&self) -> Vec<DownloadQueueItem> {
+        let state = self.state.lock().unwrap();
+        let mut items: Vec<DownloadQueueItem> = state
+            .active
+            .iter()
+            .map(|id| DownloadQueueItem {
+                model_id: id.clone(),
+                status: QueueItemStatus::Downloading,
+            })
+            .collect();
+        items.extend(
+            state
+                .queued
+                .iter()
+                .enumerate()
+                .map(|(position, id)| DownloadQueueItem {
+                    model_id: id.clone(),
+                    status: QueueItemStatus::Queued { position },
+                }),
+        );
+        items
+    }
+
+    /// Starts queued downloads until the concurrency limit is reached, e.g.
+    /// after a download finished or the limit was raised.
+    pub fn pump(self: &Arc<Self>) {
+        let limit = get_settings(&self.app_handle)
+            .max_concurrent_downloads
+            .max(1);
+        {
+            // Marking with the lock held keeps a task that finishes right
+            // away from removing itself before it was inserted
+            let mut state = self.state.lock().unwrap();
+            while state.active.len() < limit {
+                let Some(model_id) = state.queued.pop_front() else {
+                    break;
+                };
+
+                info!("Starting queued download of {}", model_id);
+                let queue = self.clone();
+                let id = model_id.clone();
+                tauri::async_runtime::spawn(async move {
+                    let result = queue.model_manager.download_model(&id).await;
+                    queue.finish(&id, result);
+                });
+                state.active.insert(model_id);
+            }
+        }
+        self.publish();
+    }
+
+    fn finish(self: &Arc<Self>, model_id: &str, result: Result<()>) {
+        self.state.lock().unwrap().active.remove(model_id);
+
+        let error = result.err().map(|e| {
+            error!("Queued download of {} failed: {}", model_id, e);
+            e.to_string()
+        });
+        events::emit(
+            &self.app_handle,
+            AppEvent::DownloadQueueItemFinished(DownloadFinished {
+                model_id: model_id.to_string(),
+                error,
+            }),
+        );
+
+        self.pump();
+    }
+
+    fn publish(&self) {
+        events::emit(
+            &self.app_handle,
+            AppEvent::DownloadQueueChanged(self.items()),
+        );
+    }
+}
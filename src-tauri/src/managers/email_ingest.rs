@@ -0,0 +1,272 @@
+use crate::audio_toolkit::{decode_audio_bytes, DECODABLE_EXTENSIONS};
+use crate::events::{self, AppEvent};
+use crate::managers::history::{HistoryManager, ImportedEntry};
+use crate::managers::transcription::TranscriptionManager;
+use crate::settings::{get_settings, EmailIngestConfig};
+use anyhow::Result;
+use log::{debug, info, warn};
+use mail_parser::{MessageParser, MimeHeaders};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::AppHandle;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(120);
+const MAX_ATTEMPTS: u32 = 3;
+/// Keychain service the IMAP passwords are stored under.
+const KEYCHAIN_SERVICE: &str = "Handy email import";
+/// History source of emailed memos.
+const SOURCE_NAME: &str = "Email";
+
+#[derive(Clone, Debug, Serialize)]
+pub struct EmailMemoEvent {
+    pub subject: Option<String>,
+    pub text: Option<String>,
+    pub error: Option<String>,
+}
+
+/// An audio attachment of a message.
+struct Attachment {
+    name: Option<String>,
+    extension: Option<String>,
+    data: Vec<u8>,
+}
+
+/// Polls an IMAP mailbox for unread mail with audio attachments and files
+/// their transcripts into history. Handled mail is marked as read; mail
+/// without audio is left alone.
+pub struct EmailIngestManager {
+    app_handle: AppHandle,
+    transcription_manager: Arc<TranscriptionManager>,
+    history_manager: Arc<HistoryManager>,
+    /// Held while polling, so a manual check doesn't import mail twice.
+    polling: Mutex<()>,
+    /// Unread messages without audio, by UID, so they aren't downloaded again.
+    skipped: Mutex<HashSet<u32>>,
+    failures: Mutex<HashMap<u32, u32>>,
+}
+
+impl EmailIngestManager {
+    pub fn new(
+        app_handle: &AppHandle,
+        transcription_manager: Arc<TranscriptionManager>,
+        history_manager: Arc<HistoryManager>,
+    ) -> Arc<Self> {
+        let manager = Arc::new(Self {
+            app_handle: app_handle.clone(),
+            transcription_manager,
+            history_manager,
+            polling: Mutex::new(()),
+            skipped: Mutex::new(HashSet::new()),
+            failures: Mutex::new(HashMap::new()),
+        });
+
+        let weak = Arc::downgrade(&manager);
+        thread::spawn(move || loop {
+            thread::sleep(POLL_INTERVAL);
+            let Some(manager) = weak.upgrade() else {
+                break;
+            };
+            if get_settings(&manager.app_handle).email_ingest.is_some() {
+                if let Err(e) = manager.poll() {
+                    warn!("Checking the memo mailbox failed: {}", e);
+                }
+            }
+        });
+
+        manager
+    }
+
+    /// Stores the IMAP password for `config` in the system keychain.
+    pub fn set_password(config: &EmailIngestConfig, password: &str) -> Result<()> {
+        keychain_entry(config)?.set_password(password)?;
+        Ok(())
+    }
+
+    /// Forgets state tied to the previous mailbox, whose UIDs mean nothing
+    /// in a new one.
+    pub fn reset(&self) {
+        self.skipped.lock().unwrap().clear();
+        self.failures.lock().unwrap().clear();
+    }
+
+    /// Checks the mailbox once, returning how many memos were transcribed.
+    pub fn poll(&self) -> Result<usize> {
+        let _polling = self.polling.lock().unwrap();
+        let config = get_settings(&self.app_handle)
+            .email_ingest
+            .ok_or_else(|| anyhow::anyhow!("Email import is not set up"))?;
+        let password = keychain_entry(&config)?
+            .get_password()
+            .map_err(|e| anyhow::anyhow!("No password in the keychain: {}", e))?;
+
+        let tls = native_tls::TlsConnector::builder().build()?;
+        let client = imap::connect(
+            (config.server.as_str(), config.port),
+            config.server.as_str(),
+            &tls,
+        )?;
+        let mut session = client
+            .login(&config.username, &password)
+            .map_err(|(e, _)| anyhow::anyhow!("Mail login failed: {}", e))?;
+        session.select(&config.mailbox)?;
+
+        let query = match &config.recipient {
+            Some(recipient) => format!("UNSEEN TO \"{}\"", recipient.replace('"', "")),
+            None => "UNSEEN".to_string(),
+        };
+        let mut uids: Vec<u32> = session.uid_search(&query)?.into_iter().collect();
+        uids.sort();
+        let skipped = self.skipped.lock().unwrap().clone();
+        uids.retain(|uid| !skipped.contains(uid));
+
+        let mut imported = 0;
+        for uid in uids {
+            // Peeking leaves the message unread until it has been handled
+            let fetches = session.uid_fetch(uid.to_string(), "BODY.PEEK[]")?;
+            let Some(raw) = fetches.iter().find_map(|fetch| fetch.body()) else {
+                continue;
+            };
+            let Some(message) = MessageParser::default().parse(raw) else {
+                self.skipped.lock().unwrap().insert(uid);
+                continue;
+            };
+            let subject = message.subject().map(str::to_string);
+            let timestamp = message
+                .date()
+                .map(|date| date.to_timestamp())
+                .unwrap_or_else(|| chrono::Utc::now().timestamp());
+            let attachments = audio_attachments(&message);
+            if attachments.is_empty() {
+                debug!("Mail {} has no audio attachment", uid);
+                self.skipped.lock().unwrap().insert(uid);
+                continue;
+            }
+
+            match self.import_attachments(attachments, subject.as_deref(), timestamp, &config) {
+                Ok(text) => {
+                    session.uid_store(uid.to_string(), "+FLAGS (\\Seen)")?;
+                    self.failures.lock().unwrap().remove(&uid);
+                    imported += 1;
+                    events::emit(
+                        &self.app_handle,
+                        AppEvent::EmailIngestTranscribed(EmailMemoEvent {
+                            subject,
+                            text: Some(text),
+                            error: None,
+                        }),
+                    );
+                }
+                Err(e) => {
+                    let attempts = {
+                        let mut failures = self.failures.lock().unwrap();
+                        let attempts = failures.entry(uid).or_insert(0);
+                        *attempts += 1;
+                        *attempts
+                    };
+                    warn!(
+                        "Failed to transcribe mail {} (attempt {}): {}",
+                        uid, attempts, e
+                    );
+                    if attempts >= MAX_ATTEMPTS {
+                        // Read, so it stops coming back, but still in the mailbox
+                        session.uid_store(uid.to_string(), "+FLAGS (\\Seen)")?;
+                        self.failures.lock().unwrap().remove(&uid);
+                        events::emit(
+                            &self.app_handle,
+                            AppEvent::EmailIngestFailed(EmailMemoEvent {
+                                subject,
+                                text: None,
+                                error: Some(e.to_string()),
+                            }),
+                        );
+                    }
+                }
+            }
+        }
+
+        let _ = session.logout();
+        if imported > 0 {
+            info!("Transcribed {} emailed memo(s)", imported);
+        }
+        Ok(imported)
+    }
+
+    /// Transcribes every attachment into its own history entry and returns
+    /// the transcripts joined. Nothing reaches history until every
+    /// attachment is transcribed, so a mail that fails is retried whole
+    /// without importing its first attachments twice.
+    fn import_attachments(
+        &self,
+        attachments: Vec<Attachment>,
+        subject: Option<&str>,
+        timestamp: i64,
+        config: &EmailIngestConfig,
+    ) -> Result<String> {
+        let mut transcripts = Vec::new();
+        for attachment in attachments {
+            let samples = decode_audio_bytes(attachment.data, attachment.extension.as_deref())?;
+            let text = tauri::async_runtime::block_on(
+                self.transcription_manager
+                    .transcribe_with_model(config.model.as_deref(), samples),
+            )?;
+            if !text.is_empty() {
+                transcripts.push((attachment.name, text));
+            }
+        }
+
+        let mut texts = Vec::new();
+        for (name, text) in transcripts {
+            self.history_manager.insert_imported_entry(ImportedEntry {
+                file_name: String::new(),
+                timestamp,
+                saved: false,
+                title: subject
+                    .filter(|s| !s.trim().is_empty())
+                    .map(str::to_string)
+                    .or(name),
+                transcription_text: text.clone(),
+                tags: Vec::new(),
+                app_name: Some(SOURCE_NAME.to_string()),
+                sync_id: None,
+            })?;
+            texts.push(text);
+        }
+        Ok(texts.join("\n\n"))
+    }
+}
+
+fn keychain_entry(config: &EmailIngestConfig) -> Result<keyring::Entry> {
+    let account = format!("{}@{}", config.username, config.server);
+    Ok(keyring::Entry::new(KEYCHAIN_SERVICE, &account)?)
+}
+
+/// Attachments that are audio by content type or by a decodable extension.
+fn audio_attachments(message: &mail_parser::Message) -> Vec<Attachment> {
+    message
+        .attachments()
+        .filter_map(|part| {
+            let name = part.attachment_name().map(str::to_string);
+            let extension = name
+                .as_deref()
+                .and_then(|name| Path::new(name).extension())
+                .and_then(|e| e.to_str())
+                .map(|e| e.to_lowercase())
+                .filter(|e| DECODABLE_EXTENSIONS.contains(&e.as_str()));
+            let is_audio = part
+                .content_type()
+                .is_some_and(|ct| ct.ctype().eq_ignore_ascii_case("audio"));
+            (is_audio || extension.is_some()).then(|| Attachment {
+                name: name
+                    .as_deref()
+                    .and_then(|name| Path::new(name).file_stem())
+                    .map(|stem| stem.to_string_lossy().to_string()),
+                extension,
+                data: part.contents().to_vec(),
+            })
+        })
+        .collect()
+}
@@ -1,3 +1,4 @@
+use crate::engine::{ScoredWord, SegmentConfidence};
 use crate::settings::get_settings;
 use anyhow::Result;
 use reqwest::multipart;
@@ -30,6 +31,24 @@ struct GladiaResult {
 #[derive(Debug, Deserialize)]
 struct GladiaTranscription {
     full_transcript: Option<String>,
+    #[serde(default)]
+    utterances: Vec<GladiaUtterance>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GladiaUtterance {
+    start: f32,
+    end: f32,
+    confidence: f32,
+    text: String,
+    #[serde(default)]
+    words: Vec<GladiaWord>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GladiaWord {
+    word: String,
+    confidence: f32,
 }
 
 #[derive(Clone)]
@@ -46,7 +65,32 @@ impl GladiaApiManager {
         }
     }
 
-    pub async fn transcribe(&self, audio_data: Vec<f32>) -> Result<String> {
+    /// Makes a cheap authenticated request, to check the API is up and keep
+    /// the connection warm.
+    pub async fn ping(&self) -> Result<()> {
+        let api_key = get_settings(&self.app_handle)
+            .gladia_api_key
+            .ok_or_else(|| anyhow::anyhow!("Gladia API key not set"))?;
+        let response = self
+            .client
+            .get("https://api.gladia.io/v2/pre-recorded")
+            .query(&[("limit", "1")])
+            .header("x-gladia-key", &api_key)
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            debug!("[Gladia] Ping failed with status {}", status);
+            return Err(anyhow::anyhow!("Gladia API returned status {}", status));
+        }
+        Ok(())
+    }
+
+    /// Returns the transcript and the confidence of its utterances.
+    pub async fn transcribe(
+        &self,
+        audio_data: Vec<f32>,
+    ) -> Result<(String, Vec<SegmentConfidence>)> {
         info!("[Gladia] Starting transcription with {} audio samples", audio_data.len());
         
         let settings = get_settings(&self.app_handle);
@@ -175,9 +219,15 @@ impl GladiaApiManager {
             
             // Check if the response contains a completed transcription
             if let Ok(status_result) = serde_json::from_str::<GladiaTranscriptionResult>(&response_text) {
-                if let Some(transcript) = status_result.result.transcription.full_transcript {
-                    info!("[Gladia] Transcription successful: {}", transcript);
-                    return Ok(transcript);
+                let transcription = status_result.result.transcription;
+                if let Some(transcript) = transcription.full_transcript {
+                    debug!("[Gladia] Transcription successful: {}", transcript);
+                    let confidence = transcription
+                        .utterances
+                        .into_iter()
+                        .map(utterance_confidence)
+                        .collect();
+                    return Ok((transcript, confidence));
                 }
             }
             
@@ -188,6 +238,23 @@ impl GladiaApiManager {
     }
 }
 
+fn utterance_confidence(utterance: GladiaUtterance) -> SegmentConfidence {
+    SegmentConfidence {
+        start: utterance.start,
+        end: utterance.end,
+        text: utterance.text.trim().to_string(),
+        confidence: utterance.confidence,
+        words: utterance
+            .words
+            .into_iter()
+            .map(|word| ScoredWord {
+                text: word.word.trim().to_string(),
+                confidence: word.confidence,
+            })
+            .collect(),
+    }
+}
+
 fn float_to_wav(audio_data: &[f32]) -> Result<Vec<u8>> {
     let spec = hound::WavSpec {
         channels: 1,
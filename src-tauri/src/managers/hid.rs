@@ -0,0 +1,306 @@
+use crate::events::{self, AppEvent};
+use crate::settings::{get_settings, HidBinding};
+use crate::shortcut::dispatch_binding_event;
+use hidapi::{DeviceInfo, HidApi, HidDevice};
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+const DEVICE_REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+const REPORT_SIZE: usize = 64;
+
+// Generic desktop usages that are already handled as keyboard shortcuts
+const USAGE_PAGE_GENERIC_DESKTOP: u16 = 0x01;
+const USAGE_MOUSE: u16 = 0x02;
+const USAGE_KEYBOARD: u16 = 0x06;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HidDeviceSummary {
+    pub vendor_id: u16,
+    pub product_id: u16,
+    pub name: String,
+}
+
+/// Emitted with `hid-control-captured` once the user presses a control while
+/// a capture is running. Passed back to `add_hid_binding` to bind it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HidCapture {
+    pub vendor_id: u16,
+    pub product_id: u16,
+    pub device_name: String,
+    pub report_index: usize,
+    pub mask: u8,
+}
+
+struct OpenDevice {
+    device: HidDevice,
+    vendor_id: u16,
+    product_id: u16,
+    name: String,
+    last_report: Vec<u8>,
+}
+
+/// Listens to generic HID devices such as USB foot pedals and presenters and
+/// turns their button reports into binding presses and releases.
+pub struct HidManager {
+    app_handle: AppHandle,
+    capturing: AtomicBool,
+    shutdown: Arc<AtomicBool>,
+    worker: Mutex<Option<thread::JoinHandle<()>>>,
+}
+
+impl HidManager {
+    pub fn new(app_handle: &AppHandle) -> Arc<Self> {
+        let manager = Arc::new(Self {
+            app_handle: app_handle.clone(),
+            capturing: AtomicBool::new(false),
+            shutdown: Arc::new(AtomicBool::new(false)),
+            worker: Mutex::new(None),
+        });
+
+        let weak = Arc::downgrade(&manager);
+        let shutdown = manager.shutdown.clone();
+        let handle = thread::spawn(move || {
+            let mut api = match HidApi::new() {
+                Ok(api) => api,
+                Err(e) => {
+                    error!("Failed to initialize HID support: {}", e);
+                    return;
+                }
+            };
+
+            let mut devices: HashMap<CString, OpenDevice> = HashMap::new();
+            let mut pressed: HashMap<usize, bool> = HashMap::new();
+            let mut last_refresh: Option<Instant> = None;
+            let mut was_capturing = false;
+            let mut bindings: Vec<HidBinding> = Vec::new();
+
+            while !shutdown.load(Ordering::Relaxed) {
+                let Some(manager) = weak.upgrade() else {
+                    break;
+                };
+
+                let capturing = manager.capturing.load(Ordering::Relaxed);
+
+                // Open every candidate device right away when a capture starts
+                if capturing != was_capturing
+                    || last_refresh.map_or(true, |t| t.elapsed() >= DEVICE_REFRESH_INTERVAL)
+                {
+                    let latest = get_settings(&manager.app_handle).hid_bindings;
+                    if latest != bindings {
+                        pressed.clear();
+                        bindings = latest;
+                    }
+                    manager.refresh_devices(&mut api, &mut devices, &bindings, capturing);
+                    last_refresh = Some(Instant::now());
+                    was_capturing = capturing;
+                }
+
+                manager.poll_devices(&mut devices, &bindings, &mut pressed, capturing);
+
+                drop(manager);
+                thread::sleep(POLL_INTERVAL);
+            }
+            debug!("HID listener thread shutting down");
+        });
+        *manager.worker.lock().unwrap() = Some(handle);
+
+        manager
+    }
+
+    pub fn list_devices(&self) -> Result<Vec<HidDeviceSummary>, String> {
+        let api = HidApi::new().map_err(|e| format!("Failed to initialize HID support: {}", e))?;
+
+        let mut devices: Vec<HidDeviceSummary> = api
+            .device_list()
+            .filter(|info| is_candidate(info))
+            .map(|info| HidDeviceSummary {
+                vendor_id: info.vendor_id(),
+                product_id: info.product_id(),
+                name: device_name(info),
+            })
+            .collect();
+        devices.sort_by(|a, b| a.name.cmp(&b.name));
+        devices.dedup_by(|a, b| a.vendor_id == b.vendor_id && a.product_id == b.product_id);
+
+        Ok(devices)
+    }
+
+    /// Starts listening on every candidate device for the next control press.
+    pub fn start_capture(&self) {
+        info!("Starting HID control capture");
+        self.capturing.store(true, Ordering::Relaxed);
+    }
+
+    pub fn cancel_capture(&self) {
+        self.capturing.store(false, Ordering::Relaxed);
+    }
+
+    /// Opens the devices that are needed right now: every candidate while
+    /// capturing, otherwise only the ones with bindings.
+    fn refresh_devices(
+        &self,
+        api: &mut HidApi,
+        devices: &mut HashMap<CString, OpenDevice>,
+        bindings: &[HidBinding],
+        capturing: bool,
+    ) {
+        if let Err(e) = api.refresh_devices() {
+            warn!("Failed to refresh HID device list: {}", e);
+            return;
+        }
+
+        let wanted: HashMap<CString, &DeviceInfo> = api
+            .device_list()
+            .filter(|info| is_candidate(info))
+            .filter(|info| {
+                capturing
+                    || bindings.iter().any(|b| {
+                        b.vendor_id == info.vendor_id() && b.product_id == info.product_id()
+                    })
+            })
+            .map(|info| (info.path().to_owned(), info))
+            .collect();
+
+        devices.retain(|path, _| wanted.contains_key(path));
+
+        for (path, info) in wanted {
+            if devices.contains_key(&path) {
+                continue;
+            }
+            match info.open_device(api) {
+                Ok(device) => {
+                    if let Err(e) = device.set_blocking_mode(false) {
+                        warn!("Failed to set HID device non-blocking: {}", e);
+                        continue;
+                    }
+                    debug!("Opened HID device {}", device_name(info));
+                    devices.insert(
+                        path,
+                        OpenDevice {
+                            device,
+                            vendor_id: info.vendor_id(),
+                            product_id: info.product_id(),
+                            name: device_name(info),
+                            last_report: Vec::new(),
+                        },
+                    );
+                }
+                Err(e) => debug!("Could not open HID device {}: {}", device_name(info), e),
+            }
+        }
+    }
+
+    fn poll_devices(
+        &self,
+        devices: &mut HashMap<CString, OpenDevice>,
+        bindings: &[HidBinding],
+        pressed: &mut HashMap<usize, bool>,
+        capturing: bool,
+    ) {
+        let mut buf = [0u8; REPORT_SIZE];
+        let mut failed = Vec::new();
+
+        for (path, open) in devices.iter_mut() {
+            // Drain every queued report so quick taps aren't missed
+            loop {
+                let len = match open.device.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(len) => len,
+                    Err(e) => {
+                        debug!("HID device {} read failed: {}", open.name, e);
+                        failed.push(path.clone());
+                        break;
+                    }
+                };
+                let report = &buf[..len];
+
+                if capturing {
+                    if let Some(capture) = find_pressed_control(open, report) {
+                        info!("Captured HID control on {}", capture.device_name);
+                        self.capturing.store(false, Ordering::Relaxed);
+                        events::emit(&self.app_handle, AppEvent::HidControlCaptured(capture));
+                    }
+                } else {
+                    for (index, binding) in bindings.iter().enumerate() {
+                        if binding.vendor_id != open.vendor_id
+                            || binding.product_id != open.product_id
+                        {
+                            continue;
+                        }
+
+                        let is_down = report
+                            .get(binding.report_index)
+                            .is_some_and(|byte| byte & binding.mask != 0);
+                        let was_down = pressed.insert(index, is_down).unwrap_or(false);
+                        if is_down != was_down {
+                            dispatch_binding_event(
+                                &self.app_handle,
+                                &binding.binding_id,
+                                &format!("hid:{}", open.name),
+                                is_down,
+                            );
+                        }
+                    }
+                }
+
+                open.last_report = report.to_vec();
+            }
+        }
+
+        // Unplugged devices are reopened on the next refresh
+        for path in failed {
+            devices.remove(&path);
+        }
+    }
+}
+
+impl Drop for HidManager {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.worker.lock().unwrap().take() {
+            // The worker may hold the last reference, never join ourselves
+            if handle.thread().id() != thread::current().id() {
+                let _ = handle.join();
+            }
+        }
+    }
+}
+
+/// Finds the first bit that went from released to pressed compared to the
+/// device's previous report. Many pedals only report on change, so a device
+/// without a previous report is assumed to have had everything released.
+fn find_pressed_control(open: &OpenDevice, report: &[u8]) -> Option<HidCapture> {
+    report.iter().enumerate().find_map(|(index, now)| {
+        let before = open.last_report.get(index).copied().unwrap_or(0);
+        let rising = now & !before;
+        (rising != 0).then(|| HidCapture {
+            vendor_id: open.vendor_id,
+            product_id: open.product_id,
+            device_name: open.name.clone(),
+            report_index: index,
+            mask: 1 << rising.trailing_zeros(),
+        })
+    })
+}
+
+/// Keyboards and mice are left to the global shortcut handling.
+fn is_candidate(info: &DeviceInfo) -> bool {
+    !(info.usage_page() == USAGE_PAGE_GENERIC_DESKTOP
+        && matches!(info.usage(), USAGE_MOUSE | USAGE_KEYBOARD))
+}
+
+fn device_name(info: &DeviceInfo) -> String {
+    match (info.manufacturer_string(), info.product_string()) {
+        (Some(m), Some(p)) if !p.starts_with(m) => format!("{} {}", m, p),
+        (_, Some(p)) => p.to_string(),
+        _ => format!("{:04x}:{:04x}", info.vendor_id(), info.product_id()),
+    }
+}
@@ -1,14 +1,32 @@
 use anyhow::Result;
 use chrono::{DateTime, Local, Utc};
-use log::{debug, error};
-use rusqlite::{params, Connection, OptionalExtension};
+use log::{debug, error, info};
+use rusqlite::backup::Backup;
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
-use tauri::{AppHandle, Emitter, Manager};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
 use tauri_plugin_sql::{Migration, MigrationKind};
 
-use crate::audio_toolkit::save_wav_file;
+use crate::audio_toolkit::{save_wav_file_at_rate, save_wav_recording, WordCorrection};
+use crate::engine::SegmentConfidence;
+use crate::events::{self, AppEvent};
+use crate::focus::FocusedWindow;
+use crate::managers::audio::FinishedRecording;
+use crate::managers::automation::AutomationManager;
+use crate::managers::transcription::Transcript;
+use crate::settings::HistoryFilter;
+
+/// Shortest cue for a marker, so one set at the very end still shows.
+const MIN_MARKER_CUE_MS: u64 = 2000;
+/// Reviewed corrections needed before a threshold is suggested.
+const MIN_REVIEWED_CORRECTIONS: usize = 20;
+/// Share of reverted corrections the suggested threshold aims to stay under.
+const TARGET_REVERT_RATE: f64 = 0.1;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct HistoryEntry {
@@ -18,6 +36,65 @@ pub struct HistoryEntry {
     pub saved: bool,
     pub title: String,
     pub transcription_text: String,
+    pub tags: Vec<String>,
+    pub app_name: Option<String>,
+    /// Title of the window the transcript was pasted into.
+    #[serde(default)]
+    pub window_title: Option<String>,
+}
+
+const ENTRY_COLUMNS: &str =
+    "id, file_name, timestamp, saved, title, transcription_text, tags, app_name, window_title";
+
+fn row_to_entry(row: &rusqlite::Row) -> rusqlite::Result<HistoryEntry> {
+    let tags: String = row.get("tags")?;
+    Ok(HistoryEntry {
+        id: row.get("id")?,
+        file_name: row.get("file_name")?,
+        timestamp: row.get("timestamp")?,
+        saved: row.get("saved")?,
+        title: row.get("title")?,
+        transcription_text: row.get("transcription_text")?,
+        tags: serde_json::from_str(&tags).unwrap_or_default(),
+        app_name: row.get("app_name")?,
+        window_title: row.get("window_title")?,
+    })
+}
+
+/// A correction pair users keep undoing.
+#[derive(Clone, Debug, Serialize)]
+pub struct RevertedCorrection {
+    pub original: String,
+    pub replacement: String,
+    pub count: usize,
+}
+
+/// How custom word corrections fared in entries the user edited afterwards.
+#[derive(Clone, Debug, Serialize)]
+pub struct CorrectionQualityReport {
+    pub total_corrections: usize,
+    /// Corrections in entries the user has edited since.
+    pub reviewed: usize,
+    pub reverted: usize,
+    pub revert_rate: Option<f64>,
+    pub current_threshold: f64,
+    /// `None` until enough corrections have been reviewed.
+    pub suggested_threshold: Option<f64>,
+    pub most_reverted: Vec<RevertedCorrection>,
+}
+
+/// An entry that originates outside of a local recording (sync, import, ...).
+#[derive(Clone, Debug)]
+pub struct ImportedEntry {
+    pub file_name: String,
+    pub timestamp: i64,
+    pub saved: bool,
+    pub title: Option<String>,
+    pub transcription_text: String,
+    pub tags: Vec<String>,
+    pub app_name: Option<String>,
+    /// Stable identifier of the entry across machines, used to avoid duplicate imports.
+    pub sync_id: Option<String>,
 }
 
 pub struct HistoryManager {
@@ -28,10 +105,10 @@ pub struct HistoryManager {
 
 impl HistoryManager {
     pub fn new(app_handle: &AppHandle) -> Result<Self> {
-        // Create recordings directory in app data dir
-        let app_data_dir = app_handle.path().app_data_dir()?;
-        let recordings_dir = app_data_dir.join("recordings");
-        let db_path = app_data_dir.join("history.db");
+        // Create recordings directory in the active profile's data dir
+        let data_dir = crate::profiles::profile_data_dir(app_handle)?;
+        let recordings_dir = data_dir.join("recordings");
+        let db_path = data_dir.join("history.db");
 
         // Ensure recordings directory exists
         if !recordings_dir.exists() {
@@ -80,10 +157,70 @@ impl HistoryManager {
             )",
             [],
         )?;
+        // Sync ids of every entry ever imported, so that entries removed by the
+        // history limit are not imported again
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS imported_sync_ids (sync_id TEXT PRIMARY KEY)",
+            [],
+        )?;
+        // Custom word corrections and whether the user reverted them. Rows
+        // outlive their entries so the statistics survive the history limit.
+        // reverted is NULL until the entry is edited.
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS word_corrections (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                entry_id INTEGER NOT NULL,
+                original TEXT NOT NULL,
+                replacement TEXT NOT NULL,
+                score REAL NOT NULL,
+                reverted BOOLEAN
+            )",
+            [],
+        )?;
+        Self::migrate_schema(&conn)?;
         debug!("Database initialized at: {:?}", self.db_path);
         Ok(())
     }
 
+    /// Adds columns introduced after the initial schema to existing databases.
+    fn migrate_schema(conn: &Connection) -> Result<()> {
+        let columns = [
+            ("tags", "TEXT NOT NULL DEFAULT '[]'"),
+            ("app_name", "TEXT"),
+            ("sync_id", "TEXT"),
+            ("alternatives", "TEXT NOT NULL DEFAULT '[]'"),
+            ("markers", "TEXT NOT NULL DEFAULT '[]'"),
+            ("raw_text", "TEXT"),
+            ("confidence", "TEXT NOT NULL DEFAULT '[]'"),
+            ("window_title", "TEXT"),
+        ];
+
+        for (name, definition) in columns {
+            Self::ensure_column(conn, name, definition)?;
+        }
+        Ok(())
+    }
+
+    fn ensure_column(conn: &Connection, name: &str, definition: &str) -> Result<()> {
+        let mut stmt = conn.prepare("PRAGMA table_info(transcription_history)")?;
+        let exists = stmt
+            .query_map([], |row| row.get::<_, String>("name"))?
+            .filter_map(|r| r.ok())
+            .any(|column| column == name);
+
+        if !exists {
+            conn.execute(
+                &format!(
+                    "ALTER TABLE transcription_history ADD COLUMN {} {}",
+                    name, definition
+                ),
+                [],
+            )?;
+            debug!("Added column '{}' to transcription_history", name);
+        }
+        Ok(())
+    }
+
     fn get_connection(&self) -> Result<Connection> {
         Ok(Connection::open(&self.db_path)?)
     }
@@ -91,9 +228,33 @@ impl HistoryManager {
     /// Save a transcription to history (both database and WAV file)
     pub async fn save_transcription(
         &self,
-        audio_samples: Vec<f32>,
-        transcription_text: String,
+        recording: FinishedRecording,
+        transcript: Transcript,
+    ) -> Result<()> {
+        self.save_transcription_for_target(recording, transcript, None)
+            .await
+    }
+
+    /// Saves a transcription along with the window it was pasted into.
+    pub async fn save_transcription_for_target(
+        &self,
+        recording: FinishedRecording,
+        transcript: Transcript,
+        target: Option<FocusedWindow>,
     ) -> Result<()> {
+        let FinishedRecording {
+            audio,
+            markers,
+            original,
+        } = recording;
+        let Transcript {
+            text: transcription_text,
+            raw_text,
+            alternatives,
+            confidence,
+            corrections,
+            ..
+        } = transcript;
         // If history limit is 0, do not save at all.
         if crate::settings::get_history_limit(&self.app_handle) == 0 {
             return Ok(());
@@ -105,17 +266,59 @@ impl HistoryManager {
 
         // Save WAV file
         let file_path = self.recordings_dir.join(&file_name);
-        save_wav_file(file_path, &audio_samples).await?;
+        match original {
+            Some((sample_rate, samples)) => {
+                save_wav_file_at_rate(file_path, &samples, sample_rate).await?
+            }
+            None => save_wav_recording(file_path, &audio).await?,
+        }
 
         // Save to database
-        self.save_to_database(file_name, timestamp, title, transcription_text)?;
+        let id = self.save_to_database(file_name, timestamp, title, transcription_text.clone())?;
+        if !alternatives.is_empty() {
+            if let Err(e) = self.set_alternatives(id, &alternatives) {
+                error!("Failed to save alternatives: {}", e);
+            }
+        }
+        if let Some(raw_text) = raw_text {
+            if let Err(e) = self.set_raw_text(id, &raw_text) {
+                error!("Failed to save raw transcript: {}", e);
+            }
+        }
+        if !confidence.is_empty() {
+            if let Err(e) = self.set_confidence(id, &confidence) {
+                error!("Failed to save confidence scores: {}", e);
+            }
+        }
+        if let Some(target) = &target {
+            if let Err(e) = self.set_target(id, target) {
+                error!("Failed to save paste target: {}", e);
+            }
+        }
+        if !markers.is_empty() {
+            if let Err(e) = self.set_markers(id, &markers) {
+                error!("Failed to save recording markers: {}", e);
+            }
+        }
+        if let Err(e) = self.save_corrections(id, &transcription_text, &corrections) {
+            error!("Failed to save word corrections: {}", e);
+        }
 
         // Clean up old entries
         self.cleanup_old_entries()?;
 
         // Emit history updated event
-        if let Err(e) = self.app_handle.emit("history-updated", ()) {
-            error!("Failed to emit history-updated event: {}", e);
+        events::emit(&self.app_handle, AppEvent::HistoryUpdated(()));
+
+        // Automation rules run in the background so a slow webhook or hook
+        // never holds up pasting
+        if let Some(automation) = self.app_handle.try_state::<Arc<AutomationManager>>() {
+            if let Ok(Some(entry)) = self.get_entry_by_id(id).await {
+                let automation = automation.inner().clone();
+                tauri::async_runtime::spawn(async move {
+                    automation.process_entry(entry).await;
+                });
+            }
         }
 
         Ok(())
@@ -127,7 +330,7 @@ impl HistoryManager {
         timestamp: i64,
         title: String,
         transcription_text: String,
-    ) -> Result<()> {
+    ) -> Result<i64> {
         let conn = self.get_connection()?;
         conn.execute(
             "INSERT INTO transcription_history (file_name, timestamp, saved, title, transcription_text) VALUES (?1, ?2, ?3, ?4, ?5)",
@@ -135,6 +338,33 @@ impl HistoryManager {
         )?;
 
         debug!("Saved transcription to database");
+        Ok(conn.last_insert_rowid())
+    }
+
+    fn save_corrections(
+        &self,
+        entry_id: i64,
+        text: &str,
+        corrections: &[WordCorrection],
+    ) -> Result<()> {
+        let words = normalized_words(text);
+        let conn = self.get_connection()?;
+        // Skip corrections from transcriptions that didn't end up in this
+        // entry, e.g. a cancelled one
+        for correction in corrections
+            .iter()
+            .filter(|c| words.contains(&c.replacement.to_lowercase()))
+        {
+            conn.execute(
+                "INSERT INTO word_corrections (entry_id, original, replacement, score) VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    entry_id,
+                    correction.original,
+                    correction.replacement,
+                    correction.score
+                ],
+            )?;
+        }
         Ok(())
     }
 
@@ -185,20 +415,12 @@ impl HistoryManager {
 
     pub async fn get_history_entries(&self) -> Result<Vec<HistoryEntry>> {
         let conn = self.get_connection()?;
-        let mut stmt = conn.prepare(
-            "SELECT id, file_name, timestamp, saved, title, transcription_text FROM transcription_history ORDER BY timestamp DESC"
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM transcription_history ORDER BY timestamp DESC",
+            ENTRY_COLUMNS
+        ))?;
 
-        let rows = stmt.query_map([], |row| {
-            Ok(HistoryEntry {
-                id: row.get("id")?,
-                file_name: row.get("file_name")?,
-                timestamp: row.get("timestamp")?,
-                saved: row.get("saved")?,
-                title: row.get("title")?,
-                transcription_text: row.get("transcription_text")?,
-            })
-        })?;
+        let rows = stmt.query_map([], row_to_entry)?;
 
         let mut entries = Vec::new();
         for row in rows {
@@ -228,36 +450,239 @@ impl HistoryManager {
         debug!("Toggled saved status for entry {}: {}", id, new_saved);
 
         // Emit history updated event
-        if let Err(e) = self.app_handle.emit("history-updated", ()) {
-            error!("Failed to emit history-updated event: {}", e);
+        events::emit(&self.app_handle, AppEvent::HistoryUpdated(()));
+
+        Ok(())
+    }
+
+    /// Returns history entries matching the given filter, newest first.
+    pub async fn search_entries(&self, filter: &HistoryFilter) -> Result<Vec<HistoryEntry>> {
+        let conn = self.get_connection()?;
+
+        let mut clauses: Vec<String> = Vec::new();
+        let mut values: Vec<rusqlite::types::Value> = Vec::new();
+
+        if let Some(query) = filter.query.as_ref().filter(|q| !q.trim().is_empty()) {
+            values.push(format!("%{}%", escape_like(query.trim())).into());
+            clauses.push(format!(
+                "transcription_text LIKE ?{} ESCAPE '\\'",
+                values.len()
+            ));
+        }
+
+        if let Some(app_name) = filter.app_name.as_ref().filter(|a| !a.is_empty()) {
+            values.push(app_name.clone().into());
+            clauses.push(format!("app_name = ?{}", values.len()));
+        }
+
+        let since = filter
+            .within_days
+            .map(|days| Utc::now().timestamp() - days as i64 * 86400);
+        let from_timestamp = match (filter.from_timestamp, since) {
+            (Some(from), Some(since)) => Some(from.max(since)),
+            (from, since) => from.or(since),
+        };
+
+        if let Some(from) = from_timestamp {
+            values.push(from.into());
+            clauses.push(format!("timestamp >= ?{}", values.len()));
+        }
+
+        if let Some(to) = filter.to_timestamp {
+            values.push(to.into());
+            clauses.push(format!("timestamp <= ?{}", values.len()));
+        }
+
+        let where_clause = if clauses.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", clauses.join(" AND "))
+        };
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM transcription_history {} ORDER BY timestamp DESC",
+            ENTRY_COLUMNS, where_clause
+        ))?;
+
+        let rows = stmt.query_map(rusqlite::params_from_iter(values), row_to_entry)?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            let entry = row?;
+            // Tags are stored as a JSON array, so they are matched here rather than in SQL
+            if filter.tags.iter().all(|tag| entry.tags.contains(tag)) {
+                entries.push(entry);
+            }
+        }
+
+        Ok(entries)
+    }
+
+    pub async fn set_tags(&self, id: i64, tags: Vec<String>) -> Result<()> {
+        let conn = self.get_connection()?;
+
+        let mut tags: Vec<String> = tags
+            .into_iter()
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect();
+        tags.sort();
+        tags.dedup();
+
+        conn.execute(
+            "UPDATE transcription_history SET tags = ?1 WHERE id = ?2",
+            params![serde_json::to_string(&tags)?, id],
+        )?;
+
+        debug!("Updated tags for entry {}: {:?}", id, tags);
+
+        events::emit(&self.app_handle, AppEvent::HistoryUpdated(()));
+
+        Ok(())
+    }
+
+    /// Inserts an entry that was not recorded locally. Returns the new row id.
+    pub fn insert_imported_entry(&self, entry: ImportedEntry) -> Result<i64> {
+        let conn = self.get_connection()?;
+        let title = entry
+            .title
+            .unwrap_or_else(|| self.format_timestamp_title(entry.timestamp));
+
+        conn.execute(
+            "INSERT INTO transcription_history (file_name, timestamp, saved, title, transcription_text, tags, app_name, sync_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                entry.file_name,
+                entry.timestamp,
+                entry.saved,
+                title,
+                entry.transcription_text,
+                serde_json::to_string(&entry.tags)?,
+                entry.app_name,
+                entry.sync_id
+            ],
+        )?;
+
+        let id = conn.last_insert_rowid();
+        if let Some(sync_id) = &entry.sync_id {
+            conn.execute(
+                "INSERT OR IGNORE INTO imported_sync_ids (sync_id) VALUES (?1)",
+                params![sync_id],
+            )?;
         }
+        debug!("Imported history entry {}", id);
 
+        events::emit(&self.app_handle, AppEvent::HistoryUpdated(()));
+
+        Ok(id)
+    }
+
+    /// Brings an imported entry in line with its copy on the machine that
+    /// recorded it. Returns whether anything changed.
+    pub fn update_imported_entry(&self, id: i64, entry: &ImportedEntry) -> Result<bool> {
+        let conn = self.get_connection()?;
+        let title = entry
+            .title
+            .clone()
+            .unwrap_or_else(|| self.format_timestamp_title(entry.timestamp));
+
+        let changed = conn.execute(
+            "UPDATE transcription_history SET saved = ?1, title = ?2, transcription_text = ?3, tags = ?4, app_name = ?5
+             WHERE id = ?6 AND (saved IS NOT ?1 OR title IS NOT ?2 OR transcription_text IS NOT ?3 OR tags IS NOT ?4 OR app_name IS NOT ?5)",
+            params![
+                entry.saved,
+                title,
+                entry.transcription_text,
+                serde_json::to_string(&entry.tags)?,
+                entry.app_name,
+                id
+            ],
+        )? > 0;
+
+        if changed {
+            debug!("Updated imported history entry {}", id);
+            events::emit(&self.app_handle, AppEvent::HistoryUpdated(()));
+        }
+        Ok(changed)
+    }
+
+    /// Deletes an imported entry that the machine which recorded it deleted.
+    /// Unlike an entry deleted here, it's imported again should it come back.
+    pub async fn delete_synced_entry(&self, id: i64, sync_id: &str) -> Result<()> {
+        self.delete_entry(id).await?;
+        let conn = self.get_connection()?;
+        conn.execute(
+            "DELETE FROM imported_sync_ids WHERE sync_id = ?1",
+            params![sync_id],
+        )?;
         Ok(())
     }
 
+    /// Returns the sync identifiers of all entries that have one, keyed by entry id.
+    pub fn get_sync_ids(&self) -> Result<HashMap<i64, String>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn
+            .prepare("SELECT id, sync_id FROM transcription_history WHERE sync_id IS NOT NULL")?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>("id")?, row.get::<_, String>("sync_id")?))
+        })?;
+
+        let mut ids = HashMap::new();
+        for row in rows {
+            let (id, sync_id) = row?;
+            ids.insert(id, sync_id);
+        }
+        Ok(ids)
+    }
+
+    pub fn get_imported_sync_ids(&self) -> Result<HashSet<String>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare("SELECT sync_id FROM imported_sync_ids")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>("sync_id"))?;
+
+        let mut ids = HashSet::new();
+        for row in rows {
+            ids.insert(row?);
+        }
+        Ok(ids)
+    }
+
+    /// Replaces the history with the database at `source`. The copy goes
+    /// through SQLite rather than over the file, so connections open meanwhile
+    /// see either the old history or the new one.
+    pub fn restore_database(&self, source: &Path) -> Result<()> {
+        let source = Connection::open_with_flags(source, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        let mut conn = self.get_connection()?;
+        Backup::new(&source, &mut conn)?.run_to_completion(100, Duration::from_millis(10), None)?;
+        drop(conn);
+
+        // The backup may predate some columns
+        self.init_database()?;
+        info!("Restored history database");
+        events::emit(&self.app_handle, AppEvent::HistoryUpdated(()));
+        Ok(())
+    }
+
+    pub fn db_path(&self) -> &Path {
+        &self.db_path
+    }
+
+    pub fn recordings_dir(&self) -> &Path {
+        &self.recordings_dir
+    }
+
     pub fn get_audio_file_path(&self, file_name: &str) -> PathBuf {
         self.recordings_dir.join(file_name)
     }
 
     pub async fn get_entry_by_id(&self, id: i64) -> Result<Option<HistoryEntry>> {
         let conn = self.get_connection()?;
-        let mut stmt = conn.prepare(
-            "SELECT id, file_name, timestamp, saved, title, transcription_text
-             FROM transcription_history WHERE id = ?1",
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM transcription_history WHERE id = ?1",
+            ENTRY_COLUMNS
+        ))?;
 
-        let entry = stmt
-            .query_row([id], |row| {
-                Ok(HistoryEntry {
-                    id: row.get("id")?,
-                    file_name: row.get("file_name")?,
-                    timestamp: row.get("timestamp")?,
-                    saved: row.get("saved")?,
-                    title: row.get("title")?,
-                    transcription_text: row.get("transcription_text")?,
-                })
-            })
-            .optional()?;
+        let entry = stmt.query_row([id], row_to_entry).optional()?;
 
         Ok(entry)
     }
@@ -286,13 +711,292 @@ impl HistoryManager {
         debug!("Deleted history entry with id: {}", id);
 
         // Emit history updated event
-        if let Err(e) = self.app_handle.emit("history-updated", ()) {
-            error!("Failed to emit history-updated event: {}", e);
+        events::emit(&self.app_handle, AppEvent::HistoryUpdated(()));
+
+        Ok(())
+    }
+
+    fn set_alternatives(&self, id: i64, alternatives: &[String]) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE transcription_history SET alternatives = ?1 WHERE id = ?2",
+            params![serde_json::to_string(alternatives)?, id],
+        )?;
+        Ok(())
+    }
+
+    /// The alternative transcripts stored with an entry, best first.
+    pub async fn get_alternatives(&self, id: i64) -> Result<Vec<String>> {
+        let conn = self.get_connection()?;
+        let alternatives: Option<String> = conn
+            .query_row(
+                "SELECT alternatives FROM transcription_history WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let alternatives =
+            alternatives.ok_or_else(|| anyhow::anyhow!("History entry {} not found", id))?;
+        Ok(serde_json::from_str(&alternatives).unwrap_or_default())
+    }
+
+    fn set_confidence(&self, id: i64, confidence: &[SegmentConfidence]) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE transcription_history SET confidence = ?1 WHERE id = ?2",
+            params![serde_json::to_string(confidence)?, id],
+        )?;
+        Ok(())
+    }
+
+    /// Confidence scores of an entry's segments and words, empty when the
+    /// model didn't report any.
+    pub async fn get_confidence(&self, id: i64) -> Result<Vec<SegmentConfidence>> {
+        let conn = self.get_connection()?;
+        let confidence: Option<String> = conn
+            .query_row(
+                "SELECT confidence FROM transcription_history WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let confidence =
+            confidence.ok_or_else(|| anyhow::anyhow!("History entry {} not found", id))?;
+        Ok(serde_json::from_str(&confidence).unwrap_or_default())
+    }
+
+    fn set_target(&self, id: i64, target: &FocusedWindow) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE transcription_history SET app_name = ?1, window_title = ?2 WHERE id = ?3",
+            params![target.app_name, target.title, id],
+        )?;
+        Ok(())
+    }
+
+    fn set_raw_text(&self, id: i64, raw_text: &str) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE transcription_history SET raw_text = ?1 WHERE id = ?2",
+            params![raw_text, id],
+        )?;
+        Ok(())
+    }
+
+    /// The transcript as recognized, before filler words were removed.
+    /// `None` when nothing was removed.
+    pub async fn get_raw_text(&self, id: i64) -> Result<Option<String>> {
+        let conn = self.get_connection()?;
+        let raw_text: Option<Option<String>> = conn
+            .query_row(
+                "SELECT raw_text FROM transcription_history WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        raw_text.ok_or_else(|| anyhow::anyhow!("History entry {} not found", id))
+    }
+
+    fn set_markers(&self, id: i64, markers: &[u64]) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE transcription_history SET markers = ?1 WHERE id = ?2",
+            params![serde_json::to_string(markers)?, id],
+        )?;
+        Ok(())
+    }
+
+    /// Markers set with the marker shortcut while the entry was recorded,
+    /// in ms from the start of the recording.
+    pub async fn get_markers(&self, id: i64) -> Result<Vec<u64>> {
+        let conn = self.get_connection()?;
+        let markers: Option<String> = conn
+            .query_row(
+                "SELECT markers FROM transcription_history WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let markers = markers.ok_or_else(|| anyhow::anyhow!("History entry {} not found", id))?;
+        Ok(serde_json::from_str(&markers).unwrap_or_default())
+    }
+
+    /// Renders an entry's markers as SRT cues, one chapter per marker
+    /// running until the next marker or the end of the recording.
+    pub async fn export_markers_srt(&self, id: i64) -> Result<String> {
+        let entry = self
+            .get_entry_by_id(id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("History entry {} not found", id))?;
+        let markers = self.get_markers(id).await?;
+        if markers.is_empty() {
+            return Err(anyhow::anyhow!("Entry {} has no markers", id));
+        }
+
+        let duration_ms = hound::WavReader::open(self.recordings_dir.join(&entry.file_name))
+            .map(|reader| reader.duration() as u64 * 1000 / reader.spec().sample_rate.max(1) as u64)
+            .unwrap_or(0);
+
+        let mut srt = String::new();
+        for (index, &start) in markers.iter().enumerate() {
+            let end = markers
+                .get(index + 1)
+                .copied()
+                .unwrap_or(duration_ms)
+                .max(start + MIN_MARKER_CUE_MS);
+            srt.push_str(&format!(
+                "{}\n{} --> {}\nMarker {}\n\n",
+                index + 1,
+                srt_timestamp(start),
+                srt_timestamp(end),
+                index + 1
+            ));
+        }
+        Ok(srt)
+    }
+
+    /// Makes an alternative the entry's text. The replaced text takes the
+    /// alternative's place, so swapping again undoes it.
+    pub async fn swap_to_alternative(&self, id: i64, index: usize) -> Result<HistoryEntry> {
+        let entry = self
+            .get_entry_by_id(id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("History entry {} not found", id))?;
+        let mut alternatives = self.get_alternatives(id).await?;
+        let alternative = alternatives
+            .get_mut(index)
+            .ok_or_else(|| anyhow::anyhow!("Entry {} has no alternative {}", id, index))?;
+        let text = std::mem::replace(alternative, entry.transcription_text);
+
+        self.set_alternatives(id, &alternatives)?;
+        self.update_transcription_text(id, text).await?;
+        self.get_entry_by_id(id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("History entry {} not found", id))
+    }
+
+    /// Replaces the text of an entry with a new formatting of `raw_text`,
+    /// which is kept to format it again. Unlike an edit, this says nothing
+    /// about the entry's word corrections.
+    pub async fn update_reprocessed_text(&self, id: i64, raw_text: &str, text: &str) -> Result<()> {
+        let conn = self.get_connection()?;
+        let updated = conn.execute(
+            "UPDATE transcription_history SET transcription_text = ?1, raw_text = ?2 WHERE id = ?3",
+            params![text, raw_text, id],
+        )?;
+        if updated == 0 {
+            return Err(anyhow::anyhow!("History entry {} not found", id));
+        }
+
+        events::emit(&self.app_handle, AppEvent::HistoryUpdated(()));
+        Ok(())
+    }
+
+    /// Replaces the text of an entry and records which of its word
+    /// corrections the edit reverted.
+    pub async fn update_transcription_text(&self, id: i64, text: String) -> Result<()> {
+        let conn = self.get_connection()?;
+        let updated = conn.execute(
+            "UPDATE transcription_history SET transcription_text = ?1 WHERE id = ?2",
+            params![text, id],
+        )?;
+        if updated == 0 {
+            return Err(anyhow::anyhow!("History entry {} not found", id));
+        }
+
+        let words = normalized_words(&text);
+        let mut stmt = conn.prepare(
+            "SELECT id, original, replacement FROM word_corrections WHERE entry_id = ?1 AND reverted IS NULL",
+        )?;
+        let pending = stmt
+            .query_map(params![id], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        for (correction_id, original, replacement) in pending {
+            let kept = words.contains(&replacement.to_lowercase());
+            let reverted = !kept && words.contains(&original.to_lowercase());
+            // When both words are gone the user rewrote the passage, which
+            // says nothing about the correction
+            if kept || reverted {
+                conn.execute(
+                    "UPDATE word_corrections SET reverted = ?1 WHERE id = ?2",
+                    params![reverted, correction_id],
+                )?;
+            }
         }
 
+        events::emit(&self.app_handle, AppEvent::HistoryUpdated(()));
         Ok(())
     }
 
+    /// Summarizes reviewed corrections and suggests the highest threshold
+    /// whose corrections were reverted at most [`TARGET_REVERT_RATE`] of the
+    /// time.
+    pub fn correction_quality_report(
+        &self,
+        current_threshold: f64,
+    ) -> Result<CorrectionQualityReport> {
+        let conn = self.get_connection()?;
+        let total_corrections: i64 =
+            conn.query_row("SELECT COUNT(*) FROM word_corrections", [], |row| {
+                row.get(0)
+            })?;
+
+        let mut stmt = conn.prepare(
+            "SELECT original, replacement, score, reverted FROM word_corrections WHERE reverted IS NOT NULL ORDER BY score ASC",
+        )?;
+        let reviewed = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, f64>(2)?,
+                    row.get::<_, bool>(3)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let reverted = reviewed.iter().filter(|(_, _, _, r)| *r).count();
+        let revert_rate = (!reviewed.is_empty()).then(|| reverted as f64 / reviewed.len() as f64);
+
+        let suggested_threshold = (reviewed.len() >= MIN_REVIEWED_CORRECTIONS).then(|| {
+            let scores: Vec<(f64, bool)> = reviewed.iter().map(|(_, _, s, r)| (*s, *r)).collect();
+            suggest_threshold(&scores, current_threshold)
+        });
+
+        let mut counts: HashMap<(String, String), usize> = HashMap::new();
+        for (original, replacement, _, _) in reviewed.iter().filter(|(_, _, _, r)| *r) {
+            *counts
+                .entry((original.to_lowercase(), replacement.to_lowercase()))
+                .or_default() += 1;
+        }
+        let mut most_reverted: Vec<RevertedCorrection> = counts
+            .into_iter()
+            .map(|((original, replacement), count)| RevertedCorrection {
+                original,
+                replacement,
+                count,
+            })
+            .collect();
+        most_reverted.sort_by(|a, b| b.count.cmp(&a.count));
+        most_reverted.truncate(10);
+
+        Ok(CorrectionQualityReport {
+            total_corrections: total_corrections as usize,
+            reviewed: reviewed.len(),
+            reverted,
+            revert_rate,
+            current_threshold,
+            suggested_threshold,
+            most_reverted,
+        })
+    }
+
     fn format_timestamp_title(&self, timestamp: i64) -> String {
         if let Some(utc_datetime) = DateTime::from_timestamp(timestamp, 0) {
             // Convert UTC to local timezone
@@ -308,3 +1012,55 @@ impl HistoryManager {
         Ok(())
     }
 }
+
+/// Lowercase words of `text` without surrounding punctuation.
+fn normalized_words(text: &str) -> HashSet<String> {
+    text.split_whitespace()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphabetic()).to_lowercase())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// Picks the threshold that keeps the most corrections while their revert
+/// rate stays within [`TARGET_REVERT_RATE`]. `reviewed` is sorted by score,
+/// every score is below `current`, since only those were applied.
+fn suggest_threshold(reviewed: &[(f64, bool)], current: f64) -> f64 {
+    let mut reverted = 0;
+    let mut best = None;
+    for (i, (_, was_reverted)) in reviewed.iter().enumerate() {
+        if *was_reverted {
+            reverted += 1;
+        }
+        if reverted as f64 / (i + 1) as f64 <= TARGET_REVERT_RATE {
+            best = Some(i);
+        }
+    }
+
+    let threshold = match best {
+        Some(i) if i + 1 == reviewed.len() => current,
+        // Halfway to the first score that would be excluded
+        Some(i) => (reviewed[i].0 + reviewed[i + 1].0) / 2.0,
+        // Even the closest matches get reverted too often
+        None => reviewed[0].0,
+    };
+    (threshold * 100.0).round() / 100.0
+}
+
+/// `text` matched literally by `LIKE ... ESCAPE '\'`, so `%` and `_` typed
+/// into a search aren't wildcards.
+fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// `HH:MM:SS,mmm` as used by SRT.
+fn srt_timestamp(ms: u64) -> String {
+    format!(
+        "{:02}:{:02}:{:02},{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
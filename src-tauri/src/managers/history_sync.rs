@@ -0,0 +1,347 @@
+use crate::events::{self, AppEvent};
+use crate::managers::history::{HistoryManager, ImportedEntry};
+use crate::settings::{get_settings, write_settings};
+use anyhow::Result;
+use log::{debug, error, info};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tauri::AppHandle;
+
+const SYNC_DIR_NAME: &str = "handy-history";
+const SYNC_INTERVAL: Duration = Duration::from_secs(60);
+
+/// One history entry as written to the shared folder. Every machine only ever
+/// writes into its own sub-directory, so files never conflict.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct SyncedEntry {
+    machine_id: String,
+    entry_id: i64,
+    timestamp: i64,
+    saved: bool,
+    title: String,
+    transcription_text: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    app_name: Option<String>,
+    #[serde(default)]
+    audio_file: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct SyncReport {
+    pub exported: usize,
+    pub imported: usize,
+    /// Imported entries changed on the machine that recorded them.
+    pub updated: usize,
+    /// Imported entries deleted on the machine that recorded them.
+    pub deleted: usize,
+    pub errors: Vec<String>,
+}
+
+pub struct HistorySyncManager {
+    app_handle: AppHandle,
+    history_manager: Arc<HistoryManager>,
+    sync_lock: Mutex<()>,
+}
+
+impl HistorySyncManager {
+    pub fn new(app_handle: &AppHandle, history_manager: Arc<HistoryManager>) -> Arc<Self> {
+        let manager = Arc::new(Self {
+            app_handle: app_handle.clone(),
+            history_manager,
+            sync_lock: Mutex::new(()),
+        });
+
+        // Periodically sync in the background while a folder is configured
+        {
+            let weak = Arc::downgrade(&manager);
+            thread::spawn(move || {
+                while let Some(manager) = weak.upgrade() {
+                    if get_settings(&manager.app_handle)
+                        .history_sync_folder
+                        .is_some()
+                    {
+                        if let Err(e) = manager.sync_now() {
+                            error!("History sync failed: {}", e);
+                        }
+                    }
+                    drop(manager);
+                    thread::sleep(SYNC_INTERVAL);
+                }
+                debug!("History sync thread shutting down");
+            });
+        }
+
+        manager
+    }
+
+    /// Returns this machine's sync id, generating and persisting one on first use.
+    fn machine_id(&self) -> String {
+        let mut settings = get_settings(&self.app_handle);
+        if let Some(id) = settings.sync_machine_id.clone() {
+            return id;
+        }
+
+        let nanos = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let id = format!("{:x}{:x}", nanos, std::process::id());
+        settings.sync_machine_id = Some(id.clone());
+        write_settings(&self.app_handle, settings);
+        id
+    }
+
+    /// Exports local entries to the sync folder and imports entries written by
+    /// other machines. Imported entries follow edits and deletions made on the
+    /// machine that recorded them; one deleted here stays deleted.
+    pub fn sync_now(&self) -> Result<SyncReport> {
+        let _guard = self.sync_lock.lock().unwrap();
+
+        let settings = get_settings(&self.app_handle);
+        let folder = settings
+            .history_sync_folder
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("No history sync folder configured"))?;
+
+        let root = PathBuf::from(folder).join(SYNC_DIR_NAME);
+        let machine_id = self.machine_id();
+        let own_dir = root.join(&machine_id);
+        fs::create_dir_all(&own_dir)?;
+
+        let mut report = SyncReport::default();
+        self.export_entries(
+            &own_dir,
+            &machine_id,
+            settings.history_sync_include_audio,
+            &mut report,
+        )?;
+        self.import_entries(&root, &machine_id, &mut report)?;
+
+        info!(
+            "History sync completed: {} exported, {} imported, {} updated, {} deleted",
+            report.exported, report.imported, report.updated, report.deleted
+        );
+        events::emit(
+            &self.app_handle,
+            AppEvent::HistorySyncCompleted(report.clone()),
+        );
+
+        Ok(report)
+    }
+
+    fn export_entries(
+        &self,
+        own_dir: &Path,
+        machine_id: &str,
+        include_audio: bool,
+        report: &mut SyncReport,
+    ) -> Result<()> {
+        let entries = tauri::async_runtime::block_on(self.history_manager.get_history_entries())?;
+        let sync_ids = self.history_manager.get_sync_ids()?;
+
+        let mut written = HashSet::new();
+        for entry in entries {
+            // Entries imported from another machine are owned by that machine
+            if sync_ids.contains_key(&entry.id) {
+                continue;
+            }
+
+            let audio_file = if include_audio && !entry.file_name.is_empty() {
+                let source = self.history_manager.get_audio_file_path(&entry.file_name);
+                let target = own_dir.join(&entry.file_name);
+                if source.exists() && !target.exists() {
+                    if let Err(e) = fs::copy(&source, &target) {
+                        report
+                            .errors
+                            .push(format!("Failed to copy {}: {}", entry.file_name, e));
+                    }
+                }
+                target.exists().then(|| entry.file_name.clone())
+            } else {
+                None
+            };
+
+            let synced = SyncedEntry {
+                machine_id: machine_id.to_string(),
+                entry_id: entry.id,
+                timestamp: entry.timestamp,
+                saved: entry.saved,
+                title: entry.title,
+                transcription_text: entry.transcription_text,
+                tags: entry.tags,
+                app_name: entry.app_name,
+                audio_file,
+            };
+
+            let file_name = format!("{}.json", entry.id);
+            let path = own_dir.join(&file_name);
+            let contents = serde_json::to_string_pretty(&synced)?;
+
+            // Only rewrite files whose content changed to keep sync clients quiet
+            if fs::read_to_string(&path).ok().as_deref() != Some(contents.as_str()) {
+                fs::write(&path, contents)?;
+                report.exported += 1;
+            }
+            written.insert(file_name);
+        }
+
+        // Remove files for entries that were deleted locally
+        for dir_entry in fs::read_dir(own_dir)?.filter_map(|e| e.ok()) {
+            let name = dir_entry.file_name().to_string_lossy().to_string();
+            if name.ends_with(".json") && !written.contains(&name) {
+                let _ = fs::remove_file(dir_entry.path());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn import_entries(&self, root: &Path, machine_id: &str, report: &mut SyncReport) -> Result<()> {
+        let known = self.history_manager.get_imported_sync_ids()?;
+        let local: HashMap<String, i64> = self
+            .history_manager
+            .get_sync_ids()?
+            .into_iter()
+            .map(|(id, sync_id)| (sync_id, id))
+            .collect();
+
+        for machine_dir in fs::read_dir(root)?.filter_map(|e| e.ok()) {
+            let path = machine_dir.path();
+            let dir_name = machine_dir.file_name().to_string_lossy().to_string();
+            if !path.is_dir() || dir_name == machine_id || !is_plain_file_name(&dir_name) {
+                continue;
+            }
+
+            // Entry files present, readable or not, so a file still being
+            // synced doesn't count as deleted
+            let mut present = HashSet::new();
+            for file in fs::read_dir(&path)?.filter_map(|e| e.ok()) {
+                let file_path = file.path();
+                if file_path.extension().and_then(|e| e.to_str()) != Some("json") {
+                    continue;
+                }
+                if let Some(stem) = file_path.file_stem().and_then(|s| s.to_str()) {
+                    present.insert(format!("{}:{}", dir_name, stem));
+                }
+
+                let synced: SyncedEntry = match fs::read_to_string(&file_path)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|c| serde_json::from_str(&c).map_err(anyhow::Error::from))
+                {
+                    Ok(entry) => entry,
+                    Err(e) => {
+                        report
+                            .errors
+                            .push(format!("Failed to read {:?}: {}", file_path, e));
+                        continue;
+                    }
+                };
+
+                // The folder may be shared with others, so nothing in it is
+                // trusted to name files outside of it
+                if synced.machine_id != dir_name
+                    || synced
+                        .audio_file
+                        .as_deref()
+                        .is_some_and(|f| !is_plain_file_name(f))
+                {
+                    report
+                        .errors
+                        .push(format!("Ignored {:?}: invalid file names", file_path));
+                    continue;
+                }
+
+                let sync_id = format!("{}:{}", synced.machine_id, synced.entry_id);
+                let mut imported = ImportedEntry {
+                    file_name: String::new(),
+                    timestamp: synced.timestamp,
+                    saved: synced.saved,
+                    title: Some(synced.title),
+                    transcription_text: synced.transcription_text,
+                    tags: synced.tags,
+                    app_name: synced.app_name,
+                    sync_id: Some(sync_id.clone()),
+                };
+
+                if let Some(&id) = local.get(&sync_id) {
+                    if self.history_manager.update_imported_entry(id, &imported)? {
+                        report.updated += 1;
+                    }
+                    continue;
+                }
+                // Deleted here after it was imported
+                if known.contains(&sync_id) {
+                    continue;
+                }
+
+                if let Some(audio_file) = &synced.audio_file {
+                    let local_name = format!("synced-{}-{}", synced.machine_id, audio_file);
+                    let target = self.history_manager.recordings_dir().join(&local_name);
+                    match fs::copy(path.join(audio_file), &target) {
+                        Ok(_) => imported.file_name = local_name,
+                        Err(e) => report
+                            .errors
+                            .push(format!("Failed to copy audio {}: {}", audio_file, e)),
+                    }
+                }
+
+                self.history_manager.insert_imported_entry(imported)?;
+                report.imported += 1;
+            }
+
+            let prefix = format!("{}:", dir_name);
+            for (sync_id, &id) in &local {
+                if sync_id.starts_with(&prefix) && !present.contains(sync_id) {
+                    tauri::async_runtime::block_on(
+                        self.history_manager.delete_synced_entry(id, sync_id),
+                    )?;
+                    report.deleted += 1;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether `name` names a file directly inside a directory, and nothing else.
+fn is_plain_file_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.contains(['/', '\\'])
+        && !name.contains("..")
+        && Path::new(name).file_name() == Some(OsStr::new(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_file_names_are_accepted() {
+        assert!(is_plain_file_name("1712345678.wav"));
+        assert!(is_plain_file_name("18c2f0a1b2c3d4"));
+    }
+
+    #[test]
+    fn names_reaching_outside_the_folder_are_rejected() {
+        for name in [
+            "",
+            ".",
+            "..",
+            "../../x",
+            "a/b.wav",
+            "a\\b.wav",
+            "/etc/passwd",
+        ] {
+            assert!(!is_plain_file_name(name), "{:?}", name);
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use crate::local_http::{respond, LocalServer, Request};
+use crate::settings::get_settings;
+use log::{error, info};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::net::TcpStream;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// Upper bounds, in seconds, of the transcription latency histogram buckets.
+const LATENCY_BUCKETS: [f64; 10] = [0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0];
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ProviderMetrics {
+    pub transcriptions: u64,
+    pub errors: u64,
+    pub latency_sum_seconds: f64,
+    /// Cumulative counts matching `LATENCY_BUCKETS`.
+    pub latency_buckets: Vec<u64>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct MetricsSnapshot {
+    pub providers: BTreeMap<String, ProviderMetrics>,
+}
+
+/// Collects transcription metrics and, when enabled in settings, serves them in
+/// the Prometheus text format on a local-only HTTP endpoint.
+pub struct MetricsManager {
+    app_handle: AppHandle,
+    metrics: Arc<Mutex<MetricsSnapshot>>,
+    server: Mutex<Option<LocalServer>>,
+}
+
+impl MetricsManager {
+    pub fn new(app_handle: &AppHandle) -> Self {
+        let manager = Self {
+            app_handle: app_handle.clone(),
+            metrics: Arc::new(Mutex::new(MetricsSnapshot::default())),
+            server: Mutex::new(None),
+        };
+
+        if let Err(e) = manager.apply_settings() {
+            error!("Failed to start metrics endpoint: {}", e);
+        }
+
+        manager
+    }
+
+    pub fn record_transcription(&self, provider: &str, latency: Duration, success: bool) {
+        let seconds = latency.as_secs_f64();
+        let mut metrics = self.metrics.lock().unwrap();
+        let entry = metrics
+            .providers
+            .entry(provider.to_string())
+            .or_insert_with(|| ProviderMetrics {
+                latency_buckets: vec![0; LATENCY_BUCKETS.len()],
+                ..Default::default()
+            });
+
+        entry.transcriptions += 1;
+        if !success {
+            entry.errors += 1;
+        }
+        entry.latency_sum_seconds += seconds;
+        for (count, bound) in entry.latency_buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if seconds <= bound {
+                *count += 1;
+            }
+        }
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        self.metrics.lock().unwrap().clone()
+    }
+
+    /// Starts, restarts or stops the endpoint to match the current settings.
+    pub fn apply_settings(&self) -> Result<(), String> {
+        let settings = get_settings(&self.app_handle);
+        let mut server = self.server.lock().unwrap();
+
+        let wanted_port = settings.metrics_enabled.then_some(settings.metrics_port);
+        if server.as_ref().map(|s| s.port) == wanted_port {
+            return Ok(());
+        }
+
+        if let Some(running) = server.take() {
+            running.stop();
+            info!("Metrics endpoint stopped");
+        }
+
+        if let Some(port) = wanted_port {
+            let metrics = self.metrics.clone();
+            // Bound to loopback only, scrapers on other hosts should go through a proxy
+            *server = Some(LocalServer::start(
+                "metrics",
+                "127.0.0.1",
+                port,
+                move |stream| handle_request(stream, &metrics),
+            )?);
+            info!(
+                "Metrics endpoint listening on http://127.0.0.1:{}/metrics",
+                port
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for MetricsManager {
+    fn drop(&mut self) {
+        if let Some(running) = self.server.lock().unwrap().take() {
+            running.stop();
+        }
+    }
+}
+
+fn handle_request(mut stream: TcpStream, metrics: &Mutex<MetricsSnapshot>) -> std::io::Result<()> {
+    let request = Request::read(&stream, Duration::from_secs(5))?;
+    if request.path == "/metrics" {
+        let snapshot = metrics.lock().unwrap().clone();
+        respond(
+            &mut stream,
+            "200 OK",
+            "text/plain; version=0.0.4",
+            &render_prometheus(&snapshot),
+        )
+    } else {
+        respond(&mut stream, "404 Not Found", "text/plain", "Not Found\n")
+    }
+}
+
+fn render_prometheus(snapshot: &MetricsSnapshot) -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(
+        out,
+        "# HELP handy_transcriptions_total Transcriptions attempted, by provider."
+    );
+    let _ = writeln!(out, "# TYPE handy_transcriptions_total counter");
+    for (provider, m) in &snapshot.providers {
+        let _ = writeln!(
+            out,
+            "handy_transcriptions_total{{provider=\"{}\"}} {}",
+            provider, m.transcriptions
+        );
+    }
+
+    let _ = writeln!(
+        out,
+        "# HELP handy_transcription_errors_total Failed transcriptions, by provider."
+    );
+    let _ = writeln!(out, "# TYPE handy_transcription_errors_total counter");
+    for (provider, m) in &snapshot.providers {
+        let _ = writeln!(
+            out,
+            "handy_transcription_errors_total{{provider=\"{}\"}} {}",
+            provider, m.errors
+        );
+    }
+
+    let _ = writeln!(
+        out,
+        "# HELP handy_transcription_duration_seconds Transcription latency, by provider."
+    );
+    let _ = writeln!(out, "# TYPE handy_transcription_duration_seconds histogram");
+    for (provider, m) in &snapshot.providers {
+        for (count, bound) in m.latency_buckets.iter().zip(LATENCY_BUCKETS) {
+            let _ = writeln!(
+                out,
+                "handy_transcription_duration_seconds_bucket{{provider=\"{}\",le=\"{}\"}} {}",
+                provider, bound, count
+            );
+        }
+        let _ = writeln!(
+            out,
+            "handy_transcription_duration_seconds_bucket{{provider=\"{}\",le=\"+Inf\"}} {}",
+            provider, m.transcriptions
+        );
+        let _ = writeln!(
+            out,
+            "handy_transcription_duration_seconds_sum{{provider=\"{}\"}} {}",
+            provider, m.latency_sum_seconds
+        );
+        let _ = writeln!(
+            out,
+            "handy_transcription_duration_seconds_count{{provider=\"{}\"}} {}",
+            provider, m.transcriptions
+        );
+    }
+
+    out
+}
@@ -0,0 +1,239 @@
+use crate::events::{self, AppEvent};
+use crate::managers::model::{is_api_model, ModelManager};
+use crate::managers::transcription::TranscriptionManager;
+use crate::settings::{get_settings, MidiAction, MidiBinding, MidiTrigger};
+use crate::shortcut::dispatch_binding_event;
+use crate::utils::cancel_current_operation;
+use log::{debug, error, info, warn};
+use midir::{MidiInput, MidiInputConnection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+const PORT_SCAN_INTERVAL: Duration = Duration::from_secs(3);
+const CLIENT_NAME: &str = "Handy";
+
+/// Emitted with `midi-message-captured` while learning a control. Passed back
+/// to `add_midi_binding` together with the action to bind.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MidiCapture {
+    pub port_name: String,
+    pub trigger: MidiTrigger,
+}
+
+/// State shared with the midir callbacks, which run on midir's own threads.
+struct Listener {
+    app_handle: AppHandle,
+    capturing: AtomicBool,
+    bindings: Mutex<Vec<MidiBinding>>,
+    held: Mutex<HashMap<(String, MidiTrigger), bool>>,
+}
+
+/// Connects to every MIDI input port and maps note and CC messages from pad
+/// or fader controllers to recording actions and model switches.
+pub struct MidiManager {
+    listener: Arc<Listener>,
+    connections: Mutex<HashMap<String, MidiInputConnection<()>>>,
+}
+
+impl MidiManager {
+    pub fn new(app_handle: &AppHandle) -> Arc<Self> {
+        let manager = Arc::new(Self {
+            listener: Arc::new(Listener {
+                app_handle: app_handle.clone(),
+                capturing: AtomicBool::new(false),
+                bindings: Mutex::new(get_settings(app_handle).midi_bindings),
+                held: Mutex::new(HashMap::new()),
+            }),
+            connections: Mutex::new(HashMap::new()),
+        });
+
+        // Pick up controllers that are plugged in or removed while running
+        let weak = Arc::downgrade(&manager);
+        thread::spawn(move || {
+            while let Some(manager) = weak.upgrade() {
+                manager.reload_bindings();
+                manager.refresh_ports();
+                drop(manager);
+                thread::sleep(PORT_SCAN_INTERVAL);
+            }
+            debug!("MIDI port scan thread shutting down");
+        });
+
+        manager
+    }
+
+    pub fn list_ports(&self) -> Result<Vec<String>, String> {
+        let midi_in = MidiInput::new(CLIENT_NAME)
+            .map_err(|e| format!("Failed to initialize MIDI input: {}", e))?;
+        Ok(midi_in
+            .ports()
+            .iter()
+            .filter_map(|port| midi_in.port_name(port).ok())
+            .collect())
+    }
+
+    pub fn reload_bindings(&self) {
+        let bindings = get_settings(&self.listener.app_handle).midi_bindings;
+        let mut current = self.listener.bindings.lock().unwrap();
+        if *current != bindings {
+            *current = bindings;
+            self.listener.held.lock().unwrap().clear();
+        }
+    }
+
+    /// The next note or control change from any port is reported instead of
+    /// being dispatched.
+    pub fn start_capture(&self) {
+        info!("Starting MIDI control capture");
+        self.listener.capturing.store(true, Ordering::Relaxed);
+    }
+
+    pub fn cancel_capture(&self) {
+        self.listener.capturing.store(false, Ordering::Relaxed);
+    }
+
+    fn refresh_ports(&self) {
+        let midi_in = match MidiInput::new(CLIENT_NAME) {
+            Ok(midi_in) => midi_in,
+            Err(e) => {
+                warn!("Failed to initialize MIDI input: {}", e);
+                return;
+            }
+        };
+        let available: Vec<String> = midi_in
+            .ports()
+            .iter()
+            .filter_map(|port| midi_in.port_name(port).ok())
+            .collect();
+
+        let mut connections = self.connections.lock().unwrap();
+        connections.retain(|name, _| available.contains(name));
+
+        for name in available {
+            if connections.contains_key(&name) {
+                continue;
+            }
+
+            // Each connection consumes its own MidiInput
+            let midi_in = match MidiInput::new(CLIENT_NAME) {
+                Ok(midi_in) => midi_in,
+                Err(_) => continue,
+            };
+            let port = midi_in
+                .ports()
+                .into_iter()
+                .find(|p| midi_in.port_name(p).ok().as_deref() == Some(name.as_str()));
+            let Some(port) = port else {
+                continue;
+            };
+
+            let listener = self.listener.clone();
+            let port_name = name.clone();
+            match midi_in.connect(
+                &port,
+                "handy-input",
+                move |_stamp, message, _| listener.handle_message(&port_name, message),
+                (),
+            ) {
+                Ok(connection) => {
+                    info!("Connected to MIDI input {}", name);
+                    connections.insert(name, connection);
+                }
+                Err(e) => debug!("Could not connect to MIDI input {}: {}", name, e),
+            }
+        }
+    }
+}
+
+impl Listener {
+    fn handle_message(&self, port_name: &str, message: &[u8]) {
+        let Some((trigger, pressed)) = parse_message(message) else {
+            return;
+        };
+
+        if self.capturing.load(Ordering::Relaxed) {
+            if pressed {
+                self.capturing.store(false, Ordering::Relaxed);
+                let capture = MidiCapture {
+                    port_name: port_name.to_string(),
+                    trigger,
+                };
+                info!("Captured MIDI control {:?} on {}", trigger, port_name);
+                events::emit(&self.app_handle, AppEvent::MidiMessageCaptured(capture));
+            }
+            return;
+        }
+
+        // Faders send a stream of values, only act on crossings of the midpoint
+        {
+            let mut held = self.held.lock().unwrap();
+            let was_pressed = held
+                .insert((port_name.to_string(), trigger), pressed)
+                .unwrap_or(false);
+            if was_pressed == pressed {
+                return;
+            }
+        }
+
+        let bindings = self.bindings.lock().unwrap().clone();
+        for binding in bindings.iter().filter(|b| {
+            b.trigger == trigger && b.port_name.as_deref().map_or(true, |p| p == port_name)
+        }) {
+            match &binding.action {
+                MidiAction::Binding { binding_id } => {
+                    dispatch_binding_event(&self.app_handle, binding_id, "midi", pressed);
+                }
+                MidiAction::Cancel if pressed => cancel_current_operation(&self.app_handle),
+                MidiAction::SelectModel { model_id } if pressed => {
+                    let app = self.app_handle.clone();
+                    let model_id = model_id.clone();
+                    // Loading a model can take a while, keep the MIDI thread free
+                    thread::spawn(move || {
+                        if let Err(e) = select_model(&app, &model_id) {
+                            error!("Failed to switch to model {} from MIDI: {}", model_id, e);
+                        }
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn select_model(app: &AppHandle, model_id: &str) -> Result<(), String> {
+    let model_manager = app.state::<Arc<ModelManager>>();
+    let model_info = model_manager
+        .get_model_info(model_id)
+        .ok_or_else(|| format!("Model not found: {}", model_id))?;
+    if !model_info.is_downloaded && !is_api_model(model_id) {
+        return Err(format!("Model not downloaded: {}", model_id));
+    }
+
+    app.state::<Arc<TranscriptionManager>>()
+        .select_model(model_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Returns the trigger and whether it is pressed for note and CC messages.
+fn parse_message(message: &[u8]) -> Option<(MidiTrigger, bool)> {
+    let (&status, data) = message.split_first()?;
+    let channel = status & 0x0F;
+
+    match (status & 0xF0, data) {
+        (0x90, &[note, velocity, ..]) => Some((MidiTrigger::Note { channel, note }, velocity > 0)),
+        (0x80, &[note, ..]) => Some((MidiTrigger::Note { channel, note }, false)),
+        (0xB0, &[controller, value, ..]) => Some((
+            MidiTrigger::ControlChange {
+                channel,
+                controller,
+            },
+            value >= 64,
+        )),
+        _ => None,
+    }
+}
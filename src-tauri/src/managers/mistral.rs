@@ -10,6 +10,21 @@ struct MistralTranscriptionResponse {
     text: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct MistralChatResponse {
+    choices: Vec<MistralChatChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MistralChatChoice {
+    message: MistralChatMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct MistralChatMessage {
+    content: String,
+}
+
 #[derive(Clone)]
 pub struct MistralApiManager {
     app_handle: AppHandle,
@@ -24,6 +39,26 @@ impl MistralApiManager {
         }
     }
 
+    /// Makes a cheap authenticated request, to check the API is up and keep
+    /// the connection warm.
+    pub async fn ping(&self) -> Result<()> {
+        let api_key = get_settings(&self.app_handle)
+            .mistral_api_key
+            .ok_or_else(|| anyhow::anyhow!("Mistral API key not set"))?;
+        let response = self
+            .client
+            .get("https://api.mistral.ai/v1/models")
+            .bearer_auth(api_key)
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            debug!("[Mistral] Ping failed with status {}", status);
+            return Err(anyhow::anyhow!("Mistral API returned status {}", status));
+        }
+        Ok(())
+    }
+
     pub async fn transcribe(&self, audio_data: Vec<f32>) -> Result<String> {
         info!("[Mistral] Starting transcription with {} audio samples", audio_data.len());
         
@@ -87,9 +122,51 @@ impl MistralApiManager {
                 anyhow::anyhow!("Failed to parse Mistral response: {}", e)
             })?;
         
-        info!("[Mistral] Transcription successful: {}", transcription.text);
+        debug!("[Mistral] Transcription successful: {}", transcription.text);
         Ok(transcription.text)
     }
+
+    /// Rewrites `text` following `instruction`, for post-processing presets.
+    pub async fn rewrite(&self, instruction: &str, text: &str) -> Result<String> {
+        let settings = get_settings(&self.app_handle);
+        let api_key = settings
+            .mistral_api_key
+            .ok_or_else(|| anyhow::anyhow!("Mistral API key not set"))?;
+
+        let response = self
+            .client
+            .post("https://api.mistral.ai/v1/chat/completions")
+            .bearer_auth(api_key)
+            .json(&serde_json::json!({
+                "model": "mistral-small-latest",
+                "messages": [
+                    { "role": "system", "content": instruction },
+                    { "role": "user", "content": text }
+                ]
+            }))
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await?;
+            return Err(anyhow::anyhow!(
+                "Mistral API request failed with status {}: {}",
+                status,
+                error_text
+            ));
+        }
+
+        let chat: MistralChatResponse = response.json().await?;
+        let content = chat
+            .choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message.content)
+            .ok_or_else(|| anyhow::anyhow!("Mistral returned no rewrite"))?;
+        debug!("[Mistral] Rewrite completed, {} characters", content.len());
+        Ok(content)
+    }
 }
 
 fn float_to_wav(audio_data: &[f32]) -> Result<Vec<u8>> {
@@ -1,8 +1,27 @@
 pub mod assemblyai;
 pub mod audio;
+pub mod automation;
+pub mod backup;
 pub mod deepgram;
+pub mod download_queue;
+pub mod email_ingest;
 pub mod gladia;
+pub mod hid;
 pub mod history;
+pub mod history_sync;
+pub mod metrics;
+pub mod midi;
 pub mod mistral;
+pub mod offline_queue;
+pub mod phone_import;
 pub mod model;
+pub mod power;
+pub mod practice;
+pub mod provider_health;
+pub mod session_lock;
+pub mod stream_deck;
 pub mod transcription;
+pub mod transcription_queue;
+pub mod vocabulary;
+pub mod voice_profile;
+pub mod watch_folder;
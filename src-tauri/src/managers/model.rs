@@ -1,30 +1,193 @@
-use crate::settings::{get_settings, write_settings};
+use crate::acceleration::{detect_hardware, HardwareProfile};
+use crate::events::{self, AppEvent, ModelCorrupted, ModelDownloadSource, ModelError};
+use crate::settings::{
+    get_settings, speed_score, write_settings, AppSettings, CustomModel, ReleaseChannel,
+    UnusedModelAction,
+};
 use anyhow::Result;
 use flate2::read::GzDecoder;
 use futures_util::StreamExt;
+use log::{debug, info, warn};
+use minisign_verify::{PublicKey, Signature};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::fs::File;
-use std::io::Write;
-use std::path::PathBuf;
-use std::sync::Mutex;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
 use tar::Archive;
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Manager};
+use tokio_util::sync::CancellationToken;
 
 pub const API_MODEL_IDS: [&str; 4] = ["voxtral-mini", "nova-3", "universal", "whisper-zero"];
 
+const CATALOG_URL: &str = "https://blob.handy.computer/models.json";
+const CATALOG_FILE: &str = "catalog.json";
+/// Describes the model inside an exported model archive.
+const EXPORT_MANIFEST: &str = "handy-model.json";
+const CATALOG_VERSION: u32 = 1;
+/// minisign public key the remote model catalog is signed with. No key has
+/// been published for the catalog yet, and until one is the remote catalog
+/// is off and the built-in list is used.
+const CATALOG_PUBLIC_KEY: Option<&str> = None;
+
+/// Model URLs with this prefix are downloaded from the Hugging Face Hub.
+const HF_SCHEME: &str = "hf://";
+
+/// `ggml` as the little-endian magic number at the start of whisper.cpp models.
+const GGML_MAGIC: [u8; 4] = *b"lmgg";
+/// Files Parakeet needs when loaded with int8 quantization.
+const PARAKEET_INT8_FILES: [&str; 4] = [
+    "encoder-model.int8.onnx",
+    "decoder_joint-model.int8.onnx",
+    "nemo128.onnx",
+    "vocab.txt",
+];
+
+/// Free space kept on top of what a download needs.
+const DISK_SPACE_MARGIN_MB: u64 = 200;
+
+/// The first unused model and integrity check runs a while after startup,
+/// then daily.
+const HOUSEKEEPING_DELAY: Duration = Duration::from_secs(10 * 60);
+const HOUSEKEEPING_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Speed measured in real use replaces the benchmark after this many
+/// transcriptions of at least `MIN_USAGE_AUDIO_SECS`.
+const MIN_USAGE_TRANSCRIPTIONS: u64 = 3;
+const MIN_USAGE_AUDIO_SECS: f32 = 2.0;
+
+/// Models shipped inside the application resources.
+const BUNDLED_MODELS: [&str; 1] = ["ggml-small.bin"];
+
+/// Languages Parakeet V3 was trained on, besides English.
+const PARAKEET_V3_LANGUAGES: [&str; 24] = [
+    "bg", "hr", "cs", "da", "nl", "et", "fi", "fr", "de", "el", "hu", "it", "lv", "lt", "mt", "pl",
+    "pt", "ro", "sk", "sl", "es", "sv", "ru", "uk",
+];
+/// Below this much memory or these many cores only the small models keep up.
+const LOW_END_MEMORY_MB: u64 = 4096;
+const LOW_END_CPU_CORES: usize = 4;
+/// Whisper Turbo needs a GPU and this much memory to keep up with dictation.
+const TURBO_MEMORY_MB: u64 = 8192;
+/// Downloadable everywhere, the fallback when the preferred model isn't.
+const FALLBACK_MODEL: &str = "small";
+
+/// Whether this build runs the Whisper encoder through Core ML when an encoder
+/// is present next to the model.
+pub fn coreml_supported() -> bool {
+    cfg!(all(
+        feature = "coreml",
+        target_os = "macos",
+        target_arch = "aarch64"
+    ))
+}
+
+/// whisper.cpp looks for `<model>-encoder.mlmodelc` next to the model file,
+/// with the `.bin` extension and any quantization suffix removed.
+fn coreml_encoder_name(filename: &str) -> String {
+    let stem = filename.strip_suffix(".bin").unwrap_or(filename);
+    let stem = match stem.rsplit_once('-') {
+        Some((base, suffix)) if suffix.starts_with('q') && suffix.contains('_') => base,
+        _ => stem,
+    };
+    format!("{}-encoder.mlmodelc", stem)
+}
+
+/// The signed catalog of a release channel. Beta and nightly list
+/// experimental models on top of the stable ones.
+fn catalog_url(channel: ReleaseChannel) -> String {
+    match channel {
+        ReleaseChannel::Stable => CATALOG_URL.to_string(),
+        other => format!("https://blob.handy.computer/models-{}.json", other.as_str()),
+    }
+}
+
+/// Distil-Whisper models are distilled on English only and produce garbage
+/// for other languages or when asked to translate.
+pub fn is_english_only_whisper(model_id: &str) -> bool {
+    model_id.starts_with("distil-")
+}
+
 pub fn is_api_model(model_id: &str) -> bool {
     API_MODEL_IDS.contains(&model_id)
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize)]
+pub struct ModelRecommendation {
+    pub model_id: String,
+    pub name: String,
+    pub size_mb: u64,
+    /// Why this model fits, for the onboarding screen.
+    pub reason: String,
+    pub hardware: HardwareProfile,
+}
+
+/// The model that fits `hardware` best and why.
+fn recommended_model_id(hardware: &HardwareProfile) -> (&'static str, String) {
+    let language = hardware.language.as_deref().unwrap_or("en");
+    let english = language == "en";
+    if hardware.memory_mb < LOW_END_MEMORY_MB || hardware.cpu_cores < LOW_END_CPU_CORES {
+        let reason = format!(
+            "Light enough for {} MB of memory and {} CPU cores",
+            hardware.memory_mb, hardware.cpu_cores
+        );
+        return (if english { "moonshine-base" } else { "small" }, reason);
+    }
+    if english {
+        return (
+            "parakeet-tdt-0.6b-v2",
+            "Fast and accurate for English".to_string(),
+        );
+    }
+    if PARAKEET_V3_LANGUAGES.contains(&language) {
+        return (
+            "parakeet-tdt-0.6b-v3",
+            format!("Fast and accurate for your system language ({})", language),
+        );
+    }
+    match &hardware.gpu_backend {
+        Some(backend) if hardware.memory_mb >= TURBO_MEMORY_MB => (
+            "turbo",
+            format!(
+                "Supports your system language ({}) and runs on {}",
+                language, backend
+            ),
+        ),
+        _ => (
+            FALLBACK_MODEL,
+            format!(
+                "Supports your system language ({}) and runs well on the CPU",
+                language
+            ),
+        ),
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub enum EngineType {
+    #[default]
     Whisper,
     Parakeet,
+    Moonshine,
+    Vosk,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl EngineType {
+    /// Whether this build can load models of this engine.
+    pub fn is_supported(&self) -> bool {
+        match self {
+            EngineType::Vosk => crate::vosk::is_supported(),
+            _ => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ModelInfo {
     pub id: String,
     pub name: String,
@@ -39,6 +202,150 @@ pub struct ModelInfo {
     pub engine_type: EngineType,
     pub accuracy_score: f32, // 0.0 to 1.0, higher is more accurate
     pub speed_score: f32,    // 0.0 to 1.0, higher is faster
+    pub sha256: Option<String>,
+    /// Core ML encoder archive for Whisper models, used on Apple Silicon.
+    #[serde(default)]
+    pub coreml_url: Option<String>,
+    #[serde(default)]
+    pub coreml_ready: bool,
+    /// Registered by the user from a file or folder outside the models
+    /// directory; `filename` holds its absolute path.
+    #[serde(default)]
+    pub is_custom: bool,
+    /// Tried in order when `url` can't be reached or returns an error.
+    #[serde(default)]
+    pub mirrors: Vec<String>,
+    /// Revision of the upstream files, bumped by the catalog when a model
+    /// is re-published.
+    #[serde(default)]
+    pub version: Option<String>,
+    /// The downloaded copy is older than `version`.
+    #[serde(default)]
+    pub update_available: bool,
+    /// SPDX identifier of the license the model weights are published under.
+    #[serde(default)]
+    pub license: Option<String>,
+    /// Where the model comes from upstream, e.g. its Hugging Face page.
+    #[serde(default)]
+    pub source_url: Option<String>,
+    /// Upstream revision the published files were built from.
+    #[serde(default)]
+    pub revision: Option<String>,
+    /// Quantizations of the same weights the user can choose between. The
+    /// file fields above always describe the selected one.
+    #[serde(default)]
+    pub variants: Vec<ModelVariant>,
+    /// Id of the selected variant, `None` for models without variants.
+    #[serde(default)]
+    pub variant: Option<String>,
+    /// Why the downloaded files failed the last integrity check. The model
+    /// has to be downloaded again before it can be used.
+    #[serde(default)]
+    pub corrupted: Option<String>,
+    /// Transcription time over audio duration on this machine, `None`
+    /// until the model was used or benchmarked.
+    #[serde(default)]
+    pub real_time_factor: Option<f32>,
+    /// The user's 1 to 5 star rating.
+    #[serde(default)]
+    pub user_rating: Option<u8>,
+}
+
+/// One quantization of a model, e.g. `q5_0` or `f16`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelVariant {
+    pub id: String,
+    pub filename: String,
+    pub url: String,
+    pub size_mb: u64,
+    #[serde(default)]
+    pub sha256: Option<String>,
+    #[serde(default)]
+    pub is_downloaded: bool,
+}
+
+fn variant(id: &str, filename: &str, url: &str, size_mb: u64) -> ModelVariant {
+    ModelVariant {
+        id: id.to_string(),
+        filename: filename.to_string(),
+        url: url.to_string(),
+        size_mb,
+        sha256: None,
+        is_downloaded: false,
+    }
+}
+
+/// Variants of a whisper.cpp model from the upstream Hugging Face repo.
+fn ggml_variant(id: &str, filename: &str, size_mb: u64) -> ModelVariant {
+    variant(
+        id,
+        filename,
+        &format!("{}ggerganov/whisper.cpp/{}", HF_SCHEME, filename),
+        size_mb,
+    )
+}
+
+/// A model as described by the remote catalog, without any local state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CatalogModel {
+    id: String,
+    name: String,
+    description: String,
+    filename: String,
+    url: String,
+    size_mb: u64,
+    #[serde(default)]
+    is_directory: bool,
+    engine_type: EngineType,
+    accuracy_score: f32,
+    speed_score: f32,
+    #[serde(default)]
+    sha256: Option<String>,
+    #[serde(default)]
+    coreml_url: Option<String>,
+    #[serde(default)]
+    mirrors: Vec<String>,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    license: Option<String>,
+    #[serde(default)]
+    source_url: Option<String>,
+    #[serde(default)]
+    revision: Option<String>,
+    /// The first variant is the default one and matches the entry's files.
+    #[serde(default)]
+    variants: Vec<ModelVariant>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ModelCatalog {
+    version: u32,
+    /// Entries are parsed one by one so a model using a newer engine type
+    /// doesn't invalidate the rest of the catalog for older builds.
+    models: Vec<serde_json::Value>,
+}
+
+impl ModelCatalog {
+    fn entries(&self) -> Vec<CatalogModel> {
+        self.models
+            .iter()
+            .filter_map(|value| match serde_json::from_value(value.clone()) {
+                Ok(model) => Some(model),
+                Err(e) => {
+                    debug!("Skipping unsupported catalog entry: {}", e);
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", content = "reason", rename_all = "snake_case")]
+pub enum IntegrityStatus {
+    Ok,
+    Corrupt(String),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,257 +354,949 @@ pub struct DownloadProgress {
     pub downloaded: u64,
     pub total: u64,
     pub percentage: f64,
+    /// Set while an archive is unpacked as it downloads, the progress then
+    /// covers both.
+    pub extracting: bool,
 }
 
-pub struct ModelManager {
-    app_handle: AppHandle,
-    models_dir: PathBuf,
-    available_models: Mutex<HashMap<String, ModelInfo>>,
+/// Validators of the blob a `.partial` file was started from, so a resumed
+/// download can tell whether the file changed upstream in the meantime.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DownloadValidator {
+    etag: Option<String>,
+    last_modified: Option<String>,
 }
 
-impl ModelManager {
-    pub fn new(app_handle: &AppHandle) -> Result<Self> {
-        // Create models directory in app data
-        let models_dir = app_handle
-            .path()
-            .app_data_dir()
-            .map_err(|e| anyhow::anyhow!("Failed to get app data dir: {}", e))?
-            .join("models");
-
-        if !models_dir.exists() {
-            fs::create_dir_all(&models_dir)?;
+impl DownloadValidator {
+    fn from_response(response: &reqwest::Response) -> Self {
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        Self {
+            etag: header(reqwest::header::ETAG),
+            last_modified: header(reqwest::header::LAST_MODIFIED),
         }
+    }
 
-        let mut available_models = HashMap::new();
+    fn load(path: &Path) -> Option<Self> {
+        serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+    }
 
-        // TODO this should be read from a JSON file or something..
-        available_models.insert(
-            "small".to_string(),
-            ModelInfo {
-                id: "small".to_string(),
-                name: "Whisper Small".to_string(),
-                description: "Fast and fairly accurate.".to_string(),
-                filename: "ggml-small.bin".to_string(),
-                url: Some("https://blob.handy.computer/ggml-small.bin".to_string()),
-                size_mb: 487,
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: false,
-                engine_type: EngineType::Whisper,
-                accuracy_score: 0.60,
-                speed_score: 0.85,
-            },
-        );
+    fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
 
-        // Add downloadable models
-        available_models.insert(
-            "medium".to_string(),
-            ModelInfo {
-                id: "medium".to_string(),
-                name: "Whisper Medium".to_string(),
-                description: "Good accuracy, medium speed".to_string(),
-                filename: "whisper-medium-q4_1.bin".to_string(),
-                url: Some("https://blob.handy.computer/whisper-medium-q4_1.bin".to_string()),
-                size_mb: 492, // Approximate size
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: false,
-                engine_type: EngineType::Whisper,
-                accuracy_score: 0.75,
-                speed_score: 0.60,
-            },
-        );
+    /// Value for `If-Range`. Weak ETags aren't allowed there, so those fall
+    /// back to Last-Modified.
+    fn if_range(&self) -> Option<&str> {
+        self.etag
+            .as_deref()
+            .filter(|etag| !etag.starts_with("W/"))
+            .or(self.last_modified.as_deref())
+    }
+}
 
-        available_models.insert(
-            "turbo".to_string(),
-            ModelInfo {
-                id: "turbo".to_string(),
-                name: "Whisper Turbo".to_string(),
-                description: "Balanced accuracy and speed.".to_string(),
-                filename: "ggml-large-v3-turbo.bin".to_string(),
-                url: Some("https://blob.handy.computer/ggml-large-v3-turbo.bin".to_string()),
-                size_mb: 1600, // Approximate size
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: false,
-                engine_type: EngineType::Whisper,
-                accuracy_score: 0.80,
-                speed_score: 0.40,
-            },
-        );
+/// Where the validators of a `.partial` download are kept.
+fn validator_path(partial_path: &Path) -> PathBuf {
+    let mut path = partial_path.as_os_str().to_owned();
+    path.push(".validator");
+    PathBuf::from(path)
+}
 
-        available_models.insert(
-            "large".to_string(),
-            ModelInfo {
-                id: "large".to_string(),
-                name: "Whisper Large".to_string(),
-                description: "Good accuracy, but slow.".to_string(),
-                filename: "ggml-large-v3-q5_0.bin".to_string(),
-                url: Some("https://blob.handy.computer/ggml-large-v3-q5_0.bin".to_string()),
-                size_mb: 1100, // Approximate size
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: false,
-                engine_type: EngineType::Whisper,
-                accuracy_score: 0.85,
-                speed_score: 0.30,
-            },
-        );
+/// A model with its local state, for auditing what is or would be run.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelDetails {
+    #[serde(flatten)]
+    pub model: ModelInfo,
+    /// Where the downloaded files live.
+    pub local_path: Option<String>,
+    /// Catalog version the downloaded copy was installed at.
+    pub installed_version: Option<String>,
+    pub downloaded_at: Option<i64>,
+}
 
-        // Add NVIDIA Parakeet models (directory-based)
-        available_models.insert(
-            "parakeet-tdt-0.6b-v2".to_string(),
-            ModelInfo {
-                id: "parakeet-tdt-0.6b-v2".to_string(),
-                name: "Parakeet V2".to_string(),
-                description: "English only. The best model for English speakers.".to_string(),
-                filename: "parakeet-tdt-0.6b-v2-int8".to_string(), // Directory name
-                url: Some("https://blob.handy.computer/parakeet-v2-int8.tar.gz".to_string()),
-                size_mb: 473, // Approximate size for int8 quantized model
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: true,
-                engine_type: EngineType::Parakeet,
-                accuracy_score: 0.85,
-                speed_score: 0.85,
-            },
-        );
+/// A downloaded model with a newer revision in the catalog.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelUpdate {
+    pub model_id: String,
+    pub name: String,
+    pub installed_version: String,
+    pub available_version: String,
+}
 
-        available_models.insert(
-            "parakeet-tdt-0.6b-v3".to_string(),
-            ModelInfo {
-                id: "parakeet-tdt-0.6b-v3".to_string(),
-                name: "Parakeet V3".to_string(),
-                description: "Fast and accurate".to_string(),
-                filename: "parakeet-tdt-0.6b-v3-int8".to_string(), // Directory name
-                url: Some("https://blob.handy.computer/parakeet-v3-int8.tar.gz".to_string()),
-                size_mb: 478, // Approximate size for int8 quantized model
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: true,
-                engine_type: EngineType::Parakeet,
-                accuracy_score: 0.80,
-                speed_score: 0.85,
-            },
-        );
+/// A downloaded model that hasn't been used within the policy's window.
+#[derive(Debug, Clone, Serialize)]
+pub struct UnusedModel {
+    pub model_id: String,
+    pub name: String,
+    pub size_mb: u64,
+    /// Unix timestamp, from the download time if it was never loaded.
+    pub last_used: i64,
+}
+
+/// First entry of an exported model archive, followed by the model's files.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ExportManifest {
+    model_id: String,
+    filename: String,
+    #[serde(default)]
+    version: Option<String>,
+}
+
+/// Why a file in the models directory is considered left over.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StaleReason {
+    /// A `.partial` download, or its validators, nothing is resuming.
+    PartialDownload,
+    /// A `.extracting` directory from an interrupted install.
+    InterruptedExtraction,
+    /// A directory that belongs to no known model.
+    UnknownDirectory,
+}
+
+/// A file or directory no model install needs anymore.
+#[derive(Debug, Clone, Serialize)]
+pub struct StaleModelFile {
+    pub path: String,
+    pub size_bytes: u64,
+    pub reason: StaleReason,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelsDirStatus {
+    pub path: String,
+    pub read_only: bool,
+    pub reason: Option<String>,
+}
 
-        // Add API-based models
+/// The catalog compiled into this build, used until a remote catalog has been
+/// fetched and verified, and as the base the remote catalog extends.
+fn builtin_models() -> HashMap<String, ModelInfo> {
+    let mut available_models = HashMap::new();
+
+    available_models.insert(
+        "small".to_string(),
+        ModelInfo {
+            id: "small".to_string(),
+            name: "Whisper Small".to_string(),
+            description: "Fast and fairly accurate.".to_string(),
+            filename: "ggml-small.bin".to_string(),
+            url: Some("https://blob.handy.computer/ggml-small.bin".to_string()),
+            size_mb: 487,
+            engine_type: EngineType::Whisper,
+            accuracy_score: 0.60,
+            speed_score: 0.85,
+            coreml_url: Some(
+                "https://blob.handy.computer/ggml-small-encoder.mlmodelc.tar.gz".to_string(),
+            ),
+            license: Some("MIT".to_string()),
+            source_url: Some("https://huggingface.co/ggerganov/whisper.cpp".to_string()),
+            variants: vec![
+                variant(
+                    "f16",
+                    "ggml-small.bin",
+                    "https://blob.handy.computer/ggml-small.bin",
+                    487,
+                ),
+                ggml_variant("q8_0", "ggml-small-q8_0.bin", 264),
+                ggml_variant("q5_1", "ggml-small-q5_1.bin", 190),
+            ],
+            variant: Some("f16".to_string()),
+            ..Default::default()
+        },
+    );
+
+    // Add downloadable models
+    available_models.insert(
+        "medium".to_string(),
+        ModelInfo {
+            id: "medium".to_string(),
+            name: "Whisper Medium".to_string(),
+            description: "Good accuracy, medium speed".to_string(),
+            filename: "whisper-medium-q4_1.bin".to_string(),
+            url: Some("https://blob.handy.computer/whisper-medium-q4_1.bin".to_string()),
+            size_mb: 492, // Approximate size
+            engine_type: EngineType::Whisper,
+            accuracy_score: 0.75,
+            speed_score: 0.60,
+            coreml_url: Some(
+                "https://blob.handy.computer/whisper-medium-encoder.mlmodelc.tar.gz".to_string(),
+            ),
+            license: Some("MIT".to_string()),
+            source_url: Some("https://huggingface.co/ggerganov/whisper.cpp".to_string()),
+            variants: vec![
+                variant(
+                    "q4_1",
+                    "whisper-medium-q4_1.bin",
+                    "https://blob.handy.computer/whisper-medium-q4_1.bin",
+                    492,
+                ),
+                ggml_variant("q5_0", "ggml-medium-q5_0.bin", 539),
+                ggml_variant("q8_0", "ggml-medium-q8_0.bin", 823),
+                ggml_variant("f16", "ggml-medium.bin", 1533),
+            ],
+            variant: Some("q4_1".to_string()),
+            ..Default::default()
+        },
+    );
+
+    available_models.insert(
+        "turbo".to_string(),
+        ModelInfo {
+            id: "turbo".to_string(),
+            name: "Whisper Turbo".to_string(),
+            description: "Balanced accuracy and speed.".to_string(),
+            filename: "ggml-large-v3-turbo.bin".to_string(),
+            url: Some("https://blob.handy.computer/ggml-large-v3-turbo.bin".to_string()),
+            size_mb: 1600, // Approximate size
+            engine_type: EngineType::Whisper,
+            accuracy_score: 0.80,
+            speed_score: 0.40,
+            coreml_url: Some(
+                "https://blob.handy.computer/ggml-large-v3-turbo-encoder.mlmodelc.tar.gz"
+                    .to_string(),
+            ),
+            license: Some("MIT".to_string()),
+            source_url: Some("https://huggingface.co/ggerganov/whisper.cpp".to_string()),
+            variants: vec![
+                variant(
+                    "f16",
+                    "ggml-large-v3-turbo.bin",
+                    "https://blob.handy.computer/ggml-large-v3-turbo.bin",
+                    1600,
+                ),
+                ggml_variant("q8_0", "ggml-large-v3-turbo-q8_0.bin", 874),
+                ggml_variant("q5_0", "ggml-large-v3-turbo-q5_0.bin", 574),
+            ],
+            variant: Some("f16".to_string()),
+            ..Default::default()
+        },
+    );
+
+    available_models.insert(
+        "large".to_string(),
+        ModelInfo {
+            id: "large".to_string(),
+            name: "Whisper Large".to_string(),
+            description: "Good accuracy, but slow.".to_string(),
+            filename: "ggml-large-v3-q5_0.bin".to_string(),
+            url: Some("https://blob.handy.computer/ggml-large-v3-q5_0.bin".to_string()),
+            size_mb: 1100, // Approximate size
+            engine_type: EngineType::Whisper,
+            accuracy_score: 0.85,
+            speed_score: 0.30,
+            coreml_url: Some(
+                "https://blob.handy.computer/ggml-large-v3-encoder.mlmodelc.tar.gz".to_string(),
+            ),
+            license: Some("MIT".to_string()),
+            source_url: Some("https://huggingface.co/ggerganov/whisper.cpp".to_string()),
+            variants: vec![
+                variant(
+                    "q5_0",
+                    "ggml-large-v3-q5_0.bin",
+                    "https://blob.handy.computer/ggml-large-v3-q5_0.bin",
+                    1100,
+                ),
+                ggml_variant("q8_0", "ggml-large-v3-q8_0.bin", 1660),
+                ggml_variant("f16", "ggml-large-v3.bin", 3095),
+            ],
+            variant: Some("q5_0".to_string()),
+            ..Default::default()
+        },
+    );
+
+    // Add NVIDIA Parakeet models (directory-based)
+    available_models.insert(
+        "distil-small-en".to_string(),
+        ModelInfo {
+            id: "distil-small-en".to_string(),
+            name: "Distil-Whisper Small".to_string(),
+            description: "English only. Faster than Whisper Small at similar accuracy.".to_string(),
+            filename: "ggml-distil-small.en.bin".to_string(),
+            url: Some("hf://distil-whisper/distil-small.en/ggml-distil-small.en.bin".to_string()),
+            size_mb: 336,
+            engine_type: EngineType::Whisper,
+            accuracy_score: 0.62,
+            speed_score: 0.85,
+            license: Some("MIT".to_string()),
+            source_url: Some("https://huggingface.co/distil-whisper/distil-small.en".to_string()),
+            ..Default::default()
+        },
+    );
+
+    available_models.insert(
+        "distil-large-v3".to_string(),
+        ModelInfo {
+            id: "distil-large-v3".to_string(),
+            name: "Distil-Whisper Large".to_string(),
+            description: "English only. Close to Whisper Large, several times faster.".to_string(),
+            filename: "ggml-distil-large-v3.bin".to_string(),
+            url: Some(
+                "hf://distil-whisper/distil-large-v3-ggml/ggml-distil-large-v3.bin".to_string(),
+            ),
+            size_mb: 1520,
+            engine_type: EngineType::Whisper,
+            accuracy_score: 0.82,
+            speed_score: 0.60,
+            license: Some("MIT".to_string()),
+            source_url: Some("https://huggingface.co/distil-whisper/distil-large-v3".to_string()),
+            ..Default::default()
+        },
+    );
+
+    available_models.insert(
+        "parakeet-tdt-0.6b-v2".to_string(),
+        ModelInfo {
+            id: "parakeet-tdt-0.6b-v2".to_string(),
+            name: "Parakeet V2".to_string(),
+            description: "English only. The best model for English speakers.".to_string(),
+            filename: "parakeet-tdt-0.6b-v2-int8".to_string(), // Directory name
+            url: Some("https://blob.handy.computer/parakeet-v2-int8.tar.gz".to_string()),
+            size_mb: 473, // Approximate size for int8 quantized model
+            is_directory: true,
+            engine_type: EngineType::Parakeet,
+            accuracy_score: 0.85,
+            speed_score: 0.85,
+            license: Some("CC-BY-4.0".to_string()),
+            source_url: Some("https://huggingface.co/nvidia/parakeet-tdt-0.6b-v2".to_string()),
+            ..Default::default()
+        },
+    );
+
+    available_models.insert(
+        "parakeet-tdt-0.6b-v3".to_string(),
+        ModelInfo {
+            id: "parakeet-tdt-0.6b-v3".to_string(),
+            name: "Parakeet V3".to_string(),
+            description: "Fast and accurate".to_string(),
+            filename: "parakeet-tdt-0.6b-v3-int8".to_string(), // Directory name
+            url: Some("https://blob.handy.computer/parakeet-v3-int8.tar.gz".to_string()),
+            size_mb: 478, // Approximate size for int8 quantized model
+            is_directory: true,
+            engine_type: EngineType::Parakeet,
+            accuracy_score: 0.80,
+            speed_score: 0.85,
+            license: Some("CC-BY-4.0".to_string()),
+            source_url: Some("https://huggingface.co/nvidia/parakeet-tdt-0.6b-v3".to_string()),
+            ..Default::default()
+        },
+    );
+
+    available_models.insert(
+        "moonshine-base".to_string(),
+        ModelInfo {
+            id: "moonshine-base".to_string(),
+            name: "Moonshine Base".to_string(),
+            description: "English only. Very fast on short dictations.".to_string(),
+            filename: "moonshine-base".to_string(), // Directory name
+            url: Some("https://blob.handy.computer/moonshine-base.tar.gz".to_string()),
+            size_mb: 58,
+            is_directory: true,
+            engine_type: EngineType::Moonshine,
+            accuracy_score: 0.70,
+            speed_score: 0.90,
+            license: Some("MIT".to_string()),
+            source_url: Some("https://huggingface.co/UsefulSensors/moonshine".to_string()),
+            ..Default::default()
+        },
+    );
+
+    if crate::vosk::is_supported() {
         available_models.insert(
-            "voxtral-mini".to_string(),
+            "vosk-small-en".to_string(),
             ModelInfo {
-                id: "voxtral-mini".to_string(),
-                name: "Voxtral Mini Transcribe (API)".to_string(),
-                description: "Fast cloud transcription via Mistral API.".to_string(),
-                filename: "".to_string(),
-                url: None,
-                size_mb: 0,
-                is_downloaded: true,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: false,
-                engine_type: EngineType::Whisper,
-                accuracy_score: 0.80,
+                id: "vosk-small-en".to_string(),
+                name: "Vosk Small".to_string(),
+                description: "English only. Tiny and light, for older machines.".to_string(),
+                filename: "vosk-model-small-en-us-0.15".to_string(), // Directory name
+                url: Some(
+                    "https://blob.handy.computer/vosk-model-small-en-us-0.15.tar.gz".to_string(),
+                ),
+                size_mb: 68,
+                is_directory: true,
+                engine_type: EngineType::Vosk,
+                accuracy_score: 0.45,
                 speed_score: 0.95,
+                license: Some("Apache-2.0".to_string()),
+                source_url: Some("https://alphacephei.com/vosk/models".to_string()),
+                ..Default::default()
             },
         );
+    }
 
-        available_models.insert(
-            "nova-3".to_string(),
-            ModelInfo {
-                id: "nova-3".to_string(),
-                name: "Nova-3 (Deepgram API)".to_string(),
-                description: "High-accuracy cloud transcription via Deepgram API.".to_string(),
-                filename: "".to_string(),
-                url: None,
-                size_mb: 0,
-                is_downloaded: true,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: false,
-                engine_type: EngineType::Whisper,
-                accuracy_score: 0.90,
-                speed_score: 0.75,
-            },
-        );
+    // Add API-based models
+    available_models.insert(
+        "voxtral-mini".to_string(),
+        ModelInfo {
+            id: "voxtral-mini".to_string(),
+            name: "Voxtral Mini Transcribe (API)".to_string(),
+            description: "Fast cloud transcription via Mistral API.".to_string(),
+            filename: "".to_string(),
+            is_downloaded: true,
+            engine_type: EngineType::Whisper,
+            accuracy_score: 0.80,
+            speed_score: 0.95,
+            ..Default::default()
+        },
+    );
+
+    available_models.insert(
+        "nova-3".to_string(),
+        ModelInfo {
+            id: "nova-3".to_string(),
+            name: "Nova-3 (Deepgram API)".to_string(),
+            description: "High-accuracy cloud transcription via Deepgram API.".to_string(),
+            filename: "".to_string(),
+            is_downloaded: true,
+            engine_type: EngineType::Whisper,
+            accuracy_score: 0.90,
+            speed_score: 0.75,
+            ..Default::default()
+        },
+    );
+
+    available_models.insert(
+        "universal".to_string(),
+        ModelInfo {
+            id: "universal".to_string(),
+            name: "Universal (AssemblyAI API)".to_string(),
+            description: "Versatile speech recognition via AssemblyAI API.".to_string(),
+            filename: "".to_string(),
+            is_downloaded: true,
+            engine_type: EngineType::Whisper,
+            accuracy_score: 0.88,
+            speed_score: 0.70,
+            ..Default::default()
+        },
+    );
+
+    available_models.insert(
+        "whisper-zero".to_string(),
+        ModelInfo {
+            id: "whisper-zero".to_string(),
+            name: "Whisper-Zero (Gladia API)".to_string(),
+            description: "Advanced Whisper model with fewer hallucinations via Gladia API."
+                .to_string(),
+            filename: "".to_string(),
+            is_downloaded: true,
+            engine_type: EngineType::Whisper,
+            accuracy_score: 0.85,
+            speed_score: 0.72,
+            ..Default::default()
+        },
+    );
+
+    available_models
+}
 
-        available_models.insert(
-            "universal".to_string(),
+/// Adds or updates models from the catalog. API models are tied to code in
+/// this build and can't be replaced.
+fn merge_catalog(models: &mut HashMap<String, ModelInfo>, catalog: ModelCatalog) {
+    for entry in catalog.entries() {
+        if is_api_model(&entry.id)
+            || entry.filename.contains(['/', '\\'])
+            || !entry.engine_type.is_supported()
+        {
+            continue;
+        }
+        models.insert(
+            entry.id.clone(),
             ModelInfo {
-                id: "universal".to_string(),
-                name: "Universal (AssemblyAI API)".to_string(),
-                description: "Versatile speech recognition via AssemblyAI API.".to_string(),
-                filename: "".to_string(),
-                url: None,
-                size_mb: 0,
-                is_downloaded: true,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: false,
-                engine_type: EngineType::Whisper,
-                accuracy_score: 0.88,
-                speed_score: 0.70,
+                id: entry.id,
+                name: entry.name,
+                description: entry.description,
+                filename: entry.filename,
+                url: Some(entry.url),
+                size_mb: entry.size_mb,
+                is_directory: entry.is_directory,
+                engine_type: entry.engine_type,
+                accuracy_score: entry.accuracy_score,
+                speed_score: entry.speed_score,
+                sha256: entry.sha256,
+                coreml_url: entry.coreml_url,
+                mirrors: entry.mirrors,
+                version: entry.version,
+                license: entry.license,
+                source_url: entry.source_url,
+                revision: entry.revision,
+                variant: entry.variants.first().map(|v| v.id.clone()),
+                variants: entry.variants,
+                ..Default::default()
             },
         );
+    }
+}
 
-        available_models.insert(
-            "whisper-zero".to_string(),
+/// Adds the user's registered models. Which engine runs a model follows from
+/// its shape: a ggml file, a Vosk folder or a Parakeet folder.
+fn merge_custom_models(models: &mut HashMap<String, ModelInfo>, custom: &[CustomModel]) {
+    for model in custom {
+        let path = Path::new(&model.path);
+        let is_directory = path.is_dir();
+        let size_bytes: u64 = if is_directory {
+            fs::read_dir(path)
+                .map(|entries| {
+                    entries
+                        .filter_map(|e| e.ok()?.metadata().ok())
+                        .map(|m| m.len())
+                        .sum()
+                })
+                .unwrap_or(0)
+        } else {
+            fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+        };
+        models.insert(
+            model.id.clone(),
             ModelInfo {
-                id: "whisper-zero".to_string(),
-                name: "Whisper-Zero (Gladia API)".to_string(),
-                description: "Advanced Whisper model with fewer hallucinations via Gladia API."
-                    .to_string(),
-                filename: "".to_string(),
-                url: None,
-                size_mb: 0,
-                is_downloaded: true,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: false,
-                engine_type: EngineType::Whisper,
-                accuracy_score: 0.85,
-                speed_score: 0.72,
+                id: model.id.clone(),
+                name: model.name.clone(),
+                description: model.path.clone(),
+                filename: model.path.clone(),
+                size_mb: size_bytes / (1024 * 1024),
+                is_directory,
+                engine_type: if crate::vosk::is_vosk_model_dir(path) {
+                    EngineType::Vosk
+                } else if is_directory {
+                    EngineType::Parakeet
+                } else {
+                    EngineType::Whisper
+                },
+                is_custom: true,
+                ..Default::default()
             },
         );
+    }
+}
+
+/// Checks that a path looks like a model the engines can load and returns
+/// whether it is a directory (Parakeet or Vosk) model.
+fn validate_custom_model(path: &Path) -> Result<bool> {
+    if crate::vosk::is_vosk_model_dir(path) {
+        if !crate::vosk::is_supported() {
+            return Err(anyhow::anyhow!("This build of Handy has no Vosk support"));
+        }
+        return Ok(true);
+    }
+    if path.is_dir() {
+        let missing: Vec<&str> = PARAKEET_INT8_FILES
+            .iter()
+            .copied()
+            .filter(|file| !path.join(file).is_file())
+            .collect();
+        if !missing.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Not a Parakeet int8 model folder, missing: {}",
+                missing.join(", ")
+            ));
+        }
+        return Ok(true);
+    }
+
+    let mut magic = [0u8; 4];
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .map_err(|e| anyhow::anyhow!("Failed to read model file: {}", e))?;
+    if magic != GGML_MAGIC {
+        return Err(anyhow::anyhow!(
+            "Not a Whisper ggml model file: {}",
+            path.display()
+        ));
+    }
+    Ok(false)
+}
+
+/// Turns `org/repo/path/to/file` or `org/repo@revision/path/to/file` into a
+/// Hugging Face Hub download URL.
+fn resolve_hf_url(hf_path: &str) -> Result<String> {
+    let mut parts = hf_path.splitn(3, '/');
+    let (Some(org), Some(repo), Some(file)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(anyhow::anyhow!(
+            "Invalid Hugging Face URL, expected hf://org/repo/file: {}{}",
+            HF_SCHEME,
+            hf_path
+        ));
+    };
+    let (repo, revision) = repo.split_once('@').unwrap_or((repo, "main"));
+    if org.is_empty() || repo.is_empty() || file.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Invalid Hugging Face URL: {}{}",
+            HF_SCHEME,
+            hf_path
+        ));
+    }
+
+    Ok(format!(
+        "https://huggingface.co/{}/{}/resolve/{}/{}",
+        org, repo, revision, file
+    ))
+}
+
+/// Parses a catalog after checking its minisign signature.
+fn verify_catalog(data: &[u8], signature: &str) -> Result<ModelCatalog> {
+    let public_key = CATALOG_PUBLIC_KEY
+        .ok_or_else(|| anyhow::anyhow!("No catalog signing key is configured"))?;
+    let public_key = PublicKey::from_base64(public_key)
+        .map_err(|e| anyhow::anyhow!("Invalid catalog public key: {}", e))?;
+    let signature = Signature::decode(signature)
+        .map_err(|e| anyhow::anyhow!("Invalid catalog signature: {}", e))?;
+    public_key
+        .verify(data, &signature, false)
+        .map_err(|e| anyhow::anyhow!("Catalog signature verification failed: {}", e))?;
+
+    let catalog: ModelCatalog = serde_json::from_slice(data)?;
+    if catalog.version > CATALOG_VERSION {
+        return Err(anyhow::anyhow!(
+            "Catalog version {} is not supported",
+            catalog.version
+        ));
+    }
+    Ok(catalog)
+}
+
+pub struct ModelManager {
+    app_handle: AppHandle,
+    models_dir: PathBuf,
+    available_models: Mutex<HashMap<String, ModelInfo>>,
+    /// Set when the models directory can't be created or written to. Downloads
+    /// are disabled, but preexisting, bundled and API models keep working.
+    read_only_reason: Option<String>,
+    /// Cancels the running download of a model.
+    download_tokens: Mutex<HashMap<String, CancellationToken>>,
+    /// Set once the startup scan of the models directory has finished.
+    ready: Mutex<bool>,
+    ready_condvar: Condvar,
+}
+
+impl ModelManager {
+    pub fn new(app_handle: &AppHandle) -> Result<Self> {
+        // Create models directory in app data
+        let models_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| anyhow::anyhow!("Failed to get app data dir: {}", e))?
+            .join("models");
+
+        let read_only_reason = match Self::prepare_models_dir(&models_dir) {
+            Ok(()) => None,
+            Err(e) => {
+                eprintln!(
+                    "Models directory {:?} is not writable, running in read-only mode: {}",
+                    models_dir, e
+                );
+                Some(e.to_string())
+            }
+        };
+
+        let mut available_models = builtin_models();
+        if let Some(catalog) = Self::load_cached_catalog(&models_dir) {
+            merge_catalog(&mut available_models, catalog);
+        }
+        let settings = get_settings(app_handle);
+        merge_custom_models(&mut available_models, &settings.custom_models);
+        apply_selected_variants(&mut available_models, &settings.model_variants);
 
         let manager = Self {
             app_handle: app_handle.clone(),
             models_dir,
             available_models: Mutex::new(available_models),
+            read_only_reason,
+            download_tokens: Mutex::new(HashMap::new()),
+            ready: Mutex::new(false),
+            ready_condvar: Condvar::new(),
         };
 
-        // Migrate any bundled models to user directory
-        manager.migrate_bundled_models()?;
+        Ok(manager)
+    }
+
+    /// Scans the models directory in the background so startup doesn't wait
+    /// on the disk, and emits `models-ready` when download states are known.
+    pub fn start_initialization(self: &Arc<Self>) {
+        let manager = self.clone();
+        thread::spawn(move || {
+            let start = std::time::Instant::now();
+
+            // Migrate any bundled models to user directory, or tell the UI why
+            // downloads are unavailable when the directory can't be written to
+            if manager.is_read_only() {
+                events::emit(
+                    &manager.app_handle,
+                    AppEvent::ModelsDirReadOnly(manager.get_models_dir_status()),
+                );
+            } else if let Err(e) = manager.migrate_bundled_models() {
+                eprintln!("Failed to migrate bundled models: {}", e);
+            }
 
-        // Check which models are already downloaded
-        manager.update_download_status()?;
+            manager.restore_interrupted_upgrades();
 
-        // Auto-select a model if none is currently selected
-        manager.auto_select_model_if_needed()?;
+            // Check which models are already downloaded
+            if let Err(e) = manager.update_download_status() {
+                warn!("Failed to check downloaded models: {}", e);
+            }
 
-        Ok(manager)
+            // Auto-select a model if none is currently selected
+            if let Err(e) = manager.auto_select_model_if_needed() {
+                warn!("Failed to auto-select a model: {}", e);
+            }
+
+            *manager.ready.lock().unwrap() = true;
+            manager.ready_condvar.notify_all();
+            debug!("Models ready in {:?}", start.elapsed());
+            events::emit(&manager.app_handle, AppEvent::ModelsReady(()));
+        });
+    }
+
+    /// Whether the startup scan has finished and download states are current.
+    pub fn is_ready(&self) -> bool {
+        *self.ready.lock().unwrap()
+    }
+
+    /// Blocks until the startup scan has finished.
+    pub fn wait_until_ready(&self) {
+        let mut ready = self.ready.lock().unwrap();
+        while !*ready {
+            ready = self.ready_condvar.wait(ready).unwrap();
+        }
+    }
+
+    /// Reads the catalog cached by the last successful refresh, verifying it
+    /// again so a tampered cache is ignored.
+    fn load_cached_catalog(models_dir: &Path) -> Option<ModelCatalog> {
+        let data = fs::read(models_dir.join(CATALOG_FILE)).ok()?;
+        let signature =
+            fs::read_to_string(models_dir.join(format!("{}.minisig", CATALOG_FILE))).ok()?;
+        match verify_catalog(&data, &signature) {
+            Ok(catalog) => Some(catalog),
+            Err(e) => {
+                warn!("Ignoring cached model catalog: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Fetches the signed remote catalog, caches it next to the models and
+    /// adds any new models to the list. Models stay available offline from
+    /// the cache or the built-in list.
+    pub async fn refresh_catalog(&self) -> Result<()> {
+        if CATALOG_PUBLIC_KEY.is_none() {
+            debug!("Remote model catalog is off, keeping the built-in list");
+            return Ok(());
+        }
+        let url = catalog_url(get_settings(&self.app_handle).release_channel);
+        let client = reqwest::Client::new();
+        let data = client
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        let signature = client
+            .get(format!("{}.minisig", url))
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        let catalog = verify_catalog(&data, &signature)?;
+
+        if !self.is_read_only() {
+            fs::write(self.models_dir.join(CATALOG_FILE), &data)?;
+            fs::write(
+                self.models_dir.join(format!("{}.minisig", CATALOG_FILE)),
+                &signature,
+            )?;
+        }
+
+        let mut updated = builtin_models();
+        merge_catalog(&mut updated, catalog);
+        let settings = get_settings(&self.app_handle);
+        merge_custom_models(&mut updated, &settings.custom_models);
+        apply_selected_variants(&mut updated, &settings.model_variants);
+        *self.available_models.lock().unwrap() = updated;
+        self.update_download_status()?;
+
+        info!("Model catalog refreshed");
+        events::emit(&self.app_handle, AppEvent::ModelCatalogUpdated(()));
+
+        let updates = self.check_for_updates();
+        if !updates.is_empty() {
+            info!("{} model update(s) available", updates.len());
+            events::emit(&self.app_handle, AppEvent::ModelUpdateAvailable(updates));
+        }
+        Ok(())
+    }
+
+    /// Registers a model file or folder that lives outside the models
+    /// directory so it can be selected like a built-in model. The file is
+    /// used in place and never modified.
+    pub fn register_custom_model(&self, path: &Path, name: Option<String>) -> Result<ModelInfo> {
+        let path = path
+            .canonicalize()
+            .map_err(|e| anyhow::anyhow!("Model not found at {}: {}", path.display(), e))?;
+        validate_custom_model(&path)?;
+
+        let path_str = path.to_string_lossy().to_string();
+        let mut settings = get_settings(&self.app_handle);
+        if settings.custom_models.iter().any(|m| m.path == path_str) {
+            return Err(anyhow::anyhow!("Model is already registered: {}", path_str));
+        }
+
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "model".to_string());
+        let slug: String = stem
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        let mut id = format!("custom-{}", slug);
+        let mut suffix = 2;
+        while self.get_model_info(&id).is_some() {
+            id = format!("custom-{}-{}", slug, suffix);
+            suffix += 1;
+        }
+
+        let custom = CustomModel {
+            id: id.clone(),
+            name: name
+                .map(|n| n.trim().to_string())
+                .filter(|n| !n.is_empty())
+                .unwrap_or(stem),
+            path: path_str,
+        };
+        settings.custom_models.push(custom.clone());
+        write_settings(&self.app_handle, settings);
+
+        merge_custom_models(&mut self.available_models.lock().unwrap(), &[custom]);
+        self.update_download_status()?;
+
+        info!("Registered custom model {} at {}", id, path.display());
+        events::emit(&self.app_handle, AppEvent::ModelCatalogUpdated(()));
+        self.get_model_info(&id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", id))
+    }
+
+    /// Forgets a custom model. Its files are left where they are.
+    pub fn remove_custom_model(&self, model_id: &str) -> Result<()> {
+        let mut settings = get_settings(&self.app_handle);
+        let before = settings.custom_models.len();
+        settings.custom_models.retain(|m| m.id != model_id);
+        if settings.custom_models.len() == before {
+            return Err(anyhow::anyhow!("Custom model not found: {}", model_id));
+        }
+        write_settings(&self.app_handle, settings);
+
+        self.available_models.lock().unwrap().remove(model_id);
+        info!("Removed custom model {}", model_id);
+        events::emit(&self.app_handle, AppEvent::ModelCatalogUpdated(()));
+        Ok(())
     }
 
     pub fn get_available_models(&self) -> Vec<ModelInfo> {
+        let settings = get_settings(&self.app_handle);
         let models = self.available_models.lock().unwrap();
-        models.values().cloned().collect()
+        models
+            .values()
+            .cloned()
+            .map(|model| with_measured_scores(model, &settings))
+            .collect()
     }
 
     pub fn get_model_info(&self, model_id: &str) -> Option<ModelInfo> {
+        let settings = get_settings(&self.app_handle);
         let models = self.available_models.lock().unwrap();
-        models.get(model_id).cloned()
+        models
+            .get(model_id)
+            .cloned()
+            .map(|model| with_measured_scores(model, &settings))
+    }
+
+    pub fn get_model_details(&self, model_id: &str) -> Option<ModelDetails> {
+        let model = self.get_model_info(model_id)?;
+        let downloaded = model.is_downloaded && !is_api_model(model_id);
+        let local_path = downloaded
+            .then(|| self.get_model_path(model_id).ok())
+            .flatten()
+            .map(|path| path.to_string_lossy().to_string());
+        let installed_version = downloaded
+            .then(|| {
+                get_settings(&self.app_handle)
+                    .model_versions
+                    .remove(model_id)
+            })
+            .flatten();
+        let downloaded_at = (downloaded && !model.is_custom)
+            .then(|| self.downloaded_at(&model))
+            .flatten();
+
+        Some(ModelDetails {
+            model,
+            local_path,
+            installed_version,
+            downloaded_at,
+        })
+    }
+
+    /// Creates the models directory if needed and verifies it can be written to.
+    fn prepare_models_dir(models_dir: &PathBuf) -> Result<()> {
+        if !models_dir.exists() {
+            fs::create_dir_all(models_dir)?;
+        }
+
+        let probe = models_dir.join(".write-test");
+        fs::write(&probe, b"")?;
+        fs::remove_file(&probe)?;
+        Ok(())
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only_reason.is_some()
+    }
+
+    pub fn get_models_dir_status(&self) -> ModelsDirStatus {
+        ModelsDirStatus {
+            path: self.models_dir.to_string_lossy().to_string(),
+            read_only: self.is_read_only(),
+            reason: self.read_only_reason.clone(),
+        }
+    }
+
+    fn ensure_writable(&self) -> Result<()> {
+        match &self.read_only_reason {
+            Some(reason) => Err(anyhow::anyhow!(
+                "Models directory is not writable, downloads are disabled: {}",
+                reason
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn bundled_model_path(&self, filename: &str) -> Option<PathBuf> {
+        if !BUNDLED_MODELS.contains(&filename) {
+            return None;
+        }
+
+        self.app_handle
+            .path()
+            .resolve(
+                format!("resources/models/{}", filename),
+                tauri::path::BaseDirectory::Resource,
+            )
+            .ok()
+            .filter(|path| path.exists())
     }
 
     fn migrate_bundled_models(&self) -> Result<()> {
         // Check for bundled models and copy them to user directory
-        let bundled_models = ["ggml-small.bin"]; // Add other bundled models here if any
-
-        for filename in &bundled_models {
+        for filename in &BUNDLED_MODELS {
             let bundled_path = self.app_handle.path().resolve(
                 &format!("resources/models/{}", filename),
                 tauri::path::BaseDirectory::Resource,
@@ -321,6 +1320,13 @@ impl ModelManager {
     }
 
     fn update_download_status(&self) -> Result<()> {
+        let active_downloads: Vec<String> = self
+            .download_tokens
+            .lock()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
         let mut models = self.available_models.lock().unwrap();
 
         for model in models.values_mut() {
@@ -331,6 +1337,14 @@ impl ModelManager {
                 continue;
             }
 
+            // Custom models are used in place, they may live on removable storage
+            if model.is_custom {
+                model.is_downloaded = Path::new(&model.filename).exists();
+                model.is_downloading = false;
+                model.partial_size = 0;
+                continue;
+            }
+
             if model.is_directory {
                 // For directory-based models, check if the directory exists
                 let model_path = self.models_dir.join(&model.filename);
@@ -339,14 +1353,18 @@ impl ModelManager {
                     .models_dir
                     .join(format!("{}.extracting", &model.filename));
 
+                // Archives are unpacked while they download, so only a
+                // directory without a running download is left over
+                let streaming = active_downloads.contains(&model.id);
+
                 // Clean up any leftover .extracting directories from interrupted extractions
-                if extracting_path.exists() {
+                if extracting_path.exists() && !streaming {
                     println!("Cleaning up interrupted extraction for model: {}", model.id);
                     let _ = fs::remove_dir_all(&extracting_path);
                 }
 
                 model.is_downloaded = model_path.exists() && model_path.is_dir();
-                model.is_downloading = partial_path.exists();
+                model.is_downloading = partial_path.exists() || streaming;
 
                 // Get partial file size if it exists (for the .tar.gz being downloaded)
                 if partial_path.exists() {
@@ -359,8 +1377,15 @@ impl ModelManager {
                 let model_path = self.models_dir.join(&model.filename);
                 let partial_path = self.models_dir.join(format!("{}.partial", &model.filename));
 
-                model.is_downloaded = model_path.exists();
+                // Bundled models can be used in place when they couldn't be migrated
+                model.is_downloaded =
+                    model_path.exists() || self.bundled_model_path(&model.filename).is_some();
                 model.is_downloading = partial_path.exists();
+                model.coreml_ready = model.coreml_url.is_some()
+                    && self
+                        .models_dir
+                        .join(coreml_encoder_name(&model.filename))
+                        .is_dir();
 
                 // Get partial file size if it exists
                 if partial_path.exists() {
@@ -369,11 +1394,59 @@ impl ModelManager {
                     model.partial_size = 0;
                 }
             }
-        }
+
+            for variant in model.variants.iter_mut() {
+                variant.is_downloaded = self.models_dir.join(&variant.filename).exists();
+            }
+            if !model.is_downloaded {
+                model.corrupted = None;
+            }
+        }
 
         Ok(())
     }
 
+    /// Switches a model to another quantization. Its file fields then
+    /// describe the variant, so downloading, loading and deleting act on it.
+    /// Other downloaded variants stay on disk.
+    pub fn select_variant(&self, model_id: &str, variant_id: &str) -> Result<ModelInfo> {
+        {
+            let models = self.available_models.lock().unwrap();
+            let model = models
+                .get(model_id)
+                .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+            if model.is_downloading {
+                return Err(anyhow::anyhow!(
+                    "Wait for the download of {} to finish",
+                    model_id
+                ));
+            }
+            if !model.variants.iter().any(|v| v.id == variant_id) {
+                return Err(anyhow::anyhow!(
+                    "{} has no variant {}",
+                    model_id,
+                    variant_id
+                ));
+            }
+        }
+
+        let mut settings = get_settings(&self.app_handle);
+        settings
+            .model_variants
+            .insert(model_id.to_string(), variant_id.to_string());
+        {
+            let mut models = self.available_models.lock().unwrap();
+            apply_selected_variants(&mut models, &settings.model_variants);
+        }
+        write_settings(&self.app_handle, settings);
+        self.update_download_status()?;
+
+        info!("Selected variant {} of {}", variant_id, model_id);
+        events::emit(&self.app_handle, AppEvent::ModelCatalogUpdated(()));
+        self.get_model_info(model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))
+    }
+
     fn auto_select_model_if_needed(&self) -> Result<()> {
         // Check if we have a selected model in settings
         let settings = get_settings(&self.app_handle);
@@ -400,7 +1473,57 @@ impl ModelManager {
         Ok(())
     }
 
+    /// The model to offer on first launch, when nothing is downloaded and
+    /// so no model could be selected. `None` once a model is in place.
+    pub fn recommend_model(&self) -> Option<ModelRecommendation> {
+        // Nothing is selected until the scan has auto-selected a model
+        self.wait_until_ready();
+        let settings = get_settings(&self.app_handle);
+        if !settings.selected_model.is_empty() || self.is_read_only() {
+            return None;
+        }
+
+        let hardware = detect_hardware(settings.gpu_acceleration);
+        let (preferred, reason) = recommended_model_id(&hardware);
+        let models = self.available_models.lock().unwrap();
+        let downloadable = |id: &str| {
+            models
+                .get(id)
+                .filter(|model| model.url.is_some() && model.engine_type.is_supported())
+                .cloned()
+        };
+        let (model, reason) = match downloadable(preferred) {
+            Some(model) => (model, reason),
+            None => (
+                downloadable(FALLBACK_MODEL)?,
+                "Runs on any machine".to_string(),
+            ),
+        };
+        Some(ModelRecommendation {
+            model_id: model.id,
+            name: model.name,
+            size_mb: model.size_mb,
+            reason,
+            hardware,
+        })
+    }
+
     pub async fn download_model(&self, model_id: &str) -> Result<()> {
+        let token = CancellationToken::new();
+        self.download_tokens
+            .lock()
+            .unwrap()
+            .insert(model_id.to_string(), token.clone());
+        let result = self.download_model_until_cancelled(model_id, &token).await;
+        self.download_tokens.lock().unwrap().remove(model_id);
+        result
+    }
+
+    async fn download_model_until_cancelled(
+        &self,
+        model_id: &str,
+        cancel: &CancellationToken,
+    ) -> Result<()> {
         if is_api_model(model_id) {
             println!(
                 "Skipping download for API-based model {} - no local files required",
@@ -409,6 +1532,8 @@ impl ModelManager {
             return Ok(());
         }
 
+        self.ensure_writable()?;
+
         let model_info = {
             let models = self.available_models.lock().unwrap();
             models.get(model_id).cloned()
@@ -424,27 +1549,56 @@ impl ModelManager {
         let partial_path = self
             .models_dir
             .join(format!("{}.partial", &model_info.filename));
+        let validator_path = validator_path(&partial_path);
 
         // Don't download if complete version already exists
         if model_path.exists() {
             // Clean up any partial file that might exist
             if partial_path.exists() {
                 let _ = fs::remove_file(&partial_path);
+                let _ = fs::remove_file(&validator_path);
             }
             self.update_download_status()?;
             return Ok(());
         }
 
-        // Check if we have a partial download to resume
-        let resume_from = if partial_path.exists() {
-            let size = partial_path.metadata()?.len();
-            println!("Resuming download of model {} from byte {}", model_id, size);
-            size
-        } else {
-            println!("Starting fresh download of model {} from {}", model_id, url);
-            0
+        // Check if we have a partial download to resume. Without validators
+        // there is no telling whether it still matches the remote file.
+        let if_range =
+            DownloadValidator::load(&validator_path).and_then(|v| v.if_range().map(str::to_string));
+        let mut resume_from = match (partial_path.exists(), &if_range) {
+            (true, Some(_)) => {
+                let size = partial_path.metadata()?.len();
+                println!("Resuming download of model {} from byte {}", model_id, size);
+                size
+            }
+            (true, None) => {
+                info!(
+                    "Partial download of {} can't be verified, starting over",
+                    model_id
+                );
+                let _ = fs::remove_file(&partial_path);
+                0
+            }
+            (false, _) => {
+                println!("Starting fresh download of model {} from {}", model_id, url);
+                0
+            }
         };
 
+        // A fresh archive download is unpacked while it streams in. Only an
+        // archive left over from an interrupted download is resumed and then
+        // extracted next to itself, which needs its size twice.
+        let stream_extract = model_info.is_directory && resume_from == 0;
+        let size_bytes = model_info.size_mb * 1024 * 1024;
+        let needed = size_bytes.saturating_sub(resume_from)
+            + if model_info.is_directory && !stream_extract {
+                size_bytes
+            } else {
+                0
+            };
+        self.ensure_disk_space(model_id, needed)?;
+
         // Mark as downloading
         {
             let mut models = self.available_models.lock().unwrap();
@@ -453,20 +1607,81 @@ impl ModelManager {
             }
         }
 
-        // Create HTTP client with range request for resuming
+        // Try the primary URL, then each mirror, with a range request for
+        // resuming
         let client = reqwest::Client::new();
-        let mut request = client.get(&url);
+        let user_mirrors = get_settings(&self.app_handle)
+            .model_mirrors
+            .remove(model_id)
+            .unwrap_or_default();
+        let mut last_error = None;
+        let mut response = None;
+        for (index, source) in std::iter::once(&url)
+            .chain(model_info.mirrors.iter())
+            .chain(user_mirrors.iter())
+            .enumerate()
+        {
+            let mut request = match self.download_request(&client, source) {
+                Ok(request) => request,
+                Err(e) => {
+                    last_error = Some(e);
+                    continue;
+                }
+            };
+            if resume_from > 0 {
+                request = request.header("Range", format!("bytes={}-", resume_from));
+                // The server sends the whole file instead of the range when
+                // the validator no longer matches
+                if let Some(if_range) = &if_range {
+                    request = request.header("If-Range", if_range.as_str());
+                }
+            }
 
-        if resume_from > 0 {
-            request = request.header("Range", format!("bytes={}-", resume_from));
-        }
+            let result = tokio::select! {
+                result = request.send() => result,
+                _ = cancel.cancelled() => {
+                    events::emit(&self.app_handle, AppEvent::ModelDownloadCancelled(model_id.to_string()));
+                    return Err(anyhow::anyhow!("Download of {} was cancelled", model_id));
+                }
+            };
 
-        let response = request.send().await?;
+            // Check for success or partial content status
+            match result {
+                Ok(r)
+                    if r.status().is_success()
+                        || r.status() == reqwest::StatusCode::PARTIAL_CONTENT =>
+                {
+                    events::emit(
+                        &self.app_handle,
+                        AppEvent::ModelDownloadSource(ModelDownloadSource {
+                            model_id: model_id.to_string(),
+                            url: source.to_string(),
+                            is_mirror: index > 0,
+                        }),
+                    );
+                    response = Some(r);
+                    break;
+                }
+                Ok(r) => {
+                    warn!(
+                        "Download of {} from {} failed: HTTP {}",
+                        model_id,
+                        source,
+                        r.status()
+                    );
+                    last_error = Some(anyhow::anyhow!(
+                        "Failed to download model: HTTP {}",
+                        r.status()
+                    ));
+                }
+                Err(e) => {
+                    warn!("Download of {} from {} failed: {}", model_id, source, e);
+                    last_error = Some(e.into());
+                }
+            }
+        }
 
-        // Check for success or partial content status
-        if !response.status().is_success()
-            && response.status() != reqwest::StatusCode::PARTIAL_CONTENT
-        {
+        let Some(response) = response else {
             // Mark as not downloading on error
             {
                 let mut models = self.available_models.lock().unwrap();
@@ -474,10 +1689,17 @@ impl ModelManager {
                     model.is_downloading = false;
                 }
             }
-            return Err(anyhow::anyhow!(
-                "Failed to download model: HTTP {}",
-                response.status()
-            ));
+            return Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No download URL for model")));
+        };
+
+        if resume_from > 0 && response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+            info!("{} changed upstream, restarting its download", model_id);
+            resume_from = 0;
+        }
+        if resume_from == 0 && !stream_extract {
+            if let Err(e) = DownloadValidator::from_response(&response).save(&validator_path) {
+                warn!("Failed to save download validators for {}: {}", model_id, e);
+            }
         }
 
         let total_size = if resume_from > 0 {
@@ -487,6 +1709,12 @@ impl ModelManager {
             response.content_length().unwrap_or(0)
         };
 
+        if stream_extract {
+            self.download_and_extract(model_id, &model_info, response, total_size, cancel)
+                .await?;
+            return self.finish_download(model_id).await;
+        }
+
         let mut downloaded = resume_from;
         let mut stream = response.bytes_stream();
 
@@ -510,13 +1738,35 @@ impl ModelManager {
             } else {
                 0.0
             },
+            extracting: false,
         };
-        let _ = self
-            .app_handle
-            .emit("model-download-progress", &initial_progress);
+        events::emit(
+            &self.app_handle,
+            AppEvent::ModelDownloadProgress(initial_progress),
+        );
 
         // Download with progress
-        while let Some(chunk) = stream.next().await {
+        loop {
+            let chunk = tokio::select! {
+                chunk = stream.next() => chunk,
+                _ = cancel.cancelled() => {
+                    // Keep what we have so the download can be resumed
+                    file.flush()?;
+                    drop(file);
+                    {
+                        let mut models = self.available_models.lock().unwrap();
+                        if let Some(model) = models.get_mut(model_id) {
+                            model.is_downloading = false;
+                            model.partial_size = downloaded;
+                        }
+                    }
+                    events::emit(&self.app_handle, AppEvent::ModelDownloadCancelled(model_id.to_string()));
+                    return Err(anyhow::anyhow!("Download of {} was cancelled", model_id));
+                }
+            };
+            let Some(chunk) = chunk else {
+                break;
+            };
             let chunk = chunk.map_err(|e| {
                 // Mark as not downloading on error
                 {
@@ -543,18 +1793,77 @@ impl ModelManager {
                 downloaded,
                 total: total_size,
                 percentage,
+                extracting: false,
             };
 
-            let _ = self.app_handle.emit("model-download-progress", &progress);
+            events::emit(&self.app_handle, AppEvent::ModelDownloadProgress(progress));
         }
 
         file.flush()?;
         drop(file); // Ensure file is closed before moving
+        let _ = fs::remove_file(&validator_path);
+
+        // A corrupt download must never be renamed into place, it would only
+        // fail later inside the engine with a confusing error. A model file
+        // without a published checksum is hashed anyway, so later checks can
+        // tell whether it changed on disk.
+        let mut recorded_hash = None;
+        if model_info.sha256.is_some() || !model_info.is_directory {
+            if model_info.sha256.is_some() {
+                events::emit(
+                    &self.app_handle,
+                    AppEvent::ModelVerificationStarted(model_id.to_string()),
+                );
+            }
+            let hash_path = partial_path.clone();
+            let actual = tauri::async_runtime::spawn_blocking(move || sha256_file(&hash_path))
+                .await
+                .map_err(|e| anyhow::anyhow!("Checksum task failed: {}", e))??;
+
+            match model_info.sha256.as_deref() {
+                None => recorded_hash = Some(actual),
+                Some(expected) if !actual.eq_ignore_ascii_case(expected) => {
+                    let _ = fs::remove_file(&partial_path);
+                    {
+                        let mut models = self.available_models.lock().unwrap();
+                        if let Some(model) = models.get_mut(model_id) {
+                            model.is_downloading = false;
+                            model.partial_size = 0;
+                        }
+                    }
+
+                    let error_msg = format!(
+                        "Checksum mismatch for {}: expected {}, got {}",
+                        model_id, expected, actual
+                    );
+                    events::emit(
+                        &self.app_handle,
+                        AppEvent::ModelVerificationFailed(ModelError {
+                            model_id: model_id.to_string(),
+                            error: error_msg.clone(),
+                        }),
+                    );
+                    return Err(anyhow::anyhow!(error_msg));
+                }
+                Some(_) => {}
+            }
+        }
 
         // Handle directory-based models (extract tar.gz) vs file-based models
         if model_info.is_directory {
+            if let Err(e) = self.ensure_disk_space(model_id, model_info.size_mb * 1024 * 1024) {
+                let mut models = self.available_models.lock().unwrap();
+                if let Some(model) = models.get_mut(model_id) {
+                    model.is_downloading = false;
+                }
+                return Err(e);
+            }
+
             // Emit extraction started event
-            let _ = self.app_handle.emit("model-extraction-started", model_id);
+            events::emit(
+                &self.app_handle,
+                AppEvent::ModelExtractionStarted(model_id.to_string()),
+            );
             println!("Extracting archive for directory-based model: {}", model_id);
 
             // Use a temporary extraction directory to ensure atomic operations
@@ -581,62 +1890,345 @@ impl ModelManager {
                 let error_msg = format!("Failed to extract archive: {}", e);
                 // Clean up failed extraction
                 let _ = fs::remove_dir_all(&temp_extract_dir);
-                let _ = self.app_handle.emit(
-                    "model-extraction-failed",
-                    &serde_json::json!({
-                        "model_id": model_id,
-                        "error": error_msg
+                events::emit(
+                    &self.app_handle,
+                    AppEvent::ModelExtractionFailed(ModelError {
+                        model_id: model_id.to_string(),
+                        error: error_msg.clone(),
                     }),
                 );
                 anyhow::anyhow!(error_msg)
             })?;
 
-            // Find the actual extracted directory (archive might have a nested structure)
-            let extracted_dirs: Vec<_> = fs::read_dir(&temp_extract_dir)?
-                .filter_map(|entry| entry.ok())
-                .filter(|entry| entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false))
-                .collect();
-
-            if extracted_dirs.len() == 1 {
-                // Single directory extracted, move it to the final location
-                let source_dir = extracted_dirs[0].path();
-                if final_model_dir.exists() {
-                    fs::remove_dir_all(&final_model_dir)?;
-                }
-                fs::rename(&source_dir, &final_model_dir)?;
-                // Clean up temp directory
-                let _ = fs::remove_dir_all(&temp_extract_dir);
-            } else {
-                // Multiple items or no directories, rename the temp directory itself
-                if final_model_dir.exists() {
-                    fs::remove_dir_all(&final_model_dir)?;
-                }
-                fs::rename(&temp_extract_dir, &final_model_dir)?;
-            }
+            install_extracted(&temp_extract_dir, &final_model_dir)?;
 
             println!("Successfully extracted archive for model: {}", model_id);
             // Emit extraction completed event
-            let _ = self.app_handle.emit("model-extraction-completed", model_id);
+            events::emit(
+                &self.app_handle,
+                AppEvent::ModelExtractionCompleted(model_id.to_string()),
+            );
 
             // Remove the downloaded tar.gz file
             let _ = fs::remove_file(&partial_path);
         } else {
             // Move partial file to final location for file-based models
             fs::rename(&partial_path, &model_path)?;
+
+            let mut settings = get_settings(&self.app_handle);
+            let changed = match recorded_hash {
+                Some(hash) => {
+                    settings
+                        .model_hashes
+                        .insert(model_id.to_string(), hash.clone())
+                        != Some(hash)
+                }
+                None => settings.model_hashes.remove(model_id).is_some(),
+            };
+            if changed {
+                write_settings(&self.app_handle, settings);
+            }
         }
 
-        // Mark as downloaded
-        {
+        self.finish_download(model_id).await
+    }
+
+    /// Marks a model as downloaded once its files are in place.
+    async fn finish_download(&self, model_id: &str) -> Result<()> {
+        let (has_coreml_encoder, version) = {
             let mut models = self.available_models.lock().unwrap();
-            if let Some(model) = models.get_mut(model_id) {
+            let model = models.get_mut(model_id);
+            let has_coreml_encoder = model.as_ref().is_some_and(|m| m.coreml_url.is_some());
+            let version = model.as_ref().and_then(|m| m.version.clone());
+            if let Some(model) = model {
                 model.is_downloaded = true;
                 model.is_downloading = false;
                 model.partial_size = 0;
+                model.update_available = false;
+                model.corrupted = None;
+            }
+            (has_coreml_encoder, version)
+        };
+
+        let mut settings = get_settings(&self.app_handle);
+        let changed = match version {
+            Some(version) => {
+                settings
+                    .model_versions
+                    .insert(model_id.to_string(), version.clone())
+                    .as_ref()
+                    != Some(&version)
             }
+            None => settings.model_versions.remove(model_id).is_some(),
+        };
+        if changed {
+            write_settings(&self.app_handle, settings);
         }
 
         // Emit download complete event
-        let _ = self.app_handle.emit("model-download-complete", model_id);
+        events::emit(
+            &self.app_handle,
+            AppEvent::ModelDownloadComplete(model_id.to_string()),
+        );
+
+        // The model works without its Core ML encoder, so a failure here is not fatal
+        if coreml_supported() && has_coreml_encoder {
+            if let Err(e) = self.download_coreml_encoder(model_id).await {
+                eprintln!("Failed to download Core ML encoder for {}: {}", model_id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Unpacks a tar.gz model archive while it downloads, so the archive
+    /// never has to be stored next to the extracted model. The checksum is
+    /// computed over the streamed bytes and the model is only moved into
+    /// place when it matches.
+    ///
+    /// Nothing is kept when the download is interrupted, it starts over the
+    /// next time.
+    async fn download_and_extract(
+        &self,
+        model_id: &str,
+        model_info: &ModelInfo,
+        response: reqwest::Response,
+        total_size: u64,
+        cancel: &CancellationToken,
+    ) -> Result<()> {
+        let temp_extract_dir = self
+            .models_dir
+            .join(format!("{}.extracting", &model_info.filename));
+        let final_model_dir = self.models_dir.join(&model_info.filename);
+        if temp_extract_dir.exists() {
+            let _ = fs::remove_dir_all(&temp_extract_dir);
+        }
+        fs::create_dir_all(&temp_extract_dir)?;
+
+        events::emit(
+            &self.app_handle,
+            AppEvent::ModelExtractionStarted(model_id.to_string()),
+        );
+        println!(
+            "Streaming extraction for directory-based model: {}",
+            model_id
+        );
+
+        let (sender, receiver) = tokio::sync::mpsc::channel::<Vec<u8>>(16);
+        let unpack_dir = temp_extract_dir.clone();
+        let extractor = tauri::async_runtime::spawn_blocking(move || {
+            Archive::new(GzDecoder::new(ChunkReader::new(receiver))).unpack(&unpack_dir)
+        });
+
+        let mut hasher = Sha256::new();
+        let mut downloaded = 0u64;
+        let mut stream = response.bytes_stream();
+        let mut extractor_running = true;
+        let streamed: Result<()> = loop {
+            let next = tokio::select! {
+                chunk = stream.next() => Some(chunk),
+                _ = cancel.cancelled() => None,
+            };
+            let Some(chunk) = next else {
+                events::emit(
+                    &self.app_handle,
+                    AppEvent::ModelDownloadCancelled(model_id.to_string()),
+                );
+                break Err(anyhow::anyhow!("Download of {} was cancelled", model_id));
+            };
+            let chunk = match chunk {
+                Some(Ok(chunk)) => chunk,
+                Some(Err(e)) => break Err(e.into()),
+                None => break Ok(()),
+            };
+
+            hasher.update(&chunk);
+            downloaded += chunk.len() as u64;
+            // The archive can end before the stream does, e.g. on trailing
+            // padding, the rest is still needed for the checksum
+            if extractor_running && sender.send(chunk.to_vec()).await.is_err() {
+                extractor_running = false;
+            }
+
+            let progress = DownloadProgress {
+                model_id: model_id.to_string(),
+                downloaded,
+                total: total_size,
+                percentage: if total_size > 0 {
+                    (downloaded as f64 / total_size as f64) * 100.0
+                } else {
+                    0.0
+                },
+                extracting: true,
+            };
+            events::emit(&self.app_handle, AppEvent::ModelDownloadProgress(progress));
+        };
+        drop(sender);
+
+        let unpacked = extractor
+            .await
+            .map_err(|e| anyhow::anyhow!("Extraction task failed: {}", e))
+            .and_then(|result| {
+                result.map_err(|e| anyhow::anyhow!("Failed to extract archive: {}", e))
+            });
+
+        let result = streamed.and(unpacked).and_then(|()| {
+            let actual: String = hasher
+                .finalize()
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect();
+            match model_info.sha256.as_deref() {
+                Some(expected) if !actual.eq_ignore_ascii_case(expected) => {
+                    let error_msg = format!(
+                        "Checksum mismatch for {}: expected {}, got {}",
+                        model_id, expected, actual
+                    );
+                    events::emit(
+                        &self.app_handle,
+                        AppEvent::ModelVerificationFailed(ModelError {
+                            model_id: model_id.to_string(),
+                            error: error_msg.clone(),
+                        }),
+                    );
+                    Err(anyhow::anyhow!(error_msg))
+                }
+                _ => install_extracted(&temp_extract_dir, &final_model_dir),
+            }
+        });
+
+        if let Err(e) = &result {
+            let _ = fs::remove_dir_all(&temp_extract_dir);
+            {
+                let mut models = self.available_models.lock().unwrap();
+                if let Some(model) = models.get_mut(model_id) {
+                    model.is_downloading = false;
+                    model.partial_size = 0;
+                }
+            }
+            if !cancel.is_cancelled() {
+                events::emit(
+                    &self.app_handle,
+                    AppEvent::ModelExtractionFailed(ModelError {
+                        model_id: model_id.to_string(),
+                        error: e.to_string(),
+                    }),
+                );
+            }
+            return result;
+        }
+
+        println!("Successfully extracted archive for model: {}", model_id);
+        events::emit(
+            &self.app_handle,
+            AppEvent::ModelExtractionCompleted(model_id.to_string()),
+        );
+        Ok(())
+    }
+
+    /// Builds the GET request for a model URL, resolving `hf://` URLs and
+    /// authenticating against Hugging Face when a token is configured.
+    /// reqwest drops the token when following the redirect to the CDN.
+    fn download_request(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+    ) -> Result<reqwest::RequestBuilder> {
+        let Some(hf_path) = url.strip_prefix(HF_SCHEME) else {
+            return Ok(client.get(url));
+        };
+
+        let request = client.get(resolve_hf_url(hf_path)?);
+        Ok(
+            match get_settings(&self.app_handle)
+                .huggingface_token
+                .filter(|t| !t.is_empty())
+            {
+                Some(token) => request.bearer_auth(token),
+                None => request,
+            },
+        )
+    }
+
+    /// Downloads and unpacks the Core ML encoder for a Whisper model so
+    /// whisper.cpp can run the encoder on the Apple Neural Engine.
+    pub async fn download_coreml_encoder(&self, model_id: &str) -> Result<()> {
+        self.ensure_writable()?;
+
+        let model_info = self
+            .get_model_info(model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+        let url = model_info
+            .coreml_url
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("No Core ML encoder available for {}", model_id))?;
+
+        let encoder_name = coreml_encoder_name(&model_info.filename);
+        let final_dir = self.models_dir.join(&encoder_name);
+        if final_dir.is_dir() {
+            return Ok(());
+        }
+
+        println!("Downloading Core ML encoder for {} from {}", model_id, url);
+        let response = self
+            .download_request(&reqwest::Client::new(), &url)?
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "Failed to download Core ML encoder: HTTP {}",
+                response.status()
+            ));
+        }
+
+        let archive_path = self
+            .models_dir
+            .join(format!("{}.tar.gz.partial", &encoder_name));
+        let mut file = File::create(&archive_path)?;
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            file.write_all(&chunk?)?;
+        }
+        file.flush()?;
+        drop(file);
+
+        let temp_extract_dir = self
+            .models_dir
+            .join(format!("{}.extracting", &encoder_name));
+        if temp_extract_dir.exists() {
+            let _ = fs::remove_dir_all(&temp_extract_dir);
+        }
+        fs::create_dir_all(&temp_extract_dir)?;
+
+        let unpacked =
+            Archive::new(GzDecoder::new(File::open(&archive_path)?)).unpack(&temp_extract_dir);
+        let _ = fs::remove_file(&archive_path);
+        if let Err(e) = unpacked {
+            let _ = fs::remove_dir_all(&temp_extract_dir);
+            return Err(anyhow::anyhow!("Failed to extract Core ML encoder: {}", e));
+        }
+
+        // The archive contains the .mlmodelc directory itself
+        let extracted = fs::read_dir(&temp_extract_dir)?
+            .filter_map(|entry| entry.ok())
+            .find(|entry| entry.path().is_dir())
+            .map(|entry| entry.path())
+            .unwrap_or_else(|| temp_extract_dir.clone());
+        fs::rename(&extracted, &final_dir)?;
+        let _ = fs::remove_dir_all(&temp_extract_dir);
+
+        {
+            let mut models = self.available_models.lock().unwrap();
+            if let Some(model) = models.get_mut(model_id) {
+                model.coreml_ready = true;
+            }
+        }
+
+        println!("Core ML encoder ready for {}", model_id);
+        events::emit(
+            &self.app_handle,
+            AppEvent::CoremlEncoderReady(model_id.to_string()),
+        );
 
         Ok(())
     }
@@ -652,6 +2244,13 @@ impl ModelManager {
 
         println!("ModelManager: delete_model called for: {}", model_id);
 
+        // Custom models belong to the user, only unregister them
+        if self.get_model_info(model_id).is_some_and(|m| m.is_custom) {
+            return self.remove_custom_model(model_id);
+        }
+
+        self.ensure_writable()?;
+
         let model_info = {
             let models = self.available_models.lock().unwrap();
             models.get(model_id).cloned()
@@ -692,10 +2291,32 @@ impl ModelManager {
             }
         }
 
+        // The Core ML encoder is useless without its model
+        let encoder_dir = self
+            .models_dir
+            .join(coreml_encoder_name(&model_info.filename));
+        if model_info.coreml_url.is_some() && encoder_dir.is_dir() {
+            println!(
+                "ModelManager: Deleting Core ML encoder at: {:?}",
+                encoder_dir
+            );
+            fs::remove_dir_all(&encoder_dir)?;
+        }
+
+        // A later download starts out as freshly used
+        let mut settings = get_settings(&self.app_handle);
+        let had_last_used = settings.model_last_used.remove(model_id).is_some();
+        let had_version = settings.model_versions.remove(model_id).is_some();
+        let had_hash = settings.model_hashes.remove(model_id).is_some();
+        if had_last_used || had_version || had_hash {
+            write_settings(&self.app_handle, settings);
+        }
+
         // Delete partial file if it exists (same for both types)
         if partial_path.exists() {
             println!("ModelManager: Deleting partial file at: {:?}", partial_path);
             fs::remove_file(&partial_path)?;
+            let _ = fs::remove_file(validator_path(&partial_path));
             println!("ModelManager: Partial file deleted successfully");
             deleted_something = true;
         }
@@ -754,6 +2375,8 @@ impl ModelManager {
             // For file-based models (existing logic)
             if model_path.exists() && !partial_path.exists() {
                 Ok(model_path)
+            } else if let Some(bundled_path) = self.bundled_model_path(&model_info.filename) {
+                Ok(bundled_path)
             } else {
                 Err(anyhow::anyhow!(
                     "Complete model file not found: {}",
@@ -763,6 +2386,725 @@ impl ModelManager {
         }
     }
 
+    /// Checks whether a downloaded model looks intact: file headers, size and,
+    /// when the catalog provides one, the SHA-256 checksum.
+    pub fn check_model_integrity(&self, model_id: &str) -> Result<IntegrityStatus> {
+        let model_info = self
+            .get_model_info(model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+        let mut model_path = self.models_dir.join(&model_info.filename);
+        if !model_path.exists() {
+            if let Some(bundled_path) = self.bundled_model_path(&model_info.filename) {
+                model_path = bundled_path;
+            }
+        }
+
+        if !model_path.exists() {
+            return Ok(IntegrityStatus::Corrupt(
+                "Model files are missing".to_string(),
+            ));
+        }
+        self.check_model_files(&model_info, &model_path)
+    }
+
+    /// Checks the files of `model_info` at `model_path`, which need not be
+    /// where the model is installed.
+    fn check_model_files(
+        &self,
+        model_info: &ModelInfo,
+        model_path: &Path,
+    ) -> Result<IntegrityStatus> {
+        if matches!(model_info.engine_type, EngineType::Vosk)
+            && !crate::vosk::is_vosk_model_dir(model_path)
+        {
+            return Ok(IntegrityStatus::Corrupt(
+                "Folder is not a Vosk model".to_string(),
+            ));
+        }
+
+        if model_info.is_directory {
+            // Directory models have no single checksum; look for empty files left by failed writes
+            for entry in fs::read_dir(model_path)?.filter_map(|e| e.ok()) {
+                let metadata = entry.metadata()?;
+                if metadata.is_file() && metadata.len() == 0 {
+                    return Ok(IntegrityStatus::Corrupt(format!(
+                        "File {} is empty",
+                        entry.file_name().to_string_lossy()
+                    )));
+                }
+            }
+            return Ok(IntegrityStatus::Ok);
+        }
+
+        let size = model_path.metadata()?.len();
+        // size_mb is approximate, so only flag files that are clearly truncated
+        if model_info.size_mb > 0 && size < model_info.size_mb * 1024 * 1024 / 2 {
+            return Ok(IntegrityStatus::Corrupt(format!(
+                "File is truncated ({} MB, expected about {} MB)",
+                size / (1024 * 1024),
+                model_info.size_mb
+            )));
+        }
+
+        if matches!(model_info.engine_type, EngineType::Whisper) {
+            let mut magic = [0u8; 4];
+            File::open(model_path)?.read_exact(&mut magic)?;
+            // whisper.cpp writes the ggml magic as a little-endian u32; GGUF is also accepted
+            if &magic != b"lmgg" && &magic != b"GGUF" {
+                return Ok(IntegrityStatus::Corrupt(
+                    "File is not a valid GGML model".to_string(),
+                ));
+            }
+        }
+
+        // Models the catalog has no checksum for are checked against the
+        // hash taken when they were downloaded
+        let expected = model_info.sha256.clone().or_else(|| {
+            get_settings(&self.app_handle)
+                .model_hashes
+                .get(&model_info.id)
+                .cloned()
+        });
+        if let Some(expected) = &expected {
+            let actual = sha256_file(model_path)?;
+            if !actual.eq_ignore_ascii_case(expected) {
+                return Ok(IntegrityStatus::Corrupt(format!(
+                    "Checksum mismatch (expected {}, got {})",
+                    expected, actual
+                )));
+            }
+        }
+
+        Ok(IntegrityStatus::Ok)
+    }
+
+    /// Runs the integrity check on a downloaded model and records the result,
+    /// emitting `model-corrupted` when the files turn out to be damaged.
+    pub fn verify_model(&self, model_id: &str) -> Result<IntegrityStatus> {
+        let status = self.check_model_integrity(model_id)?;
+        let corrupted = match &status {
+            IntegrityStatus::Ok => None,
+            IntegrityStatus::Corrupt(reason) => Some(reason.clone()),
+        };
+        if let Some(model) = self.available_models.lock().unwrap().get_mut(model_id) {
+            model.corrupted = corrupted.clone();
+        }
+        if let Some(reason) = corrupted {
+            warn!("Model {} is corrupt: {}", model_id, reason);
+            events::emit(
+                &self.app_handle,
+                AppEvent::ModelCorrupted(ModelCorrupted {
+                    model_id: model_id.to_string(),
+                    reason: reason,
+                }),
+            );
+        }
+        Ok(status)
+    }
+
+    /// Verifies every downloaded model, returning the ids of corrupted ones.
+    /// Custom models are left out, they may be on storage that isn't mounted.
+    pub fn verify_downloaded_models(&self) -> Vec<String> {
+        let model_ids: Vec<String> = self
+            .available_models
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|m| m.is_downloaded && !m.is_downloading && !m.is_custom)
+            .filter(|m| !is_api_model(&m.id))
+            .map(|m| m.id.clone())
+            .collect();
+        model_ids
+            .into_iter()
+            .filter(|model_id| match self.verify_model(model_id) {
+                Ok(IntegrityStatus::Corrupt(_)) => true,
+                Ok(IntegrityStatus::Ok) => false,
+                Err(e) => {
+                    warn!("Failed to verify model {}: {}", model_id, e);
+                    false
+                }
+            })
+            .collect()
+    }
+
+    /// Deletes a corrupted model and downloads it again.
+    pub async fn repair_model(&self, model_id: &str) -> Result<()> {
+        println!("ModelManager: repairing model {}", model_id);
+        self.ensure_writable()?;
+        if let Err(e) = self.delete_model(model_id) {
+            // Nothing to delete is fine, the download below recreates the files
+            println!("ModelManager: nothing removed before repair: {}", e);
+        }
+        self.download_model(model_id).await?;
+        events::emit(
+            &self.app_handle,
+            AppEvent::ModelRepaired(model_id.to_string()),
+        );
+        Ok(())
+    }
+
+    /// Fails with a `model-download-failed` event when the models directory
+    /// doesn't have `needed` bytes plus a margin free. If free space can't be
+    /// determined the download goes ahead.
+    fn ensure_disk_space(&self, model_id: &str, needed: u64) -> Result<()> {
+        let available = match fs2::available_space(&self.models_dir) {
+            Ok(available) => available,
+            Err(e) => {
+                warn!("Could not determine free disk space: {}", e);
+                return Ok(());
+            }
+        };
+
+        let needed_mb = needed.div_ceil(1024 * 1024) + DISK_SPACE_MARGIN_MB;
+        let available_mb = available / (1024 * 1024);
+        if available_mb >= needed_mb {
+            return Ok(());
+        }
+
+        let error_msg = format!(
+            "Insufficient disk space: {} needs {} MB, {} MB free",
+            model_id, needed_mb, available_mb
+        );
+        events::emit(
+            &self.app_handle,
+            AppEvent::ModelDownloadFailed(ModelError {
+                model_id: model_id.to_string(),
+                error: error_msg.clone(),
+            }),
+        );
+        Err(anyhow::anyhow!(error_msg))
+    }
+
+    /// Compares the version each downloaded model was installed at with the
+    /// catalog. Models downloaded before versions were tracked are assumed
+    /// to be current.
+    pub fn check_for_updates(&self) -> Vec<ModelUpdate> {
+        let mut settings = get_settings(&self.app_handle);
+        let mut adopted = false;
+        let mut updates = Vec::new();
+        {
+            let mut models = self.available_models.lock().unwrap();
+            for model in models.values_mut() {
+                model.update_available = false;
+                let Some(available) = model.version.clone() else {
+                    continue;
+                };
+                if !model.is_downloaded || model.is_custom {
+                    continue;
+                }
+
+                let installed = match settings.model_versions.get(&model.id) {
+                    Some(installed) => installed.clone(),
+                    None => {
+                        settings
+                            .model_versions
+                            .insert(model.id.clone(), available.clone());
+                        adopted = true;
+                        continue;
+                    }
+                };
+                if installed != available {
+                    model.update_available = true;
+                    updates.push(ModelUpdate {
+                        model_id: model.id.clone(),
+                        name: model.name.clone(),
+                        installed_version: installed,
+                        available_version: available,
+                    });
+                }
+            }
+        }
+        if adopted {
+            write_settings(&self.app_handle, settings);
+        }
+        updates
+    }
+
+    /// Replaces a downloaded model with its newer revision. The old copy is
+    /// moved aside and only removed once the new one downloaded and passed
+    /// verification, a failed or cancelled upgrade puts it back.
+    pub async fn upgrade_model(&self, model_id: &str) -> Result<()> {
+        self.ensure_writable()?;
+        let model_info = self
+            .get_model_info(model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+        if !model_info.update_available {
+            return Err(anyhow::anyhow!("{} is already up to date", model_id));
+        }
+
+        let model_path = self.models_dir.join(&model_info.filename);
+        let previous_path = self.previous_path(&model_info.filename);
+        remove_path(&previous_path)?;
+        fs::rename(&model_path, &previous_path)?;
+        {
+            let mut models = self.available_models.lock().unwrap();
+            if let Some(model) = models.get_mut(model_id) {
+                model.is_downloaded = false;
+            }
+        }
+        info!(
+            "Upgrading {} to version {}",
+            model_id,
+            model_info.version.as_deref().unwrap_or_default()
+        );
+
+        match self.download_model(model_id).await {
+            Ok(()) => {
+                remove_path(&previous_path)?;
+                events::emit(
+                    &self.app_handle,
+                    AppEvent::ModelUpgraded(model_id.to_string()),
+                );
+                Ok(())
+            }
+            Err(e) => {
+                warn!(
+                    "Upgrade of {} failed, keeping the old copy: {}",
+                    model_id, e
+                );
+                remove_path(&model_path)?;
+                fs::rename(&previous_path, &model_path)?;
+                {
+                    let mut models = self.available_models.lock().unwrap();
+                    if let Some(model) = models.get_mut(model_id) {
+                        model.is_downloaded = true;
+                        model.is_downloading = false;
+                        model.update_available = true;
+                    }
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Where the old copy of a model waits while it is upgraded.
+    fn previous_path(&self, filename: &str) -> PathBuf {
+        self.models_dir.join(format!("{}.previous", filename))
+    }
+
+    /// Puts back old copies left behind when the app quit mid-upgrade, or
+    /// removes them when the new copy was already in place.
+    fn restore_interrupted_upgrades(&self) {
+        let models = self.available_models.lock().unwrap().clone();
+        for model in models.values().filter(|m| !m.is_custom) {
+            let previous_path = self.previous_path(&model.filename);
+            if !previous_path.exists() {
+                continue;
+            }
+            // Downloads only land at the model path once verified, so a
+            // model there means the upgrade got far enough
+            let model_path = self.models_dir.join(&model.filename);
+            let result = if model_path.exists() {
+                remove_path(&previous_path)
+            } else {
+                println!("Restoring model {} from an interrupted upgrade", model.id);
+                fs::rename(&previous_path, &model_path).map_err(Into::into)
+            };
+            if let Err(e) = result {
+                eprintln!("Failed to clean up upgrade of {}: {}", model.id, e);
+            }
+        }
+    }
+
+    /// Adds or removes a model from the favorites the quick-switch shortcut
+    /// cycles through. New favorites go last.
+    pub fn set_favorite(&self, model_id: &str, favorite: bool) -> Result<()> {
+        if favorite && self.get_model_info(model_id).is_none() {
+            return Err(anyhow::anyhow!("Model not found: {}", model_id));
+        }
+        let mut settings = get_settings(&self.app_handle);
+        settings.favorite_models.retain(|id| id != model_id);
+        if favorite {
+            settings.favorite_models.push(model_id.to_string());
+        }
+        write_settings(&self.app_handle, settings);
+        Ok(())
+    }
+
+    /// The favorite after `current` that can be selected right now, wrapping
+    /// around. Favorites that were deleted or aren't downloaded are skipped.
+    pub fn next_favorite(&self, current: &str) -> Option<String> {
+        let favorites: Vec<String> = get_settings(&self.app_handle)
+            .favorite_models
+            .into_iter()
+            .filter(|id| {
+                self.get_model_info(id)
+                    .is_some_and(|m| m.is_downloaded || is_api_model(id))
+            })
+            .collect();
+        let next = match favorites.iter().position(|id| id == current) {
+            Some(index) => favorites.get(index + 1).or(favorites.first()),
+            None => favorites.first(),
+        };
+        next.filter(|id| id.as_str() != current).cloned()
+    }
+
+    /// Records that a local model was just loaded.
+    pub fn mark_used(&self, model_id: &str) {
+        let mut settings = get_settings(&self.app_handle);
+        settings
+            .model_last_used
+            .insert(model_id.to_string(), chrono::Utc::now().timestamp());
+        write_settings(&self.app_handle, settings);
+    }
+
+    /// Adds a transcription of `audio_seconds` that took `elapsed` to the
+    /// model's measured speed.
+    pub fn record_usage(&self, model_id: &str, audio_seconds: f32, elapsed: Duration) {
+        // Too short to say anything about the speed
+        if audio_seconds < MIN_USAGE_AUDIO_SECS {
+            return;
+        }
+        let mut settings = get_settings(&self.app_handle);
+        settings
+            .model_usage
+            .entry(model_id.to_string())
+            .or_default()
+            .record(elapsed.as_secs_f32() / audio_seconds);
+        write_settings(&self.app_handle, settings);
+    }
+
+    /// Sets the user's 1 to 5 star rating of a model, `None` clears it.
+    pub fn set_rating(&self, model_id: &str, rating: Option<u8>) -> Result<()> {
+        if self.get_model_info(model_id).is_none() {
+            return Err(anyhow::anyhow!("Model not found: {}", model_id));
+        }
+        let mut settings = get_settings(&self.app_handle);
+        match rating {
+            Some(rating) if (1..=5).contains(&rating) => {
+                settings.model_ratings.insert(model_id.to_string(), rating);
+            }
+            Some(rating) => return Err(anyhow::anyhow!("Invalid rating: {}", rating)),
+            None => {
+                settings.model_ratings.remove(model_id);
+            }
+        }
+        write_settings(&self.app_handle, settings);
+        Ok(())
+    }
+
+    /// Downloaded models not loaded for `days` days, largest first. The
+    /// selected model, the battery saver model, custom and API models are
+    /// never included.
+    pub fn unused_models(&self, days: u32) -> Vec<UnusedModel> {
+        let settings = get_settings(&self.app_handle);
+        let cutoff = chrono::Utc::now().timestamp() - i64::from(days) * 24 * 60 * 60;
+        let models = self.available_models.lock().unwrap().clone();
+
+        let mut unused: Vec<UnusedModel> = models
+            .into_values()
+            .filter(|m| m.is_downloaded && !m.is_custom && !is_api_model(&m.id))
+            .filter(|m| {
+                m.id != settings.selected_model
+                    && settings.battery_saver.model_id.as_deref() != Some(m.id.as_str())
+            })
+            .filter_map(|m| {
+                let last_used = match settings.model_last_used.get(&m.id) {
+                    Some(timestamp) => *timestamp,
+                    None => self.downloaded_at(&m)?,
+                };
+                (last_used < cutoff).then(|| UnusedModel {
+                    model_id: m.id,
+                    name: m.name,
+                    size_mb: m.size_mb,
+                    last_used,
+                })
+            })
+            .collect();
+        unused.sort_by(|a, b| b.size_mb.cmp(&a.size_mb));
+        unused
+    }
+
+    fn downloaded_at(&self, model: &ModelInfo) -> Option<i64> {
+        let modified = self
+            .models_dir
+            .join(&model.filename)
+            .metadata()
+            .ok()?
+            .modified()
+            .ok()?;
+        let secs = modified
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .ok()?
+            .as_secs();
+        Some(secs as i64)
+    }
+
+    /// Deletes the given models if they are still unused under the current
+    /// policy, returning the ids that were removed.
+    pub fn delete_unused_models(&self, model_ids: &[String]) -> Result<Vec<String>> {
+        let days = get_settings(&self.app_handle).unused_model_policy.days;
+        let unused = self.unused_models(days);
+
+        let mut deleted = Vec::new();
+        for model_id in model_ids {
+            if !unused.iter().any(|m| &m.model_id == model_id) {
+                warn!(
+                    "Not deleting {}, it is in use or was used recently",
+                    model_id
+                );
+                continue;
+            }
+            self.delete_model(model_id)?;
+            deleted.push(model_id.clone());
+        }
+        Ok(deleted)
+    }
+
+    /// Finds leftovers of interrupted downloads and installs: `.partial`
+    /// files, `.extracting` directories and directories matching no known
+    /// model. Files of downloads in progress are skipped. Unless `dry_run`
+    /// is set they are removed; either way the list is returned.
+    pub fn clean_up_stale_files(&self, dry_run: bool) -> Result<Vec<StaleModelFile>> {
+        if !dry_run {
+            self.ensure_writable()?;
+        }
+
+        let (known, active) = {
+            let models = self.available_models.lock().unwrap();
+            let active_ids = self.download_tokens.lock().unwrap();
+            let mut known = HashSet::new();
+            let mut active = Vec::new();
+            for model in models.values().filter(|m| !m.is_custom) {
+                let filenames = std::iter::once(model.filename.clone())
+                    .chain(model.variants.iter().map(|v| v.filename.clone()));
+                for filename in filenames {
+                    known.insert(coreml_encoder_name(&filename));
+                    // Kept for rolling back an upgrade
+                    known.insert(format!("{}.previous", filename));
+                    if active_ids.contains_key(&model.id) {
+                        active.push(coreml_encoder_name(&filename));
+                        active.push(filename.clone());
+                    }
+                    known.insert(filename);
+                }
+            }
+            (known, active)
+        };
+
+        let mut stale = Vec::new();
+        for entry in fs::read_dir(&self.models_dir)? {
+            let path = entry?.path();
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            let reason = if name.ends_with(".partial") || name.ends_with(".partial.validator") {
+                StaleReason::PartialDownload
+            } else if name.ends_with(".extracting") && path.is_dir() {
+                StaleReason::InterruptedExtraction
+            } else if path.is_dir() && !known.contains(name) {
+                StaleReason::UnknownDirectory
+            } else {
+                continue;
+            };
+            if active
+                .iter()
+                .any(|prefix| name.starts_with(prefix.as_str()))
+            {
+                continue;
+            }
+            stale.push(StaleModelFile {
+                path: path.to_string_lossy().to_string(),
+                size_bytes: path_size(&path),
+                reason,
+            });
+        }
+
+        if !dry_run {
+            for file in &stale {
+                remove_path(Path::new(&file.path))?;
+            }
+            let freed: u64 = stale.iter().map(|f| f.size_bytes).sum();
+            info!(
+                "Removed {} stale model files, {} MB freed",
+                stale.len(),
+                freed / (1024 * 1024)
+            );
+            self.update_download_status()?;
+            events::emit(&self.app_handle, AppEvent::ModelCatalogUpdated(()));
+        }
+        Ok(stale)
+    }
+
+    /// Copies a downloaded model to `dest` for moving it to another machine.
+    /// With `archive` the model is written as a single tar file that
+    /// `import_model` recognizes, otherwise the file or directory is copied
+    /// as is, into `dest` if that is an existing directory.
+    pub fn export_model(&self, model_id: &str, dest: &Path, archive: bool) -> Result<()> {
+        let model_info = self
+            .get_model_info(model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+        if is_api_model(model_id) || model_info.is_custom {
+            return Err(anyhow::anyhow!(
+                "{} has no files to export",
+                model_info.name
+            ));
+        }
+        let source = self.get_model_path(model_id)?;
+
+        if archive {
+            let manifest = ExportManifest {
+                model_id: model_id.to_string(),
+                filename: model_info.filename.clone(),
+                version: get_settings(&self.app_handle)
+                    .model_versions
+                    .remove(model_id),
+            };
+            let manifest = serde_json::to_vec_pretty(&manifest)?;
+            let mut builder = tar::Builder::new(File::create(dest)?);
+            let mut header = tar::Header::new_gnu();
+            header.set_size(manifest.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, EXPORT_MANIFEST, manifest.as_slice())?;
+            if source.is_dir() {
+                builder.append_dir_all(&model_info.filename, &source)?;
+            } else {
+                builder.append_path_with_name(&source, &model_info.filename)?;
+            }
+            builder.into_inner()?.sync_all()?;
+        } else {
+            let dest = if dest.is_dir() {
+                dest.join(&model_info.filename)
+            } else {
+                dest.to_path_buf()
+            };
+            copy_path(&source, &dest)?;
+        }
+
+        info!("Exported model {} to {}", model_id, dest.display());
+        Ok(())
+    }
+
+    /// Installs a model exported by `export_model`. Archives name their
+    /// model, a plain copy of a model file or directory needs `model_id`.
+    /// The files are checked like a download before they are moved into
+    /// place. Returns the id of the installed model.
+    pub async fn import_model(&self, source: &Path, model_id: Option<&str>) -> Result<String> {
+        self.ensure_writable()?;
+
+        let manifest = read_export_manifest(source);
+        let model_id = match (&manifest, model_id) {
+            (Some(manifest), _) => manifest.model_id.clone(),
+            (None, Some(model_id)) => model_id.to_string(),
+            (None, None) => {
+                return Err(anyhow::anyhow!(
+                    "Not a model archive, pick the model this file belongs to"
+                ))
+            }
+        };
+        let model_info = self
+            .get_model_info(&model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+        if is_api_model(&model_id) || model_info.is_custom {
+            return Err(anyhow::anyhow!("{} can't be imported", model_info.name));
+        }
+
+        let model_path = self.models_dir.join(&model_info.filename);
+        if model_path.exists() {
+            return Err(anyhow::anyhow!("{} is already installed", model_info.name));
+        }
+        let size = path_size(source);
+        self.ensure_disk_space(&model_id, size)?;
+
+        // Staged under the names interrupted downloads use, so a failed
+        // import is cleaned up like one
+        let staging = self.models_dir.join(format!(
+            "{}.{}",
+            model_info.filename,
+            if model_info.is_directory {
+                "extracting"
+            } else {
+                "partial"
+            }
+        ));
+        let (source, target, is_archive) =
+            (source.to_path_buf(), staging.clone(), manifest.is_some());
+        let filename = model_info.filename.clone();
+        tauri::async_runtime::spawn_blocking(move || -> Result<()> {
+            let _ = remove_path(&target);
+            if is_archive {
+                let unpack_dir = target.with_extension("unpacking");
+                let _ = remove_path(&unpack_dir);
+                Archive::new(File::open(&source)?).unpack(&unpack_dir)?;
+                fs::rename(unpack_dir.join(&filename), &target)?;
+                remove_path(&unpack_dir)?;
+            } else {
+                copy_path(&source, &target)?;
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!("Import task failed: {}", e))??;
+
+        // Checked while staged, so a damaged model is never loadable
+        let status = self.check_model_files(&model_info, &staging);
+        if !matches!(status, Ok(IntegrityStatus::Ok)) {
+            let _ = remove_path(&staging);
+        }
+        if let IntegrityStatus::Corrupt(reason) = status? {
+            return Err(anyhow::anyhow!(
+                "Imported {} is damaged: {}",
+                model_info.name,
+                reason
+            ));
+        }
+        fs::rename(&staging, &model_path)?;
+
+        self.update_download_status()?;
+        self.finish_download(&model_id).await?;
+        // The archive knows which version it holds, a plain copy is assumed
+        // to be the catalog's
+        if let Some(version) = manifest.and_then(|m| m.version) {
+            let mut settings = get_settings(&self.app_handle);
+            settings.model_versions.insert(model_id.clone(), version);
+            write_settings(&self.app_handle, settings);
+            let _ = self.check_for_updates();
+        }
+
+        info!("Imported model {}", model_id);
+        Ok(model_id)
+    }
+
+    /// Periodically looks for unused models and, depending on the policy,
+    /// emits `unused-models-found` or asks for confirmation to delete them
+    /// with `unused-models-cleanup-requested`. Downloaded models are
+    /// verified on the same schedule.
+    pub fn start_housekeeping(self: &Arc<Self>) {
+        let weak = Arc::downgrade(self);
+        thread::spawn(move || {
+            thread::sleep(HOUSEKEEPING_DELAY);
+            loop {
+                let Some(manager) = weak.upgrade() else {
+                    break;
+                };
+
+                let policy = get_settings(&manager.app_handle).unused_model_policy;
+                let event: Option<fn(Vec<UnusedModel>) -> AppEvent> = match policy.action {
+                    UnusedModelAction::Off => None,
+                    UnusedModelAction::Suggest => Some(AppEvent::UnusedModelsFound),
+                    UnusedModelAction::Delete => Some(AppEvent::UnusedModelsCleanupRequested),
+                };
+                if let Some(event) = event {
+                    let unused = manager.unused_models(policy.days);
+                    if !unused.is_empty() {
+                        info!("Found {} unused models", unused.len());
+                        events::emit(&manager.app_handle, event(unused));
+                    }
+                }
+
+                // Catch files damaged on disk before a load fails on them
+                let corrupted = manager.verify_downloaded_models();
+                if !corrupted.is_empty() {
+                    info!("Found {} corrupted models", corrupted.len());
+                }
+
+                drop(manager);
+                thread::sleep(HOUSEKEEPING_INTERVAL);
+            }
+        });
+    }
+
     pub fn cancel_download(&self, model_id: &str) -> Result<()> {
         if is_api_model(model_id) {
             println!(
@@ -782,6 +3124,12 @@ impl ModelManager {
         let _model_info =
             _model_info.ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
 
+        // Stop the running download, which keeps the partial file so the
+        // download can be resumed later
+        if let Some(token) = self.download_tokens.lock().unwrap().get(model_id) {
+            token.cancel();
+        }
+
         // Mark as not downloading
         {
             let mut models = self.available_models.lock().unwrap();
@@ -790,10 +3138,6 @@ impl ModelManager {
             }
         }
 
-        // Note: The actual download cancellation would need to be handled
-        // by the download task itself. This just updates the state.
-        // The partial file is kept so the download can be resumed later.
-
         // Update download status to reflect current state
         self.update_download_status()?;
 
@@ -801,3 +3145,189 @@ impl ModelManager {
         Ok(())
     }
 }
+
+/// Replaces the estimated scores of a model with what was measured on this
+/// machine. Speed comes from real use once there's enough of it, otherwise
+/// from `benchmark_model`. The user's rating counts twice as much as the
+/// estimated or benchmarked accuracy.
+fn with_measured_scores(mut model: ModelInfo, settings: &AppSettings) -> ModelInfo {
+    let benchmark = settings.model_benchmarks.get(&model.id);
+    let usage = settings
+        .model_usage
+        .get(&model.id)
+        .filter(|usage| usage.transcriptions >= MIN_USAGE_TRANSCRIPTIONS);
+
+    model.real_time_factor = usage
+        .map(|usage| usage.real_time_factor)
+        .or(benchmark.map(|benchmark| benchmark.real_time_factor));
+    if let Some(real_time_factor) = model.real_time_factor {
+        model.speed_score = speed_score(real_time_factor);
+    }
+    if let Some(accuracy) = benchmark.and_then(|benchmark| benchmark.accuracy_score()) {
+        model.accuracy_score = accuracy;
+    }
+
+    model.user_rating = settings.model_ratings.get(&model.id).copied();
+    if let Some(rating) = model.user_rating {
+        let rated = f32::from(rating.saturating_sub(1)) / 4.0;
+        model.accuracy_score = (model.accuracy_score + 2.0 * rated) / 3.0;
+    }
+    model
+}
+
+/// Points each model's file fields at the variant selected in settings.
+fn apply_selected_variants(
+    models: &mut HashMap<String, ModelInfo>,
+    selected: &HashMap<String, String>,
+) {
+    for model in models.values_mut() {
+        let Some(variant) = selected
+            .get(&model.id)
+            .and_then(|id| model.variants.iter().find(|v| &v.id == id))
+            .cloned()
+        else {
+            continue;
+        };
+        model.filename = variant.filename;
+        model.url = Some(variant.url);
+        model.size_mb = variant.size_mb;
+        model.sha256 = variant.sha256;
+        model.variant = Some(variant.id);
+    }
+}
+
+/// The manifest of an exported model archive, `None` for anything else.
+fn read_export_manifest(path: &Path) -> Option<ExportManifest> {
+    if !path.is_file() {
+        return None;
+    }
+    let mut archive = Archive::new(File::open(path).ok()?);
+    let mut entry = archive.entries().ok()?.next()?.ok()?;
+    if entry.path().ok()?.to_str()? != EXPORT_MANIFEST {
+        return None;
+    }
+    let mut contents = Vec::new();
+    entry.read_to_end(&mut contents).ok()?;
+    serde_json::from_slice(&contents).ok()
+}
+
+/// Copies a file, or a directory with everything below it.
+fn copy_path(source: &Path, dest: &Path) -> Result<()> {
+    if source.is_dir() {
+        fs::create_dir_all(dest)?;
+        for entry in fs::read_dir(source)? {
+            let entry = entry?;
+            copy_path(&entry.path(), &dest.join(entry.file_name()))?;
+        }
+    } else {
+        fs::copy(source, dest)?;
+    }
+    Ok(())
+}
+
+/// Size of a file, or of everything below a directory.
+fn path_size(path: &Path) -> u64 {
+    if path.is_dir() {
+        fs::read_dir(path)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| path_size(&entry.path()))
+                    .sum()
+            })
+            .unwrap_or(0)
+    } else {
+        path.metadata().map(|m| m.len()).unwrap_or(0)
+    }
+}
+
+/// Removes a model file or directory, if there is one.
+fn remove_path(path: &Path) -> Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)?;
+    } else if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Moves an extracted archive into place. Archives usually contain a single
+/// top-level directory, which becomes the model directory.
+fn install_extracted(temp_extract_dir: &Path, final_model_dir: &Path) -> Result<()> {
+    // Find the actual extracted directory (archive might have a nested structure)
+    let extracted_dirs: Vec<_> = fs::read_dir(temp_extract_dir)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false))
+        .collect();
+
+    if final_model_dir.exists() {
+        fs::remove_dir_all(final_model_dir)?;
+    }
+    if extracted_dirs.len() == 1 {
+        // Single directory extracted, move it to the final location
+        fs::rename(extracted_dirs[0].path(), final_model_dir)?;
+        // Clean up temp directory
+        let _ = fs::remove_dir_all(temp_extract_dir);
+    } else {
+        // Multiple items or no directories, rename the temp directory itself
+        fs::rename(temp_extract_dir, final_model_dir)?;
+    }
+    Ok(())
+}
+
+/// Blocking reader over the chunks of a download, so the synchronous tar and
+/// gzip decoders can consume a response as it arrives.
+struct ChunkReader {
+    receiver: tokio::sync::mpsc::Receiver<Vec<u8>>,
+    chunk: Vec<u8>,
+    position: usize,
+}
+
+impl ChunkReader {
+    fn new(receiver: tokio::sync::mpsc::Receiver<Vec<u8>>) -> Self {
+        Self {
+            receiver,
+            chunk: Vec::new(),
+            position: 0,
+        }
+    }
+}
+
+impl Read for ChunkReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.position >= self.chunk.len() {
+            match self.receiver.blocking_recv() {
+                Some(chunk) => {
+                    self.chunk = chunk;
+                    self.position = 0;
+                }
+                // The download ended or was stopped
+                None => return Ok(0),
+            }
+        }
+        let n = buf.len().min(self.chunk.len() - self.position);
+        buf[..n].copy_from_slice(&self.chunk[self.position..self.position + n]);
+        self.position += n;
+        Ok(n)
+    }
+}
+
+/// Computes the hex-encoded SHA-256 digest of a file without loading it into memory.
+pub fn sha256_file(path: &std::path::Path) -> Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
@@ -0,0 +1,174 @@
+use crate::audio_toolkit::{read_wav_file, save_wav_recording, Recording};
+use crate::events::{self, AppEvent};
+use crate::managers::history::HistoryManager;
+use crate::managers::transcription::TranscriptionManager;
+use anyhow::Result;
+use log::{debug, error, info};
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+const RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Debug, Serialize)]
+pub struct OfflineDelivery {
+    pub text: String,
+    /// Recordings still waiting for a connection.
+    pub remaining: usize,
+}
+
+/// Keeps recordings that couldn't reach an API provider on disk and
+/// transcribes them into history once the provider is reachable again.
+pub struct OfflineQueueManager {
+    app_handle: AppHandle,
+    transcription_manager: Arc<TranscriptionManager>,
+    history_manager: Arc<HistoryManager>,
+    queue_dir: PathBuf,
+    /// Set while draining so the retry thread and a manual retry don't
+    /// deliver the same recording twice.
+    draining: AtomicBool,
+}
+
+impl OfflineQueueManager {
+    pub fn new(
+        app_handle: &AppHandle,
+        transcription_manager: Arc<TranscriptionManager>,
+        history_manager: Arc<HistoryManager>,
+    ) -> Result<Arc<Self>> {
+        // Queued recordings end up in history, so they belong to the profile
+        let queue_dir = crate::profiles::profile_data_dir(app_handle)?.join("offline_queue");
+        fs::create_dir_all(&queue_dir)?;
+
+        let manager = Arc::new(Self {
+            app_handle: app_handle.clone(),
+            transcription_manager,
+            history_manager,
+            queue_dir,
+            draining: AtomicBool::new(false),
+        });
+
+        let weak = Arc::downgrade(&manager);
+        thread::spawn(move || loop {
+            thread::sleep(RETRY_INTERVAL);
+            let Some(manager) = weak.upgrade() else {
+                break;
+            };
+            if !manager.pending().is_empty() {
+                tauri::async_runtime::block_on(manager.drain());
+            }
+        });
+
+        Ok(manager)
+    }
+
+    /// Queued recordings, oldest first.
+    fn pending(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = fs::read_dir(&self.queue_dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| Some(e.ok()?.path()))
+                    .filter(|p| p.extension().is_some_and(|ext| ext == "wav"))
+                    .collect()
+            })
+            .unwrap_or_default();
+        files.sort();
+        files
+    }
+
+    pub fn queued_count(&self) -> usize {
+        self.pending().len()
+    }
+
+    pub async fn enqueue(&self, recording: &Recording) -> Result<()> {
+        let file_name = format!("{}.wav", chrono::Utc::now().timestamp_millis());
+        save_wav_recording(self.queue_dir.join(&file_name), recording).await?;
+        let queued = self.queued_count();
+        info!(
+            "Queued recording {} until the connection is back",
+            file_name
+        );
+        events::emit(
+            &self.app_handle,
+            AppEvent::TranscriptionQueuedOffline(queued),
+        );
+        Ok(())
+    }
+
+    /// Transcribes queued recordings in order, stopping at the first one that
+    /// still can't reach the provider.
+    pub async fn drain(&self) {
+        if self.draining.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        self.drain_pending().await;
+        self.draining.store(false, Ordering::Release);
+    }
+
+    async fn drain_pending(&self) {
+        for path in self.pending() {
+            let samples = match read_wav_file(&path) {
+                Ok(samples) => Recording::from(samples),
+                Err(e) => {
+                    error!("Dropping unreadable queued recording {:?}: {}", path, e);
+                    let _ = fs::remove_file(&path);
+                    continue;
+                }
+            };
+
+            self.transcription_manager.initiate_model_load();
+            let transcript = match self
+                .transcription_manager
+                .transcribe(&samples, &Arc::default())
+                .await
+            {
+                Ok(transcript) => transcript,
+                Err(e) if is_offline_error(&e) => {
+                    debug!(
+                        "Still offline, keeping {} queued recordings",
+                        self.queued_count()
+                    );
+                    return;
+                }
+                Err(e) => {
+                    error!("Failed to transcribe queued recording {:?}: {}", path, e);
+                    return;
+                }
+            };
+
+            if !transcript.text.is_empty() {
+                if let Err(e) = self
+                    .history_manager
+                    .save_transcription(samples.into(), transcript)
+                    .await
+                {
+                    error!("Failed to save queued transcription to history: {}", e);
+                    return;
+                }
+            }
+            let _ = fs::remove_file(&path);
+
+            events::emit(
+                &self.app_handle,
+                AppEvent::OfflineTranscriptionDelivered(OfflineDelivery {
+                    text,
+                    remaining: self.queued_count(),
+                }),
+            );
+        }
+    }
+}
+
+/// Whether a transcription failed because the provider couldn't be reached,
+/// as opposed to being rejected.
+pub fn is_offline_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause
+            .downcast_ref::<reqwest::Error>()
+            .is_some_and(|e| e.is_connect() || e.is_timeout())
+    })
+}
@@ -0,0 +1,291 @@
+use crate::audio_toolkit::decode_audio_bytes;
+use crate::local_http::{self, respond_json, LocalServer, Request};
+use crate::managers::history::{HistoryManager, ImportedEntry};
+use crate::managers::transcription::TranscriptionManager;
+use crate::settings::{get_settings, AppSettings};
+use log::{error, info};
+use qrcode::render::svg;
+use qrcode::QrCode;
+use serde::Serialize;
+use std::io::Read;
+use std::net::{IpAddr, TcpStream, UdpSocket};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// Voice memos are a few minutes at most, anything bigger is refused.
+const MAX_UPLOAD_BYTES: usize = 100 * 1024 * 1024;
+/// History source of imported memos.
+const SOURCE_NAME: &str = "Phone";
+const SOURCE_TAG: &str = "from phone";
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PhoneImportConfig {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: String,
+    /// Upload URL including the token, `None` while no network is up.
+    pub url: Option<String>,
+    /// The upload URL as an SVG QR code, for pairing the phone.
+    pub qr_code_svg: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ImportResponse {
+    text: String,
+}
+
+struct Shared {
+    transcription_manager: Arc<TranscriptionManager>,
+    history_manager: Arc<HistoryManager>,
+}
+
+/// A LAN endpoint phone shortcuts can push recorded voice memos to. Each memo
+/// is transcribed with the selected model and lands in history tagged as
+/// coming from the phone.
+///
+/// * `POST /memos?name=<file name>` with the audio file as the body returns
+///   the transcript as JSON. The file name, or else the content type, tells
+///   the decoder the format.
+///
+/// Every request must carry the token, either as a bearer token or `?token=`.
+/// Pairing hands both the address and the token over in a QR code.
+pub struct PhoneImportManager {
+    app_handle: AppHandle,
+    shared: Arc<Shared>,
+    server: Mutex<Option<LocalServer>>,
+}
+
+impl PhoneImportManager {
+    pub fn new(
+        app_handle: &AppHandle,
+        transcription_manager: Arc<TranscriptionManager>,
+        history_manager: Arc<HistoryManager>,
+    ) -> Self {
+        let manager = Self {
+            app_handle: app_handle.clone(),
+            shared: Arc::new(Shared {
+                transcription_manager,
+                history_manager,
+            }),
+            server: Mutex::new(None),
+        };
+
+        if let Err(e) = manager.apply_settings() {
+            error!("Failed to start phone import endpoint: {}", e);
+        }
+
+        manager
+    }
+
+    pub fn get_config(&self) -> PhoneImportConfig {
+        let settings = get_settings(&self.app_handle);
+        let token = self.token();
+        let url = lan_address().map(|ip| {
+            format!(
+                "http://{}:{}/memos?token={}",
+                ip, settings.phone_import_port, token
+            )
+        });
+        let qr_code_svg = url.as_ref().and_then(|url| {
+            QrCode::new(url.as_bytes())
+                .map(|code| code.render::<svg::Color>().min_dimensions(200, 200).build())
+                .ok()
+        });
+        PhoneImportConfig {
+            enabled: settings.phone_import_enabled,
+            port: settings.phone_import_port,
+            token,
+            url,
+            qr_code_svg,
+        }
+    }
+
+    fn token(&self) -> String {
+        local_http::token(&self.app_handle, token_slot)
+    }
+
+    /// Replaces the token, unpairing every phone paired so far.
+    pub fn regenerate_token(&self) -> Result<PhoneImportConfig, String> {
+        local_http::clear_token(&self.app_handle, token_slot);
+
+        // Restart so the running server picks up the new token
+        self.stop_server();
+        self.apply_settings()?;
+        Ok(self.get_config())
+    }
+
+    /// Starts, restarts or stops the endpoint to match the current settings.
+    pub fn apply_settings(&self) -> Result<(), String> {
+        let settings = get_settings(&self.app_handle);
+        let wanted_port = settings
+            .phone_import_enabled
+            .then_some(settings.phone_import_port);
+
+        let running_port = self.server.lock().unwrap().as_ref().map(|s| s.port);
+        if running_port == wanted_port {
+            return Ok(());
+        }
+
+        self.stop_server();
+
+        if let Some(port) = wanted_port {
+            let token = self.token();
+            let shared = self.shared.clone();
+            // Unlike the other endpoints this one has to be reachable from the phone
+            let server = LocalServer::start("phone import", "0.0.0.0", port, move |stream| {
+                handle_request(stream, &token, &shared)
+            })?;
+            *self.server.lock().unwrap() = Some(server);
+            info!("Phone import endpoint listening on port {}", port);
+        }
+
+        Ok(())
+    }
+
+    fn stop_server(&self) {
+        if let Some(running) = self.server.lock().unwrap().take() {
+            running.stop();
+            info!("Phone import endpoint stopped");
+        }
+    }
+}
+
+impl Drop for PhoneImportManager {
+    fn drop(&mut self) {
+        self.stop_server();
+    }
+}
+
+/// The address other devices on the network reach this machine at. Connecting
+/// a UDP socket only picks the outgoing interface, nothing is sent.
+fn lan_address() -> Option<IpAddr> {
+    let socket = UdpSocket::bind(("0.0.0.0", 0)).ok()?;
+    socket.connect(("192.0.2.1", 80)).ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    (!ip.is_loopback() && !ip.is_unspecified()).then_some(ip)
+}
+
+fn token_slot(settings: &mut AppSettings) -> &mut Option<String> {
+    &mut settings.phone_import_token
+}
+
+fn handle_request(mut stream: TcpStream, token: &str, shared: &Shared) -> std::io::Result<()> {
+    // Uploads over a slow Wi-Fi connection can stall for a while
+    let mut request = Request::read(&stream, Duration::from_secs(30))?;
+    if !request.has_token(token) {
+        return respond_json(
+            &mut stream,
+            "401 Unauthorized",
+            "{\"error\":\"unauthorized\"}",
+        );
+    }
+
+    match (request.method.as_str(), request.segments().as_slice()) {
+        ("POST", ["memos"]) => {
+            let Some(length) = request.content_length() else {
+                return respond_json(
+                    &mut stream,
+                    "411 Length Required",
+                    "{\"error\":\"missing content length\"}",
+                );
+            };
+            if length > MAX_UPLOAD_BYTES {
+                return respond_json(
+                    &mut stream,
+                    "413 Payload Too Large",
+                    "{\"error\":\"memo too large\"}",
+                );
+            }
+            let mut body = vec![0u8; length];
+            request.reader.read_exact(&mut body)?;
+
+            let name = request.query_param("name");
+            let content_type = request.header("content-type").map(str::to_lowercase);
+            match import_memo(shared, body, name.as_deref(), content_type.as_deref()) {
+                Ok(text) => {
+                    let body = serde_json::to_string(&ImportResponse { text }).unwrap_or_default();
+                    respond_json(&mut stream, "200 OK", &body)
+                }
+                Err(e) => {
+                    error!("Failed to import phone memo: {}", e);
+                    let body = serde_json::json!({ "error": e.to_string() }).to_string();
+                    respond_json(&mut stream, "422 Unprocessable Entity", &body)
+                }
+            }
+        }
+        _ => respond_json(&mut stream, "404 Not Found", "{\"error\":\"not found\"}"),
+    }
+}
+
+/// Transcribes an uploaded memo into history and returns the transcript.
+fn import_memo(
+    shared: &Shared,
+    audio: Vec<u8>,
+    name: Option<&str>,
+    content_type: Option<&str>,
+) -> anyhow::Result<String> {
+    let extension = name
+        .and_then(|name| Path::new(name).extension())
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .or_else(|| content_type.and_then(extension_for).map(str::to_string));
+    let size = audio.len();
+    let samples = decode_audio_bytes(audio, extension.as_deref())?;
+
+    let text = tauri::async_runtime::block_on(
+        shared
+            .transcription_manager
+            .transcribe_with_model(None, samples),
+    )?;
+    if !text.is_empty() {
+        shared
+            .history_manager
+            .insert_imported_entry(ImportedEntry {
+                file_name: String::new(),
+                timestamp: chrono::Utc::now().timestamp(),
+                saved: false,
+                title: name
+                    .and_then(|name| Path::new(name).file_stem())
+                    .map(|stem| stem.to_string_lossy().to_string()),
+                transcription_text: text.clone(),
+                tags: vec![SOURCE_TAG.to_string()],
+                app_name: Some(SOURCE_NAME.to_string()),
+                sync_id: None,
+            })?;
+    }
+    info!("Imported a {} byte memo from the phone", size);
+    Ok(text)
+}
+
+fn extension_for(content_type: &str) -> Option<&'static str> {
+    let mime = content_type.split(';').next().unwrap_or("").trim();
+    match mime {
+        "audio/mp4" | "audio/m4a" | "audio/x-m4a" => Some("m4a"),
+        "audio/mpeg" | "audio/mp3" => Some("mp3"),
+        "audio/wav" | "audio/x-wav" | "audio/wave" => Some("wav"),
+        "audio/aac" => Some("aac"),
+        "audio/flac" | "audio/x-flac" => Some("flac"),
+        "audio/ogg" => Some("ogg"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extension_for_ignores_parameters() {
+        assert_eq!(extension_for("audio/x-m4a"), Some("m4a"));
+        assert_eq!(extension_for("audio/mpeg; charset=binary"), Some("mp3"));
+        assert_eq!(extension_for(" audio/wav"), Some("wav"));
+    }
+
+    #[test]
+    fn extension_for_unknown_types_is_none() {
+        assert_eq!(extension_for("application/octet-stream"), None);
+        assert_eq!(extension_for(""), None);
+    }
+}
@@ -0,0 +1,143 @@
+use crate::events::{self, AppEvent};
+use crate::managers::audio::{AudioRecordingManager, MicrophoneMode};
+use crate::managers::transcription::TranscriptionManager;
+use crate::settings::{get_settings, AppSettings};
+use log::{debug, error, info, warn};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PowerStatus {
+    pub on_battery: bool,
+    pub battery_saver_active: bool,
+}
+
+/// Watches the power source and applies the battery saver policy: a different
+/// model for transcription and no always-on microphone while on battery.
+pub struct PowerManager {
+    app_handle: AppHandle,
+    on_battery: AtomicBool,
+}
+
+impl PowerManager {
+    pub fn new(app_handle: &AppHandle) -> Arc<Self> {
+        let manager = Arc::new(Self {
+            app_handle: app_handle.clone(),
+            on_battery: AtomicBool::new(is_on_battery()),
+        });
+
+        let weak = Arc::downgrade(&manager);
+        thread::spawn(move || loop {
+            thread::sleep(POLL_INTERVAL);
+            let Some(manager) = weak.upgrade() else {
+                break;
+            };
+
+            let on_battery = is_on_battery();
+            if manager.on_battery.swap(on_battery, Ordering::Relaxed) != on_battery {
+                info!(
+                    "Power source changed: {}",
+                    if on_battery { "battery" } else { "AC" }
+                );
+                manager.apply_policy();
+            }
+        });
+
+        manager
+    }
+
+    pub fn status(&self) -> PowerStatus {
+        let settings = get_settings(&self.app_handle);
+        PowerStatus {
+            on_battery: self.on_battery.load(Ordering::Relaxed),
+            battery_saver_active: self.saver_active(&settings),
+        }
+    }
+
+    fn saver_active(&self, settings: &AppSettings) -> bool {
+        settings.battery_saver.enabled && self.on_battery.load(Ordering::Relaxed)
+    }
+
+    /// The model transcription should use right now.
+    pub fn preferred_model(&self, settings: &AppSettings) -> String {
+        match &settings.battery_saver.model_id {
+            Some(model_id) if self.saver_active(settings) && !model_id.is_empty() => {
+                model_id.clone()
+            }
+            _ => settings.selected_model.clone(),
+        }
+    }
+
+    pub fn allows_always_on_microphone(&self, settings: &AppSettings) -> bool {
+        settings.always_on_microphone
+            && !(self.saver_active(settings) && settings.battery_saver.disable_always_on_microphone)
+    }
+
+    /// Brings the microphone mode and loaded model in line with the current
+    /// power source and policy.
+    pub fn apply_policy(&self) {
+        let settings = get_settings(&self.app_handle);
+        events::emit(
+            &self.app_handle,
+            AppEvent::PowerStatusChanged(self.status()),
+        );
+
+        if let Some(rm) = self.app_handle.try_state::<Arc<AudioRecordingManager>>() {
+            let mode = if self.allows_always_on_microphone(&settings) {
+                MicrophoneMode::AlwaysOn
+            } else {
+                MicrophoneMode::OnDemand
+            };
+            if let Err(e) = rm.update_mode(mode) {
+                error!("Failed to apply battery saver microphone mode: {}", e);
+            }
+        }
+
+        // Only swap a model that is already loaded, an unloaded one picks up
+        // the preferred model the next time it is needed
+        let Some(tm) = self.app_handle.try_state::<Arc<TranscriptionManager>>() else {
+            return;
+        };
+        let preferred = self.preferred_model(&settings);
+        let current = tm.get_current_model();
+        if current.is_some() && current.as_deref() != Some(preferred.as_str()) {
+            let tm = tm.inner().clone();
+            thread::spawn(move || {
+                debug!(
+                    "Switching to model {} for the current power source",
+                    preferred
+                );
+                if let Err(e) = tm.load_model(&preferred) {
+                    warn!("Failed to switch model for power source: {}", e);
+                }
+            });
+        }
+    }
+}
+
+/// True when a battery is discharging, i.e. the machine is not on AC power.
+fn is_on_battery() -> bool {
+    let manager = match starship_battery::Manager::new() {
+        Ok(manager) => manager,
+        Err(e) => {
+            debug!("Battery information unavailable: {}", e);
+            return false;
+        }
+    };
+
+    match manager.batteries() {
+        Ok(batteries) => batteries
+            .filter_map(|battery| battery.ok())
+            .any(|battery| battery.state() == starship_battery::State::Discharging),
+        Err(e) => {
+            debug!("Failed to read battery state: {}", e);
+            false
+        }
+    }
+}
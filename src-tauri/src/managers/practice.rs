@@ -0,0 +1,213 @@
+use crate::audio_toolkit::word_error_rate;
+use crate::managers::audio::AudioRecordingManager;
+use crate::managers::transcription::TranscriptionManager;
+use crate::settings::{get_settings, write_settings, PracticeScore};
+use anyhow::Result;
+use chrono::Utc;
+use log::info;
+use rand::Rng;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use tauri::AppHandle;
+
+/// Recording binding used while a practice sentence is read.
+const PRACTICE_BINDING: &str = "practice";
+/// Oldest scores are dropped beyond this.
+const MAX_PRACTICE_SCORES: usize = 500;
+/// Attempts counted as recent in the summary.
+const RECENT_ATTEMPTS: usize = 10;
+
+/// Phonetically balanced sentences from the Harvard lists, which cover the
+/// sounds of English in roughly the proportions they're spoken.
+const PRACTICE_SENTENCES: &[&str] = &[
+    "The birch canoe slid on the smooth planks.",
+    "Glue the sheet to the dark blue background.",
+    "It's easy to tell the depth of a well.",
+    "These days a chicken leg is a rare dish.",
+    "Rice is often served in round bowls.",
+    "The juice of lemons makes fine punch.",
+    "The box was thrown beside the parked truck.",
+    "The hogs were fed chopped corn and garbage.",
+    "Four hours of steady work faced us.",
+    "A large size in stockings is hard to sell.",
+    "The boy was there when the sun rose.",
+    "A rod is used to catch pink salmon.",
+    "The source of the huge river is the clear spring.",
+    "Kick the ball straight and follow through.",
+    "Help the woman get back to her feet.",
+    "A pot of tea helps to pass the evening.",
+    "Smoky fires lack flame and heat.",
+    "The soft cushion broke the man's fall.",
+    "The salt breeze came across from the sea.",
+    "The girl at the booth sold fifty bonds.",
+    "The small pup gnawed a hole in the sock.",
+    "The fish twisted and turned on the bent hook.",
+    "Press the pants and sew a button on the vest.",
+    "The swan dive was far short of perfect.",
+    "The beauty of the view stunned the young boy.",
+];
+
+/// A sentence to read aloud.
+#[derive(Clone, Debug, Serialize)]
+pub struct PracticeSentence {
+    pub index: usize,
+    pub text: String,
+}
+
+/// Average word error rate of the attempts made with one microphone.
+#[derive(Clone, Debug, Serialize)]
+pub struct MicrophoneScore {
+    /// `None` for the system default.
+    pub microphone: Option<String>,
+    pub attempts: usize,
+    pub average_word_error_rate: f32,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PracticeSummary {
+    pub attempts: usize,
+    pub average_word_error_rate: Option<f32>,
+    /// Average of the last few attempts, to see whether practice or a
+    /// moved microphone helped.
+    pub recent_word_error_rate: Option<f32>,
+    pub best_word_error_rate: Option<f32>,
+    pub microphones: Vec<MicrophoneScore>,
+}
+
+/// Dictation practice: the user reads sentences from a bundled corpus and
+/// every reading is scored by the word error rate of its transcript. Scores
+/// are kept with the model and microphone used, so they also show how well
+/// a microphone placement works.
+pub struct PracticeManager {
+    app_handle: AppHandle,
+    recording_manager: Arc<AudioRecordingManager>,
+    transcription_manager: Arc<TranscriptionManager>,
+    last_sentence: Mutex<Option<usize>>,
+}
+
+impl PracticeManager {
+    pub fn new(
+        app_handle: &AppHandle,
+        recording_manager: Arc<AudioRecordingManager>,
+        transcription_manager: Arc<TranscriptionManager>,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            app_handle: app_handle.clone(),
+            recording_manager,
+            transcription_manager,
+            last_sentence: Mutex::new(None),
+        })
+    }
+
+    /// A random sentence from the corpus, never the one shown last.
+    pub fn next_sentence(&self) -> PracticeSentence {
+        let mut last = self.last_sentence.lock().unwrap();
+        let mut rng = rand::thread_rng();
+        let mut index = rng.gen_range(0..PRACTICE_SENTENCES.len());
+        if Some(index) == *last {
+            index = (index + 1) % PRACTICE_SENTENCES.len();
+        }
+        *last = Some(index);
+        PracticeSentence {
+            index,
+            text: PRACTICE_SENTENCES[index].to_string(),
+        }
+    }
+
+    pub fn start_attempt(&self) -> Result<()> {
+        if !self.recording_manager.try_start_recording(PRACTICE_BINDING) {
+            return Err(anyhow::anyhow!(
+                "Can't start recording, another recording is in progress"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Stops the recording, transcribes it and scores it against the
+    /// sentence at `index`.
+    pub async fn finish_attempt(&self, index: usize) -> Result<PracticeScore> {
+        let audio = self
+            .recording_manager
+            .stop_recording(PRACTICE_BINDING)
+            .map(|recording| recording.audio)
+            .ok_or_else(|| anyhow::anyhow!("No practice sentence is being recorded"))?;
+        let sentence = PRACTICE_SENTENCES
+            .get(index)
+            .ok_or_else(|| anyhow::anyhow!("Unknown practice sentence {}", index))?;
+
+        self.transcription_manager.initiate_model_load();
+        // Practice readings shouldn't prompt the next real dictation
+        let transcript = self
+            .transcription_manager
+            .transcribe_background(audio.into_samples())
+            .await?
+            .text;
+
+        let mut settings = get_settings(&self.app_handle);
+        let score = PracticeScore {
+            sentence: sentence.to_string(),
+            word_error_rate: word_error_rate(sentence, &transcript),
+            transcript,
+            model_id: self.transcription_manager.get_current_model(),
+            microphone: settings.selected_microphone.clone(),
+            recorded_at: Utc::now().timestamp(),
+        };
+        info!(
+            "Practice attempt scored a word error rate of {:.2}",
+            score.word_error_rate
+        );
+
+        settings.practice_scores.push(score.clone());
+        let excess = settings
+            .practice_scores
+            .len()
+            .saturating_sub(MAX_PRACTICE_SCORES);
+        settings.practice_scores.drain(..excess);
+        write_settings(&self.app_handle, settings);
+        Ok(score)
+    }
+
+    /// Scores oldest first.
+    pub fn scores(&self) -> Vec<PracticeScore> {
+        get_settings(&self.app_handle).practice_scores
+    }
+
+    pub fn summary(&self) -> PracticeSummary {
+        let scores = self.scores();
+        let average = |scores: &[&PracticeScore]| -> Option<f32> {
+            (!scores.is_empty()).then(|| {
+                scores.iter().map(|s| s.word_error_rate).sum::<f32>() / scores.len() as f32
+            })
+        };
+        let all: Vec<&PracticeScore> = scores.iter().collect();
+
+        let mut microphones: Vec<(Option<String>, Vec<&PracticeScore>)> = Vec::new();
+        for &score in &all {
+            match microphones.iter_mut().find(|(m, _)| *m == score.microphone) {
+                Some((_, scores)) => scores.push(score),
+                None => microphones.push((score.microphone.clone(), vec![score])),
+            }
+        }
+
+        PracticeSummary {
+            attempts: all.len(),
+            average_word_error_rate: average(&all),
+            recent_word_error_rate: average(&all[all.len().saturating_sub(RECENT_ATTEMPTS)..]),
+            best_word_error_rate: all.iter().map(|s| s.word_error_rate).reduce(f32::min),
+            microphones: microphones
+                .into_iter()
+                .map(|(microphone, scores)| MicrophoneScore {
+                    microphone,
+                    attempts: scores.len(),
+                    average_word_error_rate: average(&scores).unwrap_or_default(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn clear_scores(&self) {
+        let mut settings = get_settings(&self.app_handle);
+        settings.practice_scores.clear();
+        write_settings(&self.app_handle, settings);
+    }
+}
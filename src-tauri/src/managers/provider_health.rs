@@ -0,0 +1,222 @@
+use crate::events::{self, AppEvent};
+use crate::managers::model::API_MODEL_IDS;
+use crate::managers::transcription::TranscriptionManager;
+use crate::settings::{get_settings, AppSettings};
+use chrono::Utc;
+use futures_util::future::join_all;
+use log::{info, warn};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+const PING_INTERVAL: Duration = Duration::from_secs(60);
+const PING_TIMEOUT: Duration = Duration::from_secs(10);
+/// Pings slower than this mark the provider as degraded.
+const SLOW_PING: Duration = Duration::from_secs(3);
+/// Consecutive failures after which a provider counts as down rather than
+/// degraded.
+const DOWN_AFTER_FAILURES: u32 = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderStatus {
+    /// Not checked yet.
+    Unknown,
+    Healthy,
+    /// Slow, or the last request failed.
+    Degraded,
+    Down,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ProviderHealth {
+    pub model_id: String,
+    pub provider: String,
+    pub status: ProviderStatus,
+    /// Round trip of the last ping, `None` when the last check was a
+    /// transcription.
+    pub latency_ms: Option<u64>,
+    pub last_error: Option<String>,
+    pub last_success_at: Option<i64>,
+    pub checked_at: Option<i64>,
+}
+
+impl ProviderHealth {
+    fn unchecked(model_id: &str, provider: &str) -> Self {
+        Self {
+            model_id: model_id.to_string(),
+            provider: provider.to_string(),
+            status: ProviderStatus::Unknown,
+            latency_ms: None,
+            last_error: None,
+            last_success_at: None,
+            checked_at: None,
+        }
+    }
+}
+
+struct ProviderState {
+    health: ProviderHealth,
+    failures: u32,
+}
+
+/// Keeps track of whether the API providers with a key are reachable, so the
+/// model picker can flag a provider before a dictation is sent to it.
+/// Providers are pinged concurrently in the background, which also keeps
+/// their connections warm, and every real transcription counts as a check.
+pub struct ProviderHealthManager {
+    app_handle: AppHandle,
+    transcription_manager: Arc<TranscriptionManager>,
+    states: Mutex<HashMap<String, ProviderState>>,
+}
+
+impl ProviderHealthManager {
+    pub fn new(
+        app_handle: &AppHandle,
+        transcription_manager: Arc<TranscriptionManager>,
+    ) -> Arc<Self> {
+        let manager = Arc::new(Self {
+            app_handle: app_handle.clone(),
+            transcription_manager,
+            states: Mutex::new(HashMap::new()),
+        });
+
+        let weak = Arc::downgrade(&manager);
+        tauri::async_runtime::spawn(async move {
+            while let Some(manager) = weak.upgrade() {
+                manager.check_all().await;
+                drop(manager);
+                tokio::time::sleep(PING_INTERVAL).await;
+            }
+        });
+
+        manager
+    }
+
+    /// Health of every provider that has an API key.
+    pub fn get(&self) -> Vec<ProviderHealth> {
+        let settings = get_settings(&self.app_handle);
+        let states = self.states.lock().unwrap();
+        configured_models(&settings)
+            .into_iter()
+            .filter_map(|model_id| match states.get(model_id) {
+                Some(state) => Some(state.health.clone()),
+                None => api_provider(&settings, model_id)
+                    .map(|(provider, _)| ProviderHealth::unchecked(model_id, provider)),
+            })
+            .collect()
+    }
+
+    /// Records the outcome of a request made for a transcription.
+    pub fn record(&self, model_id: &str, result: Result<(), String>) {
+        self.update(model_id, result.map(|_| None));
+    }
+
+    /// Pings every provider with an API key that wasn't used successfully
+    /// since the last round.
+    async fn check_all(&self) {
+        let settings = get_settings(&self.app_handle);
+        let now = Utc::now().timestamp();
+        let due: Vec<&str> = {
+            let states = self.states.lock().unwrap();
+            configured_models(&settings)
+                .into_iter()
+                .filter(|model_id| {
+                    states
+                        .get(*model_id)
+                        .and_then(|state| state.health.last_success_at)
+                        .is_none_or(|at| now - at >= PING_INTERVAL.as_secs() as i64)
+                })
+                .collect()
+        };
+
+        join_all(due.into_iter().map(|model_id| async move {
+            let start = Instant::now();
+            let result = tokio::time::timeout(
+                PING_TIMEOUT,
+                self.transcription_manager.ping_provider(model_id),
+            )
+            .await
+            .map_err(|_| "Timed out".to_string())
+            .and_then(|r| r.map_err(|e| e.to_string()));
+            self.update(model_id, result.map(|_| Some(start.elapsed())));
+        }))
+        .await;
+    }
+
+    fn update(&self, model_id: &str, result: Result<Option<Duration>, String>) {
+        let settings = get_settings(&self.app_handle);
+        let Some((provider, _)) = api_provider(&settings, model_id) else {
+            return;
+        };
+        let now = Utc::now().timestamp();
+
+        let mut states = self.states.lock().unwrap();
+        let state = states
+            .entry(model_id.to_string())
+            .or_insert_with(|| ProviderState {
+                health: ProviderHealth::unchecked(model_id, provider),
+                failures: 0,
+            });
+        let previous = state.health.status;
+
+        state.health.checked_at = Some(now);
+        match result {
+            Ok(latency) => {
+                state.failures = 0;
+                state.health.status = if latency.is_some_and(|l| l > SLOW_PING) {
+                    ProviderStatus::Degraded
+                } else {
+                    ProviderStatus::Healthy
+                };
+                state.health.latency_ms = latency.map(|l| l.as_millis() as u64);
+                state.health.last_error = None;
+                state.health.last_success_at = Some(now);
+            }
+            Err(e) => {
+                state.failures += 1;
+                state.health.status = if state.failures >= DOWN_AFTER_FAILURES {
+                    ProviderStatus::Down
+                } else {
+                    ProviderStatus::Degraded
+                };
+                state.health.latency_ms = None;
+                state.health.last_error = Some(e);
+            }
+        }
+
+        if state.health.status != previous {
+            match &state.health.last_error {
+                Some(e) => warn!("{} is {:?}: {}", provider, state.health.status, e),
+                None => info!("{} is {:?}", provider, state.health.status),
+            }
+            let health = state.health.clone();
+            drop(states);
+            events::emit(&self.app_handle, AppEvent::ProviderHealthChanged(health));
+        }
+    }
+}
+
+/// The provider of an API model and its API key, `None` for local models.
+pub fn api_provider<'a>(
+    settings: &'a AppSettings,
+    model_id: &str,
+) -> Option<(&'static str, Option<&'a str>)> {
+    let (provider, key) = match model_id {
+        "voxtral-mini" => ("Mistral", &settings.mistral_api_key),
+        "nova-3" => ("Deepgram", &settings.deepgram_api_key),
+        "universal" => ("AssemblyAI", &settings.assemblyai_api_key),
+        "whisper-zero" => ("Gladia", &settings.gladia_api_key),
+        _ => return None,
+    };
+    Some((provider, key.as_deref().filter(|k| !k.trim().is_empty())))
+}
+
+fn configured_models(settings: &AppSettings) -> Vec<&'static str> {
+    API_MODEL_IDS
+        .into_iter()
+        .filter(|model_id| api_provider(settings, model_id).is_some_and(|(_, key)| key.is_some()))
+        .collect()
+}
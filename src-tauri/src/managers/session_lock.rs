@@ -0,0 +1,193 @@
+use crate::events::{self, AppEvent};
+use crate::managers::audio::AudioRecordingManager;
+use crate::managers::history::HistoryManager;
+use crate::managers::transcription::TranscriptionManager;
+use crate::settings::{get_settings, LockBehavior};
+use crate::tray::{change_tray_icon, TrayIconState};
+use crate::utils;
+use crate::ManagedToggleState;
+use log::{debug, error, info};
+use serde::Serialize;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Debug, Serialize)]
+pub struct LockedRecordingEvent {
+    pub behavior: LockBehavior,
+    /// The transcription kept in history, when the recording was saved.
+    pub transcription: Option<String>,
+}
+
+/// Watches for the session locking while a recording is running and stops it
+/// according to the lock behavior setting, so nothing is left half-recorded or
+/// pasted into the lock screen.
+pub struct SessionLockManager {
+    app_handle: AppHandle,
+}
+
+impl SessionLockManager {
+    pub fn new(app_handle: &AppHandle) -> Arc<Self> {
+        let manager = Arc::new(Self {
+            app_handle: app_handle.clone(),
+        });
+
+        let weak = Arc::downgrade(&manager);
+        thread::spawn(move || loop {
+            thread::sleep(POLL_INTERVAL);
+            let Some(manager) = weak.upgrade() else {
+                break;
+            };
+
+            // Checking the lock state can be costly, so only do it while recording
+            let rm = manager.app_handle.state::<Arc<AudioRecordingManager>>();
+            if let Some(binding_id) = rm.active_binding() {
+                if is_session_locked() {
+                    manager.handle_lock(&binding_id);
+                }
+            }
+        });
+
+        manager
+    }
+
+    fn handle_lock(&self, binding_id: &str) {
+        let behavior = get_settings(&self.app_handle).lock_behavior;
+        info!(
+            "Session locked while recording, applying {:?} to binding {}",
+            behavior, binding_id
+        );
+
+        // The shortcut is no longer "on" for toggle mode
+        if let Ok(mut states) = self.app_handle.state::<ManagedToggleState>().lock() {
+            states.active_toggles.insert(binding_id.to_string(), false);
+        }
+
+        let rm = Arc::clone(&self.app_handle.state::<Arc<AudioRecordingManager>>());
+        match behavior {
+            LockBehavior::Discard => {
+                rm.cancel_recording();
+                finish(
+                    &self.app_handle,
+                    LockedRecordingEvent {
+                        behavior,
+                        transcription: None,
+                    },
+                );
+            }
+            LockBehavior::SaveToHistory => {
+                let Some(recording) = rm.stop_recording(binding_id) else {
+                    return;
+                };
+                change_tray_icon(&self.app_handle, TrayIconState::Transcribing);
+
+                let tm = Arc::clone(&self.app_handle.state::<Arc<TranscriptionManager>>());
+                let hm = Arc::clone(&self.app_handle.state::<Arc<HistoryManager>>());
+                let ah = self.app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    // Not in the transcription queue, so nothing cancels it
+                    let result = tm.transcribe(&recording.audio, &Arc::default()).await;
+                    let transcription = match result {
+                        Ok(transcript) if !transcript.text.is_empty() => {
+                            let text = transcript.text.clone();
+                            if let Err(e) = hm.save_transcription(recording, transcript).await {
+                                error!("Failed to save locked-session transcription: {}", e);
+                            }
+                            Some(text)
+                        }
+                        Ok(_) => None,
+                        Err(e) => {
+                            error!("Failed to transcribe locked-session recording: {}", e);
+                            None
+                        }
+                    };
+
+                    finish(
+                        &ah,
+                        LockedRecordingEvent {
+                            behavior,
+                            transcription,
+                        },
+                    );
+                });
+            }
+        }
+    }
+}
+
+fn finish(app: &AppHandle, event: LockedRecordingEvent) {
+    utils::hide_recording_overlay(app);
+    change_tray_icon(app, TrayIconState::Idle);
+    events::emit(app, AppEvent::RecordingStoppedOnLock(event));
+}
+
+#[cfg(target_os = "linux")]
+fn is_session_locked() -> bool {
+    let Ok(session_id) = std::env::var("XDG_SESSION_ID") else {
+        return false;
+    };
+
+    match std::process::Command::new("loginctl")
+        .args(["show-session", &session_id, "-p", "LockedHint", "--value"])
+        .output()
+    {
+        Ok(output) => String::from_utf8_lossy(&output.stdout).trim() == "yes",
+        Err(e) => {
+            debug!("Failed to query session lock state: {}", e);
+            false
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn is_session_locked() -> bool {
+    use core_foundation::base::{CFType, TCFType};
+    use core_foundation::boolean::CFBoolean;
+    use core_foundation::dictionary::{CFDictionary, CFDictionaryRef};
+    use core_foundation::string::CFString;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGSessionCopyCurrentDictionary() -> CFDictionaryRef;
+    }
+
+    // SAFETY: the dictionary is returned with +1 retain count (create rule)
+    // and is checked for null before being wrapped.
+    unsafe {
+        let dict_ref = CGSessionCopyCurrentDictionary();
+        if dict_ref.is_null() {
+            return false;
+        }
+        let dict: CFDictionary<CFString, CFType> = CFDictionary::wrap_under_create_rule(dict_ref);
+        dict.find(CFString::from_static_string("CGSSessionScreenIsLocked"))
+            .and_then(|value| value.downcast::<CFBoolean>())
+            .map(bool::from)
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn is_session_locked() -> bool {
+    use windows_sys::Win32::System::StationsAndDesktops::{
+        CloseDesktop, OpenInputDesktop, DESKTOP_SWITCHDESKTOP,
+    };
+
+    // The input desktop can't be opened while the secure lock screen is shown
+    // SAFETY: plain Win32 calls, the handle is closed when it was opened.
+    unsafe {
+        let desktop = OpenInputDesktop(0, 0, DESKTOP_SWITCHDESKTOP);
+        if desktop.is_null() {
+            return true;
+        }
+        CloseDesktop(desktop);
+        false
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn is_session_locked() -> bool {
+    false
+}
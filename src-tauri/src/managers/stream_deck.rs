@@ -0,0 +1,228 @@
+use crate::local_http::{self, respond_json, LocalServer, Request};
+use crate::settings::{get_settings, AppSettings};
+use crate::shortcut::dispatch_binding_event;
+use crate::tray::TrayIconState;
+use crate::utils::cancel_current_operation;
+use log::{error, info};
+use serde::Serialize;
+use std::io::Write;
+use std::net::TcpStream;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::AppHandle;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct StreamDeckConfig {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct StateMessage {
+    state: &'static str,
+}
+
+struct Shared {
+    app_handle: AppHandle,
+    state: Mutex<&'static str>,
+    /// Open `/events` connections that receive every state change.
+    subscribers: Mutex<Vec<TcpStream>>,
+}
+
+/// A small local HTTP control surface shaped for a Stream Deck companion
+/// plugin: it reports idle/recording/transcribing for button icons and lets
+/// buttons press and release bindings.
+///
+/// * `GET /state` returns the current state as JSON.
+/// * `GET /events` streams state changes as server-sent `state` events, and
+///   every app event as an `app` event carrying its versioned envelope.
+/// * `POST /bindings/<id>/down` and `/up` act like pressing a shortcut.
+/// * `POST /cancel` cancels the current operation.
+///
+/// Every request must carry the token, either as a bearer token or `?token=`.
+pub struct StreamDeckManager {
+    shared: Arc<Shared>,
+    server: Mutex<Option<LocalServer>>,
+}
+
+impl StreamDeckManager {
+    pub fn new(app_handle: &AppHandle) -> Self {
+        let manager = Self {
+            shared: Arc::new(Shared {
+                app_handle: app_handle.clone(),
+                state: Mutex::new(state_name(&TrayIconState::Idle)),
+                subscribers: Mutex::new(Vec::new()),
+            }),
+            server: Mutex::new(None),
+        };
+
+        if let Err(e) = manager.apply_settings() {
+            error!("Failed to start Stream Deck endpoint: {}", e);
+        }
+
+        manager
+    }
+
+    pub fn get_config(&self) -> StreamDeckConfig {
+        let settings = get_settings(&self.shared.app_handle);
+        StreamDeckConfig {
+            enabled: settings.stream_deck_enabled,
+            port: settings.stream_deck_port,
+            token: self.token(),
+        }
+    }
+
+    fn token(&self) -> String {
+        local_http::token(&self.shared.app_handle, token_slot)
+    }
+
+    pub fn regenerate_token(&self) -> Result<StreamDeckConfig, String> {
+        local_http::clear_token(&self.shared.app_handle, token_slot);
+
+        // Restart so the running server picks up the new token
+        self.stop_server();
+        self.apply_settings()?;
+        Ok(self.get_config())
+    }
+
+    /// Pushes a state change to every connected plugin.
+    pub fn publish_state(&self, state: &TrayIconState) {
+        let name = state_name(state);
+        *self.shared.state.lock().unwrap() = name;
+
+        let message = format_event(name);
+        self.shared
+            .subscribers
+            .lock()
+            .unwrap()
+            .retain_mut(|stream| stream.write_all(message.as_bytes()).is_ok());
+    }
+
+    /// Forwards an app event envelope to every connected plugin.
+    pub fn publish_event(&self, envelope: &str) {
+        let message = format!("event: app\ndata: {}\n\n", envelope);
+        self.shared
+            .subscribers
+            .lock()
+            .unwrap()
+            .retain_mut(|stream| stream.write_all(message.as_bytes()).is_ok());
+    }
+
+    /// Starts, restarts or stops the endpoint to match the current settings.
+    pub fn apply_settings(&self) -> Result<(), String> {
+        let settings = get_settings(&self.shared.app_handle);
+        let wanted_port = settings
+            .stream_deck_enabled
+            .then_some(settings.stream_deck_port);
+
+        let running_port = self.server.lock().unwrap().as_ref().map(|s| s.port);
+        if running_port == wanted_port {
+            return Ok(());
+        }
+
+        self.stop_server();
+
+        if let Some(port) = wanted_port {
+            let token = self.token();
+            let shared = self.shared.clone();
+            let server = LocalServer::start("Stream Deck", "127.0.0.1", port, move |stream| {
+                handle_request(stream, &token, &shared)
+            })?;
+            *self.server.lock().unwrap() = Some(server);
+            info!(
+                "Stream Deck endpoint listening on http://127.0.0.1:{}",
+                port
+            );
+        }
+
+        Ok(())
+    }
+
+    fn stop_server(&self) {
+        if let Some(running) = self.server.lock().unwrap().take() {
+            running.stop();
+            self.shared.subscribers.lock().unwrap().clear();
+            info!("Stream Deck endpoint stopped");
+        }
+    }
+}
+
+impl Drop for StreamDeckManager {
+    fn drop(&mut self) {
+        self.stop_server();
+    }
+}
+
+fn state_name(state: &TrayIconState) -> &'static str {
+    match state {
+        TrayIconState::Idle => "idle",
+        TrayIconState::Recording => "recording",
+        TrayIconState::Transcribing => "transcribing",
+    }
+}
+
+fn format_event(state: &'static str) -> String {
+    let json = serde_json::to_string(&StateMessage { state }).unwrap_or_default();
+    format!("event: state\ndata: {}\n\n", json)
+}
+
+fn token_slot(settings: &mut AppSettings) -> &mut Option<String> {
+    &mut settings.stream_deck_token
+}
+
+fn handle_request(mut stream: TcpStream, token: &str, shared: &Shared) -> std::io::Result<()> {
+    let request = Request::read(&stream, Duration::from_secs(5))?;
+    if !request.has_token(token) {
+        return respond_json(
+            &mut stream,
+            "401 Unauthorized",
+            "{\"error\":\"unauthorized\"}",
+        );
+    }
+
+    match (request.method.as_str(), request.segments().as_slice()) {
+        ("GET", ["state"]) => {
+            let state = *shared.state.lock().unwrap();
+            let body = serde_json::to_string(&StateMessage { state }).unwrap_or_default();
+            respond_json(&mut stream, "200 OK", &body)
+        }
+        ("GET", ["events"]) => {
+            stream.set_read_timeout(None)?;
+            // A stalled plugin must not block state updates for everyone else
+            stream.set_write_timeout(Some(Duration::from_secs(1)))?;
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n"
+            )?;
+            let state = *shared.state.lock().unwrap();
+            stream.write_all(format_event(state).as_bytes())?;
+            shared.subscribers.lock().unwrap().push(stream);
+            Ok(())
+        }
+        ("POST", ["bindings", binding_id, direction @ ("down" | "up")]) => {
+            if !get_settings(&shared.app_handle)
+                .bindings
+                .contains_key(*binding_id)
+            {
+                return respond_json(
+                    &mut stream,
+                    "404 Not Found",
+                    "{\"error\":\"unknown binding\"}",
+                );
+            }
+            dispatch_binding_event(
+                &shared.app_handle,
+                binding_id,
+                "streamdeck",
+                *direction == "down",
+            );
+            respond_json(&mut stream, "200 OK", "{\"ok\":true}")
+        }
+        ("POST", ["cancel"]) => {
+            cancel_current_operation(&shared.app_handle);
+            respond_json(&mut stream, "200 OK", "{\"ok\":true}")
+        }
+        _ => respond_json(&mut stream, "404 Not Found", "{\"error\":\"not found\"}"),
+    }
+}
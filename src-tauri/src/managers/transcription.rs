@@ -1,27 +1,82 @@
-use crate::audio_toolkit::apply_custom_words;
+use crate::acceleration::whisper_backend;
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use crate::audio_toolkit::vad::SmoothedVad;
+use crate::audio_toolkit::{
+    apply_custom_words_with_corrections, apply_replacement_rules, capitalize_sentences,
+    remove_filler_words, segment_paragraphs, split_at_pauses, trim_silence, word_diff,
+    word_error_rate, DiffSegment, Recording, SileroVad, TimedSegment, WordCorrection,
+};
+use crate::diarization;
+use crate::engine::{self, DecodeOptions, SegmentConfidence, SpeechEngine, TranscriptionCancelled};
+use crate::events::{self, AppEvent};
 use crate::managers::assemblyai::AssemblyAIApiManager;
 use crate::managers::deepgram::DeepgramApiManager;
 use crate::managers::gladia::GladiaApiManager;
+use crate::managers::metrics::MetricsManager;
 use crate::managers::mistral::MistralApiManager;
-use crate::managers::model::{is_api_model, EngineType, ModelManager};
-use crate::settings::{get_settings, ModelUnloadTimeout};
+use crate::managers::model::{is_api_model, is_english_only_whisper, ModelManager};
+use crate::managers::power::PowerManager;
+use crate::managers::provider_health::ProviderHealthManager;
+use crate::managers::vocabulary::{effective_filler_words, VocabularyManager};
+use crate::settings::{
+    get_settings, write_settings, AppSettings, ModelBenchmark, ModelUnloadTimeout,
+    PostProcessPreset,
+};
 use anyhow::Result;
+use futures_util::future::{select, Either};
 use log::{debug, info, warn};
 use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::pin::pin;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Condvar, Mutex};
 use std::thread;
-use std::time::{Duration, SystemTime};
-use tauri::{AppHandle, Emitter};
-use transcribe_rs::{
-    engines::{
-        parakeet::{
-            ParakeetEngine, ParakeetInferenceParams, ParakeetModelParams, TimestampGranularity,
-        },
-        whisper::{WhisperEngine, WhisperInferenceParams},
-    },
-    TranscriptionEngine,
-};
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Manager};
+use transcribe_rs::TranscriptionResult;
+
+/// Upper bound on the stored previous transcript, above any sensible window.
+const MAX_CONTEXT_CHARS: usize = 2000;
+/// Time between two `model-load-progress` events while a model loads.
+const LOAD_PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+/// One second of audio, enough for every engine to run a full pass.
+const WARM_UP_SAMPLES: usize = WHISPER_SAMPLE_RATE as usize;
+/// Whisper wants at least a second, shorter speech is padded to this.
+const MIN_SPEECH_SAMPLES: usize = WHISPER_SAMPLE_RATE as usize * 5 / 4;
+/// How often an API request checks whether it was cancelled.
+const CANCEL_POLL: Duration = Duration::from_millis(100);
+/// Where a long recording may be split, before the chunk length is reached.
+const CHUNK_SEARCH_SAMPLES: usize = 10 * WHISPER_SAMPLE_RATE as usize;
+
+/// How far loading a model got. None of the engines report it, so
+/// `bytes_read` is estimated from the size of the model files and how fast
+/// the last model loaded. It stays 0 until a model has loaded once and
+/// reaches `total_bytes` when the engine is done.
+#[derive(Clone, Debug, Serialize)]
+pub struct ModelLoadProgress {
+    pub model_id: String,
+    pub stage: String,
+    pub bytes_read: u64,
+    pub total_bytes: u64,
+}
+
+/// A model that failed on the GPU and now runs on the CPU.
+#[derive(Clone, Debug, Serialize)]
+pub struct GpuFallbackEvent {
+    pub model_id: String,
+    /// The backend that failed.
+    pub backend: String,
+    pub error: String,
+}
+
+/// Chunks of a long recording transcribed so far. Chunks are cut as they
+/// are read, so `total` is the fewest the rest can take and may grow.
+#[derive(Clone, Debug, Serialize)]
+pub struct TranscriptionProgress {
+    pub completed: usize,
+    pub total: usize,
+}
 
 #[derive(Clone, Debug, Serialize)]
 pub struct ModelStateEvent {
@@ -31,15 +86,56 @@ pub struct ModelStateEvent {
     pub error: Option<String>,
 }
 
-enum LoadedEngine {
-    Whisper(WhisperEngine),
-    Parakeet(ParakeetEngine),
+/// What an API provider returned for a transcription.
+struct ApiTranscript {
+    text: String,
+    alternatives: Vec<String>,
+    confidence: Vec<SegmentConfidence>,
+}
+
+/// A finished transcription with what came out of it besides the text.
+#[derive(Clone, Debug, Default)]
+pub struct Transcript {
+    pub text: String,
+    /// The text before filler word removal or a preset changed it, if one did.
+    pub raw_text: Option<String>,
+    /// Alternative transcripts, if the provider returned any.
+    pub alternatives: Vec<String>,
+    /// Confidence per segment, if the engine or provider reported it.
+    pub confidence: Vec<SegmentConfidence>,
+    /// Custom word corrections made in the text.
+    pub corrections: Vec<WordCorrection>,
+    /// Backend it ran on, `api` for API providers.
+    pub backend: Option<String>,
+}
+
+/// One model's side of an A/B comparison.
+#[derive(Clone, Debug, Serialize)]
+pub struct ComparisonRun {
+    pub model_id: String,
+    pub text: Option<String>,
+    pub error: Option<String>,
+    /// Time spent loading a local model that wasn't already loaded.
+    pub load_ms: u64,
+    pub transcribe_ms: u64,
+}
+
+/// The same audio transcribed by two models, without custom words or
+/// replacement rules applied.
+#[derive(Clone, Debug, Serialize)]
+pub struct TranscriptionComparison {
+    pub a: ComparisonRun,
+    pub b: ComparisonRun,
+    pub diff: Vec<DiffSegment>,
 }
 
 #[derive(Clone)]
 pub struct TranscriptionManager {
-    engine: Arc<Mutex<Option<LoadedEngine>>>,
+    engine: Arc<Mutex<Option<Box<dyn SpeechEngine>>>>,
     model_manager: Arc<ModelManager>,
+    vocabulary_manager: Arc<VocabularyManager>,
+    metrics_manager: Arc<MetricsManager>,
+    power_manager: Arc<PowerManager>,
     app_handle: AppHandle,
     current_model_id: Arc<Mutex<Option<String>>>,
     last_activity: Arc<AtomicU64>,
@@ -47,6 +143,21 @@ pub struct TranscriptionManager {
     watcher_handle: Arc<Mutex<Option<thread::JoinHandle<()>>>>,
     is_loading: Arc<Mutex<bool>>,
     loading_condvar: Arc<Condvar>,
+    /// The previous transcript and when it finished, for context continuation.
+    previous_context: Arc<Mutex<Option<(String, Instant)>>>,
+    /// The last non-empty dictation, for reading it back.
+    last_transcript: Arc<Mutex<Option<String>>>,
+    /// Stops the running partial transcription, so a full one doesn't wait
+    /// for the engine behind it.
+    partial_abort: Arc<Mutex<Option<Arc<AtomicBool>>>>,
+    /// Bytes per second the last local model loaded at, 0 before the first.
+    load_rate: Arc<AtomicU64>,
+    /// Backend the loaded local model runs on, e.g. `cuda` or `cpu`.
+    engine_backend: Arc<Mutex<Option<String>>>,
+    /// Backend the last dictation ran on, `api` for API providers.
+    last_backend: Arc<Mutex<Option<String>>>,
+    /// VAD for trimming silence, with the threshold it was created with.
+    vad: Arc<Mutex<Option<(f32, SmoothedVad)>>>,
     mistral_manager: MistralApiManager,
     deepgram_manager: DeepgramApiManager,
     assemblyai_manager: AssemblyAIApiManager,
@@ -54,10 +165,19 @@ pub struct TranscriptionManager {
 }
 
 impl TranscriptionManager {
-    pub fn new(app_handle: &AppHandle, model_manager: Arc<ModelManager>) -> Result<Self> {
+    pub fn new(
+        app_handle: &AppHandle,
+        model_manager: Arc<ModelManager>,
+        vocabulary_manager: Arc<VocabularyManager>,
+        metrics_manager: Arc<MetricsManager>,
+        power_manager: Arc<PowerManager>,
+    ) -> Result<Self> {
         let manager = Self {
             engine: Arc::new(Mutex::new(None)),
             model_manager,
+            vocabulary_manager,
+            metrics_manager,
+            power_manager,
             app_handle: app_handle.clone(),
             current_model_id: Arc::new(Mutex::new(None)),
             last_activity: Arc::new(AtomicU64::new(
@@ -70,6 +190,13 @@ impl TranscriptionManager {
             watcher_handle: Arc::new(Mutex::new(None)),
             is_loading: Arc::new(Mutex::new(false)),
             loading_condvar: Arc::new(Condvar::new()),
+            previous_context: Arc::new(Mutex::new(None)),
+            last_transcript: Arc::new(Mutex::new(None)),
+            partial_abort: Arc::new(Mutex::new(None)),
+            load_rate: Arc::new(AtomicU64::new(0)),
+            engine_backend: Arc::new(Mutex::new(None)),
+            last_backend: Arc::new(Mutex::new(None)),
+            vad: Arc::new(Mutex::new(None)),
             mistral_manager: MistralApiManager::new(app_handle.clone()),
             deepgram_manager: DeepgramApiManager::new(app_handle.clone()),
             assemblyai_manager: AssemblyAIApiManager::new(app_handle.clone()),
@@ -112,14 +239,14 @@ impl TranscriptionManager {
                                 debug!("Starting to unload model due to inactivity");
 
                                 if let Ok(()) = manager_cloned.unload_model() {
-                                    let _ = app_handle_cloned.emit(
-                                        "model-state-changed",
-                                        ModelStateEvent {
+                                    events::emit(
+                                        &app_handle_cloned,
+                                        AppEvent::ModelStateChanged(ModelStateEvent {
                                             event_type: "unloaded".to_string(),
                                             model_id: None,
                                             model_name: None,
                                             error: None,
-                                        },
+                                        }),
                                     );
                                     let unload_duration = unload_start.elapsed();
                                     debug!(
@@ -159,28 +286,27 @@ impl TranscriptionManager {
 
         {
             let mut engine = self.engine.lock().unwrap();
-            if let Some(ref mut loaded_engine) = *engine {
-                match loaded_engine {
-                    LoadedEngine::Whisper(ref mut whisper) => whisper.unload_model(),
-                    LoadedEngine::Parakeet(ref mut parakeet) => parakeet.unload_model(),
-                }
+            if let Some(loaded_engine) = engine.as_mut() {
+                loaded_engine.unload();
             }
             *engine = None; // Drop the engine to free memory
         }
+        *self.engine_backend.lock().unwrap() = None;
+        crate::crash_report::set_model(None);
         {
             let mut current_model = self.current_model_id.lock().unwrap();
             *current_model = None;
         }
 
         // Emit unloaded event
-        let _ = self.app_handle.emit(
-            "model-state-changed",
-            ModelStateEvent {
+        events::emit(
+            &self.app_handle,
+            AppEvent::ModelStateChanged(ModelStateEvent {
                 event_type: "unloaded".to_string(),
                 model_id: None,
                 model_name: None,
                 error: None,
-            },
+            }),
         );
 
         let unload_duration = unload_start.elapsed();
@@ -196,16 +322,18 @@ impl TranscriptionManager {
         debug!("Starting to load model: {}", model_id);
 
         // Emit loading started event
-        let _ = self.app_handle.emit(
-            "model-state-changed",
-            ModelStateEvent {
+        events::emit(
+            &self.app_handle,
+            AppEvent::ModelStateChanged(ModelStateEvent {
                 event_type: "loading_started".to_string(),
                 model_id: Some(model_id.to_string()),
                 model_name: None,
                 error: None,
-            },
+            }),
         );
 
+        // A model loaded right after launch must wait for the startup scan
+        self.model_manager.wait_until_ready();
         let model_info = self
             .model_manager
             .get_model_info(model_id)
@@ -217,19 +345,20 @@ impl TranscriptionManager {
                 let mut engine = self.engine.lock().unwrap();
                 *engine = None;
             }
+            *self.engine_backend.lock().unwrap() = None;
             {
                 let mut current_model = self.current_model_id.lock().unwrap();
                 *current_model = Some(model_id.to_string());
             }
 
-            let _ = self.app_handle.emit(
-                "model-state-changed",
-                ModelStateEvent {
+            events::emit(
+                &self.app_handle,
+                AppEvent::ModelStateChanged(ModelStateEvent {
                     event_type: "loading_completed".to_string(),
                     model_id: Some(model_id.to_string()),
                     model_name: Some(model_info.name.clone()),
                     error: None,
-                },
+                }),
             );
 
             debug!(
@@ -242,80 +371,93 @@ impl TranscriptionManager {
 
         if !model_info.is_downloaded {
             let error_msg = "Model not downloaded";
-            let _ = self.app_handle.emit(
-                "model-state-changed",
-                ModelStateEvent {
+            events::emit(
+                &self.app_handle,
+                AppEvent::ModelStateChanged(ModelStateEvent {
                     event_type: "loading_failed".to_string(),
                     model_id: Some(model_id.to_string()),
                     model_name: Some(model_info.name.clone()),
                     error: Some(error_msg.to_string()),
-                },
+                }),
             );
             return Err(anyhow::anyhow!(error_msg));
         }
 
         let model_path = self.model_manager.get_model_path(model_id)?;
 
-        // Create appropriate engine based on model type
-        let loaded_engine = match model_info.engine_type {
-            EngineType::Whisper => {
-                let mut engine = WhisperEngine::new();
-                engine.load_model(&model_path).map_err(|e| {
-                    let error_msg = format!("Failed to load whisper model {}: {}", model_id, e);
-                    let _ = self.app_handle.emit(
-                        "model-state-changed",
-                        ModelStateEvent {
-                            event_type: "loading_failed".to_string(),
-                            model_id: Some(model_id.to_string()),
-                            model_name: Some(model_info.name.clone()),
-                            error: Some(error_msg.clone()),
-                        },
-                    );
-                    anyhow::anyhow!(error_msg)
-                })?;
-                LoadedEngine::Whisper(engine)
-            }
-            EngineType::Parakeet => {
-                let mut engine = ParakeetEngine::new();
-                engine
-                    .load_model_with_params(&model_path, ParakeetModelParams::int8())
-                    .map_err(|e| {
-                        let error_msg =
-                            format!("Failed to load parakeet model {}: {}", model_id, e);
-                        let _ = self.app_handle.emit(
-                            "model-state-changed",
-                            ModelStateEvent {
-                                event_type: "loading_failed".to_string(),
-                                model_id: Some(model_id.to_string()),
-                                model_name: Some(model_info.name.clone()),
-                                error: Some(error_msg.clone()),
-                            },
-                        );
-                        anyhow::anyhow!(error_msg)
-                    })?;
-                LoadedEngine::Parakeet(engine)
+        let total_bytes = model_size(&model_path);
+        self.emit_load_progress(model_id, "loading", 0, total_bytes);
+
+        let settings = get_settings(&self.app_handle);
+        // Engines without GPU support ignore the backend
+        let requested = settings
+            .model_backends
+            .get(model_id)
+            .cloned()
+            .unwrap_or_else(|| whisper_backend(settings.gpu_acceleration));
+        let engine_start = Instant::now();
+        let loaded = AtomicBool::new(false);
+        let load_result = thread::scope(|scope| {
+            let rate = self.load_rate.load(Ordering::Relaxed);
+            if rate > 0 {
+                scope.spawn(|| {
+                    while !loaded.load(Ordering::Relaxed) {
+                        thread::sleep(LOAD_PROGRESS_INTERVAL);
+                        let estimate = (engine_start.elapsed().as_secs_f64() * rate as f64) as u64;
+                        // Held short of the total until the engine is done
+                        let bytes = estimate.min(total_bytes / 100 * 99);
+                        self.emit_load_progress(model_id, "loading", bytes, total_bytes);
+                    }
+                });
             }
-        };
+            let result = engine::load(
+                &model_info.engine_type,
+                &model_path,
+                &requested,
+                is_english_only_whisper(model_id),
+            );
+            loaded.store(true, Ordering::Relaxed);
+            result
+        });
+        let (loaded_engine, gpu_error) = load_result.map_err(|e| {
+            let error_msg = format!("Failed to load model {}: {}", model_id, e);
+            self.report_load_failure(model_id, &model_info.name, &error_msg);
+            anyhow::anyhow!(error_msg)
+        })?;
+        if let Some(error) = gpu_error {
+            self.remember_cpu_fallback(model_id, &requested, &error);
+        }
+        let load_secs = engine_start.elapsed().as_secs_f64();
+        if load_secs > 0.0 {
+            self.load_rate
+                .store((total_bytes as f64 / load_secs) as u64, Ordering::Relaxed);
+        }
+        self.emit_load_progress(model_id, "loading", total_bytes, total_bytes);
+        let backend = loaded_engine.backend().to_string();
+        info!("Model {} runs on {}", model_id, backend);
 
         // Update the current engine and model ID
         {
             let mut engine = self.engine.lock().unwrap();
             *engine = Some(loaded_engine);
         }
+        *self.engine_backend.lock().unwrap() = Some(backend);
+        self.model_manager.mark_used(model_id);
+        crate::crash_report::set_model(Some(model_id));
         {
             let mut current_model = self.current_model_id.lock().unwrap();
             *current_model = Some(model_id.to_string());
         }
 
         // Emit loading completed event
-        let _ = self.app_handle.emit(
-            "model-state-changed",
-            ModelStateEvent {
+        events::emit(
+            &self.app_handle,
+            AppEvent::ModelStateChanged(ModelStateEvent {
                 event_type: "loading_completed".to_string(),
                 model_id: Some(model_id.to_string()),
                 model_name: Some(model_info.name.clone()),
                 error: None,
-            },
+            }),
         );
 
         let load_duration = load_start.elapsed();
@@ -324,9 +466,153 @@ impl TranscriptionManager {
             model_id,
             load_duration.as_millis()
         );
+        self.warm_up(model_id);
         Ok(())
     }
 
+    /// Runs a short silent buffer through the freshly loaded model in the
+    /// background, so the first dictation doesn't pay for allocating the
+    /// inference state. Emits `model-warmed` once done.
+    fn warm_up(&self, model_id: &str) {
+        let engine = self.engine.clone();
+        let current_model_id = self.current_model_id.clone();
+        let app_handle = self.app_handle.clone();
+        let model_id = model_id.to_string();
+
+        thread::spawn(move || {
+            let warm_start = Instant::now();
+            {
+                let mut engine_guard = engine.lock().unwrap();
+                // Another model may have replaced this one in the meantime
+                if current_model_id.lock().unwrap().as_deref() != Some(model_id.as_str()) {
+                    return;
+                }
+                let Some(engine) = engine_guard.as_mut() else {
+                    return;
+                };
+                let silence = vec![0.0; WARM_UP_SAMPLES];
+                let settings = get_settings(&app_handle);
+                if let Err(e) = engine.transcribe(silence, &decode_options(&settings)) {
+                    warn!("Warming up model {} failed: {}", model_id, e);
+                    return;
+                }
+            }
+            debug!(
+                "Warmed up model {} in {}ms",
+                model_id,
+                warm_start.elapsed().as_millis()
+            );
+            events::emit(&app_handle, AppEvent::ModelWarmed(model_id.to_string()));
+        });
+    }
+
+    fn emit_load_progress(&self, model_id: &str, stage: &str, bytes_read: u64, total_bytes: u64) {
+        events::emit(
+            &self.app_handle,
+            AppEvent::ModelLoadProgress(ModelLoadProgress {
+                model_id: model_id.to_string(),
+                stage: stage.to_string(),
+                bytes_read,
+                total_bytes,
+            }),
+        );
+    }
+
+    /// Remembers that `model_id` only works on the CPU and tells the UI why.
+    fn remember_cpu_fallback(&self, model_id: &str, backend: &str, error: &str) {
+        warn!(
+            "Model {} failed on {}, using the CPU from now on: {}",
+            model_id, backend, error
+        );
+        let mut settings = get_settings(&self.app_handle);
+        settings
+            .model_backends
+            .insert(model_id.to_string(), "cpu".to_string());
+        write_settings(&self.app_handle, settings);
+        events::emit(
+            &self.app_handle,
+            AppEvent::GpuFallback(GpuFallbackEvent {
+                model_id: model_id.to_string(),
+                backend: backend.to_string(),
+                error: error.to_string(),
+            }),
+        );
+    }
+
+    /// Reloads the current model on the CPU after it failed on the GPU
+    /// mid-transcription, e.g. because the GPU ran out of memory.
+    fn reload_on_cpu(&self, model_id: &str, error: &str) -> Result<Box<dyn SpeechEngine>> {
+        let backend = self.engine_backend().unwrap_or_default();
+        let model_info = self
+            .model_manager
+            .get_model_info(model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+        let model_path = self.model_manager.get_model_path(model_id)?;
+        let (engine, _) = engine::load(
+            &model_info.engine_type,
+            &model_path,
+            "cpu",
+            is_english_only_whisper(model_id),
+        )?;
+        *self.engine_backend.lock().unwrap() = Some("cpu".to_string());
+        self.remember_cpu_fallback(model_id, &backend, error);
+        Ok(engine)
+    }
+
+    /// Emits the loading failure and checks whether the model files are corrupt,
+    /// in which case the frontend is told so it can offer a repair download.
+    fn report_load_failure(&self, model_id: &str, model_name: &str, error_msg: &str) {
+        events::emit(
+            &self.app_handle,
+            AppEvent::ModelStateChanged(ModelStateEvent {
+                event_type: "loading_failed".to_string(),
+                model_id: Some(model_id.to_string()),
+                model_name: Some(model_name.to_string()),
+                error: Some(error_msg.to_string()),
+            }),
+        );
+
+        if let Err(e) = self.model_manager.verify_model(model_id) {
+            warn!("Failed to check integrity of model {}: {}", model_id, e);
+        }
+    }
+
+    /// Loads `model_id`, makes it the selected model and applies the settings
+    /// saved for it.
+    pub fn select_model(&self, model_id: &str) -> Result<()> {
+        self.load_model(model_id)?;
+
+        let mut settings = get_settings(&self.app_handle);
+        settings.selected_model = model_id.to_string();
+        settings.apply_model_overrides(model_id);
+        write_settings(&self.app_handle, settings);
+        Ok(())
+    }
+
+    /// Selects the next favorite model and emits `model-state-changed` with
+    /// `switched`, so the overlay can show which model is now active.
+    pub fn cycle_favorite_model(&self) -> Result<String> {
+        let current = get_settings(&self.app_handle).selected_model;
+        let next = self
+            .model_manager
+            .next_favorite(&current)
+            .ok_or_else(|| anyhow::anyhow!("No other favorite model to switch to"))?;
+        self.select_model(&next)?;
+
+        let model_name = self.model_manager.get_model_info(&next).map(|m| m.name);
+        events::emit(
+            &self.app_handle,
+            AppEvent::ModelStateChanged(ModelStateEvent {
+                event_type: "switched".to_string(),
+                model_id: Some(next.clone()),
+                model_name,
+                error: None,
+            }),
+        );
+        info!("Switched from {} to favorite model {}", current, next);
+        Ok(next)
+    }
+
     /// Kicks off the model loading in a background thread if it's not already loaded
     pub fn initiate_model_load(&self) {
         let mut is_loading = self.is_loading.lock().unwrap();
@@ -338,7 +624,8 @@ impl TranscriptionManager {
         let self_clone = self.clone();
         thread::spawn(move || {
             let settings = get_settings(&self_clone.app_handle);
-            if let Err(e) = self_clone.load_model(&settings.selected_model) {
+            let model_id = self_clone.power_manager.preferred_model(&settings);
+            if let Err(e) = self_clone.load_model(&model_id) {
                 eprintln!("Failed to load model: {}", e);
             }
             let mut is_loading = self_clone.is_loading.lock().unwrap();
@@ -347,12 +634,808 @@ impl TranscriptionManager {
         });
     }
 
+    /// Applies custom word correction and replacement rules, including the
+    /// shared team vocabulary layered beneath the user's own lists and the
+    /// lists for the transcript's language when it is known. Returns the
+    /// text with the custom word corrections made in it.
+    fn post_process(
+        &self,
+        text: String,
+        settings: &AppSettings,
+        language: Option<&str>,
+    ) -> (String, Vec<WordCorrection>) {
+        let custom_words = self
+            .vocabulary_manager
+            .effective_custom_words(settings, language);
+        let (corrected, corrections) = if !custom_words.is_empty() {
+            apply_custom_words_with_corrections(
+                &text,
+                &custom_words,
+                settings.word_correction_threshold,
+            )
+        } else {
+            (text, Vec::new())
+        };
+
+        let rules = self
+            .vocabulary_manager
+            .effective_replacement_rules(settings, language);
+        if rules.is_empty() {
+            (corrected, corrections)
+        } else {
+            (apply_replacement_rules(&corrected, &rules), corrections)
+        }
+    }
+
+    /// Runs text that didn't come from dictation, e.g. OCR output, through
+    /// the same correction and replacement rules.
+    pub fn post_process_text(&self, text: String) -> String {
+        let settings = get_settings(&self.app_handle);
+        let (processed, _) = self.post_process(text, &settings, selected_language(&settings));
+        processed.trim().to_string()
+    }
+
     pub fn get_current_model(&self) -> Option<String> {
         let current_model = self.current_model_id.lock().unwrap();
         current_model.clone()
     }
 
-    pub async fn transcribe(&self, audio: Vec<f32>) -> Result<String> {
+    /// Final post-processing stage, run on the whole transcript. The raw
+    /// text is kept for history when anything was removed.
+    fn strip_filler_words(&self, mut transcript: Transcript) -> Transcript {
+        let settings = get_settings(&self.app_handle);
+        if !settings.remove_filler_words {
+            return transcript;
+        }
+
+        let fillers = effective_filler_words(&settings, selected_language(&settings));
+        let cleaned = remove_filler_words(&transcript.text, &fillers);
+        if cleaned != transcript.text {
+            transcript.raw_text = Some(std::mem::replace(&mut transcript.text, cleaned));
+        }
+        transcript
+    }
+
+    /// Reformats a transcript with the preset of `binding_id`, if any. The
+    /// preset starts from the text before global filler word removal, so it
+    /// can keep fillers in as well as take them out.
+    async fn apply_preset(&self, mut transcript: Transcript, binding_id: &str) -> Transcript {
+        let settings = get_settings(&self.app_handle);
+        let Some(preset) = settings.preset_for_binding(binding_id) else {
+            return transcript;
+        };
+        debug!("Applying post-processing preset '{}'", preset.id);
+
+        let text = std::mem::take(&mut transcript.text);
+        let raw = transcript.raw_text.take().unwrap_or(text);
+        transcript.text = self.format_with_preset(&raw, &preset, &settings).await;
+        transcript.raw_text = (transcript.text != raw).then_some(raw);
+        transcript
+    }
+
+    async fn format_with_preset(
+        &self,
+        raw: &str,
+        preset: &PostProcessPreset,
+        settings: &AppSettings,
+    ) -> String {
+        let mut result = if preset.remove_filler_words {
+            let fillers = effective_filler_words(settings, selected_language(settings));
+            remove_filler_words(raw, &fillers)
+        } else {
+            raw.to_string()
+        };
+        if preset.capitalize {
+            result = capitalize_sentences(&result);
+        }
+        match preset.trailing_period {
+            Some(true) if !result.is_empty() && !result.ends_with(['.', '!', '?']) => {
+                result.push('.')
+            }
+            Some(false) => result = result.trim_end_matches('.').to_string(),
+            _ => {}
+        }
+        if let Some(prompt) = preset.llm_prompt.as_deref().filter(|_| preset.llm_rewrite) {
+            if settings.mistral_api_key.is_some() && !result.is_empty() {
+                match self.mistral_manager.rewrite(prompt, &result).await {
+                    Ok(rewritten) => result = rewritten.trim().to_string(),
+                    Err(e) => warn!("Preset '{}' rewrite failed: {}", preset.id, e),
+                }
+            }
+        }
+        result
+    }
+
+    /// Runs a stored raw transcript through the custom words, replacement
+    /// rules and the preset `preset_id` again, without transcribing. With no
+    /// preset, the global filler word setting applies as for a dictation.
+    pub async fn reprocess(&self, raw: String, preset_id: Option<&str>) -> Result<String> {
+        let settings = get_settings(&self.app_handle);
+        let preset = preset_id
+            .map(|id| {
+                settings
+                    .presets()
+                    .into_iter()
+                    .find(|p| p.id == id)
+                    .ok_or_else(|| anyhow::anyhow!("Unknown preset '{}'", id))
+            })
+            .transpose()?;
+
+        let language = selected_language(&settings);
+        // The entry's corrections were reported when it was dictated
+        let (text, _) = self.post_process(raw, &settings, language);
+        let text = text.trim();
+
+        Ok(match preset {
+            Some(preset) => self.format_with_preset(text, &preset, &settings).await,
+            None if settings.remove_filler_words => {
+                remove_filler_words(text, &effective_filler_words(&settings, language))
+            }
+            None => text.to_string(),
+        })
+    }
+
+    pub fn last_transcript(&self) -> Option<String> {
+        self.last_transcript.lock().unwrap().clone()
+    }
+
+    /// Backend the loaded local model runs on, `None` without one.
+    pub fn engine_backend(&self) -> Option<String> {
+        self.engine_backend.lock().unwrap().clone()
+    }
+
+    /// Backend the last dictation ran on, `api` for API providers.
+    pub fn last_backend(&self) -> Option<String> {
+        self.last_backend.lock().unwrap().clone()
+    }
+
+    /// Forgets the previous transcript, e.g. when starting a new document.
+    pub fn reset_context(&self) {
+        *self.previous_context.lock().unwrap() = None;
+        debug!("Dictation context reset");
+    }
+
+    /// The tail of the previous transcript to prompt Whisper with, if context
+    /// continuation is enabled and the last dictation was recent enough.
+    fn context_prompt(&self, settings: &AppSettings) -> Option<String> {
+        let continuation = &settings.context_continuation;
+        if !continuation.enabled || continuation.window_chars == 0 {
+            return None;
+        }
+
+        let previous = self.previous_context.lock().unwrap();
+        let (text, finished_at) = previous.as_ref()?;
+        if finished_at.elapsed() > Duration::from_secs(continuation.max_gap_seconds) {
+            return None;
+        }
+
+        let skip = text
+            .chars()
+            .count()
+            .saturating_sub(continuation.window_chars);
+        let tail: String = text.chars().skip(skip).collect();
+        // Start the prompt on a word boundary rather than mid-word
+        let tail = match tail.split_once(' ') {
+            Some((_, rest)) if skip > 0 => rest.to_string(),
+            _ => tail,
+        };
+        Some(tail)
+    }
+
+    /// The voice profile's glossary followed by the dictation context, so
+    /// the context stays closest to the audio.
+    fn initial_prompt(&self, settings: &AppSettings, dictation: bool) -> Option<String> {
+        let context = dictation.then(|| self.context_prompt(settings)).flatten();
+        match (settings.voice_profile.prompt(), context) {
+            (Some(profile), Some(context)) => Some(format!("{} {}", profile, context)),
+            (profile, context) => profile.or(context),
+        }
+    }
+
+    fn remember_context(&self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let max_gap = Duration::from_secs(
+            get_settings(&self.app_handle)
+                .context_continuation
+                .max_gap_seconds,
+        );
+
+        let mut previous = self.previous_context.lock().unwrap();
+        let combined = match previous.take() {
+            Some((before, finished_at)) if finished_at.elapsed() <= max_gap => {
+                format!("{} {}", before, text)
+            }
+            _ => text.to_string(),
+        };
+        // Only the tail is ever used, keep the stored text bounded
+        let skip = combined.chars().count().saturating_sub(MAX_CONTEXT_CHARS);
+        *previous = Some((combined.chars().skip(skip).collect(), Instant::now()));
+    }
+
+    /// Transcribes a dictation, prompted with the previous one and kept as
+    /// context for the next. Setting `cancel` stops it: a local model stops
+    /// decoding where the engine allows it and an API request is dropped,
+    /// and it fails with [`TranscriptionCancelled`].
+    pub async fn transcribe(
+        &self,
+        audio: &Recording,
+        cancel: &Arc<AtomicBool>,
+    ) -> Result<Transcript> {
+        let result = self.run_transcription(audio, true, cancel).await;
+        if let Ok(transcript) = &result {
+            self.remember_context(&transcript.text);
+            if !transcript.text.is_empty() {
+                *self.last_transcript.lock().unwrap() = Some(transcript.text.clone());
+            }
+            if transcript.backend.is_some() {
+                *self.last_backend.lock().unwrap() = transcript.backend.clone();
+            }
+        }
+        result
+    }
+
+    /// Transcribes audio that isn't part of the dictation, e.g. an imported
+    /// file or a spoken command. It runs without the dictation context and
+    /// leaves the context and the last transcript alone.
+    pub async fn transcribe_background(&self, audio: Vec<f32>) -> Result<Transcript> {
+        self.run_transcription(&audio.into(), false, &Arc::default())
+            .await
+    }
+
+    async fn run_transcription(
+        &self,
+        audio: &Recording,
+        dictation: bool,
+        cancel: &Arc<AtomicBool>,
+    ) -> Result<Transcript> {
+        if audio.is_empty() {
+            warn!("TranscriptionManager received empty audio buffer");
+            return Ok(Transcript::default());
+        }
+
+        let start = std::time::Instant::now();
+        let provider = metrics_provider(self.get_current_model().as_deref());
+        let result = self
+            .transcribe_in_chunks(audio, dictation, cancel)
+            .await
+            .map(|transcript| self.strip_filler_words(transcript));
+        self.metrics_manager
+            .record_transcription(provider, start.elapsed(), result.is_ok());
+        result
+    }
+
+    /// A rough transcript of a recording still in progress. Skips API models
+    /// and gives up instead of waiting when the model is busy or not loaded,
+    /// and a full transcription starting meanwhile stops it, so it never
+    /// holds up the final transcription. Nothing is recorded in the context,
+    /// history or metrics.
+    pub fn transcribe_partial(&self, audio: Vec<f32>) -> Option<String> {
+        let current_model = self.get_current_model()?;
+        if is_api_model(&current_model) {
+            return None;
+        }
+        let settings = get_settings(&self.app_handle);
+        // Set before taking the engine, so a full transcription that got in
+        // between still stops this one
+        let abort = Arc::new(AtomicBool::new(false));
+        *self.partial_abort.lock().unwrap() = Some(abort.clone());
+        let options = DecodeOptions {
+            abort: Some(abort),
+            ..self.model_decode_options(&settings, Some(&current_model), true)
+        };
+
+        let result =
+            self.engine.try_lock().ok().and_then(|mut engine_guard| {
+                Some(engine_guard.as_mut()?.transcribe(audio, &options))
+            });
+        *self.partial_abort.lock().unwrap() = None;
+        match result? {
+            Ok(result) => Some(result.text.trim().to_string()),
+            Err(e) if e.is::<TranscriptionCancelled>() => None,
+            Err(e) => {
+                debug!("Partial transcription failed: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Transcribes `audio` and formats it with the post-processing preset
+    /// assigned to `binding_id`.
+    pub async fn transcribe_for_binding(
+        &self,
+        audio: &Recording,
+        binding_id: &str,
+        cancel: &Arc<AtomicBool>,
+    ) -> Result<Transcript> {
+        let transcript = self.transcribe(audio, cancel).await?;
+        if transcript.text.is_empty() {
+            return Ok(transcript);
+        }
+        let transcript = self.apply_preset(transcript, binding_id).await;
+        *self.last_transcript.lock().unwrap() = Some(transcript.text.clone());
+        Ok(transcript)
+    }
+
+    /// Transcribes with an API provider, returning the transcript with any
+    /// alternatives and confidence scores the provider sent.
+    async fn transcribe_api(&self, model_id: &str, audio: Vec<f32>) -> Result<ApiTranscript> {
+        let result = match model_id {
+            "voxtral-mini" => {
+                self.mistral_manager
+                    .transcribe(audio)
+                    .await
+                    .map(|text| ApiTranscript {
+                        text,
+                        alternatives: Vec::new(),
+                        confidence: Vec::new(),
+                    })
+            }
+            "nova-3" => self.deepgram_manager.transcribe(audio).await.map(
+                |(text, alternatives, confidence)| ApiTranscript {
+                    text,
+                    alternatives,
+                    confidence,
+                },
+            ),
+            "universal" => {
+                self.assemblyai_manager
+                    .transcribe(audio)
+                    .await
+                    .map(|(text, confidence)| ApiTranscript {
+                        text,
+                        alternatives: Vec::new(),
+                        confidence,
+                    })
+            }
+            "whisper-zero" => {
+                self.gladia_manager
+                    .transcribe(audio)
+                    .await
+                    .map(|(text, confidence)| ApiTranscript {
+                        text,
+                        alternatives: Vec::new(),
+                        confidence,
+                    })
+            }
+            _ => Err(anyhow::anyhow!(
+                "Unsupported API model selected: {}",
+                model_id
+            )),
+        };
+        // A real request says as much about the provider as a ping
+        if let Some(provider_health) = self.app_handle.try_state::<Arc<ProviderHealthManager>>() {
+            provider_health.record(
+                model_id,
+                result.as_ref().map(|_| ()).map_err(|e| e.to_string()),
+            );
+        }
+        result
+    }
+
+    /// Makes a cheap request to the provider of an API model.
+    pub async fn ping_provider(&self, model_id: &str) -> Result<()> {
+        match model_id {
+            "voxtral-mini" => self.mistral_manager.ping().await,
+            "nova-3" => self.deepgram_manager.ping().await,
+            "universal" => self.assemblyai_manager.ping().await,
+            "whisper-zero" => self.gladia_manager.ping().await,
+            _ => Err(anyhow::anyhow!("Not an API model: {}", model_id)),
+        }
+    }
+
+    /// Transcribes with `model_id` without making it the active model, e.g.
+    /// for audio files, or with the active model when `None`. Runs in the
+    /// background, outside the dictation context.
+    pub async fn transcribe_with_model(
+        &self,
+        model_id: Option<&str>,
+        audio: Vec<f32>,
+    ) -> Result<String> {
+        let current = self.get_current_model();
+        let Some(model_id) = model_id.filter(|id| current.as_deref() != Some(*id)) else {
+            self.initiate_model_load();
+            return Ok(self.transcribe_background(audio).await?.text);
+        };
+
+        let text = if is_api_model(model_id) {
+            self.transcribe_api(model_id, audio).await?.text
+        } else {
+            self.compare_local(model_id, audio).await?.0
+        };
+        Ok(self.post_process_text(text))
+    }
+
+    /// Transcribes a recording of several people with the selected local
+    /// model and labels who said what, one "Speaker N:" paragraph per turn.
+    /// The model has to report segment timestamps.
+    pub async fn transcribe_with_speakers(&self, audio: Vec<f32>) -> Result<String> {
+        self.initiate_model_load();
+        {
+            let mut is_loading = self.is_loading.lock().unwrap();
+            while *is_loading {
+                is_loading = self.loading_condvar.wait(is_loading).unwrap();
+            }
+        }
+        let current_model = self
+            .get_current_model()
+            .ok_or_else(|| anyhow::anyhow!("Model is not loaded for transcription."))?;
+        if is_api_model(&current_model) {
+            return Err(anyhow::anyhow!("Speaker labels need a local model"));
+        }
+
+        let settings = get_settings(&self.app_handle);
+        let (result, language) = {
+            let mut engine_guard = self.engine.lock().unwrap();
+            let engine = engine_guard
+                .as_mut()
+                .ok_or_else(|| anyhow::anyhow!("Model is not loaded for transcription."))?;
+            // A separate recording, so no context from the last dictation
+            let result = engine.transcribe(audio.clone(), &decode_options(&settings))?;
+            let language = engine
+                .fixed_language()
+                .or_else(|| selected_language(&settings))
+                .map(str::to_string);
+            (result, language)
+        };
+
+        let segments: Vec<TimedSegment> = result
+            .segments
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "{} doesn't report timestamps to find speakers",
+                    current_model
+                )
+            })?
+            .into_iter()
+            .map(|s| TimedSegment {
+                start: s.start,
+                end: s.end,
+                text: s.text,
+            })
+            .collect();
+        let mut turns = diarization::speaker_turns(&audio, &segments);
+        for turn in &mut turns {
+            let text = std::mem::take(&mut turn.text);
+            turn.text = self.post_process(text, &settings, language.as_deref()).0;
+        }
+        turns.retain(|turn| !turn.text.is_empty());
+        Ok(diarization::format_turns(&turns))
+    }
+
+    /// Runs the same audio through two models in parallel and emits the
+    /// result as `transcription-comparison`. A model that isn't loaded is
+    /// loaded just for the comparison; the active model is left alone.
+    pub async fn compare(
+        &self,
+        audio: Vec<f32>,
+        model_a: &str,
+        model_b: &str,
+    ) -> Result<TranscriptionComparison> {
+        if model_a == model_b {
+            return Err(anyhow::anyhow!("Pick two different models to compare"));
+        }
+
+        let (a, b) = tokio::join!(
+            self.comparison_run(model_a, audio.clone()),
+            self.comparison_run(model_b, audio)
+        );
+        let diff = word_diff(
+            a.text.as_deref().unwrap_or_default(),
+            b.text.as_deref().unwrap_or_default(),
+        );
+        let comparison = TranscriptionComparison { a, b, diff };
+
+        events::emit(
+            &self.app_handle,
+            AppEvent::TranscriptionComparison(comparison.clone()),
+        );
+        Ok(comparison)
+    }
+
+    /// Measures a downloaded local model on `audio` and stores the result,
+    /// with the word error rate when the audio's `reference` transcript is
+    /// known. Models are loaded on the side, like for a comparison.
+    pub async fn benchmark(
+        &self,
+        model_id: &str,
+        audio: Vec<f32>,
+        reference: Option<&str>,
+    ) -> Result<ModelBenchmark> {
+        if is_api_model(model_id) {
+            return Err(anyhow::anyhow!("Only local models can be benchmarked"));
+        }
+        let audio_seconds = audio.len() as f32 / WHISPER_SAMPLE_RATE as f32;
+        if audio_seconds == 0.0 {
+            return Err(anyhow::anyhow!("Benchmark audio is empty"));
+        }
+
+        // A first run warms up caches and measures the load, the second
+        // one the transcription speed
+        let cold = self.comparison_run(model_id, audio.clone()).await;
+        if let Some(error) = cold.error {
+            return Err(anyhow::anyhow!(error));
+        }
+        let warm = self.comparison_run(model_id, audio).await;
+        let text = warm
+            .text
+            .ok_or_else(|| anyhow::anyhow!(warm.error.unwrap_or_default()))?;
+
+        let benchmark = ModelBenchmark {
+            model_id: model_id.to_string(),
+            audio_seconds,
+            load_ms: cold.load_ms,
+            transcribe_ms: warm.transcribe_ms,
+            real_time_factor: warm.transcribe_ms as f32 / 1000.0 / audio_seconds,
+            word_error_rate: reference.map(|reference| word_error_rate(reference, &text)),
+            measured_at: chrono::Utc::now().timestamp(),
+        };
+        info!(
+            "Benchmarked {}: RTF {:.3}, load {}ms",
+            model_id, benchmark.real_time_factor, benchmark.load_ms
+        );
+
+        let mut settings = get_settings(&self.app_handle);
+        settings
+            .model_benchmarks
+            .insert(model_id.to_string(), benchmark.clone());
+        write_settings(&self.app_handle, settings);
+        events::emit(
+            &self.app_handle,
+            AppEvent::ModelBenchmarkCompleted(benchmark.clone()),
+        );
+        Ok(benchmark)
+    }
+
+    async fn comparison_run(&self, model_id: &str, audio: Vec<f32>) -> ComparisonRun {
+        let start = Instant::now();
+        let result = if is_api_model(model_id) {
+            self.transcribe_api(model_id, audio)
+                .await
+                .map(|transcript| (transcript.text, 0))
+        } else {
+            self.compare_local(model_id, audio).await
+        };
+        let elapsed = start.elapsed().as_millis() as u64;
+
+        match result {
+            Ok((text, load_ms)) => ComparisonRun {
+                model_id: model_id.to_string(),
+                text: Some(text.trim().to_string()),
+                error: None,
+                load_ms,
+                transcribe_ms: elapsed.saturating_sub(load_ms),
+            },
+            Err(e) => ComparisonRun {
+                model_id: model_id.to_string(),
+                text: None,
+                error: Some(e.to_string()),
+                load_ms: 0,
+                transcribe_ms: elapsed,
+            },
+        }
+    }
+
+    /// Transcribes with a local model on a blocking thread, returning the
+    /// text and how long loading the model took.
+    async fn compare_local(&self, model_id: &str, audio: Vec<f32>) -> Result<(String, u64)> {
+        let model_info = self
+            .model_manager
+            .get_model_info(model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+        if !model_info.is_downloaded {
+            return Err(anyhow::anyhow!("Model not downloaded"));
+        }
+        let model_path = self.model_manager.get_model_path(model_id)?;
+        let is_loaded = self.get_current_model().as_deref() == Some(model_id);
+        let engine = self.engine.clone();
+        let settings = get_settings(&self.app_handle);
+
+        tauri::async_runtime::spawn_blocking(move || {
+            let options = decode_options(&settings);
+            if is_loaded {
+                if let Some(engine) = engine.lock().unwrap().as_mut() {
+                    return engine.transcribe(audio, &options).map(|r| (r.text, 0));
+                }
+            }
+            let load_start = Instant::now();
+            let (mut engine, _) = engine::load(
+                &model_info.engine_type,
+                &model_path,
+                &whisper_backend(settings.gpu_acceleration),
+                is_english_only_whisper(&model_info.id),
+            )?;
+            let load_ms = load_start.elapsed().as_millis() as u64;
+            engine
+                .transcribe(audio, &options)
+                .map(|r| (r.text, load_ms))
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!("Comparison task failed: {}", e))?
+    }
+
+    /// Decoding options for the current model, with the overrides saved for
+    /// the model and, for a dictation, the context prompt.
+    fn model_decode_options(
+        &self,
+        settings: &AppSettings,
+        model_id: Option<&str>,
+        dictation: bool,
+    ) -> DecodeOptions {
+        let overrides = model_id.and_then(|id| settings.model_overrides.get(id));
+        let options = decode_options(settings);
+        DecodeOptions {
+            initial_prompt: self.initial_prompt(settings, dictation),
+            no_speech_threshold: overrides
+                .and_then(|o| o.no_speech_threshold)
+                .or(options.no_speech_threshold),
+            suppress_blank: overrides.and_then(|o| o.suppress_blank),
+            ..options
+        }
+    }
+
+    /// Cuts the silence out of `audio` with the recorder's VAD settings.
+    /// Returns nothing when there is no speech at all, and the audio as is
+    /// when the VAD can't run.
+    fn trim_silence(&self, audio: Vec<f32>, settings: &AppSettings) -> Vec<f32> {
+        if !settings.trim_silence {
+            return audio;
+        }
+        let mut vad = self.vad.lock().unwrap();
+        if vad.as_ref().map(|(threshold, _)| *threshold) != Some(settings.vad_threshold) {
+            let path = match self.app_handle.path().resolve(
+                "resources/models/silero_vad_v4.onnx",
+                tauri::path::BaseDirectory::Resource,
+            ) {
+                Ok(path) => path,
+                Err(e) => {
+                    warn!("Failed to resolve VAD path: {}", e);
+                    return audio;
+                }
+            };
+            match SileroVad::new(path, settings.vad_threshold) {
+                Ok(silero) => {
+                    let smoothed = SmoothedVad::new(Box::new(silero), 15, 15, 2);
+                    *vad = Some((settings.vad_threshold, smoothed));
+                }
+                Err(e) => {
+                    warn!("Failed to create SileroVad: {}", e);
+                    return audio;
+                }
+            }
+        }
+        let Some((_, vad)) = vad.as_mut() else {
+            return audio;
+        };
+
+        match trim_silence(vad, &audio) {
+            Ok(speech) if speech.is_empty() => speech,
+            Ok(mut speech) => {
+                debug!(
+                    "Trimmed {} of {} samples of silence",
+                    audio.len().saturating_sub(speech.len()),
+                    audio.len()
+                );
+                if speech.len() < MIN_SPEECH_SAMPLES {
+                    speech.resize(MIN_SPEECH_SAMPLES, 0.0);
+                }
+                speech
+            }
+            Err(e) => {
+                warn!("Trimming silence failed: {}", e);
+                audio
+            }
+        }
+    }
+
+    /// Transcribes recordings longer than the chunk setting piece by piece,
+    /// split at pauses, and reports every finished piece so a long
+    /// recording doesn't look stuck.
+    async fn transcribe_in_chunks(
+        &self,
+        audio: &Recording,
+        dictation: bool,
+        cancel: &Arc<AtomicBool>,
+    ) -> Result<Transcript> {
+        let settings = get_settings(&self.app_handle);
+        let max_len = settings.chunk_transcription_seconds as usize * WHISPER_SAMPLE_RATE as usize;
+        let result = if max_len == 0 || audio.len() <= max_len {
+            match audio.samples() {
+                Ok(samples) => {
+                    self.transcribe_with_current_model(samples, dictation, cancel)
+                        .await
+                }
+                Err(e) => Err(e.into()),
+            }
+        } else {
+            self.transcribe_chunks(audio, max_len, dictation, cancel)
+                .await
+        };
+
+        // Only once every chunk is done, they all need the model
+        if settings.model_unload_timeout == ModelUnloadTimeout::Immediately
+            && self.engine.lock().unwrap().is_some()
+        {
+            println!("⚡ Immediately unloading model after transcription");
+            if let Err(e) = self.unload_model() {
+                eprintln!("Failed to immediately unload model: {}", e);
+            }
+        }
+        result
+    }
+
+    /// Reads the recording one chunk at a time, so a long recording that
+    /// was spilled to disk is never in memory as a whole.
+    async fn transcribe_chunks(
+        &self,
+        audio: &Recording,
+        max_len: usize,
+        dictation: bool,
+        cancel: &Arc<AtomicBool>,
+    ) -> Result<Transcript> {
+        info!(
+            "Transcribing a long recording in chunks of up to {}s",
+            max_len / WHISPER_SAMPLE_RATE as usize
+        );
+
+        let mut texts = Vec::new();
+        let mut merged = Transcript::default();
+        let mut offset = 0;
+        let mut completed = 0;
+        while offset < audio.len() {
+            events::emit(
+                &self.app_handle,
+                AppEvent::TranscriptionProgress(TranscriptionProgress {
+                    completed,
+                    total: completed + (audio.len() - offset).div_ceil(max_len),
+                }),
+            );
+            // One sample past the limit tells whether the rest needs a cut
+            let window = audio.read(offset, max_len + 1)?;
+            let chunk = split_at_pauses(&window, max_len, CHUNK_SEARCH_SAMPLES)[0].to_vec();
+            // Segment times are relative to the chunk
+            let chunk_start = offset as f32 / WHISPER_SAMPLE_RATE as f32;
+            offset += chunk.len();
+
+            let transcript = self
+                .transcribe_with_current_model(chunk, dictation, cancel)
+                .await?;
+            merged
+                .confidence
+                .extend(transcript.confidence.into_iter().map(|mut segment| {
+                    segment.start += chunk_start;
+                    segment.end += chunk_start;
+                    segment
+                }));
+            merged.corrections.extend(transcript.corrections);
+            merged.backend = transcript.backend.or(merged.backend);
+            if !transcript.text.is_empty() {
+                texts.push(transcript.text);
+            }
+            completed += 1;
+        }
+        events::emit(
+            &self.app_handle,
+            AppEvent::TranscriptionProgress(TranscriptionProgress {
+                completed,
+                total: completed,
+            }),
+        );
+        // Alternatives are for the whole transcript, a chunk's don't fit
+        merged.text = texts.join(" ");
+        Ok(merged)
+    }
+
+    async fn transcribe_with_current_model(
+        &self,
+        audio: Vec<f32>,
+        dictation: bool,
+        cancel: &Arc<AtomicBool>,
+    ) -> Result<Transcript> {
+        // Later chunks of a cancelled recording don't start
+        check_cancelled(cancel)?;
+
         // Update last activity timestamp
         self.last_activity.store(
             SystemTime::now()
@@ -364,46 +1447,50 @@ impl TranscriptionManager {
 
         let st = std::time::Instant::now();
 
-        if audio.is_empty() {
-            warn!("TranscriptionManager received empty audio buffer");
-            return Ok(String::new());
-        }
-
         let settings = get_settings(&self.app_handle);
         let current_model = self.get_current_model();
 
         if let Some(model_id) = current_model.clone() {
             if is_api_model(&model_id) {
                 info!("Using API-based model '{}' for transcription", model_id);
-                let transcript = match model_id.as_str() {
-                    "voxtral-mini" => self.mistral_manager.transcribe(audio).await,
-                    "nova-3" => self.deepgram_manager.transcribe(audio).await,
-                    "universal" => self.assemblyai_manager.transcribe(audio).await,
-                    "whisper-zero" => self.gladia_manager.transcribe(audio).await,
-                    _ => Err(anyhow::anyhow!(
-                        "Unsupported API model selected: {}",
-                        model_id
-                    )),
-                }?;
-
-                let corrected_result = if !settings.custom_words.is_empty() {
-                    apply_custom_words(
-                        &transcript,
-                        &settings.custom_words,
-                        settings.word_correction_threshold,
-                    )
-                } else {
-                    transcript
+                let audio_seconds = audio.len() as f32 / WHISPER_SAMPLE_RATE as f32;
+                let api_start = Instant::now();
+                // Dropping the request future abandons the upload or polling
+                let request = pin!(self.transcribe_api(&model_id, audio));
+                let transcript = match select(request, pin!(cancelled(cancel))).await {
+                    Either::Left((transcript, _)) => transcript?,
+                    Either::Right(_) => return Err(TranscriptionCancelled.into()),
                 };
+                self.model_manager
+                    .record_usage(&model_id, audio_seconds, api_start.elapsed());
+                let (corrected_result, corrections) =
+                    self.post_process(transcript.text, &settings, selected_language(&settings));
 
                 debug!(
                     "API transcription completed in {}ms",
                     st.elapsed().as_millis()
                 );
-                return Ok(corrected_result.trim().to_string());
+                return Ok(Transcript {
+                    text: corrected_result.trim().to_string(),
+                    alternatives: transcript
+                        .alternatives
+                        .into_iter()
+                        .map(|a| a.trim().to_string())
+                        .collect(),
+                    confidence: transcript.confidence,
+                    corrections,
+                    backend: Some("api".to_string()),
+                    ..Default::default()
+                });
             }
         }
 
+        let audio = self.trim_silence(audio, &settings);
+        if audio.is_empty() {
+            debug!("No speech in the audio, skipping transcription");
+            return Ok(Transcript::default());
+        }
+
         {
             // If the model is loading, wait for it to complete.
             let mut is_loading = self.is_loading.lock().unwrap();
@@ -411,6 +1498,11 @@ impl TranscriptionManager {
                 is_loading = self.loading_condvar.wait(is_loading).unwrap();
             }
 
+            // A partial transcription would keep the engine until it's done
+            if let Some(abort) = self.partial_abort.lock().unwrap().as_ref() {
+                abort.store(true, Ordering::Relaxed);
+            }
+
             let engine_guard = self.engine.lock().unwrap();
             if engine_guard.is_none() {
                 return Err(anyhow::anyhow!("Model is not loaded for transcription."));
@@ -418,7 +1510,8 @@ impl TranscriptionManager {
         }
 
         // Perform transcription with the appropriate engine
-        let result = {
+        let audio_seconds = audio.len() as f32 / WHISPER_SAMPLE_RATE as f32;
+        let (result, confidence, language, transcribe_time) = {
             let mut engine_guard = self.engine.lock().unwrap();
             let engine = engine_guard.as_mut().ok_or_else(|| {
                 anyhow::anyhow!(
@@ -426,63 +1519,166 @@ impl TranscriptionManager {
                 )
             })?;
 
-            match engine {
-                LoadedEngine::Whisper(whisper_engine) => {
-                    let params = WhisperInferenceParams {
-                        language: if settings.selected_language == "auto" {
-                            None
-                        } else {
-                            Some(settings.selected_language.clone())
-                        },
-                        translate: settings.translate_to_english,
-                        ..Default::default()
-                    };
-
-                    whisper_engine
-                        .transcribe_samples(audio, Some(params))
-                        .map_err(|e| anyhow::anyhow!("Whisper transcription failed: {}", e))?
-                }
-                LoadedEngine::Parakeet(parakeet_engine) => {
-                    let params = ParakeetInferenceParams {
-                        timestamp_granularity: TimestampGranularity::Segment,
-                        ..Default::default()
-                    };
-
-                    parakeet_engine
-                        .transcribe_samples(audio, Some(params))
-                        .map_err(|e| anyhow::anyhow!("Parakeet transcription failed: {}", e))?
-                }
-            }
+            let options = DecodeOptions {
+                abort: Some(cancel.clone()),
+                ..self.model_decode_options(&settings, current_model.as_deref(), dictation)
+            };
+            let on_gpu = self
+                .engine_backend()
+                .is_some_and(|backend| backend != "cpu");
+            // Kept for a retry on the CPU if the GPU fails
+            let retry_audio = on_gpu.then(|| audio.clone());
+
+            let transcribe_start = Instant::now();
+            let (result, confidence) = match engine.transcribe_scored(audio, &options) {
+                Ok(scored) => scored,
+                Err(e) if e.is::<TranscriptionCancelled>() => return Err(e),
+                Err(e) => match (retry_audio, current_model.as_deref()) {
+                    (Some(audio), Some(model_id)) => {
+                        *engine = self.reload_on_cpu(model_id, &e.to_string())?;
+                        engine.transcribe_scored(audio, &options)?
+                    }
+                    _ => return Err(e),
+                },
+            };
+            // Engines that can't stop early still finish, drop their result
+            check_cancelled(cancel)?;
+            let language = engine
+                .fixed_language()
+                .or_else(|| selected_language(&settings));
+            (result, confidence, language, transcribe_start.elapsed())
         };
+        if let Some(model_id) = current_model.as_deref() {
+            self.model_manager
+                .record_usage(model_id, audio_seconds, transcribe_time);
+        }
 
-        // Apply word correction if custom words are configured
-        let corrected_result = if !settings.custom_words.is_empty() {
-            apply_custom_words(
-                &result.text,
-                &settings.custom_words,
-                settings.word_correction_threshold,
-            )
-        } else {
-            result.text
+        let (corrected_result, corrections) = match paragraphs(&result, &settings) {
+            // Each paragraph is corrected on its own so the breaks survive
+            Some(paragraphs) => {
+                let mut corrections = Vec::new();
+                let text = paragraphs
+                    .into_iter()
+                    .map(|p| {
+                        let (text, made) = self.post_process(p, &settings, language);
+                        corrections.extend(made);
+                        text.trim().to_string()
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n\n");
+                (text, corrections)
+            }
+            None => self.post_process(result.text, &settings, language),
         };
 
+        let backend = self.engine_backend();
+
         let et = std::time::Instant::now();
         let translation_note = if settings.translate_to_english {
             " (translated)"
         } else {
             ""
         };
-        println!("\ntook {}ms{}", (et - st).as_millis(), translation_note);
+        println!(
+            "\ntook {}ms{} on {}",
+            (et - st).as_millis(),
+            translation_note,
+            backend.as_deref().unwrap_or("cpu")
+        );
 
-        // Check if we should immediately unload the model after transcription
-        if settings.model_unload_timeout == ModelUnloadTimeout::Immediately {
-            println!("⚡ Immediately unloading model after transcription");
-            if let Err(e) = self.unload_model() {
-                eprintln!("Failed to immediately unload model: {}", e);
-            }
-        }
+        Ok(Transcript {
+            text: corrected_result.trim().to_string(),
+            confidence,
+            corrections,
+            backend,
+            ..Default::default()
+        })
+    }
+}
+
+fn check_cancelled(cancel: &AtomicBool) -> Result<()> {
+    if cancel.load(Ordering::Relaxed) {
+        return Err(TranscriptionCancelled.into());
+    }
+    Ok(())
+}
+
+/// Resolves once `cancel` is set.
+async fn cancelled(cancel: &AtomicBool) {
+    while !cancel.load(Ordering::Relaxed) {
+        tokio::time::sleep(CANCEL_POLL).await;
+    }
+}
+
+/// The transcript split into paragraphs when paragraph breaks are on and
+/// the engine returned segment timestamps.
+fn paragraphs(result: &TranscriptionResult, settings: &AppSettings) -> Option<Vec<String>> {
+    let pause_secs = settings.paragraph_breaks.pause_secs()?;
+    let segments: Vec<TimedSegment> = result
+        .segments
+        .as_ref()?
+        .iter()
+        .map(|s| TimedSegment {
+            start: s.start,
+            end: s.end,
+            text: s.text.clone(),
+        })
+        .collect();
+    let paragraphs = segment_paragraphs(&segments, pause_secs);
+    (paragraphs.len() > 1).then_some(paragraphs)
+}
+
+/// Size of a model's files, which is a file or a directory of them.
+fn model_size(path: &Path) -> u64 {
+    let size = |path: &Path| path.metadata().map(|m| m.len()).unwrap_or(0);
+    match fs::read_dir(path) {
+        Ok(entries) => entries
+            .filter_map(|e| Some(e.ok()?.path()))
+            .filter(|p| p.is_file())
+            .map(|p| size(&p))
+            .sum(),
+        Err(_) => size(path),
+    }
+}
+
+/// Decoding options from the global settings alone, without context
+/// prompting or per-model overrides.
+fn decode_options(settings: &AppSettings) -> DecodeOptions {
+    DecodeOptions {
+        language: (settings.selected_language != "auto")
+            .then(|| settings.selected_language.clone()),
+        translate: settings.translate_to_english,
+        threads: settings.cpu_usage.threads,
+        low_priority: settings.cpu_usage.low_priority,
+        sampling: settings.whisper_sampling,
+        fallback: settings.whisper_fallback,
+        filter_hallucinations: settings.filter_hallucinations,
+        no_speech_threshold: settings.no_speech_threshold,
+        suppress_non_speech: Some(settings.suppress_non_speech_tokens),
+        ..Default::default()
+    }
+}
+
+/// The language the transcript is in when the user picked one, `None` for
+/// auto-detection. Translation always produces English.
+fn selected_language(settings: &AppSettings) -> Option<&str> {
+    if settings.translate_to_english {
+        Some("en")
+    } else if settings.selected_language == "auto" {
+        None
+    } else {
+        Some(settings.selected_language.as_str())
+    }
+}
 
-        Ok(corrected_result.trim().to_string())
+/// Label used for the provider dimension of the transcription metrics.
+fn metrics_provider(model_id: Option<&str>) -> &'static str {
+    match model_id {
+        Some("voxtral-mini") => "mistral",
+        Some("nova-3") => "deepgram",
+        Some("universal") => "assemblyai",
+        Some("whisper-zero") => "gladia",
+        _ => "local",
     }
 }
 
@@ -0,0 +1,231 @@
+use crate::events::{self, AppEvent};
+use log::{debug, info};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::AppHandle;
+use tokio::sync::Notify;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Queued,
+    Running,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TranscriptionJob {
+    pub job_id: u64,
+    pub binding_id: String,
+    pub state: JobState,
+    /// Jobs that run before this one.
+    pub jobs_ahead: usize,
+    /// Set to stop this job's transcription.
+    #[serde(skip)]
+    cancel: Arc<AtomicBool>,
+}
+
+/// Runs the transcriptions of recordings one at a time, in the order their
+/// audio was taken from the recorder. A recording that finishes while
+/// another is still being transcribed waits its turn here instead of on the
+/// engine lock, and every job reports `transcription-queued`,
+/// `transcription-started` and `transcription-finished` events.
+pub struct TranscriptionQueue {
+    app_handle: AppHandle,
+    jobs: Mutex<JobList>,
+    /// Woken whenever a job leaves, so the next one can check its turn.
+    advanced: Notify,
+}
+
+impl TranscriptionQueue {
+    pub fn new(app_handle: &AppHandle) -> Arc<Self> {
+        Arc::new(Self {
+            app_handle: app_handle.clone(),
+            jobs: Mutex::new(JobList::default()),
+            advanced: Notify::new(),
+        })
+    }
+
+    /// Takes a job's audio with `take` and queues the job for `binding_id`
+    /// in the same step, so audio taken first is also transcribed first.
+    /// Nothing is queued when `take` has no audio. The job waits for its
+    /// turn with [`JobTicket::wait`].
+    pub fn reserve<T>(
+        &self,
+        binding_id: &str,
+        take: impl FnOnce() -> Option<T>,
+    ) -> Option<(JobTicket<'_>, T)> {
+        let (queued, audio) = {
+            let mut jobs = self.jobs.lock().unwrap();
+            let audio = take()?;
+            (jobs.push(binding_id), audio)
+        };
+        debug!(
+            "Transcription job {} queued behind {} others",
+            queued.job_id, queued.jobs_ahead
+        );
+        let turn = JobTurn {
+            queue: self,
+            job_id: queued.job_id,
+            cancel: queued.cancel.clone(),
+        };
+        events::emit(&self.app_handle, AppEvent::TranscriptionQueued(queued));
+
+        Some((JobTicket(turn), audio))
+    }
+
+    /// Jobs waiting or running, in the order they run.
+    pub fn jobs(&self) -> Vec<TranscriptionJob> {
+        self.jobs.lock().unwrap().jobs.clone()
+    }
+
+    /// Stops the transcription of the running job: a local model stops
+    /// decoding where the engine allows it and an API request is dropped.
+    /// The transcription then fails with [`TranscriptionCancelled`]. Jobs
+    /// still waiting their turn are left alone. Returns whether a job was
+    /// running.
+    ///
+    /// [`TranscriptionCancelled`]: crate::engine::TranscriptionCancelled
+    pub fn cancel_running(&self) -> bool {
+        let running = self
+            .jobs
+            .lock()
+            .unwrap()
+            .jobs
+            .first()
+            .filter(|job| job.state == JobState::Running)
+            .map(|job| (job.job_id, job.cancel.clone()));
+        let Some((job_id, cancel)) = running else {
+            return false;
+        };
+        info!("Cancelling transcription job {}", job_id);
+        cancel.store(true, Ordering::Relaxed);
+        events::emit(&self.app_handle, AppEvent::TranscriptionCancelled(()));
+        true
+    }
+
+    fn finish(&self, job_id: u64) {
+        let finished = self.jobs.lock().unwrap().remove(job_id);
+        self.advanced.notify_waiters();
+        if let Some(finished) = finished {
+            debug!("Transcription job {} finished", job_id);
+            events::emit(&self.app_handle, AppEvent::TranscriptionFinished(finished));
+        }
+    }
+}
+
+/// The jobs in the order they run, the first one running.
+#[derive(Default)]
+struct JobList {
+    last_id: u64,
+    jobs: Vec<TranscriptionJob>,
+}
+
+impl JobList {
+    fn push(&mut self, binding_id: &str) -> TranscriptionJob {
+        self.last_id += 1;
+        let job = TranscriptionJob {
+            job_id: self.last_id,
+            binding_id: binding_id.to_string(),
+            state: JobState::Queued,
+            jobs_ahead: self.jobs.len(),
+            cancel: Arc::default(),
+        };
+        self.jobs.push(job.clone());
+        job
+    }
+
+    /// Marks `job_id` running if every job before it has finished.
+    fn start(&mut self, job_id: u64) -> Option<TranscriptionJob> {
+        let job = self.jobs.first_mut().filter(|job| job.job_id == job_id)?;
+        job.state = JobState::Running;
+        Some(job.clone())
+    }
+
+    fn remove(&mut self, job_id: u64) -> Option<TranscriptionJob> {
+        let index = self.jobs.iter().position(|j| j.job_id == job_id)?;
+        let removed = self.jobs.remove(index);
+        // Everyone behind moved up
+        for (ahead, job) in self.jobs.iter_mut().enumerate() {
+            job.jobs_ahead = ahead;
+        }
+        Some(removed)
+    }
+}
+
+/// A job's place in the [`TranscriptionQueue`]. Dropping it before its turn
+/// takes the job out of the queue.
+pub struct JobTicket<'a>(JobTurn<'a>);
+
+impl<'a> JobTicket<'a> {
+    /// Waits until every job queued before this one has finished. The job
+    /// runs until the returned turn is dropped.
+    pub async fn wait(self) -> JobTurn<'a> {
+        let queue = self.0.queue;
+        loop {
+            // Created before checking, so a job finishing in between still
+            // wakes it
+            let advanced = queue.advanced.notified();
+            let started = queue.jobs.lock().unwrap().start(self.0.job_id);
+            if let Some(started) = started {
+                events::emit(&queue.app_handle, AppEvent::TranscriptionStarted(started));
+                return self.0;
+            }
+            advanced.await;
+        }
+    }
+}
+
+/// A job's turn in the [`TranscriptionQueue`]. Dropping it reports the job
+/// finished and lets the next one run.
+pub struct JobTurn<'a> {
+    queue: &'a TranscriptionQueue,
+    pub job_id: u64,
+    /// Set by [`TranscriptionQueue::cancel_running`], for the transcription
+    /// to stop on.
+    pub cancel: Arc<AtomicBool>,
+}
+
+impl Drop for JobTurn<'_> {
+    fn drop(&mut self) {
+        self.queue.finish(self.job_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jobs_start_in_the_order_they_were_queued() {
+        let mut list = JobList::default();
+        let first = list.push("transcribe");
+        let second = list.push("transcribe");
+        assert_eq!(second.jobs_ahead, 1);
+
+        assert!(list.start(second.job_id).is_none());
+        assert!(list.start(first.job_id).is_some());
+        assert!(list.start(second.job_id).is_none());
+
+        list.remove(first.job_id);
+        let started = list.start(second.job_id).unwrap();
+        assert_eq!(started.state, JobState::Running);
+        assert_eq!(started.jobs_ahead, 0);
+    }
+
+    #[test]
+    fn a_job_leaving_early_moves_the_rest_up() {
+        let mut list = JobList::default();
+        let first = list.push("transcribe");
+        let second = list.push("transcribe");
+        let third = list.push("transcribe");
+
+        assert_eq!(list.remove(second.job_id).unwrap().job_id, second.job_id);
+        assert_eq!(list.jobs[1].job_id, third.job_id);
+        assert_eq!(list.jobs[1].jobs_ahead, 1);
+        assert!(list.start(third.job_id).is_none());
+
+        list.remove(first.job_id);
+        assert!(list.start(third.job_id).is_some());
+    }
+}
@@ -0,0 +1,252 @@
+use crate::audio_toolkit::ReplacementRule;
+use crate::events::{self, AppEvent};
+use crate::settings::{get_settings, AppSettings, LanguageVocabulary};
+use anyhow::Result;
+use chrono::Utc;
+use log::{debug, error, info};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+const SHARED_VOCABULARY_FILE: &str = "shared_vocabulary.json";
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// A team-maintained vocabulary fetched from a remote URL. It is read-only
+/// and sits beneath the user's personal custom words and rules.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SharedVocabulary {
+    #[serde(default)]
+    pub custom_words: Vec<String>,
+    #[serde(default)]
+    pub replacement_rules: Vec<ReplacementRule>,
+    #[serde(default)]
+    pub source_url: Option<String>,
+    #[serde(default)]
+    pub fetched_at: Option<i64>,
+}
+
+pub struct VocabularyManager {
+    app_handle: AppHandle,
+    cache_path: PathBuf,
+    shared: Mutex<SharedVocabulary>,
+    /// Words for this session only, e.g. the names of people in today's
+    /// meeting. They aren't saved and are gone after a restart.
+    session_words: Mutex<Vec<String>>,
+    client: reqwest::Client,
+}
+
+impl VocabularyManager {
+    pub fn new(app_handle: &AppHandle) -> Result<Arc<Self>> {
+        let cache_path = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| anyhow::anyhow!("Failed to get app data dir: {}", e))?
+            .join(SHARED_VOCABULARY_FILE);
+
+        // Start from the last fetched copy so the vocabulary works offline
+        let shared = fs::read_to_string(&cache_path)
+            .ok()
+            .and_then(|c| serde_json::from_str(&c).ok())
+            .unwrap_or_default();
+
+        let manager = Arc::new(Self {
+            app_handle: app_handle.clone(),
+            cache_path,
+            shared: Mutex::new(shared),
+            session_words: Mutex::new(Vec::new()),
+            client: reqwest::Client::new(),
+        });
+
+        let weak = Arc::downgrade(&manager);
+        tauri::async_runtime::spawn(async move {
+            while let Some(manager) = weak.upgrade() {
+                if get_settings(&manager.app_handle)
+                    .shared_vocabulary_url
+                    .is_some()
+                {
+                    if let Err(e) = manager.refresh().await {
+                        error!("Failed to refresh shared vocabulary: {}", e);
+                    }
+                }
+                drop(manager);
+                tokio::time::sleep(REFRESH_INTERVAL).await;
+            }
+        });
+
+        Ok(manager)
+    }
+
+    pub fn get_shared(&self) -> SharedVocabulary {
+        self.shared.lock().unwrap().clone()
+    }
+
+    /// Fetches the shared vocabulary from the configured URL and caches it.
+    pub async fn refresh(&self) -> Result<SharedVocabulary> {
+        let url = match get_settings(&self.app_handle).shared_vocabulary_url {
+            Some(url) => url,
+            None => {
+                self.clear()?;
+                return Ok(SharedVocabulary::default());
+            }
+        };
+
+        debug!("Fetching shared vocabulary from {}", url);
+        let response = self.client.get(&url).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow::anyhow!(
+                "Shared vocabulary request failed with status {}",
+                status
+            ));
+        }
+
+        let body = response.text().await?;
+        let mut vocabulary = parse_shared_vocabulary(&body);
+        vocabulary.source_url = Some(url);
+        vocabulary.fetched_at = Some(Utc::now().timestamp());
+
+        fs::write(&self.cache_path, serde_json::to_string_pretty(&vocabulary)?)?;
+        *self.shared.lock().unwrap() = vocabulary.clone();
+
+        info!(
+            "Shared vocabulary updated: {} words, {} rules",
+            vocabulary.custom_words.len(),
+            vocabulary.replacement_rules.len()
+        );
+        events::emit(
+            &self.app_handle,
+            AppEvent::SharedVocabularyUpdated(vocabulary.clone()),
+        );
+
+        Ok(vocabulary)
+    }
+
+    pub fn clear(&self) -> Result<()> {
+        *self.shared.lock().unwrap() = SharedVocabulary::default();
+        if self.cache_path.exists() {
+            fs::remove_file(&self.cache_path)?;
+        }
+        Ok(())
+    }
+
+    pub fn session_words(&self) -> Vec<String> {
+        self.session_words.lock().unwrap().clone()
+    }
+
+    /// Replaces the session's words, dropping blanks and duplicates.
+    pub fn set_session_words(&self, words: Vec<String>) -> Vec<String> {
+        let mut unique: Vec<String> = Vec::new();
+        for word in words {
+            let word = word.trim();
+            if !word.is_empty() && !unique.iter().any(|w| w.eq_ignore_ascii_case(word)) {
+                unique.push(word.to_string());
+            }
+        }
+        info!("Session vocabulary set to {} words", unique.len());
+        *self.session_words.lock().unwrap() = unique.clone();
+        unique
+    }
+
+    /// Personal custom words, then the session's words, then words for the
+    /// transcript's language, then any shared words not already present.
+    pub fn effective_custom_words(
+        &self,
+        settings: &AppSettings,
+        language: Option<&str>,
+    ) -> Vec<String> {
+        let shared = self.shared.lock().unwrap();
+        let session_words = self.session_words.lock().unwrap();
+        let mut words = settings.custom_words.clone();
+        let language_words = language_vocabulary(settings, language)
+            .map(|v| v.custom_words.as_slice())
+            .unwrap_or_default();
+        for word in session_words
+            .iter()
+            .chain(language_words)
+            .chain(&shared.custom_words)
+        {
+            if !words.iter().any(|w| w.eq_ignore_ascii_case(word)) {
+                words.push(word.clone());
+            }
+        }
+        words
+    }
+
+    /// Shared rules first, then personal rules, then rules for the
+    /// transcript's language; a later rule for the same phrase replaces an
+    /// earlier one.
+    pub fn effective_replacement_rules(
+        &self,
+        settings: &AppSettings,
+        language: Option<&str>,
+    ) -> Vec<ReplacementRule> {
+        let shared = self.shared.lock().unwrap();
+        let layers = [
+            shared.replacement_rules.as_slice(),
+            settings.replacement_rules.as_slice(),
+            language_vocabulary(settings, language)
+                .map(|v| v.replacement_rules.as_slice())
+                .unwrap_or_default(),
+        ];
+
+        let mut rules: Vec<ReplacementRule> = Vec::new();
+        for layer in layers {
+            rules.retain(|rule| {
+                !layer
+                    .iter()
+                    .any(|own| own.from.eq_ignore_ascii_case(&rule.from))
+            });
+            rules.extend(layer.iter().cloned());
+        }
+        rules
+    }
+}
+
+/// Looks up the lists for a language, matching `de` for `de-AT` as well.
+/// Filler words for the transcript's language, falling back to the
+/// default list when the language has none of its own.
+pub fn effective_filler_words(settings: &AppSettings, language: Option<&str>) -> Vec<String> {
+    language_vocabulary(settings, language)
+        .map(|v| v.filler_words.clone())
+        .filter(|words| !words.is_empty())
+        .unwrap_or_else(|| settings.filler_words.clone())
+}
+
+fn language_vocabulary<'a>(
+    settings: &'a AppSettings,
+    language: Option<&str>,
+) -> Option<&'a LanguageVocabulary> {
+    let language = language?.to_lowercase();
+    let base = language.split(['-', '_']).next().unwrap_or(&language);
+    settings
+        .language_vocabularies
+        .iter()
+        .find(|(code, _)| code.to_lowercase() == language)
+        .or_else(|| {
+            settings
+                .language_vocabularies
+                .iter()
+                .find(|(code, _)| code.to_lowercase() == base)
+        })
+        .map(|(_, vocabulary)| vocabulary)
+}
+
+/// Accepts either the JSON format or a plain list with one custom word per line.
+fn parse_shared_vocabulary(body: &str) -> SharedVocabulary {
+    if let Ok(vocabulary) = serde_json::from_str::<SharedVocabulary>(body) {
+        return vocabulary;
+    }
+
+    SharedVocabulary {
+        custom_words: body
+            .lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .map(|l| l.to_string())
+            .collect(),
+        ..Default::default()
+    }
+}
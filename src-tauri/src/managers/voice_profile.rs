@@ -0,0 +1,157 @@
+use crate::audio_toolkit::{word_diff, DiffSegment};
+use crate::managers::audio::AudioRecordingManager;
+use crate::managers::transcription::TranscriptionManager;
+use crate::settings::{get_settings, write_settings, VoiceProfile};
+use anyhow::Result;
+use log::info;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tauri::AppHandle;
+
+/// Recording binding used while a calibration sentence is read.
+const CALIBRATION_BINDING: &str = "voice_profile";
+const MAX_PROFILE_KEYWORDS: usize = 50;
+/// Shorter words are mostly function words, biasing towards them only hurts.
+const MIN_KEYWORD_CHARS: usize = 3;
+
+/// A calibration sentence as read by the user and as it was recognized.
+#[derive(Clone, Debug, Serialize)]
+pub struct CalibrationSample {
+    pub expected: String,
+    pub transcript: String,
+    /// Words of the sentence that were not recognized.
+    pub missed: Vec<String>,
+}
+
+/// Builds a voice profile from sentences the user reads aloud. Words that
+/// keep getting misrecognized, typically names, companies and jargon,
+/// become keywords that bias later transcriptions towards them.
+pub struct VoiceProfileManager {
+    app_handle: AppHandle,
+    recording_manager: Arc<AudioRecordingManager>,
+    transcription_manager: Arc<TranscriptionManager>,
+    samples: Mutex<Vec<CalibrationSample>>,
+}
+
+impl VoiceProfileManager {
+    pub fn new(
+        app_handle: &AppHandle,
+        recording_manager: Arc<AudioRecordingManager>,
+        transcription_manager: Arc<TranscriptionManager>,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            app_handle: app_handle.clone(),
+            recording_manager,
+            transcription_manager,
+            samples: Mutex::new(Vec::new()),
+        })
+    }
+
+    pub fn start_sample(&self) -> Result<()> {
+        if !self
+            .recording_manager
+            .try_start_recording(CALIBRATION_BINDING)
+        {
+            return Err(anyhow::anyhow!(
+                "Can't start recording, another recording is in progress"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Stops the sample recording and compares what was recognized with the
+    /// sentence the user read.
+    pub async fn finish_sample(&self, expected: &str) -> Result<CalibrationSample> {
+        let audio = self
+            .recording_manager
+            .stop_recording(CALIBRATION_BINDING)
+            .map(|recording| recording.audio)
+            .ok_or_else(|| anyhow::anyhow!("No calibration sample is being recorded"))?;
+
+        self.transcription_manager.initiate_model_load();
+        // Calibration sentences shouldn't prompt the next real dictation
+        let transcript = self
+            .transcription_manager
+            .transcribe_background(audio.into_samples())
+            .await?
+            .text;
+
+        let missed = word_diff(expected, &transcript)
+            .into_iter()
+            .filter_map(|segment| match segment {
+                DiffSegment::Removed(words) => Some(words),
+                _ => None,
+            })
+            .flat_map(|words| {
+                words
+                    .split_whitespace()
+                    .filter_map(keyword)
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        let sample = CalibrationSample {
+            expected: expected.to_string(),
+            transcript,
+            missed,
+        };
+        self.samples.lock().unwrap().push(sample.clone());
+        Ok(sample)
+    }
+
+    pub fn samples(&self) -> Vec<CalibrationSample> {
+        self.samples.lock().unwrap().clone()
+    }
+
+    pub fn clear_samples(&self) {
+        self.samples.lock().unwrap().clear();
+    }
+
+    /// Adds the words missed most often across the recorded samples to the
+    /// profile's keywords and enables the profile.
+    pub fn build_profile(&self) -> Result<VoiceProfile> {
+        let samples = self.samples();
+        if samples.is_empty() {
+            return Err(anyhow::anyhow!("Record at least one calibration sentence"));
+        }
+
+        let mut counts: HashMap<String, (usize, usize)> = HashMap::new();
+        for (order, word) in samples.iter().flat_map(|s| s.missed.iter()).enumerate() {
+            counts.entry(word.clone()).or_insert((0, order)).0 += 1;
+        }
+        let mut missed: Vec<(String, (usize, usize))> = counts.into_iter().collect();
+        // Most frequent first, then in the order they were read
+        missed.sort_by(|(_, (ca, oa)), (_, (cb, ob))| cb.cmp(ca).then(oa.cmp(ob)));
+
+        let mut settings = get_settings(&self.app_handle);
+        let profile = &mut settings.voice_profile;
+        for (word, _) in missed {
+            if !profile
+                .keywords
+                .iter()
+                .any(|k| k.to_lowercase() == word.to_lowercase())
+            {
+                profile.keywords.push(word);
+            }
+        }
+        profile.keywords.truncate(MAX_PROFILE_KEYWORDS);
+        profile.enabled = true;
+
+        let profile = profile.clone();
+        info!(
+            "Voice profile built from {} samples, {} keywords",
+            samples.len(),
+            profile.keywords.len()
+        );
+        write_settings(&self.app_handle, settings);
+        self.clear_samples();
+        Ok(profile)
+    }
+}
+
+/// The word without surrounding punctuation, if it is worth biasing towards.
+fn keyword(word: &str) -> Option<String> {
+    let word = word.trim_matches(|c: char| !c.is_alphanumeric());
+    (word.chars().count() >= MIN_KEYWORD_CHARS).then(|| word.to_string())
+}
@@ -0,0 +1,238 @@
+use crate::audio_toolkit::{decode_audio_file, DECODABLE_EXTENSIONS};
+use crate::events::{self, AppEvent};
+use crate::managers::history::{HistoryManager, ImportedEntry};
+use crate::managers::transcription::TranscriptionManager;
+use crate::settings::{get_settings, WatchFolderConfig};
+use anyhow::Result;
+use log::{debug, info, warn};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// A file must stay unchanged this long before it is picked up, so files
+/// still being synced or copied aren't read half-written.
+const SETTLE_TIME: Duration = Duration::from_secs(10);
+const MAX_ATTEMPTS: u32 = 3;
+/// Doubled after every failed attempt.
+const RETRY_BACKOFF: Duration = Duration::from_secs(60);
+/// History source of watched files.
+const SOURCE_NAME: &str = "Watch folder";
+
+#[derive(Clone, Debug, Serialize)]
+pub struct WatchedFileEvent {
+    pub path: String,
+    pub text: Option<String>,
+    pub error: Option<String>,
+}
+
+struct SeenFile {
+    size: u64,
+    modified: Option<SystemTime>,
+    unchanged_since: Instant,
+}
+
+struct Failure {
+    attempts: u32,
+    retry_at: Instant,
+}
+
+/// Transcribes audio files that appear in the configured folder, writing the
+/// transcript next to each file and into history.
+pub struct WatchFolderManager {
+    app_handle: AppHandle,
+    transcription_manager: Arc<TranscriptionManager>,
+    history_manager: Arc<HistoryManager>,
+    seen: Mutex<HashMap<PathBuf, SeenFile>>,
+    failures: Mutex<HashMap<PathBuf, Failure>>,
+}
+
+impl WatchFolderManager {
+    pub fn new(
+        app_handle: &AppHandle,
+        transcription_manager: Arc<TranscriptionManager>,
+        history_manager: Arc<HistoryManager>,
+    ) -> Arc<Self> {
+        let manager = Arc::new(Self {
+            app_handle: app_handle.clone(),
+            transcription_manager,
+            history_manager,
+            seen: Mutex::new(HashMap::new()),
+            failures: Mutex::new(HashMap::new()),
+        });
+
+        let weak = Arc::downgrade(&manager);
+        thread::spawn(move || loop {
+            thread::sleep(POLL_INTERVAL);
+            let Some(manager) = weak.upgrade() else {
+                break;
+            };
+            if let Some(config) = get_settings(&manager.app_handle).watch_folder {
+                tauri::async_runtime::block_on(manager.scan(&config));
+            }
+        });
+
+        manager
+    }
+
+    /// Gives files that failed too often another round of attempts.
+    pub fn retry_failed(&self) {
+        self.failures.lock().unwrap().clear();
+    }
+
+    async fn scan(&self, config: &WatchFolderConfig) {
+        let entries = match fs::read_dir(&config.path) {
+            Ok(entries) => entries,
+            Err(e) => {
+                debug!("Watch folder {} unavailable: {}", config.path, e);
+                return;
+            }
+        };
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(|e| Some(e.ok()?.path()))
+            .filter(|p| p.is_file() && is_audio_file(p) && !transcript_path(p).exists())
+            .collect();
+        files.sort();
+
+        for path in files {
+            if self.is_ready(&path) {
+                self.process(&path, config).await;
+            }
+        }
+    }
+
+    /// Whether a file has settled and isn't waiting for a retry.
+    fn is_ready(&self, path: &Path) -> bool {
+        let Ok(metadata) = fs::metadata(path) else {
+            return false;
+        };
+        let (size, modified) = (metadata.len(), metadata.modified().ok());
+
+        let mut seen = self.seen.lock().unwrap();
+        let entry = seen.entry(path.to_path_buf()).or_insert(SeenFile {
+            size,
+            modified,
+            unchanged_since: Instant::now(),
+        });
+        if entry.size != size || entry.modified != modified {
+            *entry = SeenFile {
+                size,
+                modified,
+                unchanged_since: Instant::now(),
+            };
+            return false;
+        }
+        if entry.unchanged_since.elapsed() < SETTLE_TIME {
+            return false;
+        }
+
+        match self.failures.lock().unwrap().get(path) {
+            Some(failure) => failure.attempts < MAX_ATTEMPTS && failure.retry_at <= Instant::now(),
+            None => true,
+        }
+    }
+
+    async fn process(&self, path: &Path, config: &WatchFolderConfig) {
+        info!("Transcribing watched file {}", path.display());
+        match self.transcribe_file(path, config).await {
+            Ok(text) => {
+                self.seen.lock().unwrap().remove(path);
+                self.failures.lock().unwrap().remove(path);
+                events::emit(
+                    &self.app_handle,
+                    AppEvent::WatchFolderTranscribed(WatchedFileEvent {
+                        path: path.to_string_lossy().to_string(),
+                        text: Some(text),
+                        error: None,
+                    }),
+                );
+            }
+            Err(e) => {
+                let mut failures = self.failures.lock().unwrap();
+                let failure = failures.entry(path.to_path_buf()).or_insert(Failure {
+                    attempts: 0,
+                    retry_at: Instant::now(),
+                });
+                failure.attempts += 1;
+                failure.retry_at = Instant::now() + RETRY_BACKOFF * 2u32.pow(failure.attempts - 1);
+                warn!(
+                    "Failed to transcribe {} (attempt {}): {}",
+                    path.display(),
+                    failure.attempts,
+                    e
+                );
+                if failure.attempts >= MAX_ATTEMPTS {
+                    events::emit(
+                        &self.app_handle,
+                        AppEvent::WatchFolderFailed(WatchedFileEvent {
+                            path: path.to_string_lossy().to_string(),
+                            text: None,
+                            error: Some(e.to_string()),
+                        }),
+                    );
+                }
+            }
+        }
+    }
+
+    async fn transcribe_file(&self, path: &Path, config: &WatchFolderConfig) -> Result<String> {
+        let decode_path = path.to_path_buf();
+        let samples = tauri::async_runtime::spawn_blocking(move || decode_audio_file(decode_path))
+            .await
+            .map_err(|e| anyhow::anyhow!("Decoding task failed: {}", e))??;
+        let text = if config.speaker_labels {
+            self.transcription_manager
+                .transcribe_with_speakers(samples)
+                .await?
+        } else {
+            self.transcription_manager
+                .transcribe_with_model(config.model.as_deref(), samples)
+                .await?
+        };
+
+        // The transcript next to the file also marks it as done
+        fs::write(transcript_path(path), &text)?;
+        if !text.is_empty() {
+            self.history_manager
+                .insert_imported_entry(history_entry(path, text))?;
+        }
+        Ok(text)
+    }
+}
+
+fn is_audio_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| DECODABLE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+fn transcript_path(path: &Path) -> PathBuf {
+    path.with_extension("txt")
+}
+
+/// History entry for a transcribed file, dated when the file was recorded.
+fn history_entry(path: &Path, text: String) -> ImportedEntry {
+    let timestamp = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_else(|| chrono::Utc::now().timestamp());
+    ImportedEntry {
+        file_name: String::new(),
+        timestamp,
+        saved: false,
+        title: path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string()),
+        transcription_text: text,
+        tags: Vec::new(),
+        app_name: Some(SOURCE_NAME.to_string()),
+        sync_id: None,
+    }
+}
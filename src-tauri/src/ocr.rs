@@ -0,0 +1,86 @@
+//! Reads text out of an image on the clipboard, e.g. a screenshot region, so
+//! it can go through the same post-processing and paste path as dictation.
+//!
+//! Recognition runs the `tesseract` command line tool, which has to be
+//! installed and on the PATH.
+
+use crate::settings::get_settings;
+use anyhow::{anyhow, Context, Result};
+use log::debug;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// Runs OCR on the image currently on the clipboard.
+pub fn recognize_clipboard_image(app: &AppHandle) -> Result<String> {
+    let image = app
+        .clipboard()
+        .read_image()
+        .map_err(|e| anyhow!("No image on the clipboard: {}", e))?;
+
+    let path = std::env::temp_dir().join(format!(
+        "handy-ocr-{}.ppm",
+        chrono::Utc::now().timestamp_millis()
+    ));
+    write_ppm(&path, image.rgba(), image.width(), image.height())?;
+
+    let language = get_settings(app).ocr_language;
+    let result = run_tesseract(&path, &language);
+    let _ = fs::remove_file(&path);
+    result
+}
+
+/// Writes RGBA pixels as a binary PPM, which tesseract reads without any
+/// image encoder on our side. Transparent pixels are flattened onto white.
+fn write_ppm(path: &Path, rgba: &[u8], width: u32, height: u32) -> Result<()> {
+    let mut data = Vec::with_capacity(rgba.len() / 4 * 3 + 32);
+    write!(data, "P6\n{} {}\n255\n", width, height)?;
+    for pixel in rgba.chunks_exact(4) {
+        let alpha = pixel[3] as u32;
+        for &channel in &pixel[..3] {
+            data.push(((channel as u32 * alpha + 255 * (255 - alpha)) / 255) as u8);
+        }
+    }
+    fs::write(path, data).context("Failed to write image for OCR")
+}
+
+fn run_tesseract(image: &Path, language: &str) -> Result<String> {
+    let output = Command::new("tesseract")
+        .arg(image)
+        .arg("stdout")
+        .args(["-l", language])
+        .output()
+        .context("Failed to run tesseract, is it installed?")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "tesseract failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let text = String::from_utf8_lossy(&output.stdout);
+    debug!("OCR recognized {} characters", text.len());
+    Ok(join_lines(&text))
+}
+
+/// Tesseract keeps the line breaks of the image. Lines within a paragraph
+/// are joined so the text reflows where it is pasted, blank lines stay
+/// paragraph breaks.
+fn join_lines(text: &str) -> String {
+    text.split("\n\n")
+        .map(|paragraph| {
+            paragraph
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .filter(|paragraph| !paragraph.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
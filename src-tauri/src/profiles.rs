@@ -0,0 +1,234 @@
+//! In-app profiles for people sharing one OS account, e.g. a family
+//! computer. Each profile has its own settings, API keys and history below
+//! `profiles/<id>` in the app data directory, optionally behind a PIN.
+//! Downloaded models are shared.
+//!
+//! The default profile keeps using the top-level files, so installs without
+//! profiles are unaffected. The active profile is picked at launch;
+//! switching restarts the app so every manager starts from the new
+//! profile's data.
+
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use log::info;
+use once_cell::sync::OnceCell;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+const PROFILES_FILE: &str = "profiles.json";
+const PROFILES_DIR: &str = "profiles";
+const PIN_ROUNDS: u32 = 100_000;
+const SALT_LEN: usize = 16;
+
+/// Profile the app was launched with, `None` for the default profile.
+static ACTIVE_PROFILE: OnceCell<Option<String>> = OnceCell::new();
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct StoredProfile {
+    id: String,
+    name: String,
+    #[serde(default)]
+    pin_salt: Option<String>,
+    #[serde(default)]
+    pin_hash: Option<String>,
+    created_at: i64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ProfileIndex {
+    #[serde(default)]
+    active: Option<String>,
+    #[serde(default)]
+    profiles: Vec<StoredProfile>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ProfileInfo {
+    pub id: String,
+    pub name: String,
+    pub has_pin: bool,
+    pub is_active: bool,
+}
+
+fn index_path(app: &AppHandle) -> Result<PathBuf> {
+    Ok(app.path().app_data_dir()?.join(PROFILES_FILE))
+}
+
+fn read_index(app: &AppHandle) -> ProfileIndex {
+    index_path(app)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn write_index(app: &AppHandle, index: &ProfileIndex) -> Result<()> {
+    fs::write(index_path(app)?, serde_json::to_string_pretty(index)?)?;
+    Ok(())
+}
+
+pub fn active_profile(app: &AppHandle) -> Option<&'static str> {
+    ACTIVE_PROFILE
+        .get_or_init(|| {
+            let index = read_index(app);
+            // A profile deleted by hand falls back to the default one
+            index
+                .active
+                .filter(|id| index.profiles.iter().any(|p| &p.id == id))
+        })
+        .as_deref()
+}
+
+/// Path of the active profile's settings store, relative to the app data
+/// directory like the store plugin expects.
+pub fn settings_store_path(app: &AppHandle) -> String {
+    match active_profile(app) {
+        Some(id) => format!(
+            "{}/{}/{}",
+            PROFILES_DIR,
+            id,
+            crate::settings::SETTINGS_STORE_PATH
+        ),
+        None => crate::settings::SETTINGS_STORE_PATH.to_string(),
+    }
+}
+
+/// Directory holding the active profile's history and recordings.
+pub fn profile_data_dir(app: &AppHandle) -> Result<PathBuf> {
+    let app_data_dir = app.path().app_data_dir()?;
+    Ok(match active_profile(app) {
+        Some(id) => app_data_dir.join(PROFILES_DIR).join(id),
+        None => app_data_dir,
+    })
+}
+
+pub fn list_profiles(app: &AppHandle) -> Vec<ProfileInfo> {
+    let active = active_profile(app);
+    read_index(app)
+        .profiles
+        .into_iter()
+        .map(|p| ProfileInfo {
+            is_active: active == Some(p.id.as_str()),
+            has_pin: p.pin_hash.is_some(),
+            id: p.id,
+            name: p.name,
+        })
+        .collect()
+}
+
+pub fn create_profile(app: &AppHandle, name: &str, pin: Option<&str>) -> Result<ProfileInfo> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(anyhow!("Profile name can't be empty"));
+    }
+
+    let mut index = read_index(app);
+    if index
+        .profiles
+        .iter()
+        .any(|p| p.name.eq_ignore_ascii_case(name))
+    {
+        return Err(anyhow!("A profile named {} already exists", name));
+    }
+
+    let created_at = chrono::Utc::now().timestamp_millis();
+    let id = format!("profile-{}", created_at);
+    fs::create_dir_all(app.path().app_data_dir()?.join(PROFILES_DIR).join(&id))?;
+
+    let (pin_salt, pin_hash) = match pin.filter(|p| !p.is_empty()) {
+        Some(pin) => {
+            let mut salt = [0u8; SALT_LEN];
+            rand::thread_rng().fill_bytes(&mut salt);
+            (Some(encode(&salt)), Some(hash_pin(pin, &salt)))
+        }
+        None => (None, None),
+    };
+    index.profiles.push(StoredProfile {
+        id: id.clone(),
+        name: name.to_string(),
+        pin_salt,
+        pin_hash,
+        created_at,
+    });
+    write_index(app, &index)?;
+
+    info!("Created profile {}", name);
+    Ok(ProfileInfo {
+        has_pin: pin.is_some_and(|p| !p.is_empty()),
+        id,
+        name: name.to_string(),
+        is_active: false,
+    })
+}
+
+/// Deletes a profile with its settings and history. The active profile
+/// can't be deleted.
+pub fn delete_profile(app: &AppHandle, id: &str, pin: Option<&str>) -> Result<()> {
+    if active_profile(app) == Some(id) {
+        return Err(anyhow!(
+            "Switch to another profile before deleting this one"
+        ));
+    }
+    let mut index = read_index(app);
+    let profile = index
+        .profiles
+        .iter()
+        .find(|p| p.id == id)
+        .ok_or_else(|| anyhow!("Profile not found: {}", id))?;
+    verify_pin(profile, pin)?;
+
+    index.profiles.retain(|p| p.id != id);
+    write_index(app, &index)?;
+    let dir = app.path().app_data_dir()?.join(PROFILES_DIR).join(id);
+    if dir.exists() {
+        fs::remove_dir_all(dir)?;
+    }
+    Ok(())
+}
+
+/// Makes `id` the profile to launch with, `None` for the default profile,
+/// and restarts the app into it.
+pub fn switch_profile(app: &AppHandle, id: Option<&str>, pin: Option<&str>) -> Result<()> {
+    let mut index = read_index(app);
+    if let Some(id) = id {
+        let profile = index
+            .profiles
+            .iter()
+            .find(|p| p.id == id)
+            .ok_or_else(|| anyhow!("Profile not found: {}", id))?;
+        verify_pin(profile, pin)?;
+    }
+    if active_profile(app) == id {
+        return Ok(());
+    }
+
+    index.active = id.map(str::to_string);
+    write_index(app, &index)?;
+    info!("Switching to profile {}", id.unwrap_or("default"));
+    app.restart();
+}
+
+fn verify_pin(profile: &StoredProfile, pin: Option<&str>) -> Result<()> {
+    let (Some(salt), Some(expected)) = (&profile.pin_salt, &profile.pin_hash) else {
+        return Ok(());
+    };
+    let salt = STANDARD.decode(salt)?;
+    match pin {
+        Some(pin) if hash_pin(pin, &salt) == *expected => Ok(()),
+        _ => Err(anyhow!("Wrong PIN for profile {}", profile.name)),
+    }
+}
+
+fn hash_pin(pin: &str, salt: &[u8]) -> String {
+    let mut hash = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(pin.as_bytes(), salt, PIN_ROUNDS, &mut hash);
+    encode(&hash)
+}
+
+fn encode(bytes: &[u8]) -> String {
+    STANDARD.encode(bytes)
+}
@@ -1,3 +1,4 @@
+use crate::audio_toolkit::ReplacementRule;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tauri::AppHandle;
@@ -71,6 +72,15 @@ pub enum ClipboardHandling {
     CopyToClipboard,
 }
 
+/// What happens to a recording that is still running when the session locks.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LockBehavior {
+    /// Stop, transcribe and keep the result in history without pasting it.
+    SaveToHistory,
+    Discard,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum SoundTheme {
@@ -79,6 +89,59 @@ pub enum SoundTheme {
     Custom,
 }
 
+/// How eagerly long dictations are split into paragraphs at pauses.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ParagraphBreaks {
+    Off,
+    Low,
+    Medium,
+    High,
+}
+
+impl ParagraphBreaks {
+    /// Pause that starts a new paragraph, `None` when breaks are off.
+    pub fn pause_secs(self) -> Option<f32> {
+        match self {
+            ParagraphBreaks::Off => None,
+            ParagraphBreaks::Low => Some(3.0),
+            ParagraphBreaks::Medium => Some(2.0),
+            ParagraphBreaks::High => Some(1.2),
+        }
+    }
+}
+
+impl Default for ParagraphBreaks {
+    fn default() -> Self {
+        ParagraphBreaks::Off
+    }
+}
+
+/// Which releases the update checker and the model catalog follow.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReleaseChannel {
+    Stable,
+    Beta,
+    Nightly,
+}
+
+impl ReleaseChannel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReleaseChannel::Stable => "stable",
+            ReleaseChannel::Beta => "beta",
+            ReleaseChannel::Nightly => "nightly",
+        }
+    }
+}
+
+impl Default for ReleaseChannel {
+    fn default() -> Self {
+        ReleaseChannel::Stable
+    }
+}
+
 impl Default for ModelUnloadTimeout {
     fn default() -> Self {
         ModelUnloadTimeout::Never
@@ -104,6 +167,12 @@ impl Default for ClipboardHandling {
     }
 }
 
+impl Default for LockBehavior {
+    fn default() -> Self {
+        LockBehavior::SaveToHistory
+    }
+}
+
 impl SoundTheme {
     fn as_str(&self) -> &'static str {
         match self {
@@ -122,6 +191,555 @@ impl SoundTheme {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct HistoryFilter {
+    #[serde(default)]
+    pub query: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub app_name: Option<String>,
+    #[serde(default)]
+    pub from_timestamp: Option<i64>,
+    #[serde(default)]
+    pub to_timestamp: Option<i64>,
+    /// Relative date range, e.g. 7 for "this week". Combined with `from_timestamp` if both are set.
+    #[serde(default)]
+    pub within_days: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SavedSearch {
+    pub id: String,
+    pub name: String,
+    pub filter: HistoryFilter,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CloudBackupConfig {
+    /// S3-compatible endpoint, e.g. `https://s3.eu-central-1.amazonaws.com`
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+}
+
+/// Folder whose audio files are transcribed as they appear, e.g. voice memos
+/// synced from a phone.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WatchFolderConfig {
+    pub path: String,
+    /// Model to transcribe with, the selected model when `None`.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Label who is speaking. Always uses the selected model.
+    #[serde(default)]
+    pub speaker_labels: bool,
+}
+
+/// IMAP mailbox whose emailed audio attachments are transcribed, for devices
+/// that can only share recordings by mail. The password is kept in the system
+/// keychain rather than in settings.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EmailIngestConfig {
+    pub server: String,
+    #[serde(default = "default_imap_port")]
+    pub port: u16,
+    pub username: String,
+    #[serde(default = "default_imap_mailbox")]
+    pub mailbox: String,
+    /// Only mail sent to this address is picked up, e.g. a `+memos` alias.
+    #[serde(default)]
+    pub recipient: Option<String>,
+    /// Model to transcribe with, the selected model when `None`.
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// A control on a generic HID device (foot pedal, presenter) bound to an action.
+/// The control is identified by the bit that flips in the device's input report.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HidBinding {
+    pub binding_id: String,
+    pub vendor_id: u16,
+    pub product_id: u16,
+    #[serde(default)]
+    pub device_name: String,
+    pub report_index: usize,
+    pub mask: u8,
+}
+
+/// The MIDI message that triggers a binding. Notes are pressed while held, control
+/// changes are pressed while the value is at or above the midpoint.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MidiTrigger {
+    Note { channel: u8, note: u8 },
+    ControlChange { channel: u8, controller: u8 },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MidiAction {
+    /// Press and release a shortcut binding such as `transcribe`.
+    Binding {
+        binding_id: String,
+    },
+    Cancel,
+    SelectModel {
+        model_id: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MidiBinding {
+    /// Only react to this input port; any port when unset.
+    #[serde(default)]
+    pub port_name: Option<String>,
+    pub trigger: MidiTrigger,
+    pub action: MidiAction,
+}
+
+/// What changes while the computer runs on battery.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct BatterySaverPolicy {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Model to use on battery, typically a smaller local model or an API provider.
+    #[serde(default)]
+    pub model_id: Option<String>,
+    #[serde(default)]
+    pub disable_always_on_microphone: bool,
+}
+
+/// How much of the CPU local Whisper transcription may take.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CpuUsage {
+    /// Threads Whisper decodes with, `None` for its default of up to four.
+    #[serde(default)]
+    pub threads: Option<u32>,
+    /// Run transcription below normal priority, so it yields to the apps in
+    /// use at the cost of taking longer when the CPU is busy.
+    #[serde(default)]
+    pub low_priority: bool,
+}
+
+/// How Whisper picks the next token. Greedy is the fastest; beam search is
+/// slower but noticeably more accurate on the larger models.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum WhisperSampling {
+    /// Keeps the best of `best_of` candidates when retrying a segment at a
+    /// higher temperature.
+    Greedy {
+        best_of: u32,
+    },
+    BeamSearch {
+        beam_size: u32,
+    },
+}
+
+impl Default for WhisperSampling {
+    fn default() -> Self {
+        WhisperSampling::BeamSearch { beam_size: 3 }
+    }
+}
+
+/// Whisper.cpp decodes with at most this many candidates.
+pub const MAX_WHISPER_CANDIDATES: u32 = 8;
+
+/// When Whisper gives up on a decoded segment and retries it at a higher
+/// temperature. The defaults are whisper.cpp's own and are the safe choice;
+/// raising `temperature_inc` or lowering `entropy_threshold` helps when a
+/// model gets stuck repeating a phrase, at the cost of more retries.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct WhisperFallback {
+    /// Temperature of the first attempt, 0 picks the most likely tokens.
+    #[serde(default)]
+    pub temperature: f32,
+    /// Added to the temperature on each retry, 0 turns retries off.
+    #[serde(default = "default_temperature_inc")]
+    pub temperature_inc: f32,
+    /// Segments whose tokens are less varied than this, e.g. a phrase
+    /// repeated over and over, are retried.
+    #[serde(default = "default_entropy_threshold")]
+    pub entropy_threshold: f32,
+    /// Segments with an average token log probability below this are
+    /// retried.
+    #[serde(default = "default_logprob_threshold")]
+    pub logprob_threshold: f32,
+}
+
+impl Default for WhisperFallback {
+    fn default() -> Self {
+        Self {
+            temperature: 0.0,
+            temperature_inc: default_temperature_inc(),
+            entropy_threshold: default_entropy_threshold(),
+            logprob_threshold: default_logprob_threshold(),
+        }
+    }
+}
+
+fn default_temperature_inc() -> f32 {
+    0.2
+}
+
+fn default_entropy_threshold() -> f32 {
+    2.4
+}
+
+fn default_logprob_threshold() -> f32 {
+    -1.0
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UnusedModelAction {
+    #[default]
+    Off,
+    /// Let the user know which models could be removed.
+    Suggest,
+    /// Ask the user to confirm deleting them.
+    Delete,
+}
+
+/// What to do about downloaded models that haven't been used for a while.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UnusedModelPolicy {
+    #[serde(default)]
+    pub action: UnusedModelAction,
+    #[serde(default = "default_unused_model_days")]
+    pub days: u32,
+}
+
+impl Default for UnusedModelPolicy {
+    fn default() -> Self {
+        Self {
+            action: UnusedModelAction::Off,
+            days: default_unused_model_days(),
+        }
+    }
+}
+
+/// Two models to compare every recording with while debug mode is on.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ComparisonModels {
+    pub model_a: String,
+    pub model_b: String,
+}
+
+/// How a local model performed on this machine with the reference clip.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ModelBenchmark {
+    pub model_id: String,
+    pub audio_seconds: f32,
+    pub load_ms: u64,
+    pub transcribe_ms: u64,
+    /// Transcription time over audio duration, below 1 is faster than real time.
+    pub real_time_factor: f32,
+    /// Only known when the clip's transcript was available.
+    pub word_error_rate: Option<f32>,
+    pub measured_at: i64,
+}
+
+impl ModelBenchmark {
+    pub fn speed_score(&self) -> f32 {
+        speed_score(self.real_time_factor)
+    }
+
+    pub fn accuracy_score(&self) -> Option<f32> {
+        self.word_error_rate.map(|wer| (1.0 - wer).clamp(0.0, 1.0))
+    }
+}
+
+/// A practice sentence as the user read it and how well it was recognized.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PracticeScore {
+    pub sentence: String,
+    pub transcript: String,
+    pub word_error_rate: f32,
+    #[serde(default)]
+    pub model_id: Option<String>,
+    /// `None` for the system default.
+    #[serde(default)]
+    pub microphone: Option<String>,
+    pub recorded_at: i64,
+}
+
+/// How fast a model transcribed the user's recordings on this machine.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ModelUsageStats {
+    pub transcriptions: u64,
+    /// Moving average of transcription time over audio duration, weighted
+    /// towards recent transcriptions.
+    pub real_time_factor: f32,
+    pub updated_at: i64,
+}
+
+impl ModelUsageStats {
+    /// Share of a new measurement in the moving average.
+    const WEIGHT: f32 = 0.2;
+
+    pub fn record(&mut self, real_time_factor: f32) {
+        self.real_time_factor = if self.transcriptions == 0 {
+            real_time_factor
+        } else {
+            self.real_time_factor + Self::WEIGHT * (real_time_factor - self.real_time_factor)
+        };
+        self.transcriptions += 1;
+        self.updated_at = chrono::Utc::now().timestamp();
+    }
+}
+
+/// Speed on the 0 to 1 scale of `ModelInfo::speed_score`, 0.5 at a real
+/// time factor of 0.1.
+pub fn speed_score(real_time_factor: f32) -> f32 {
+    1.0 / (1.0 + 10.0 * real_time_factor)
+}
+
+/// Words the user's voice tends to get wrong, learned from calibration
+/// sentences and used to bias recognition towards them.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct VoiceProfile {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Also send the keywords as boosted terms to API providers that
+    /// support it.
+    #[serde(default)]
+    pub boost_provider_keywords: bool,
+}
+
+impl VoiceProfile {
+    /// Whisper prompt that primes the model with the keywords' spelling.
+    pub fn prompt(&self) -> Option<String> {
+        if !self.enabled || self.keywords.is_empty() {
+            return None;
+        }
+        Some(format!("Glossary: {}.", self.keywords.join(", ")))
+    }
+
+    /// Keywords to boost with API providers.
+    pub fn provider_keywords(&self) -> &[String] {
+        if self.enabled && self.boost_provider_keywords {
+            &self.keywords
+        } else {
+            &[]
+        }
+    }
+}
+
+/// Settings that switch along with the model, for values that work better
+/// with one model than another. `None` keeps the global setting.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ModelOverrides {
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub translate_to_english: Option<bool>,
+    /// Whisper's no-speech probability above which a segment is dropped.
+    #[serde(default)]
+    pub no_speech_threshold: Option<f32>,
+    /// Whisper only: suppress blank outputs at the start of a segment.
+    #[serde(default)]
+    pub suppress_blank: Option<bool>,
+    /// Post-processing preset to make active.
+    #[serde(default)]
+    pub preset: Option<String>,
+}
+
+impl ModelOverrides {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Formatting applied to a transcript before it is pasted, e.g. for an
+/// email or a commit message.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PostProcessPreset {
+    pub id: String,
+    pub name: String,
+    /// Overrides the global filler word setting while the preset is used.
+    #[serde(default)]
+    pub remove_filler_words: bool,
+    /// Capitalize the first letter of every sentence.
+    #[serde(default)]
+    pub capitalize: bool,
+    /// `Some(true)` ends the text with a period, `Some(false)` removes a
+    /// trailing one, `None` leaves it as transcribed.
+    #[serde(default)]
+    pub trailing_period: Option<bool>,
+    /// Instruction for rewriting the text with an LLM.
+    #[serde(default)]
+    pub llm_prompt: Option<String>,
+    /// Sends the text to the LLM provider for `llm_prompt`. Off unless the
+    /// user turns it on, since the text leaves the machine even when it was
+    /// transcribed locally.
+    #[serde(default)]
+    pub llm_rewrite: bool,
+}
+
+/// Presets that ship with the app. User presets with the same id replace
+/// them.
+pub fn builtin_presets() -> Vec<PostProcessPreset> {
+    vec![
+        PostProcessPreset {
+            id: "verbatim".to_string(),
+            name: "Verbatim".to_string(),
+            remove_filler_words: false,
+            capitalize: false,
+            trailing_period: None,
+            llm_prompt: None,
+            llm_rewrite: false,
+        },
+        PostProcessPreset {
+            id: "email".to_string(),
+            name: "Email".to_string(),
+            remove_filler_words: true,
+            capitalize: true,
+            trailing_period: Some(true),
+            llm_prompt: Some(
+                "Rewrite the dictated text as the body of a clear, friendly email. \
+                 Keep its meaning, language and first-person voice. \
+                 Reply with the email text only."
+                    .to_string(),
+            ),
+            llm_rewrite: false,
+        },
+        PostProcessPreset {
+            id: "commit_message".to_string(),
+            name: "Commit message".to_string(),
+            remove_filler_words: true,
+            capitalize: true,
+            trailing_period: Some(false),
+            llm_prompt: Some(
+                "Turn the dictated text into a git commit message: an imperative \
+                 summary line of at most 72 characters without a trailing period, \
+                 followed by a blank line and a short body only if needed. \
+                 Reply with the commit message only."
+                    .to_string(),
+            ),
+            llm_rewrite: false,
+        },
+        PostProcessPreset {
+            id: "chat_reply".to_string(),
+            name: "Chat reply".to_string(),
+            remove_filler_words: true,
+            capitalize: true,
+            trailing_period: Some(false),
+            llm_prompt: None,
+            llm_rewrite: false,
+        },
+    ]
+}
+
+/// A model file or folder the user registered from outside the models directory.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CustomModel {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+}
+
+/// A named set of shortcut overrides, e.g. a "coding" layer, that can be
+/// switched to at runtime. Bindings it doesn't override keep their base
+/// shortcut.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ShortcutLayer {
+    pub id: String,
+    pub name: String,
+    /// Binding id to shortcut string.
+    #[serde(default)]
+    pub bindings: HashMap<String, String>,
+}
+
+/// Custom words and replacement rules that only apply when transcribing one
+/// language, on top of the language-independent lists.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct LanguageVocabulary {
+    #[serde(default)]
+    pub custom_words: Vec<String>,
+    #[serde(default)]
+    pub replacement_rules: Vec<ReplacementRule>,
+    /// Filler words for this language, replacing the default list.
+    #[serde(default)]
+    pub filler_words: Vec<String>,
+}
+
+/// Carries the end of the previous dictation into the next one as the
+/// Whisper prompt, so names and capitalization stay consistent across bursts.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ContextContinuation {
+    #[serde(default)]
+    pub enabled: bool,
+    /// How much of the previous transcript is passed on, in characters.
+    #[serde(default = "default_context_window_chars")]
+    pub window_chars: usize,
+    /// The context is dropped after this long without a dictation.
+    #[serde(default = "default_context_max_gap_seconds")]
+    pub max_gap_seconds: u64,
+}
+
+impl Default for ContextContinuation {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            window_chars: default_context_window_chars(),
+            max_gap_seconds: default_context_max_gap_seconds(),
+        }
+    }
+}
+
+/// Every set condition must hold for a rule to fire.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct AutomationConditions {
+    #[serde(default)]
+    pub app_name: Option<String>,
+    #[serde(default)]
+    pub min_length: Option<usize>,
+    #[serde(default)]
+    pub max_length: Option<usize>,
+    /// Matches when the transcription contains any of these, ignoring case.
+    #[serde(default)]
+    pub keywords: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AutomationAction {
+    Tag {
+        tag: String,
+    },
+    /// POSTs the history entry as JSON.
+    Webhook {
+        url: String,
+    },
+    AppendToFile {
+        path: String,
+    },
+    /// Runs a program with the transcription on stdin.
+    RunHook {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AutomationRule {
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_rule_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub conditions: AutomationConditions,
+    pub actions: Vec<AutomationAction>,
+}
+
 /* still handy for composing the initial JSON in the store ------------- */
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AppSettings {
@@ -174,14 +792,334 @@ pub struct AppSettings {
     pub assemblyai_api_key: Option<String>,
     #[serde(default)]
     pub gladia_api_key: Option<String>,
+    /// Used for `hf://` model downloads from gated or private repositories.
+    #[serde(default)]
+    pub huggingface_token: Option<String>,
     #[serde(default = "default_transcription_provider")]
     pub transcription_provider: String,
+    #[serde(default)]
+    pub saved_searches: Vec<SavedSearch>,
+    #[serde(default)]
+    pub history_sync_folder: Option<String>,
+    #[serde(default)]
+    pub history_sync_include_audio: bool,
+    #[serde(default)]
+    pub sync_machine_id: Option<String>,
+    #[serde(default)]
+    pub cloud_backup: Option<CloudBackupConfig>,
+    #[serde(default)]
+    pub replacement_rules: Vec<ReplacementRule>,
+    #[serde(default)]
+    pub shared_vocabulary_url: Option<String>,
+    #[serde(default)]
+    pub metrics_enabled: bool,
+    #[serde(default = "default_metrics_port")]
+    pub metrics_port: u16,
+    #[serde(default)]
+    pub hid_bindings: Vec<HidBinding>,
+    #[serde(default)]
+    pub stream_deck_enabled: bool,
+    #[serde(default = "default_stream_deck_port")]
+    pub stream_deck_port: u16,
+    #[serde(default)]
+    pub stream_deck_token: Option<String>,
+    #[serde(default)]
+    pub midi_bindings: Vec<MidiBinding>,
+    #[serde(default)]
+    pub battery_saver: BatterySaverPolicy,
+    /// In toggle mode, finalize and paste the dictation after roughly this
+    /// many seconds of speech, at the next pause. 0 disables it.
+    #[serde(default)]
+    pub auto_submit_seconds: u32,
+    #[serde(default)]
+    pub lock_behavior: LockBehavior,
+    #[serde(default)]
+    pub automation_rules: Vec<AutomationRule>,
+    #[serde(default)]
+    pub context_continuation: ContextContinuation,
+    /// Keyed by language code, e.g. `de`.
+    #[serde(default)]
+    pub language_vocabularies: HashMap<String, LanguageVocabulary>,
+    #[serde(default)]
+    pub custom_models: Vec<CustomModel>,
+    /// Speech probability above which Silero treats a frame as speech.
+    #[serde(default = "default_vad_threshold")]
+    pub vad_threshold: f32,
+    #[serde(default = "default_input_gain")]
+    pub input_gain: f32,
+    /// Ambient level measured by the last calibration, in dBFS.
+    #[serde(default)]
+    pub noise_floor_db: Option<f32>,
+    /// Fixed capture buffer size in frames, `None` for the host default.
+    #[serde(default)]
+    pub audio_buffer_size: Option<u32>,
+    #[serde(default)]
+    pub shortcut_layers: Vec<ShortcutLayer>,
+    /// `None` when only the base bindings are active.
+    #[serde(default)]
+    pub active_shortcut_layer: Option<String>,
+    #[serde(default = "default_max_concurrent_downloads")]
+    pub max_concurrent_downloads: usize,
+    /// Alternative transcripts to keep with each history entry, for
+    /// providers that return them. 0 disables it.
+    #[serde(default)]
+    pub alternatives_count: u32,
+    #[serde(default)]
+    pub unused_model_policy: UnusedModelPolicy,
+    /// Unix timestamp of the last time each local model was loaded.
+    #[serde(default)]
+    pub model_last_used: HashMap<String, i64>,
+    /// Extra download URLs per model id, tried after the catalog's mirrors.
+    #[serde(default)]
+    pub model_mirrors: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub comparison_models: Option<ComparisonModels>,
+    /// Keep recordings an API provider couldn't be reached for and
+    /// transcribe them once the connection is back.
+    #[serde(default)]
+    pub queue_when_offline: bool,
+    /// Hold transcriptions back until the paste here shortcut is pressed,
+    /// instead of pasting into whatever has focus.
+    #[serde(default)]
+    pub deferred_paste: bool,
+    /// Tesseract language codes for clipboard OCR, e.g. "eng" or "eng+deu".
+    #[serde(default = "default_ocr_language")]
+    pub ocr_language: String,
+    /// Platform voice used to read back transcripts, `None` for the
+    /// system default.
+    #[serde(default)]
+    pub readback_voice: Option<String>,
+    /// Catalog version each downloaded model was installed at.
+    #[serde(default)]
+    pub model_versions: HashMap<String, String>,
+    #[serde(default)]
+    pub voice_profile: VoiceProfile,
+    /// Measured performance of local models, replacing their estimated
+    /// scores.
+    #[serde(default)]
+    pub model_benchmarks: HashMap<String, ModelBenchmark>,
+    /// Selected quantization per model id, models without an entry use
+    /// their default variant.
+    #[serde(default)]
+    pub model_variants: HashMap<String, String>,
+    /// Look for new releases without the auto-updater, sending nothing but
+    /// the request for the release manifest.
+    #[serde(default = "default_update_checks_enabled")]
+    pub update_checks_enabled: bool,
+    /// Run Whisper on a detected GPU backend, falling back to the CPU.
+    #[serde(default = "default_gpu_acceleration")]
+    pub gpu_acceleration: bool,
+    /// Beta and nightly get experimental models and releases early.
+    #[serde(default)]
+    pub release_channel: ReleaseChannel,
+    /// Splits long local dictations into paragraphs at pauses and topic
+    /// changes.
+    #[serde(default)]
+    pub paragraph_breaks: ParagraphBreaks,
+    /// Strip filler words from the pasted transcript. History keeps the raw
+    /// text as well.
+    #[serde(default)]
+    pub remove_filler_words: bool,
+    /// Filler words for languages without their own list.
+    #[serde(default = "default_filler_words")]
+    pub filler_words: Vec<String>,
+    /// User presets, on top of the built-in ones.
+    #[serde(default)]
+    pub post_process_presets: Vec<PostProcessPreset>,
+    /// Preset used for bindings without one of their own.
+    #[serde(default)]
+    pub active_preset: Option<String>,
+    /// Preset per binding id.
+    #[serde(default)]
+    pub binding_presets: HashMap<String, String>,
+    /// Overrides per model id, applied when the model is selected.
+    #[serde(default)]
+    pub model_overrides: HashMap<String, ModelOverrides>,
+    /// Models the quick-switch shortcut cycles through, in order.
+    #[serde(default)]
+    pub favorite_models: Vec<String>,
+    #[serde(default)]
+    pub watch_folder: Option<WatchFolderConfig>,
+    #[serde(default)]
+    pub phone_import_enabled: bool,
+    #[serde(default = "default_phone_import_port")]
+    pub phone_import_port: u16,
+    #[serde(default)]
+    pub phone_import_token: Option<String>,
+    #[serde(default)]
+    pub email_ingest: Option<EmailIngestConfig>,
+    /// Backend per model id for models that failed on the GPU, so they load
+    /// straight on the one that worked. Cleared when GPU acceleration is
+    /// turned back on.
+    #[serde(default)]
+    pub model_backends: HashMap<String, String>,
+    /// Transcribe while recording and emit the text so far as
+    /// `transcription-partial`. Local models only.
+    #[serde(default)]
+    pub live_transcription: bool,
+    /// Cut silence out of the audio before a local model transcribes it.
+    #[serde(default = "default_trim_silence")]
+    pub trim_silence: bool,
+    /// Speed of each model in real use on this machine.
+    #[serde(default)]
+    pub model_usage: HashMap<String, ModelUsageStats>,
+    /// The user's 1 to 5 star rating per model id.
+    #[serde(default)]
+    pub model_ratings: HashMap<String, u8>,
+    #[serde(default)]
+    pub cpu_usage: CpuUsage,
+    #[serde(default)]
+    pub whisper_sampling: WhisperSampling,
+    #[serde(default)]
+    pub practice_scores: Vec<PracticeScore>,
+    /// Spoken corrections also become replacement rules, so the same
+    /// mistake is fixed in later transcripts.
+    #[serde(default)]
+    pub correction_adds_rule: bool,
+    #[serde(default)]
+    pub whisper_fallback: WhisperFallback,
+    /// Drop what Whisper makes up from silence or noise, such as "Thanks
+    /// for watching!", instead of pasting it.
+    #[serde(default = "default_filter_hallucinations")]
+    pub filter_hallucinations: bool,
+    /// Hold a transcript back for the paste here shortcut when another app
+    /// got the focus while it was being recorded.
+    #[serde(default)]
+    pub confirm_paste_on_focus_change: bool,
+    /// Whisper's no-speech probability above which a segment is dropped,
+    /// `None` for the engine's default of 0.2. Models can override it.
+    #[serde(default = "default_no_speech_threshold")]
+    pub no_speech_threshold: Option<f32>,
+    /// Keep Whisper from transcribing non-speech such as "[Music]". Turn it
+    /// off to keep those annotations, e.g. for audio with music in it.
+    #[serde(default = "default_suppress_non_speech_tokens")]
+    pub suppress_non_speech_tokens: bool,
+    /// Paste into the app that had the focus when recording started: it is
+    /// brought back to the front, or the paste waits until it is.
+    #[serde(default)]
+    pub lock_to_recording_app: bool,
+    /// Recorded audio kept in memory, in MB. Older audio of longer
+    /// recordings is moved to a temporary file. 0 keeps it all in memory.
+    #[serde(default = "default_recording_memory_limit_mb")]
+    pub recording_memory_limit_mb: u32,
+    /// Stop and transcribe recordings after this many seconds. 0 disables
+    /// the limit.
+    #[serde(default)]
+    pub max_recording_seconds: u32,
+    /// Transcribe recordings longer than this many seconds in chunks split
+    /// at pauses, reporting progress. 0 transcribes them in one go.
+    #[serde(default)]
+    pub chunk_transcription_seconds: u32,
+    /// Sample rate to capture at. `None` captures at 16 kHz, what the models
+    /// take, or at the device's own rate when the original audio is kept.
+    #[serde(default)]
+    pub capture_sample_rate: Option<u32>,
+    /// Save recordings to history as captured, at the capture rate and with
+    /// the pauses, instead of the 16 kHz speech the model got.
+    #[serde(default)]
+    pub keep_original_audio: bool,
+    /// SHA-256 of downloaded model files the catalog has no checksum for,
+    /// taken when the download finished.
+    #[serde(default)]
+    pub model_hashes: HashMap<String, String>,
+    /// Where crash reports are submitted to, `None` to only keep them here.
+    #[serde(default)]
+    pub crash_report_url: Option<String>,
+}
+
+impl AppSettings {
+    /// Switches the language, translation and active preset to the values
+    /// saved for `model_id`. Decoding parameters are read at transcription
+    /// time instead.
+    pub fn apply_model_overrides(&mut self, model_id: &str) {
+        let Some(overrides) = self.model_overrides.get(model_id).cloned() else {
+            return;
+        };
+        if let Some(language) = overrides.language {
+            self.selected_language = language;
+        }
+        if let Some(translate) = overrides.translate_to_english {
+            self.translate_to_english = translate;
+        }
+        if let Some(preset) = overrides.preset {
+            self.active_preset = Some(preset);
+        }
+    }
+
+    /// Built-in presets followed by the user's, a user preset with a
+    /// built-in id takes its place.
+    pub fn presets(&self) -> Vec<PostProcessPreset> {
+        let mut presets = builtin_presets();
+        for preset in &self.post_process_presets {
+            match presets.iter_mut().find(|p| p.id == preset.id) {
+                Some(existing) => *existing = preset.clone(),
+                None => presets.push(preset.clone()),
+            }
+        }
+        presets
+    }
+
+    /// The preset that applies to `binding_id`, if any.
+    pub fn preset_for_binding(&self, binding_id: &str) -> Option<PostProcessPreset> {
+        let id = self
+            .binding_presets
+            .get(binding_id)
+            .or(self.active_preset.as_ref())?;
+        self.presets().into_iter().find(|p| &p.id == id)
+    }
+}
+
+fn default_filler_words() -> Vec<String> {
+    ["um", "uh", "erm", "you know", "like"]
+        .iter()
+        .map(|w| w.to_string())
+        .collect()
+}
+
+fn default_gpu_acceleration() -> bool {
+    true
+}
+
+fn default_update_checks_enabled() -> bool {
+    true
+}
+
+fn default_ocr_language() -> String {
+    "eng".to_string()
 }
 
 fn default_model() -> String {
     "".to_string()
 }
 
+fn default_max_concurrent_downloads() -> usize {
+    2
+}
+
+fn default_unused_model_days() -> u32 {
+    30
+}
+
+fn default_rule_enabled() -> bool {
+    true
+}
+
+fn default_vad_threshold() -> f32 {
+    0.3
+}
+
+fn default_input_gain() -> f32 {
+    1.0
+}
+
+fn default_context_window_chars() -> usize {
+    200
+}
+
+fn default_context_max_gap_seconds() -> u64 {
+    120
+}
+
 fn default_always_on_microphone() -> bool {
     false
 }
@@ -237,6 +1175,47 @@ fn default_transcription_provider() -> String {
     "local".to_string()
 }
 
+fn default_metrics_port() -> u16 {
+    9464
+}
+
+fn default_stream_deck_port() -> u16 {
+    47812
+}
+
+fn default_phone_import_port() -> u16 {
+    47813
+}
+
+fn default_trim_silence() -> bool {
+    true
+}
+
+fn default_filter_hallucinations() -> bool {
+    true
+}
+
+fn default_no_speech_threshold() -> Option<f32> {
+    Some(0.2)
+}
+
+fn default_suppress_non_speech_tokens() -> bool {
+    true
+}
+
+fn default_recording_memory_limit_mb() -> u32 {
+    // About 17 minutes of 16 kHz audio
+    64
+}
+
+fn default_imap_port() -> u16 {
+    993
+}
+
+fn default_imap_mailbox() -> String {
+    "INBOX".to_string()
+}
+
 pub const SETTINGS_STORE_PATH: &str = "settings_store.json";
 
 pub fn get_default_settings() -> AppSettings {
@@ -248,6 +1227,34 @@ pub fn get_default_settings() -> AppSettings {
     let default_shortcut = "ctrl+space";
     #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
     let default_shortcut = "alt+space";
+    #[cfg(target_os = "macos")]
+    let default_paste_here_shortcut = "ctrl+option+cmd+v";
+    #[cfg(not(target_os = "macos"))]
+    let default_paste_here_shortcut = "ctrl+alt+shift+v";
+    #[cfg(target_os = "macos")]
+    let default_ocr_shortcut = "ctrl+option+cmd+o";
+    #[cfg(not(target_os = "macos"))]
+    let default_ocr_shortcut = "ctrl+alt+shift+o";
+    #[cfg(target_os = "macos")]
+    let default_read_back_shortcut = "ctrl+option+cmd+r";
+    #[cfg(not(target_os = "macos"))]
+    let default_read_back_shortcut = "ctrl+alt+shift+r";
+    #[cfg(target_os = "macos")]
+    let default_marker_shortcut = "ctrl+option+cmd+m";
+    #[cfg(not(target_os = "macos"))]
+    let default_marker_shortcut = "ctrl+alt+shift+m";
+    #[cfg(target_os = "macos")]
+    let default_cycle_model_shortcut = "ctrl+option+cmd+n";
+    #[cfg(not(target_os = "macos"))]
+    let default_cycle_model_shortcut = "ctrl+alt+shift+n";
+    #[cfg(target_os = "macos")]
+    let default_correct_shortcut = "ctrl+option+cmd+c";
+    #[cfg(not(target_os = "macos"))]
+    let default_correct_shortcut = "ctrl+alt+shift+c";
+    #[cfg(target_os = "macos")]
+    let default_cancel_transcription_shortcut = "ctrl+option+cmd+x";
+    #[cfg(not(target_os = "macos"))]
+    let default_cancel_transcription_shortcut = "ctrl+alt+shift+x";
 
     let mut bindings = HashMap::new();
     bindings.insert(
@@ -260,6 +1267,77 @@ pub fn get_default_settings() -> AppSettings {
             current_binding: default_shortcut.to_string(),
         },
     );
+    bindings.insert(
+        "paste_here".to_string(),
+        ShortcutBinding {
+            id: "paste_here".to_string(),
+            name: "Paste Here".to_string(),
+            description: "Pastes a transcription held back by deferred paste.".to_string(),
+            default_binding: default_paste_here_shortcut.to_string(),
+            current_binding: default_paste_here_shortcut.to_string(),
+        },
+    );
+    bindings.insert(
+        "ocr_clipboard".to_string(),
+        ShortcutBinding {
+            id: "ocr_clipboard".to_string(),
+            name: "Paste Text from Image".to_string(),
+            description: "Reads the text in the clipboard image and pastes it.".to_string(),
+            default_binding: default_ocr_shortcut.to_string(),
+            current_binding: default_ocr_shortcut.to_string(),
+        },
+    );
+    bindings.insert(
+        "read_back".to_string(),
+        ShortcutBinding {
+            id: "read_back".to_string(),
+            name: "Read Back".to_string(),
+            description: "Reads the last transcript aloud, press again to stop.".to_string(),
+            default_binding: default_read_back_shortcut.to_string(),
+            current_binding: default_read_back_shortcut.to_string(),
+        },
+    );
+    bindings.insert(
+        "marker".to_string(),
+        ShortcutBinding {
+            id: "marker".to_string(),
+            name: "Marker".to_string(),
+            description: "Marks the current moment of a recording.".to_string(),
+            default_binding: default_marker_shortcut.to_string(),
+            current_binding: default_marker_shortcut.to_string(),
+        },
+    );
+    bindings.insert(
+        "cycle_model".to_string(),
+        ShortcutBinding {
+            id: "cycle_model".to_string(),
+            name: "Switch Model".to_string(),
+            description: "Switches to the next of your favorite models.".to_string(),
+            default_binding: default_cycle_model_shortcut.to_string(),
+            current_binding: default_cycle_model_shortcut.to_string(),
+        },
+    );
+    bindings.insert(
+        "correct".to_string(),
+        ShortcutBinding {
+            id: "correct".to_string(),
+            name: "Correct".to_string(),
+            description: "Fixes the last paste by voice, e.g. \"change acme to ACME Corp\"."
+                .to_string(),
+            default_binding: default_correct_shortcut.to_string(),
+            current_binding: default_correct_shortcut.to_string(),
+        },
+    );
+    bindings.insert(
+        "cancel_transcription".to_string(),
+        ShortcutBinding {
+            id: "cancel_transcription".to_string(),
+            name: "Cancel Transcription".to_string(),
+            description: "Stops the running transcription without pasting anything.".to_string(),
+            default_binding: default_cancel_transcription_shortcut.to_string(),
+            current_binding: default_cancel_transcription_shortcut.to_string(),
+        },
+    );
 
     AppSettings {
         bindings,
@@ -288,22 +1366,114 @@ pub fn get_default_settings() -> AppSettings {
         deepgram_api_key: None,
         assemblyai_api_key: None,
         gladia_api_key: None,
+        huggingface_token: None,
         transcription_provider: default_transcription_provider(),
+        saved_searches: Vec::new(),
+        history_sync_folder: None,
+        history_sync_include_audio: false,
+        sync_machine_id: None,
+        cloud_backup: None,
+        replacement_rules: Vec::new(),
+        shared_vocabulary_url: None,
+        metrics_enabled: false,
+        metrics_port: default_metrics_port(),
+        hid_bindings: Vec::new(),
+        stream_deck_enabled: false,
+        stream_deck_port: default_stream_deck_port(),
+        stream_deck_token: None,
+        midi_bindings: Vec::new(),
+        battery_saver: BatterySaverPolicy::default(),
+        auto_submit_seconds: 0,
+        lock_behavior: LockBehavior::default(),
+        automation_rules: Vec::new(),
+        context_continuation: ContextContinuation::default(),
+        language_vocabularies: HashMap::new(),
+        custom_models: Vec::new(),
+        vad_threshold: default_vad_threshold(),
+        input_gain: default_input_gain(),
+        noise_floor_db: None,
+        audio_buffer_size: None,
+        shortcut_layers: Vec::new(),
+        active_shortcut_layer: None,
+        max_concurrent_downloads: default_max_concurrent_downloads(),
+        alternatives_count: 0,
+        unused_model_policy: UnusedModelPolicy::default(),
+        model_last_used: HashMap::new(),
+        model_mirrors: HashMap::new(),
+        comparison_models: None,
+        queue_when_offline: false,
+        deferred_paste: false,
+        ocr_language: default_ocr_language(),
+        readback_voice: None,
+        model_versions: HashMap::new(),
+        voice_profile: VoiceProfile::default(),
+        model_benchmarks: HashMap::new(),
+        model_variants: HashMap::new(),
+        update_checks_enabled: default_update_checks_enabled(),
+        gpu_acceleration: default_gpu_acceleration(),
+        release_channel: ReleaseChannel::default(),
+        paragraph_breaks: ParagraphBreaks::default(),
+        remove_filler_words: false,
+        filler_words: default_filler_words(),
+        post_process_presets: Vec::new(),
+        active_preset: None,
+        binding_presets: HashMap::new(),
+        model_overrides: HashMap::new(),
+        favorite_models: Vec::new(),
+        watch_folder: None,
+        phone_import_enabled: false,
+        phone_import_port: default_phone_import_port(),
+        phone_import_token: None,
+        email_ingest: None,
+        model_backends: HashMap::new(),
+        live_transcription: false,
+        trim_silence: default_trim_silence(),
+        model_usage: HashMap::new(),
+        model_ratings: HashMap::new(),
+        cpu_usage: CpuUsage::default(),
+        whisper_sampling: WhisperSampling::default(),
+        practice_scores: Vec::new(),
+        correction_adds_rule: false,
+        whisper_fallback: WhisperFallback::default(),
+        filter_hallucinations: true,
+        confirm_paste_on_focus_change: false,
+        no_speech_threshold: default_no_speech_threshold(),
+        suppress_non_speech_tokens: default_suppress_non_speech_tokens(),
+        lock_to_recording_app: false,
+        recording_memory_limit_mb: default_recording_memory_limit_mb(),
+        max_recording_seconds: 0,
+        chunk_transcription_seconds: 0,
+        capture_sample_rate: None,
+        keep_original_audio: false,
+        model_hashes: HashMap::new(),
+        crash_report_url: None,
     }
 }
 
 pub fn load_or_create_app_settings(app: &AppHandle) -> AppSettings {
     // Initialize store
     let store = app
-        .store(SETTINGS_STORE_PATH)
+        .store(crate::profiles::settings_store_path(app))
         .expect("Failed to initialize store");
 
     let settings = if let Some(settings_value) = store.get("settings") {
         // Parse the entire settings object
         match serde_json::from_value::<AppSettings>(settings_value) {
-            Ok(settings) => {
+            Ok(mut settings) => {
                 println!("Found existing settings: {:?}", settings);
 
+                // Pick up bindings added since the settings were written
+                let mut added = false;
+                for (id, binding) in get_default_settings().bindings {
+                    if !settings.bindings.contains_key(&id) {
+                        settings.bindings.insert(id, binding);
+                        added = true;
+                    }
+                }
+                if added {
+                    store.set("settings", serde_json::to_value(&settings).unwrap());
+                }
+
                 settings
             }
             Err(e) => {
@@ -332,7 +1502,7 @@ pub fn load_or_create_app_settings(app: &AppHandle) -> AppSettings {
 
 pub fn get_settings(app: &AppHandle) -> AppSettings {
     let store = app
-        .store(SETTINGS_STORE_PATH)
+        .store(crate::profiles::settings_store_path(app))
         .expect("Failed to initialize store");
 
     if let Some(settings_value) = store.get("settings") {
@@ -345,7 +1515,7 @@ pub fn get_settings(app: &AppHandle) -> AppSettings {
 
 pub fn write_settings(app: &AppHandle, settings: AppSettings) {
     let store = app
-        .store(SETTINGS_STORE_PATH)
+        .store(crate::profiles::settings_store_path(app))
         .expect("Failed to initialize store");
 
     store.set("settings", serde_json::to_value(&settings).unwrap());
@@ -1,18 +1,26 @@
 use serde::Serialize;
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Manager};
 use tauri_plugin_autostart::ManagerExt;
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 
 use crate::actions::ACTION_MAP;
-use crate::settings::ShortcutBinding;
-use crate::settings::{self, get_settings, ClipboardHandling, OverlayPosition, PasteMethod, SoundTheme};
+use crate::audio_toolkit::ReplacementRule;
+use crate::events::{self, AppEvent, SettingChanged};
+use crate::managers::audio::AudioRecordingManager;
+use crate::settings::{
+    self, get_settings, AppSettings, ClipboardHandling, LanguageVocabulary, LockBehavior,
+    OverlayPosition, PasteMethod, ShortcutBinding, ShortcutLayer, SoundTheme,
+};
+use crate::utils::{update_tray_menu, TrayIconState};
 use crate::ManagedToggleState;
+use std::collections::HashMap;
+use std::sync::Arc;
 
 pub fn init_shortcuts(app: &AppHandle) {
     let settings = settings::load_or_create_app_settings(app);
 
-    // Register shortcuts with the bindings from settings
-    for (_id, binding) in settings.bindings {
+    // Register shortcuts with the bindings of the active layer
+    for (_id, binding) in active_bindings(&settings) {
         if let Err(e) = _register_shortcut(app, binding) {
             eprintln!("Failed to register shortcut {} during init: {}", _id, e);
         }
@@ -48,6 +56,21 @@ pub fn change_binding(
         }
     };
 
+    // The active layer overrides this binding, so the registered shortcut
+    // stays as it is and only the base binding changes
+    if active_layer(&settings).is_some_and(|layer| layer.bindings.contains_key(&id)) {
+        validate_shortcut_string(&binding)?;
+        let mut updated_binding = binding_to_modify;
+        updated_binding.current_binding = binding;
+        settings.bindings.insert(id, updated_binding.clone());
+        settings::write_settings(&app, settings);
+        return Ok(BindingResponse {
+            success: true,
+            binding: Some(updated_binding),
+            error: None,
+        });
+    }
+
     // Unregister the existing binding
     if let Err(e) = _unregister_shortcut(&app, binding_to_modify.clone()) {
         let error_msg = format!("Failed to unregister shortcut: {}", e);
@@ -109,6 +132,38 @@ pub fn change_ptt_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
     Ok(())
 }
 
+#[tauri::command]
+pub fn change_auto_submit_setting(app: AppHandle, seconds: u32) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.auto_submit_seconds = seconds;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn change_max_recording_setting(app: AppHandle, seconds: u32) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.max_recording_seconds = seconds;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn change_live_transcription_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.live_transcription = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn change_lock_behavior_setting(app: AppHandle, behavior: LockBehavior) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.lock_behavior = behavior;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 pub fn change_audio_feedback_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
     let mut settings = settings::get_settings(&app);
@@ -186,11 +241,11 @@ pub fn change_debug_mode_setting(app: AppHandle, enabled: bool) -> Result<(), St
     settings::write_settings(&app, settings);
 
     // Emit event to notify frontend of debug mode change
-    let _ = app.emit(
-        "settings-changed",
-        serde_json::json!({
-            "setting": "debug_mode",
-            "value": enabled
+    events::emit(
+        &app,
+        AppEvent::SettingsChanged(SettingChanged {
+            setting: "debug_mode".to_string(),
+            value: enabled.into(),
         }),
     );
 
@@ -204,11 +259,11 @@ pub fn change_start_hidden_setting(app: AppHandle, enabled: bool) -> Result<(),
     settings::write_settings(&app, settings);
 
     // Notify frontend
-    let _ = app.emit(
-        "settings-changed",
-        serde_json::json!({
-            "setting": "start_hidden",
-            "value": enabled
+    events::emit(
+        &app,
+        AppEvent::SettingsChanged(SettingChanged {
+            setting: "start_hidden".to_string(),
+            value: enabled.into(),
         }),
     );
 
@@ -230,11 +285,11 @@ pub fn change_autostart_setting(app: AppHandle, enabled: bool) -> Result<(), Str
     }
 
     // Notify frontend
-    let _ = app.emit(
-        "settings-changed",
-        serde_json::json!({
-            "setting": "autostart_enabled",
-            "value": enabled
+    events::emit(
+        &app,
+        AppEvent::SettingsChanged(SettingChanged {
+            setting: "autostart_enabled".to_string(),
+            value: enabled.into(),
         }),
     );
 
@@ -249,6 +304,66 @@ pub fn update_custom_words(app: AppHandle, words: Vec<String>) -> Result<(), Str
     Ok(())
 }
 
+#[tauri::command]
+pub fn change_remove_filler_words_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.remove_filler_words = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn update_filler_words(app: AppHandle, words: Vec<String>) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.filler_words = words
+        .into_iter()
+        .map(|w| w.trim().to_string())
+        .filter(|w| !w.is_empty())
+        .collect();
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn update_replacement_rules(app: AppHandle, rules: Vec<ReplacementRule>) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.replacement_rules = rules
+        .into_iter()
+        .filter(|rule| !rule.from.trim().is_empty())
+        .collect();
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+/// Sets the custom words and rules for one language; empty lists remove it.
+#[tauri::command]
+pub fn update_language_vocabulary(
+    app: AppHandle,
+    language: String,
+    mut vocabulary: LanguageVocabulary,
+) -> Result<(), String> {
+    let language = language.trim().to_lowercase();
+    if language.is_empty() || language == "auto" {
+        return Err("A specific language is required".to_string());
+    }
+
+    vocabulary
+        .replacement_rules
+        .retain(|rule| !rule.from.trim().is_empty());
+
+    let mut settings = settings::get_settings(&app);
+    if vocabulary.custom_words.is_empty()
+        && vocabulary.replacement_rules.is_empty()
+        && vocabulary.filler_words.is_empty()
+    {
+        settings.language_vocabularies.remove(&language);
+    } else {
+        settings.language_vocabularies.insert(language, vocabulary);
+    }
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 pub fn change_word_correction_threshold_setting(
     app: AppHandle,
@@ -292,6 +407,31 @@ pub fn change_clipboard_handling_setting(app: AppHandle, handling: String) -> Re
     Ok(())
 }
 
+#[tauri::command]
+pub fn change_update_checks_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.update_checks_enabled = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn change_deferred_paste_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.deferred_paste = enabled;
+    settings::write_settings(&app, settings);
+    if !enabled {
+        crate::actions::discard_pending_paste(&app);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn discard_pending_paste(app: AppHandle) -> Result<(), String> {
+    crate::actions::discard_pending_paste(&app);
+    Ok(())
+}
+
 #[tauri::command]
 pub fn change_mute_while_recording_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
     let mut settings = settings::get_settings(&app);
@@ -301,6 +441,154 @@ pub fn change_mute_while_recording_setting(app: AppHandle, enabled: bool) -> Res
     Ok(())
 }
 
+fn active_layer(settings: &AppSettings) -> Option<&ShortcutLayer> {
+    let id = settings.active_shortcut_layer.as_ref()?;
+    settings.shortcut_layers.iter().find(|l| &l.id == id)
+}
+
+/// The base bindings with the active layer's overrides applied, i.e. the
+/// shortcuts that are currently registered.
+pub fn active_bindings(settings: &AppSettings) -> HashMap<String, ShortcutBinding> {
+    let mut bindings = settings.bindings.clone();
+    if let Some(layer) = active_layer(settings) {
+        for (id, shortcut) in &layer.bindings {
+            if let Some(binding) = bindings.get_mut(id) {
+                binding.current_binding = shortcut.clone();
+            }
+        }
+    }
+    bindings
+}
+
+/// Swaps the registered shortcuts from the bindings in `from` to those in
+/// `settings`. If any of the new shortcuts can't be registered the previous
+/// ones are restored.
+fn apply_shortcut_layer(
+    app: &AppHandle,
+    from: &HashMap<String, ShortcutBinding>,
+    settings: &AppSettings,
+) -> Result<(), String> {
+    if app
+        .try_state::<Arc<AudioRecordingManager>>()
+        .is_some_and(|rm| rm.active_binding().is_some())
+    {
+        return Err("Cannot switch shortcut layers while recording".to_string());
+    }
+
+    for binding in from.values() {
+        let _ = _unregister_shortcut(app, binding.clone());
+    }
+
+    let to = active_bindings(settings);
+    let mut registered = Vec::new();
+    for binding in to.values() {
+        if let Err(e) = _register_shortcut(app, binding.clone()) {
+            for b in registered {
+                let _ = _unregister_shortcut(app, b);
+            }
+            for b in from.values() {
+                let _ = _register_shortcut(app, b.clone());
+            }
+            return Err(format!("Binding '{}': {}", binding.id, e));
+        }
+        registered.push(binding.clone());
+    }
+
+    update_tray_menu(app, &TrayIconState::Idle);
+    events::emit(
+        app,
+        AppEvent::ShortcutLayerChanged(settings.active_shortcut_layer.clone()),
+    );
+    Ok(())
+}
+
+/// Rebuilds the tray's layer list unless a recording owns the menu.
+pub fn refresh_layer_menu(app: &AppHandle) {
+    let recording = app
+        .try_state::<Arc<AudioRecordingManager>>()
+        .is_some_and(|rm| rm.active_binding().is_some());
+    if !recording {
+        update_tray_menu(app, &TrayIconState::Idle);
+    }
+}
+
+/// Activates a shortcut layer, or only the base bindings when `id` is `None`.
+#[tauri::command]
+pub fn switch_shortcut_layer(app: AppHandle, id: Option<String>) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    if let Some(id) = &id {
+        if !settings.shortcut_layers.iter().any(|l| &l.id == id) {
+            return Err(format!("Shortcut layer '{}' not found", id));
+        }
+    }
+    if settings.active_shortcut_layer == id {
+        return Ok(());
+    }
+
+    let previous = active_bindings(&settings);
+    settings.active_shortcut_layer = id;
+    apply_shortcut_layer(&app, &previous, &settings)?;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+/// Adds a layer, or replaces the layer with the same id. An empty id creates
+/// a new layer.
+#[tauri::command]
+pub fn save_shortcut_layer(
+    app: AppHandle,
+    mut layer: ShortcutLayer,
+) -> Result<ShortcutLayer, String> {
+    layer.name = layer.name.trim().to_string();
+    if layer.name.is_empty() {
+        return Err("Layer name cannot be empty".to_string());
+    }
+    let mut settings = get_settings(&app);
+    for (binding_id, shortcut) in &layer.bindings {
+        if !settings.bindings.contains_key(binding_id) {
+            return Err(format!("Binding with id '{}' not found", binding_id));
+        }
+        validate_shortcut_string(shortcut)?;
+    }
+    if layer.id.is_empty() {
+        layer.id = format!("layer-{}", chrono::Utc::now().timestamp_millis());
+    }
+
+    let previous = active_bindings(&settings);
+    match settings
+        .shortcut_layers
+        .iter_mut()
+        .find(|l| l.id == layer.id)
+    {
+        Some(existing) => *existing = layer.clone(),
+        None => settings.shortcut_layers.push(layer.clone()),
+    }
+    // Editing the active layer takes effect immediately
+    if settings.active_shortcut_layer.as_ref() == Some(&layer.id) {
+        apply_shortcut_layer(&app, &previous, &settings)?;
+    } else {
+        refresh_layer_menu(&app);
+    }
+    settings::write_settings(&app, settings);
+
+    Ok(layer)
+}
+
+#[tauri::command]
+pub fn delete_shortcut_layer(app: AppHandle, id: String) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    let previous = active_bindings(&settings);
+    settings.shortcut_layers.retain(|l| l.id != id);
+    if settings.active_shortcut_layer.as_ref() == Some(&id) {
+        settings.active_shortcut_layer = None;
+        apply_shortcut_layer(&app, &previous, &settings)?;
+    } else {
+        refresh_layer_menu(&app);
+    }
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 /// Determine whether a shortcut string contains at least one non-modifier key.
 /// We allow single non-modifier keys (e.g. "f5" or "space") but disallow
 /// modifier-only combos (e.g. "ctrl" or "ctrl+shift").
@@ -323,7 +611,7 @@ fn validate_shortcut_string(raw: &str) -> Result<(), String> {
 /// This avoids firing the action while keys are being recorded.
 #[tauri::command]
 pub fn suspend_binding(app: AppHandle, id: String) -> Result<(), String> {
-    if let Some(b) = settings::get_bindings(&app).get(&id).cloned() {
+    if let Some(b) = active_bindings(&get_settings(&app)).remove(&id) {
         if let Err(e) = _unregister_shortcut(&app, b) {
             eprintln!("suspend_binding error for id '{}': {}", id, e);
             return Err(e);
@@ -335,7 +623,7 @@ pub fn suspend_binding(app: AppHandle, id: String) -> Result<(), String> {
 /// Re-register the binding after the user has finished editing.
 #[tauri::command]
 pub fn resume_binding(app: AppHandle, id: String) -> Result<(), String> {
-    if let Some(b) = settings::get_bindings(&app).get(&id).cloned() {
+    if let Some(b) = active_bindings(&get_settings(&app)).remove(&id) {
         if let Err(e) = _register_shortcut(&app, b) {
             eprintln!("resume_binding error for id '{}': {}", id, e);
             return Err(e);
@@ -381,44 +669,12 @@ fn _register_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<(), S
         .on_shortcut(shortcut, move |ah, scut, event| {
             if scut == &shortcut {
                 let shortcut_string = scut.into_string();
-                let settings = get_settings(ah);
-
-                if let Some(action) = ACTION_MAP.get(&binding_id_for_closure) {
-                    if settings.push_to_talk {
-                        if event.state == ShortcutState::Pressed {
-                            action.start(ah, &binding_id_for_closure, &shortcut_string);
-                        } else if event.state == ShortcutState::Released {
-                            action.stop(ah, &binding_id_for_closure, &shortcut_string);
-                        }
-                    } else {
-                        if event.state == ShortcutState::Pressed {
-                            let toggle_state_manager = ah.state::<ManagedToggleState>();
-
-                            let mut states = toggle_state_manager.lock().expect("Failed to lock toggle state manager");
-
-                            let is_currently_active = states.active_toggles
-                                .entry(binding_id_for_closure.clone())
-                                .or_insert(false);
-
-                            if *is_currently_active {
-                                action.stop(
-                                    ah,
-                                    &binding_id_for_closure,
-                                    &shortcut_string,
-                                );
-                                *is_currently_active = false; // Update state to inactive
-                            } else {
-                                action.start(ah, &binding_id_for_closure, &shortcut_string);
-                                *is_currently_active = true; // Update state to active
-                            }
-                        }
-                    }
-                } else {
-                    println!(
-                        "Warning: No action defined in ACTION_MAP for shortcut ID '{}'. Shortcut: '{}', State: {:?}",
-                        binding_id_for_closure, shortcut_string, event.state
-                    );
-                }
+                dispatch_binding_event(
+                    ah,
+                    &binding_id_for_closure,
+                    &shortcut_string,
+                    event.state == ShortcutState::Pressed,
+                );
             }
         })
         .map_err(|e| {
@@ -430,6 +686,52 @@ fn _register_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<(), S
     Ok(())
 }
 
+/// Runs the action bound to `binding_id` for a press or release of its trigger,
+/// honouring the push-to-talk setting. Shared by keyboard shortcuts and other
+/// input sources such as HID devices.
+pub fn dispatch_binding_event(app: &AppHandle, binding_id: &str, trigger: &str, pressed: bool) {
+    let settings = get_settings(app);
+
+    let Some(action) = ACTION_MAP.get(binding_id) else {
+        println!(
+            "Warning: No action defined in ACTION_MAP for shortcut ID '{}'. Shortcut: '{}', Pressed: {}",
+            binding_id, trigger, pressed
+        );
+        return;
+    };
+
+    if action.is_momentary() {
+        if pressed {
+            action.start(app, binding_id, trigger);
+        }
+    } else if settings.push_to_talk {
+        if pressed {
+            action.start(app, binding_id, trigger);
+        } else {
+            action.stop(app, binding_id, trigger);
+        }
+    } else if pressed {
+        let toggle_state_manager = app.state::<ManagedToggleState>();
+
+        let mut states = toggle_state_manager
+            .lock()
+            .expect("Failed to lock toggle state manager");
+
+        let is_currently_active = states
+            .active_toggles
+            .entry(binding_id.to_string())
+            .or_insert(false);
+
+        if *is_currently_active {
+            action.stop(app, binding_id, trigger);
+            *is_currently_active = false; // Update state to inactive
+        } else {
+            action.start(app, binding_id, trigger);
+            *is_currently_active = true; // Update state to active
+        }
+    }
+}
+
 fn _unregister_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<(), String> {
     let shortcut = match binding.current_binding.parse::<Shortcut>() {
         Ok(s) => s,
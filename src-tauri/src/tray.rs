@@ -1,5 +1,8 @@
+use crate::managers::stream_deck::StreamDeckManager;
+use crate::settings::get_settings;
+use std::sync::Arc;
 use tauri::image::Image;
-use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
 use tauri::tray::TrayIcon;
 use tauri::{AppHandle, Manager, Theme};
 
@@ -71,6 +74,11 @@ pub fn change_tray_icon(app: &AppHandle, icon: TrayIconState) {
 
     // Update menu based on state
     update_tray_menu(app, &icon);
+
+    // Keep Stream Deck button icons in sync
+    if let Some(stream_deck) = app.try_state::<Arc<StreamDeckManager>>() {
+        stream_deck.publish_state(&icon);
+    }
 }
 
 pub fn update_tray_menu(app: &AppHandle, state: &TrayIconState) {
@@ -98,6 +106,42 @@ pub fn update_tray_menu(app: &AppHandle, state: &TrayIconState) {
         .expect("failed to create quit item");
     let separator = || PredefinedMenuItem::separator(app).expect("failed to create separator");
 
+    // Shortcut layers, switchable from the tray when the user has any
+    let settings = get_settings(app);
+    let active_layer = settings
+        .active_shortcut_layer
+        .as_ref()
+        .and_then(|id| settings.shortcut_layers.iter().find(|l| &l.id == id));
+    let layers_i = if settings.shortcut_layers.is_empty() {
+        None
+    } else {
+        let submenu = Submenu::with_id(app, "layers", "Shortcut Layer", true)
+            .expect("failed to create layers submenu");
+        let default_i = CheckMenuItem::with_id(
+            app,
+            "layer:",
+            "Default",
+            true,
+            active_layer.is_none(),
+            None::<&str>,
+        )
+        .expect("failed to create layer item");
+        let _ = submenu.append(&default_i);
+        for layer in &settings.shortcut_layers {
+            let layer_i = CheckMenuItem::with_id(
+                app,
+                format!("layer:{}", layer.id),
+                &layer.name,
+                true,
+                active_layer.is_some_and(|l| l.id == layer.id),
+                None::<&str>,
+            )
+            .expect("failed to create layer item");
+            let _ = submenu.append(&layer_i);
+        }
+        Some(submenu)
+    };
+
     let menu = match state {
         TrayIconState::Recording | TrayIconState::Transcribing => {
             let cancel_i = MenuItem::with_id(app, "cancel", "Cancel", true, None::<&str>)
@@ -117,21 +161,32 @@ pub fn update_tray_menu(app: &AppHandle, state: &TrayIconState) {
             )
             .expect("failed to create menu")
         }
-        TrayIconState::Idle => Menu::with_items(
-            app,
-            &[
-                &version_i,
-                &separator(),
-                &settings_i,
-                &check_updates_i,
-                &separator(),
-                &quit_i,
-            ],
-        )
-        .expect("failed to create menu"),
+        TrayIconState::Idle => {
+            let menu = Menu::with_items(
+                app,
+                &[
+                    &version_i,
+                    &separator(),
+                    &settings_i,
+                    &check_updates_i,
+                    &separator(),
+                    &quit_i,
+                ],
+            )
+            .expect("failed to create menu");
+            if let Some(layers_i) = &layers_i {
+                let _ = menu.insert_items(&[layers_i, &separator()], 2);
+            }
+            menu
+        }
     };
 
     let tray = app.state::<TrayIcon>();
+    let tooltip = match active_layer {
+        Some(layer) => format!("Handy ({})", layer.name),
+        None => "Handy".to_string(),
+    };
+    let _ = tray.set_tooltip(Some(tooltip));
     let _ = tray.set_menu(Some(menu));
     let _ = tray.set_icon_as_template(true);
 }
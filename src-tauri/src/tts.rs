@@ -0,0 +1,101 @@
+//! Reads text aloud with the platform's speech synthesizer, so a long
+//! dictation can be checked without looking at the screen.
+//!
+//! macOS uses `say`, Windows the System.Speech synthesizer through
+//! PowerShell and Linux speech-dispatcher's `spd-say`.
+
+use anyhow::{anyhow, Context, Result};
+use log::debug;
+use once_cell::sync::Lazy;
+use std::io::Write;
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+
+/// The synthesizer process that is currently speaking, if any.
+static SPEAKER: Lazy<Mutex<Option<Child>>> = Lazy::new(|| Mutex::new(None));
+
+/// Starts reading `text` aloud, interrupting anything still being read.
+pub fn speak(text: &str, voice: Option<&str>) -> Result<()> {
+    stop();
+
+    let mut child = speech_command(voice)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("Failed to start the speech synthesizer")?;
+
+    // Text goes through stdin so long transcripts don't hit argument limits
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow!("Speech synthesizer has no input"))?;
+    stdin.write_all(text.as_bytes())?;
+    drop(stdin);
+
+    debug!("Reading back {} characters", text.len());
+    *SPEAKER.lock().unwrap() = Some(child);
+    Ok(())
+}
+
+/// Stops reading aloud.
+pub fn stop() {
+    if let Some(mut child) = SPEAKER.lock().unwrap().take() {
+        let _ = child.kill();
+        let _ = child.wait();
+        // spd-say only hands the text to the speech-dispatcher daemon
+        #[cfg(target_os = "linux")]
+        let _ = Command::new("spd-say").arg("--cancel").status();
+    }
+}
+
+pub fn is_speaking() -> bool {
+    let mut speaker = SPEAKER.lock().unwrap();
+    match speaker.as_mut().map(|child| child.try_wait()) {
+        Some(Ok(None)) => true,
+        Some(_) => {
+            *speaker = None;
+            false
+        }
+        None => false,
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn speech_command(voice: Option<&str>) -> Command {
+    let mut command = Command::new("say");
+    if let Some(voice) = voice {
+        command.args(["-v", voice]);
+    }
+    command.args(["-f", "-"]);
+    command
+}
+
+#[cfg(target_os = "windows")]
+fn speech_command(voice: Option<&str>) -> Command {
+    let select_voice = voice
+        .map(|v| format!("$s.SelectVoice('{}');", v.replace('\'', "''")))
+        .unwrap_or_default();
+    let mut command = Command::new("powershell");
+    command.args([
+        "-NoProfile",
+        "-Command",
+        &format!(
+            "Add-Type -AssemblyName System.Speech; \
+             $s = New-Object System.Speech.Synthesis.SpeechSynthesizer; {} \
+             $s.Speak([Console]::In.ReadToEnd())",
+            select_voice
+        ),
+    ]);
+    command
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn speech_command(voice: Option<&str>) -> Command {
+    let mut command = Command::new("spd-say");
+    command.args(["--wait", "--pipe-mode"]);
+    if let Some(voice) = voice {
+        command.args(["--synthesis-voice", voice]);
+    }
+    command
+}
@@ -0,0 +1,129 @@
+//! Lets people who turned off the auto-updater still hear about new
+//! releases. The check only downloads the public release manifest: no
+//! identifiers, settings or usage data are sent, and reqwest adds no
+//! user agent unless asked to.
+
+use crate::events::{self, AppEvent};
+use crate::settings::{get_settings, ReleaseChannel};
+use anyhow::Result;
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::thread;
+use std::time::Duration;
+use tauri::AppHandle;
+
+const RELEASE_MANIFEST_URL: &str =
+    "https://github.com/cjpais/Handy/releases/latest/download/latest.json";
+/// Manifests of the beta and nightly channels, which aren't GitHub releases.
+const CHANNEL_MANIFEST_URL: &str = "https://blob.handy.computer/releases";
+/// Give startup some room before touching the network.
+const CHECK_DELAY: Duration = Duration::from_secs(30);
+const CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The parts of the updater's `latest.json` we care about.
+#[derive(Deserialize)]
+struct ReleaseManifest {
+    version: String,
+    #[serde(default)]
+    notes: Option<String>,
+    #[serde(default)]
+    pub_date: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AppUpdate {
+    pub current_version: String,
+    pub version: String,
+    pub notes: Option<String>,
+    pub pub_date: Option<String>,
+}
+
+fn manifest_url(channel: ReleaseChannel) -> String {
+    match channel {
+        ReleaseChannel::Stable => RELEASE_MANIFEST_URL.to_string(),
+        other => format!("{}/{}/latest.json", CHANNEL_MANIFEST_URL, other.as_str()),
+    }
+}
+
+/// Fetches the latest release manifest of `channel` and returns it when it
+/// is newer than the running version.
+pub async fn check_for_update(channel: ReleaseChannel) -> Result<Option<AppUpdate>> {
+    let manifest: ReleaseManifest = reqwest::Client::new()
+        .get(manifest_url(channel))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    let current = env!("CARGO_PKG_VERSION");
+    debug!(
+        "Latest release is {}, running {}",
+        manifest.version, current
+    );
+    if !is_newer(&manifest.version, current) {
+        return Ok(None);
+    }
+    Ok(Some(AppUpdate {
+        current_version: current.to_string(),
+        version: manifest.version.trim_start_matches('v').to_string(),
+        notes: manifest.notes,
+        pub_date: manifest.pub_date,
+    }))
+}
+
+/// Checks shortly after launch and then once a day while update checks are
+/// enabled, emitting `update-available` when a newer release is out.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    thread::spawn(move || {
+        thread::sleep(CHECK_DELAY);
+        let mut notified: Option<String> = None;
+        loop {
+            let settings = get_settings(&app);
+            if settings.update_checks_enabled {
+                match tauri::async_runtime::block_on(check_for_update(settings.release_channel)) {
+                    // Only tell the UI once per release
+                    Ok(Some(update)) if notified.as_ref() != Some(&update.version) => {
+                        info!("Handy {} is available", update.version);
+                        events::emit(&app, AppEvent::UpdateAvailable(update.clone()));
+                        notified = Some(update.version);
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("Update check failed: {}", e),
+                }
+            }
+            thread::sleep(CHECK_INTERVAL);
+        }
+    });
+}
+
+/// Compares dotted versions numerically, ignoring a leading `v` and any
+/// build suffix. A pre-release such as `0.6.0-beta.2` is older than
+/// `0.6.0`, and nightlies order by their date stamp.
+fn is_newer(latest: &str, current: &str) -> bool {
+    let (latest_numbers, latest_pre) = parse_version(latest);
+    let (current_numbers, current_pre) = parse_version(current);
+    let pre_release = match (latest_pre, current_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(latest), Some(current)) => latest.cmp(current),
+    };
+    latest_numbers.cmp(&current_numbers).then(pre_release) == Ordering::Greater
+}
+
+fn parse_version(version: &str) -> (Vec<u64>, Option<&str>) {
+    let version = version.trim().trim_start_matches('v');
+    let version = version.split('+').next().unwrap_or_default();
+    let (numbers, pre_release) = match version.split_once('-') {
+        Some((numbers, pre_release)) => (numbers, Some(pre_release)),
+        None => (version, None),
+    };
+    let numbers = numbers
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect();
+    (numbers, pre_release)
+}
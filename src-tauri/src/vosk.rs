@@ -0,0 +1,65 @@
+//! Vosk (Kaldi) recognizer for the small offline models that run on
+//! machines too slow for whisper.cpp. Vosk links against the native
+//! libvosk, so it is only compiled in with the `vosk` feature; other builds
+//! report Vosk models as unsupported.
+
+use anyhow::Result;
+use std::path::Path;
+
+/// File every Vosk model folder has, used to tell them apart from Parakeet.
+const MODEL_MARKER: &str = "am/final.mdl";
+
+pub fn is_vosk_model_dir(path: &Path) -> bool {
+    path.join(MODEL_MARKER).is_file()
+}
+
+/// Whether this build can run Vosk models.
+pub fn is_supported() -> bool {
+    cfg!(feature = "vosk")
+}
+
+pub struct VoskEngine {
+    #[cfg(feature = "vosk")]
+    model: vosk::Model,
+}
+
+#[cfg(feature = "vosk")]
+impl VoskEngine {
+    pub fn load(model_path: &Path) -> Result<Self> {
+        vosk::set_log_level(vosk::LogLevel::Error);
+        let model = vosk::Model::new(model_path.to_string_lossy())
+            .ok_or_else(|| anyhow::anyhow!("Not a Vosk model: {}", model_path.display()))?;
+        Ok(Self { model })
+    }
+
+    /// Transcribes 16 kHz mono samples. A recognizer is cheap next to the
+    /// model, so each call gets a fresh one.
+    pub fn transcribe(&mut self, audio: &[f32], sample_rate: u32) -> Result<String> {
+        let mut recognizer = vosk::Recognizer::new(&self.model, sample_rate as f32)
+            .ok_or_else(|| anyhow::anyhow!("Failed to create Vosk recognizer"))?;
+        let samples: Vec<i16> = audio
+            .iter()
+            .map(|s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+            .collect();
+        recognizer
+            .accept_waveform(&samples)
+            .map_err(|e| anyhow::anyhow!("Vosk rejected the audio: {:?}", e))?;
+        let text = recognizer
+            .final_result()
+            .single()
+            .map(|result| result.text.to_string())
+            .unwrap_or_default();
+        Ok(text)
+    }
+}
+
+#[cfg(not(feature = "vosk"))]
+impl VoskEngine {
+    pub fn load(_model_path: &Path) -> Result<Self> {
+        Err(anyhow::anyhow!("This build of Handy has no Vosk support"))
+    }
+
+    pub fn transcribe(&mut self, _audio: &[f32], _sample_rate: u32) -> Result<String> {
+        Err(anyhow::anyhow!("This build of Handy has no Vosk support"))
+    }
+}